@@ -0,0 +1,30 @@
+//! Compares every engine on the standard boards, with `cargo bench`.
+//!
+//! The number of timed generations can be changed with `VIDA_BENCH_UPDATES`, and the sizes with
+//! `VIDA_BENCH_SIZES`, like `VIDA_BENCH_SIZES=256,1024 cargo bench`.
+
+use std::env;
+
+use vida::bench::{self, Workload};
+
+const UPDATES: usize = 20;
+
+fn main() {
+    let updates = env::var("VIDA_BENCH_UPDATES").ok().and_then(|updates| updates.parse().ok()).unwrap_or(UPDATES);
+    let sizes = env::var("VIDA_BENCH_SIZES").ok()
+        .and_then(|sizes| sizes.split(',').map(|size| size.trim().parse().ok()).collect::<Option<Vec<usize>>>())
+        .unwrap_or_else(|| bench::SIZES.to_vec());
+
+    let engines = bench::standard_engines();
+    println!("{:<10} {:<12} {:>6} {:>12} {:>14}", "engine", "workload", "size", "median", "cells/s");
+    for size in sizes {
+        for measurement in bench::run_matrix(&engines, &[size], &Workload::ALL, updates) {
+            let mut times = measurement.times.clone();
+            times.sort_unstable();
+            let median = times.get(times.len() / 2).copied().unwrap_or_default();
+
+            let name = measurement.workload.name();
+            println!("{:<10} {name:<12} {size:>6} {median:>12.3?} {:>14.3e}", measurement.engine, measurement.cells_per_second());
+        }
+    }
+}
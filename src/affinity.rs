@@ -0,0 +1,249 @@
+//! Pinning of worker threads to physical CPU cores.
+//!
+//! Letting the OS scheduler migrate rayon workers between cores (or between sockets, or between
+//! performance and efficiency cores) adds a lot of run-to-run variance to benchmarks. The
+//! functions here pin each worker to a distinct physical core, skipping SMT siblings.
+//!
+//! The same topology information is used to find NUMA nodes, so that memory and the threads
+//! working on it can be kept on the same socket.
+//!
+//! Pinning is only implemented on Linux. On other platforms no cores are reported and pinning
+//! requests are silently ignored.
+
+use rayon::{ThreadPoolBuildError, ThreadPoolBuilder};
+
+/// Identifies a logical CPU, as numbered by the operating system.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(transparent)]
+pub struct CoreId(usize);
+
+impl CoreId {
+    #[inline]
+    #[must_use]
+    /// The OS index of this logical CPU.
+    pub const fn index(self) -> usize {
+        self.0
+    }
+
+    #[inline]
+    /// Pins the calling thread to this CPU.
+    ///
+    /// Returns `false` if the operating system refused the request or pinning is not supported.
+    pub fn pin_current_thread(self) -> bool {
+        sys::set_affinity(self.0)
+    }
+}
+
+#[must_use]
+/// Lists one logical CPU for each physical core available to this process.
+///
+/// Hyper-threading siblings are collapsed into the first logical CPU of each core. The result is
+/// sorted by CPU index and is empty when the topology can't be determined.
+pub fn physical_cores() -> Vec<CoreId> {
+    let mut seen = Vec::new();
+    let mut cores = Vec::new();
+
+    for cpu in sys::available_cpus() {
+        let key = sys::core_key(cpu);
+        if !seen.contains(&key) {
+            seen.push(key);
+            cores.push(CoreId(cpu));
+        }
+    }
+    cores
+}
+
+/// A NUMA node and the physical cores attached to it.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct NumaNode {
+    id: usize,
+    cores: Vec<CoreId>,
+}
+
+impl NumaNode {
+    #[inline]
+    #[must_use]
+    /// The OS index of this node.
+    pub const fn id(&self) -> usize {
+        self.id
+    }
+
+    #[inline]
+    #[must_use]
+    /// One logical CPU for each physical core in this node, as in [`physical_cores`].
+    pub fn cores(&self) -> &[CoreId] {
+        &self.cores
+    }
+}
+
+#[must_use]
+/// Lists the NUMA nodes that have at least one physical core available to this process.
+///
+/// Machines without NUMA information are reported as a single node holding every core. The
+/// result is empty only when [`physical_cores`] is.
+pub fn numa_nodes() -> Vec<NumaNode> {
+    let physical = physical_cores();
+    if physical.is_empty() {
+        return Vec::new();
+    }
+
+    let nodes: Vec<_> = sys::node_cpus().into_iter()
+        .map(|(id, cpus)| {
+            let cores = physical.iter().copied().filter(|core| cpus.contains(&core.0)).collect();
+            NumaNode { id, cores }
+        })
+        .filter(|node| !node.cores.is_empty())
+        .collect();
+
+    if nodes.is_empty() {
+        vec![NumaNode { id: 0, cores: physical }]
+    } else {
+        nodes
+    }
+}
+
+#[must_use]
+/// A rayon [`ThreadPoolBuilder`] whose workers pin themselves to the given cores.
+///
+/// The number of threads defaults to the number of cores. Worker `i` is pinned to
+/// `cores[i % cores.len()]`, so asking for more threads than cores wraps around. When `cores` is
+/// empty, the builder is returned unchanged.
+pub fn pinned_pool_builder(cores: Vec<CoreId>) -> ThreadPoolBuilder {
+    let builder = ThreadPoolBuilder::new();
+    if cores.is_empty() {
+        return builder;
+    }
+
+    builder
+        .num_threads(cores.len())
+        .start_handler(move |index| {
+            cores[index % cores.len()].pin_current_thread();
+        })
+}
+
+/// Builds rayon's global thread pool with one worker pinned to each physical core.
+///
+/// Must be called before anything uses the global pool, otherwise it fails like
+/// [`ThreadPoolBuilder::build_global`].
+pub fn pin_global_pool() -> Result<(), ThreadPoolBuildError> {
+    pinned_pool_builder(physical_cores()).build_global()
+}
+
+#[cfg(target_os = "linux")]
+mod sys {
+    use std::fs;
+    use std::mem;
+
+    /// Logical CPUs in the affinity mask of the current process.
+    pub fn available_cpus() -> Vec<usize> {
+        // SAFETY: `cpu_set_t` is a plain bit array, valid when zeroed, and the size passed matches.
+        unsafe {
+            let mut set: libc::cpu_set_t = mem::zeroed();
+            if libc::sched_getaffinity(0, mem::size_of::<libc::cpu_set_t>(), &mut set) != 0 {
+                return Vec::new();
+            }
+            (0..libc::CPU_SETSIZE as usize).filter(|&cpu| libc::CPU_ISSET(cpu, &set)).collect()
+        }
+    }
+
+    /// The `(package, core)` pair identifying the physical core of a logical CPU.
+    ///
+    /// Falls back to the logical CPU itself when sysfs isn't readable.
+    pub fn core_key(cpu: usize) -> (isize, isize) {
+        let read = |name: &str| {
+            let path = format!("/sys/devices/system/cpu/cpu{cpu}/topology/{name}");
+            fs::read_to_string(path).ok()?.trim().parse().ok()
+        };
+
+        match (read("physical_package_id"), read("core_id")) {
+            (Some(package), Some(core)) => (package, core),
+            _ => (-1, cpu as isize),
+        }
+    }
+
+    /// Each NUMA node index and its logical CPUs, sorted by node.
+    pub fn node_cpus() -> Vec<(usize, Vec<usize>)> {
+        let Ok(entries) = fs::read_dir("/sys/devices/system/node") else {
+            return Vec::new();
+        };
+
+        let mut nodes: Vec<_> = entries
+            .filter_map(|entry| {
+                let entry = entry.ok()?;
+                let id = entry.file_name().to_str()?.strip_prefix("node")?.parse().ok()?;
+                let list = fs::read_to_string(entry.path().join("cpulist")).ok()?;
+                Some((id, parse_cpu_list(list.trim())?))
+            })
+            .collect();
+
+        nodes.sort_unstable();
+        nodes
+    }
+
+    /// Parses the kernel list format, like `0-3,8,10-11`.
+    fn parse_cpu_list(list: &str) -> Option<Vec<usize>> {
+        let mut cpus = Vec::new();
+        for range in list.split(',').filter(|range| !range.is_empty()) {
+            match range.split_once('-') {
+                Some((start, end)) => cpus.extend(start.parse::<usize>().ok()? ..= end.parse().ok()?),
+                None => cpus.push(range.parse().ok()?),
+            }
+        }
+        Some(cpus)
+    }
+
+    pub fn set_affinity(cpu: usize) -> bool {
+        if cpu >= libc::CPU_SETSIZE as usize {
+            return false;
+        }
+        // SAFETY: `cpu` is inside the set bounds and the size passed matches the set.
+        unsafe {
+            let mut set: libc::cpu_set_t = mem::zeroed();
+            libc::CPU_SET(cpu, &mut set);
+            libc::sched_setaffinity(0, mem::size_of::<libc::cpu_set_t>(), &set) == 0
+        }
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+mod sys {
+    pub fn available_cpus() -> Vec<usize> {
+        Vec::new()
+    }
+
+    pub fn core_key(cpu: usize) -> (isize, isize) {
+        (-1, cpu as isize)
+    }
+
+    pub fn node_cpus() -> Vec<(usize, Vec<usize>)> {
+        Vec::new()
+    }
+
+    pub fn set_affinity(_cpu: usize) -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    pub fn pin_to_first_core() {
+        let cores = physical_cores();
+        assert!(!cores.is_empty());
+
+        let handle = std::thread::spawn(move || cores[0].pin_current_thread());
+        assert!(handle.join().unwrap());
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    pub fn nodes_partition_cores() {
+        let mut cores: Vec<_> = numa_nodes().iter().flat_map(|node| node.cores().to_vec()).collect();
+        cores.sort_unstable();
+
+        assert_eq!(cores, physical_cores());
+    }
+}
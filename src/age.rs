@@ -0,0 +1,167 @@
+//! How long each cell has been alive.
+
+use std::ops::Index;
+
+use crate::cell::Grid;
+use crate::par::*;
+
+/// The age of every cell of a grid: the number of consecutive generations it has been live,
+/// counting the current one.
+///
+/// Dead cells have age `0` and newborn cells have age `1`. Ages saturate at [`u16::MAX`].
+///
+/// # Example
+///
+/// ```
+/// # use vida::age::Ages;
+/// # use vida::cell::{Cell, Grid};
+/// # use vida::engine::{Engine, SerialEngine};
+/// #
+/// let mut blinker = Grid::new(5, 5);
+/// blinker[2][1..4].fill(Cell::Live);
+/// let mut ages = Ages::new(&blinker);
+///
+/// let next = SerialEngine::default().update(&blinker);
+/// ages.update(&next);
+///
+/// assert_eq!(ages[(2, 2)], 2);
+/// assert_eq!(ages[(1, 2)], 1);
+/// assert_eq!(ages[(2, 1)], 0);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Ages {
+    ages: Box<[u16]>,
+    rows: usize,
+    columns: usize,
+}
+
+impl Ages {
+    #[must_use]
+    /// Starts counting from a grid, where every live cell is newborn.
+    pub fn new(grid: &Grid) -> Self {
+        let ages = grid.flat().par_iter().map(|cell| u16::from(cell.is_live())).collect();
+        Self { ages, rows: grid.rows(), columns: grid.columns() }
+    }
+
+    /// Moves on to the next generation, aging the cells that are still live.
+    ///
+    /// If the grid has a different shape, counting starts again.
+    pub fn update(&mut self, grid: &Grid) {
+        if self.shape() != grid.shape() {
+            *self = Self::new(grid);
+            return;
+        }
+
+        self.ages.par_iter_mut().zip(grid.flat().par_iter()).for_each(|(age, cell)| {
+            *age = if cell.is_live() { age.saturating_add(1) } else { 0 };
+        });
+    }
+
+    /// Goes back to the previous generation, given by `grid`.
+    ///
+    /// Cells live in both generations get one generation younger, while cells that were only live
+    /// in the previous one are counted as newborn, since how long they had been live is lost.
+    pub fn step_back(&mut self, grid: &Grid) {
+        if self.shape() != grid.shape() {
+            *self = Self::new(grid);
+            return;
+        }
+
+        self.ages.par_iter_mut().zip(grid.flat().par_iter()).for_each(|(age, cell)| {
+            *age = if cell.is_live() { age.saturating_sub(1).max(1) } else { 0 };
+        });
+    }
+
+    #[inline]
+    #[must_use]
+    /// The shape `(rows, columns)` of the grid.
+    pub const fn shape(&self) -> (usize, usize) {
+        (self.rows, self.columns)
+    }
+
+    #[inline]
+    #[must_use]
+    /// The ages of all cells, in row-major order.
+    pub const fn flat(&self) -> &[u16] {
+        &self.ages
+    }
+
+    #[inline]
+    #[must_use]
+    /// The age of a cell, or [`None`] if the index is out-of-bounds.
+    pub fn get(&self, row: usize, col: usize) -> Option<u16> {
+        if col < self.columns {
+            self.ages.get(row.checked_mul(self.columns)?.checked_add(col)?).copied()
+        } else {
+            None
+        }
+    }
+
+    #[must_use]
+    /// The ages of a larger grid of `(rows, columns)` cells, where this one was copied at
+    /// `(top, left)`, like [`Grid::expanded`].
+    ///
+    /// # Panics
+    ///
+    /// If this grid doesn't fit in the new one at that position.
+    pub fn expanded(&self, (top, left): (usize, usize), (rows, columns): (usize, usize)) -> Self {
+        assert!(top + self.rows <= rows && left + self.columns <= columns, "ages don't fit in the expanded grid");
+
+        let mut ages = vec![0; rows * columns].into_boxed_slice();
+        if self.columns > 0 {
+            for (old, row) in self.ages.chunks_exact(self.columns).zip(top..) {
+                let start = row * columns + left;
+                ages[start..start + self.columns].copy_from_slice(old);
+            }
+        }
+        Self { ages, rows, columns }
+    }
+
+    #[must_use]
+    /// The age of the oldest cell.
+    pub fn max(&self) -> u16 {
+        self.ages.par_iter().copied().max().unwrap_or(0)
+    }
+}
+
+impl Index<(usize, usize)> for Ages {
+    type Output = u16;
+
+    #[inline]
+    fn index(&self, (row, col): (usize, usize)) -> &u16 {
+        assert!(col < self.columns, "column out of bounds");
+        &self.ages[row * self.columns + col]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cell::Cell;
+    use crate::engine::SerialEngine;
+    use crate::simulation::Simulation;
+
+    use super::*;
+
+    #[test]
+    pub fn still_life_keeps_aging() {
+        let mut block = Grid::new(4, 4);
+        block[(1, 1)] = Cell::Live;
+        block[(1, 2)] = Cell::Live;
+        block[(2, 1)] = Cell::Live;
+        block[(2, 2)] = Cell::Live;
+
+        let mut simulation = Simulation::new(SerialEngine::default(), block).track_ages(true);
+        for _ in 0..10 {
+            simulation.step();
+        }
+        let ages = simulation.ages().unwrap();
+        assert_eq!(ages.max(), 11);
+        assert_eq!(ages.get(2, 2), Some(11));
+        assert_eq!(ages.get(0, 0), Some(0));
+        assert_eq!(ages.get(0, 4), None);
+
+        simulation.reset(Grid::new_with(2, 3, Cell::Live), 0);
+        assert_eq!(simulation.ages().unwrap().shape(), (2, 3));
+        assert_eq!(simulation.ages().unwrap().flat(), [1; 6]);
+    }
+}
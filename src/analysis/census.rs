@@ -0,0 +1,369 @@
+//! Counting the objects left on a board, like the blocks and blinkers that remain after a soup
+//! settles.
+//!
+//! Live cells are split into objects of cells touching each other, including diagonally, and each
+//! object is recognized from its canonical form: its cells moved to the origin, in the rotation or
+//! reflection that sorts first. Every phase of the known oscillators and spaceships is recognized.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::{self, Display, Formatter};
+use std::sync::OnceLock;
+
+use crate::cell::Grid;
+use crate::engine::{Engine, SerialEngine};
+use crate::io::plaintext;
+use crate::patterns;
+
+/// Common objects of the Game of Life.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Kind {
+    Block,
+    Beehive,
+    Loaf,
+    Boat,
+    Ship,
+    Tub,
+    Pond,
+    Blinker,
+    Toad,
+    Beacon,
+    Glider,
+}
+
+impl Kind {
+    /// Every kind, from the still lifes to the glider.
+    pub const ALL: [Self; 11] = [
+        Self::Block, Self::Beehive, Self::Loaf, Self::Boat, Self::Ship, Self::Tub, Self::Pond,
+        Self::Blinker, Self::Toad, Self::Beacon, Self::Glider,
+    ];
+
+    #[inline]
+    #[must_use]
+    /// Name of the kind, for reports.
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Block => "block",
+            Self::Beehive => "beehive",
+            Self::Loaf => "loaf",
+            Self::Boat => "boat",
+            Self::Ship => "ship",
+            Self::Tub => "tub",
+            Self::Pond => "pond",
+            Self::Blinker => "blinker",
+            Self::Toad => "toad",
+            Self::Beacon => "beacon",
+            Self::Glider => "glider",
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    /// Generations until the object repeats, possibly moved.
+    pub const fn period(self) -> usize {
+        match self {
+            Self::Blinker | Self::Toad | Self::Beacon => 2,
+            Self::Glider => 4,
+            _ => 1,
+        }
+    }
+
+    #[must_use]
+    /// The object in one of its phases.
+    pub fn grid(self) -> Grid {
+        let cells = match self {
+            Self::Block => "OO\nOO\n",
+            Self::Beehive => ".OO\nO..O\n.OO\n",
+            Self::Loaf => ".OO\nO..O\n.O.O\n..O\n",
+            Self::Boat => "OO\nO.O\n.O\n",
+            Self::Ship => "OO\nO.O\n.OO\n",
+            Self::Tub => ".O\nO.O\n.O\n",
+            Self::Pond => ".OO\nO..O\nO..O\n.OO\n",
+            Self::Blinker => "OOO\n",
+            Self::Toad => ".OOO\nOOO\n",
+            Self::Beacon => "OO\nOO\n..OO\n..OO\n",
+            Self::Glider => return patterns::glider(),
+        };
+        plaintext::parse(cells).expect("built-in objects are valid").grid
+    }
+}
+
+impl Display for Kind {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Cells of an object, as `(row, col)` positions.
+pub type Cells = Vec<(usize, usize)>;
+
+/// Splits the live cells of a grid into objects of cells touching each other, including
+/// diagonally. The cells of each object are sorted, and the objects are in the order of their
+/// first cell.
+#[must_use]
+pub fn objects(grid: &Grid) -> Vec<Cells> {
+    let (rows, columns) = grid.shape();
+    let mut seen = vec![false; rows * columns];
+    let mut objects = Vec::new();
+
+    for (row, col) in grid.live_cells() {
+        if std::mem::replace(&mut seen[row * columns + col], true) {
+            continue;
+        }
+
+        let mut object = vec![(row, col)];
+        let mut next = 0;
+        while let Some(&(row, col)) = object.get(next) {
+            next += 1;
+            for near in row.saturating_sub(1)..(row + 2).min(rows) {
+                for side in col.saturating_sub(1)..(col + 2).min(columns) {
+                    if grid[(near, side)].is_live() && !std::mem::replace(&mut seen[near * columns + side], true) {
+                        object.push((near, side));
+                    }
+                }
+            }
+        }
+        object.sort_unstable();
+        objects.push(object);
+    }
+    objects
+}
+
+/// The cells of an object moved to the origin, in the rotation or reflection that sorts first, so
+/// objects have the same canonical form exactly when they are the same up to symmetry.
+#[must_use]
+pub fn canonical(cells: &[(usize, usize)]) -> Cells {
+    // every combination of swapping the axes and flipping each of them
+    (0..8_u8)
+        .map(|symmetry| {
+            let moved: Vec<_> = cells
+                .iter()
+                .map(|&(row, col)| {
+                    let (row, col) = if symmetry & 4 == 0 { (row as isize, col as isize) } else { (col as isize, row as isize) };
+                    (if symmetry & 2 == 0 { row } else { -row }, if symmetry & 1 == 0 { col } else { -col })
+                })
+                .collect();
+            let top = moved.iter().map(|&(row, _)| row).min().unwrap_or(0);
+            let left = moved.iter().map(|&(_, col)| col).min().unwrap_or(0);
+
+            let mut normalized: Cells = moved.iter().map(|&(row, col)| ((row - top) as usize, (col - left) as usize)).collect();
+            normalized.sort_unstable();
+            normalized
+        })
+        .min()
+        .unwrap_or_default()
+}
+
+/// Canonical forms of every phase of every known kind.
+fn known() -> &'static HashMap<Cells, Kind> {
+    static KNOWN: OnceLock<HashMap<Cells, Kind>> = OnceLock::new();
+
+    KNOWN.get_or_init(|| {
+        let engine = SerialEngine::default();
+        let mut known = HashMap::new();
+        for kind in Kind::ALL {
+            // enough room for every phase, since no object here grows by more than a cell
+            let pattern = kind.grid();
+            let (rows, columns) = pattern.shape();
+            let mut grid = pattern.expanded((2, 2), (rows + 4, columns + 4));
+
+            for _ in 0..kind.period() {
+                let cells: Cells = grid.live_cells().collect();
+                known.insert(canonical(&cells), kind);
+                grid = engine.update(&grid);
+            }
+        }
+        known
+    })
+}
+
+/// The kind of an object, if it is a phase of a known one.
+#[must_use]
+pub fn classify(cells: &[(usize, usize)]) -> Option<Kind> {
+    known().get(&canonical(cells)).copied()
+}
+
+/// Number of objects of each kind on a board.
+///
+/// Objects are split as in [`objects`], but two unknown objects at most two cells apart are
+/// counted as one when together they are a known one, like the two halves of a beacon in one of
+/// its phases.
+///
+/// # Example
+///
+/// ```
+/// # use vida::analysis::census::{Census, Kind};
+/// # use vida::cell::{Grid, Overflow};
+/// # use vida::patterns;
+/// #
+/// let mut grid = Grid::new(20, 20);
+/// grid.blit(&Kind::Block.grid(), 1, 1, Overflow::Clip).unwrap();
+/// grid.blit(&Kind::Block.grid(), 10, 15, Overflow::Clip).unwrap();
+/// grid.blit(&patterns::glider(), 12, 3, Overflow::Clip).unwrap();
+///
+/// let census = Census::new(&grid);
+/// assert_eq!(census.count(Kind::Block), 2);
+/// assert_eq!(census.count(Kind::Glider), 1);
+/// assert_eq!(census.to_string(), "block 2, glider 1");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Census {
+    counts: BTreeMap<Kind, usize>,
+    unknown: usize,
+}
+
+impl Census {
+    #[must_use]
+    /// Counts the objects on a board.
+    pub fn new(grid: &Grid) -> Self {
+        let mut census = Self::default();
+        let mut unknown = Vec::new();
+        for object in objects(grid) {
+            match classify(&object) {
+                Some(kind) => census.add(kind, 1),
+                None => unknown.push(Some(object)),
+            }
+        }
+
+        for first in 0..unknown.len() {
+            let Some(object) = unknown[first].take() else {
+                continue;
+            };
+
+            let pair = (first + 1..unknown.len()).find_map(|second| {
+                let other = unknown[second].as_ref().filter(|other| near(&object, other))?;
+                let mut union = object.clone();
+                union.extend_from_slice(other);
+                classify(&union).map(|kind| (second, kind))
+            });
+            match pair {
+                Some((second, kind)) => {
+                    unknown[second] = None;
+                    census.add(kind, 1);
+                },
+                None => census.unknown += 1,
+            }
+        }
+        census
+    }
+
+    /// Counts `count` more objects of a kind.
+    fn add(&mut self, kind: Kind, count: usize) {
+        *self.counts.entry(kind).or_default() += count;
+    }
+
+    #[inline]
+    #[must_use]
+    /// Number of objects of the given kind.
+    pub fn count(&self, kind: Kind) -> usize {
+        self.counts.get(&kind).copied().unwrap_or(0)
+    }
+
+    #[inline]
+    #[must_use]
+    /// Number of objects that are none of the known kinds.
+    pub const fn unknown(&self) -> usize {
+        self.unknown
+    }
+
+    #[inline]
+    #[must_use]
+    /// Number of objects, known or not.
+    pub fn objects(&self) -> usize {
+        self.counts.values().sum::<usize>() + self.unknown
+    }
+
+    #[inline]
+    /// The kinds found and how many of each, in the order of [`Kind::ALL`].
+    pub fn iter(&self) -> impl Iterator<Item = (Kind, usize)> + '_ {
+        self.counts.iter().map(|(&kind, &count)| (kind, count))
+    }
+
+    /// Adds the objects of another census, like the one of another board.
+    pub fn merge(&mut self, other: &Self) {
+        for (kind, count) in other.iter() {
+            self.add(kind, count);
+        }
+        self.unknown += other.unknown;
+    }
+}
+
+impl Display for Census {
+    /// Counts of each kind, like `block 2, blinker 1, other 3`.
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let mut parts = self.iter().map(|(kind, count)| format!("{kind} {count}")).collect::<Vec<_>>();
+        if self.unknown > 0 {
+            parts.push(format!("other {}", self.unknown));
+        }
+
+        if parts.is_empty() { f.write_str("nothing") } else { f.write_str(&parts.join(", ")) }
+    }
+}
+
+/// Whether two objects have cells at most two rows and columns apart.
+fn near(first: &[(usize, usize)], second: &[(usize, usize)]) -> bool {
+    first.iter().any(|&(row, col)| second.iter().any(|&(other_row, other_col)| row.abs_diff(other_row) <= 2 && col.abs_diff(other_col) <= 2))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cell::{Cell, Overflow};
+
+    use super::*;
+
+    /// The pattern turned a quarter clockwise.
+    fn turned(pattern: &Grid) -> Grid {
+        let (rows, columns) = pattern.shape();
+        let mut turned = Grid::new(columns, rows);
+        for (row, col) in pattern.live_cells() {
+            turned[(col, rows - 1 - row)] = Cell::Live;
+        }
+        turned
+    }
+
+    #[test]
+    pub fn every_phase_and_orientation() {
+        let engine = SerialEngine::default();
+        for kind in Kind::ALL {
+            let mut grid = Grid::new(12, 12);
+            grid.blit(&turned(&kind.grid()), 4, 4, Overflow::Clip).unwrap();
+
+            for _ in 0..kind.period() {
+                let census = Census::new(&grid);
+                assert_eq!(census.count(kind), 1, "{kind}: {census}");
+                assert_eq!(census.objects(), 1, "{kind}: {census}");
+                grid = engine.update(&grid);
+            }
+        }
+    }
+
+    #[test]
+    pub fn objects_apart() {
+        let mut grid = Grid::new(16, 16);
+        for (row, col) in [(0, 0), (0, 4), (5, 0), (12, 12)] {
+            grid.blit(&Kind::Block.grid(), row, col, Overflow::Clip).unwrap();
+        }
+        grid.blit(&Kind::Blinker.grid(), 9, 2, Overflow::Clip).unwrap();
+        grid[(15, 0)] = Cell::Live;
+
+        assert_eq!(objects(&grid).len(), 6);
+        let census = Census::new(&grid);
+        assert_eq!((census.count(Kind::Block), census.count(Kind::Blinker), census.unknown()), (4, 1, 1));
+        assert_eq!(census.to_string(), "block 4, blinker 1, other 1");
+
+        let mut total = census.clone();
+        total.merge(&census);
+        assert_eq!(total.count(Kind::Block), 8);
+        assert_eq!(total.objects(), 12);
+        assert_eq!(Census::new(&Grid::new(3, 3)).to_string(), "nothing");
+    }
+
+    #[test]
+    pub fn canonical_forms() {
+        let boat = [(0, 0), (0, 1), (1, 0), (1, 2), (2, 1)];
+        let turned = [(5, 7), (5, 8), (6, 6), (6, 8), (7, 7)];
+        assert_eq!(canonical(&boat), canonical(&turned));
+        assert_ne!(canonical(&boat), canonical(&[(0, 0), (0, 1), (1, 0), (1, 2), (2, 1), (2, 2)]));
+        assert_eq!(classify(&turned), Some(Kind::Boat));
+    }
+}
@@ -0,0 +1,3 @@
+//! Finding out what is on a board.
+
+pub mod census;
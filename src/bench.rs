@@ -0,0 +1,165 @@
+//! Timing the engines on standard boards, to compare them.
+//!
+//! Every engine runs the same workloads at the same sizes, so the results of
+//! [`run_matrix`] can be put side by side. This is used by the `vida bench --matrix` command and
+//! by `cargo bench`.
+
+use std::time::{Duration, Instant};
+
+use crate::cell::{Grid, Overflow};
+use crate::engine::{BitEngine, Engine, HashLifeEngine, SerialEngine, SparseEngine};
+#[cfg(feature = "parallel")]
+use crate::engine::{NumaEngine, ParallelEngine};
+use crate::patterns::Preset;
+
+/// Side of the square boards in the standard matrix.
+pub const SIZES: [usize; 3] = [256, 1024, 4096];
+
+/// Seed of the random soups, so every engine gets the same board.
+const SOUP_SEED: u64 = 548;
+
+/// The initial board of a benchmark.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Workload {
+    /// Half of the cells live, at random.
+    Soup,
+    /// A Gosper glider gun at the top left corner, shooting across an empty board.
+    GliderGun,
+    /// No live cells at all.
+    Empty,
+}
+
+impl Workload {
+    /// Every workload, in order.
+    pub const ALL: [Self; 3] = [Self::Soup, Self::GliderGun, Self::Empty];
+
+    #[inline]
+    #[must_use]
+    /// Name of the workload, for reports.
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Soup => "soup",
+            Self::GliderGun => "glider-gun",
+            Self::Empty => "empty",
+        }
+    }
+
+    #[must_use]
+    /// The board of `size` by `size` cells for this workload. The glider gun is cut to fit in
+    /// boards smaller than it.
+    pub fn grid(self, size: usize) -> Grid {
+        match self {
+            Self::Soup => Grid::random_seeded(size, size, SOUP_SEED),
+            Self::Empty => Grid::new(size, size),
+            Self::GliderGun => {
+                let mut grid = Grid::new(size, size);
+                grid.blit(&Preset::GosperGun.grid(), 1, 1, Overflow::Clip).expect("clipping never fails");
+                grid
+            },
+        }
+    }
+}
+
+/// The time taken by each update of an engine on a workload.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Measurement {
+    /// Name of the engine, see [`Engine::name`].
+    pub engine: &'static str,
+    pub workload: Workload,
+    /// Side of the square board.
+    pub size: usize,
+    /// Duration of each update, in order.
+    pub times: Vec<Duration>,
+}
+
+impl Measurement {
+    #[inline]
+    #[must_use]
+    /// Time of all updates together.
+    pub fn total(&self) -> Duration {
+        self.times.iter().sum()
+    }
+
+    #[must_use]
+    /// Cells computed per second, over all updates.
+    pub fn cells_per_second(&self) -> f64 {
+        let cells = self.size as f64 * self.size as f64 * self.times.len() as f64;
+        cells / self.total().as_secs_f64().max(f64::MIN_POSITIVE)
+    }
+}
+
+#[must_use]
+/// The engines compared by default: serial, parallel, numa, bit, sparse and hashlife, all
+/// running Conway's rule one generation at a time. The numa engine is left out where its thread
+/// pools can't be built, and both multithreaded engines without the `parallel` feature.
+pub fn standard_engines() -> Vec<Box<dyn Engine>> {
+    let mut engines: Vec<Box<dyn Engine>> = vec![Box::<SerialEngine>::default()];
+    #[cfg(feature = "parallel")]
+    {
+        engines.push(Box::<ParallelEngine>::default());
+        if let Ok(numa) = NumaEngine::new() {
+            engines.push(Box::new(numa));
+        }
+    }
+    engines.push(Box::new(BitEngine));
+    engines.push(Box::<SparseEngine>::default());
+    engines.push(Box::new(HashLifeEngine::new()));
+    engines
+}
+
+#[must_use]
+/// Times `updates` generations of every engine, on every workload at every size.
+///
+/// Each engine is updated once before timing, to warm up caches and thread pools. The results
+/// are grouped by size, then by workload, in the order given.
+pub fn run_matrix(engines: &[Box<dyn Engine>], sizes: &[usize], workloads: &[Workload], updates: usize) -> Vec<Measurement> {
+    let mut measurements = Vec::with_capacity(engines.len() * sizes.len() * workloads.len());
+    for &size in sizes {
+        for &workload in workloads {
+            let initial = workload.grid(size);
+            for engine in engines {
+                measurements.push(Measurement { engine: engine.name(), workload, size, times: time_updates(engine.as_ref(), &initial, updates) });
+            }
+        }
+    }
+    measurements
+}
+
+/// Duration of each update, after a first one that isn't timed.
+fn time_updates(engine: &dyn Engine, initial: &Grid, updates: usize) -> Vec<Duration> {
+    let mut grid = engine.update(initial);
+    let mut next = Grid::empty();
+
+    let mut times = Vec::with_capacity(updates);
+    for _ in 0..updates {
+        let start = Instant::now();
+        engine.update_into(&grid, &mut next);
+        times.push(start.elapsed());
+        std::mem::swap(&mut grid, &mut next);
+    }
+    times
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn small_matrix() {
+        let engines = standard_engines();
+        let measurements = run_matrix(&engines, &[8, 64], &Workload::ALL, 3);
+        assert_eq!(measurements.len(), engines.len() * 2 * Workload::ALL.len());
+
+        for measurement in &measurements {
+            assert_eq!(measurement.times.len(), 3);
+            assert!(measurement.cells_per_second() > 0.0);
+        }
+        assert_eq!(measurements[0].engine, "serial");
+        assert_eq!((measurements.last().unwrap().size, measurements.last().unwrap().workload), (64, Workload::Empty));
+
+        // the gun is cut in small boards
+        assert_eq!(Workload::GliderGun.grid(8).shape(), (8, 8));
+        assert_eq!(Workload::GliderGun.grid(64).population(), Preset::GosperGun.grid().population());
+        assert_eq!(Workload::Empty.grid(64).population(), 0);
+    }
+}
@@ -0,0 +1,373 @@
+use std::fmt::{self, Display, Formatter, Write};
+use std::ops::{Deref, Index};
+
+use crate::error::Error;
+
+use super::{Cell, Grid};
+
+/// Cells stored in each word.
+const BITS: usize = u64::BITS as usize;
+
+/// A [`Grid`] packed 64 cells per `u64` word, taking an eighth of the memory.
+///
+/// Each row starts at a new word, with bit `i` of word `w` being the cell at column `64 w + i`,
+/// and the bits past the last column always dead. This is the layout used by word-at-a-time
+/// engines like [`BitEngine`](crate::engine::BitEngine), which can read the rows directly with
+/// [`row_words`](BitGrid::row_words).
+///
+/// Cells can't be borrowed from inside a word, so reading by index gives references to constant
+/// cells, and writing goes through the [`CellMut`] proxy or [`set`](BitGrid::set).
+///
+/// # Example
+///
+/// ```
+/// # use vida::cell::{BitGrid, Cell, Grid};
+/// #
+/// let grid = Grid::random_seeded(100, 100, 0);
+/// let mut packed = BitGrid::from(&grid);
+/// assert_eq!(packed.population(), grid.population());
+///
+/// packed.set(3, 70, Cell::Live);
+/// packed.get_cell_mut(3, 71).unwrap().set(Cell::Dead);
+/// assert_eq!((packed[(3, 70)], packed.row(3)[71]), (Cell::Live, Cell::Dead));
+/// assert_eq!(Grid::from(&packed)[3][..70], grid[3][..70]);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct BitGrid {
+    words: Box<[u64]>,
+    rows: usize,
+    columns: usize,
+}
+
+/// The constant cells referenced by [`BitGrid`] indexing.
+static CELLS: [Cell; 2] = [Cell::Dead, Cell::Live];
+
+impl BitGrid {
+    #[must_use]
+    /// Creates a packed grid of `(rows, columns)` dead cells.
+    pub fn new(rows: usize, columns: usize) -> Self {
+        let words = vec![0; rows * Self::words_for(columns)].into_boxed_slice();
+        Self { words, rows, columns }
+    }
+
+    #[inline]
+    #[must_use]
+    /// Words needed for a row of `columns` cells.
+    pub const fn words_for(columns: usize) -> usize {
+        (columns + BITS - 1) / BITS
+    }
+
+    #[inline]
+    #[must_use]
+    /// Number of rows in the grid.
+    pub const fn rows(&self) -> usize {
+        self.rows
+    }
+
+    #[inline]
+    #[must_use]
+    /// Number of columns in the grid.
+    pub const fn columns(&self) -> usize {
+        self.columns
+    }
+
+    #[inline]
+    #[must_use]
+    /// Number of `(rows, columns)` in the grid.
+    pub const fn shape(&self) -> (usize, usize) {
+        (self.rows, self.columns)
+    }
+
+    #[inline]
+    #[must_use]
+    /// The total number of cells in the grid.
+    pub const fn cells(&self) -> usize {
+        self.rows * self.columns
+    }
+
+    #[inline]
+    #[must_use]
+    /// Words in each row.
+    pub const fn row_len(&self) -> usize {
+        Self::words_for(self.columns)
+    }
+
+    #[must_use]
+    /// The number of live cells, counted a word at a time.
+    pub fn population(&self) -> usize {
+        self.words.iter().map(|word| word.count_ones() as usize).sum()
+    }
+
+    #[inline]
+    #[must_use]
+    /// All the words, row by row.
+    pub const fn words(&self) -> &[u64] {
+        &self.words
+    }
+
+    #[inline]
+    #[must_use]
+    /// The words of a row, or [`None`] if it is outside of the grid.
+    pub fn row_words(&self, row: usize) -> Option<&[u64]> {
+        let len = self.row_len();
+        (row < self.rows).then(|| &self.words[row * len..(row + 1) * len])
+    }
+
+    #[inline]
+    #[must_use]
+    /// Mutable words of a row, or [`None`] if it is outside of the grid.
+    ///
+    /// The bits past the last column must be left dead.
+    pub fn row_words_mut(&mut self, row: usize) -> Option<&mut [u64]> {
+        let len = self.row_len();
+        (row < self.rows).then(|| &mut self.words[row * len..(row + 1) * len])
+    }
+
+    #[inline]
+    #[must_use]
+    /// Index of the word and bit of a cell, or [`None`] if it is outside of the grid.
+    fn locate(&self, row: usize, col: usize) -> Option<(usize, u64)> {
+        (row < self.rows && col < self.columns).then(|| (row * self.row_len() + col / BITS, 1 << (col % BITS)))
+    }
+
+    #[inline]
+    #[must_use]
+    /// A cell, or [`None`] if it is outside of the grid.
+    pub fn get_cell(&self, row: usize, col: usize) -> Option<Cell> {
+        let (index, bit) = self.locate(row, col)?;
+        Some(Cell::from(self.words[index] & bit != 0))
+    }
+
+    #[inline]
+    #[must_use]
+    /// A proxy for writing a cell, or [`None`] if it is outside of the grid.
+    pub fn get_cell_mut(&mut self, row: usize, col: usize) -> Option<CellMut<'_>> {
+        let (index, bit) = self.locate(row, col)?;
+        Some(CellMut { word: &mut self.words[index], bit })
+    }
+
+    #[inline]
+    /// Changes a cell.
+    ///
+    /// # Panics
+    ///
+    /// If the position is outside of the grid.
+    pub fn set(&mut self, row: usize, col: usize, cell: Cell) {
+        let shape = self.shape();
+        match self.get_cell_mut(row, col) {
+            Some(mut proxy) => proxy.set(cell),
+            None => panic!("{}", Error::OutOfBounds { position: (row, col), shape }),
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    /// A row of cells.
+    ///
+    /// # Panics
+    ///
+    /// If the row is outside of the grid.
+    pub fn row(&self, row: usize) -> BitRow<'_> {
+        let words = self.row_words(row).unwrap_or_else(|| panic!("{}", Error::OutOfBounds { position: (row, 0), shape: self.shape() }));
+        BitRow { words, columns: self.columns }
+    }
+
+    #[inline]
+    /// The rows of the grid, from top to bottom.
+    pub fn iter(&self) -> impl ExactSizeIterator<Item = BitRow<'_>> + DoubleEndedIterator {
+        (0..self.rows).map(|row| self.row(row))
+    }
+
+    /// The `(row, col)` position of every live cell, in row-major order, skipping empty words.
+    pub fn live_cells(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        let len = self.row_len().max(1);
+        self.words.iter().enumerate().filter(|(_, &word)| word != 0).flat_map(move |(index, &word)| {
+            let (row, first) = (index / len, (index % len) * BITS);
+            (0..BITS).filter(move |bit| word & (1 << bit) != 0).map(move |bit| (row, first + bit))
+        })
+    }
+}
+
+impl From<&Grid> for BitGrid {
+    fn from(grid: &Grid) -> Self {
+        let mut packed = Self::new(grid.rows(), grid.columns());
+        let len = packed.row_len().max(1);
+
+        for (cells, words) in grid.iter().zip(packed.words.chunks_exact_mut(len)) {
+            for (cells, word) in cells.chunks(BITS).zip(words.iter_mut()) {
+                *word = cells.iter().enumerate().fold(0, |word, (i, cell)| word | (u64::from(cell.is_live()) << i));
+            }
+        }
+        packed
+    }
+}
+
+impl From<&BitGrid> for Grid {
+    fn from(packed: &BitGrid) -> Self {
+        let mut grid = Self::new(packed.rows(), packed.columns());
+        for (cells, row) in grid.iter_mut().zip(packed.iter()) {
+            for (cell, state) in cells.iter_mut().zip(row.iter()) {
+                *cell = state;
+            }
+        }
+        grid
+    }
+}
+
+impl Index<(usize, usize)> for BitGrid {
+    type Output = Cell;
+
+    #[inline]
+    fn index(&self, (row, col): (usize, usize)) -> &Cell {
+        match self.get_cell(row, col) {
+            Some(cell) => &CELLS[cell as usize],
+            None => panic!("{}", Error::OutOfBounds { position: (row, col), shape: self.shape() }),
+        }
+    }
+}
+
+impl Display for BitGrid {
+    /// Writes the cells like [`Grid`], including the alternate form.
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for row in self.iter() {
+            for cell in row.iter() {
+                cell.fmt(f)?
+            }
+            f.write_char('\n')?
+        }
+        Ok(())
+    }
+}
+
+/// A row of a [`BitGrid`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BitRow<'a> {
+    words: &'a [u64],
+    columns: usize,
+}
+
+impl<'a> BitRow<'a> {
+    #[inline]
+    #[must_use]
+    /// Number of cells in the row.
+    pub const fn len(&self) -> usize {
+        self.columns
+    }
+
+    #[inline]
+    #[must_use]
+    /// Checks if the row has no cells.
+    pub const fn is_empty(&self) -> bool {
+        self.columns == 0
+    }
+
+    #[inline]
+    #[must_use]
+    /// The words of the row.
+    pub const fn words(&self) -> &'a [u64] {
+        self.words
+    }
+
+    #[inline]
+    #[must_use]
+    /// A cell, or [`None`] if it is outside of the row.
+    pub fn get(&self, col: usize) -> Option<Cell> {
+        (col < self.columns).then(|| Cell::from(self.words[col / BITS] & (1 << (col % BITS)) != 0))
+    }
+
+    #[inline]
+    /// The cells of the row, from left to right.
+    pub fn iter(&self) -> impl ExactSizeIterator<Item = Cell> + DoubleEndedIterator + 'a {
+        let words = self.words;
+        (0..self.columns).map(move |col| Cell::from(words[col / BITS] & (1 << (col % BITS)) != 0))
+    }
+}
+
+impl Index<usize> for BitRow<'_> {
+    type Output = Cell;
+
+    #[inline]
+    fn index(&self, col: usize) -> &Cell {
+        match self.get(col) {
+            Some(cell) => &CELLS[cell as usize],
+            None => panic!("{}", Error::OutOfBounds { position: (0, col), shape: (1, self.columns) }),
+        }
+    }
+}
+
+/// A writable cell of a [`BitGrid`], standing for `&mut Cell`.
+///
+/// It reads as a [`Cell`] through [`Deref`], and writes with [`set`](CellMut::set).
+#[derive(Debug)]
+pub struct CellMut<'a> {
+    word: &'a mut u64,
+    bit: u64,
+}
+
+impl CellMut<'_> {
+    #[inline]
+    #[must_use]
+    /// The current state of the cell.
+    pub fn get(&self) -> Cell {
+        Cell::from(*self.word & self.bit != 0)
+    }
+
+    #[inline]
+    /// Changes the cell.
+    pub fn set(&mut self, cell: Cell) {
+        match cell {
+            Cell::Live => *self.word |= self.bit,
+            Cell::Dead => *self.word &= !self.bit,
+        }
+    }
+}
+
+impl Deref for CellMut<'_> {
+    type Target = Cell;
+
+    #[inline]
+    fn deref(&self) -> &Cell {
+        &CELLS[self.get() as usize]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn packed_round_trip() {
+        for (rows, columns) in [(7, 130), (3, 64), (5, 1), (0, 9), (4, 0)] {
+            let grid = Grid::random_seeded(rows, columns, 568);
+            let packed = BitGrid::from(&grid);
+
+            assert_eq!(packed.shape(), grid.shape());
+            assert_eq!(packed.words().len(), rows * BitGrid::words_for(columns));
+            assert_eq!(packed.population(), grid.population());
+            assert_eq!(Grid::from(&packed), grid);
+            assert_eq!(packed.live_cells().collect::<Vec<_>>(), grid.live_cells().collect::<Vec<_>>());
+            assert_eq!(format!("{packed:#}"), format!("{grid:#}"));
+
+            for (row, col) in grid.live_cells() {
+                assert_eq!(packed[(row, col)], Cell::Live);
+                assert_eq!(packed.row(row)[col], Cell::Live);
+            }
+        }
+    }
+
+    #[test]
+    pub fn proxy_writes() {
+        let mut packed = BitGrid::new(3, 70);
+        packed.set(1, 65, Cell::Live);
+        assert_eq!(packed.row_words(1), Some(&[0, 2][..]));
+
+        let mut cell = packed.get_cell_mut(1, 65).unwrap();
+        assert!(cell.is_live());
+        cell.set(!*cell);
+        assert_eq!(packed.population(), 0);
+
+        assert!(packed.get_cell_mut(3, 0).is_none());
+        assert_eq!(packed.get_cell(0, 70), None);
+        assert_eq!(packed.row(2).iter().len(), 70);
+    }
+}
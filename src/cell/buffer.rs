@@ -0,0 +1,233 @@
+use std::alloc::{self, Layout};
+use std::cmp::Ordering;
+use std::fmt::{self, Debug, Formatter};
+use std::hash::{Hash, Hasher};
+use std::mem::MaybeUninit;
+use std::ops::{Deref, DerefMut};
+use std::ptr::NonNull;
+
+use super::Cell;
+
+/// Alignment of cell buffers, the size of a cache line on most processors.
+pub const CACHE_LINE: usize = 64;
+
+/// The cells of a [`Grid`](super::Grid), in a buffer aligned to [`CACHE_LINE`].
+///
+/// It works like a `Box<[Cell]>`, except that the first cell starts a cache line, so rows with a
+/// multiple of 64 cells never share a line with each other and can be loaded with aligned SIMD
+/// instructions.
+pub(crate) struct CellBuffer {
+    ptr: NonNull<Cell>,
+    len: usize,
+}
+
+// SAFETY: the buffer owns its cells, like a `Box<[Cell]>`
+unsafe impl Send for CellBuffer {}
+// SAFETY: shared references only read the cells
+unsafe impl Sync for CellBuffer {}
+
+impl CellBuffer {
+    #[must_use]
+    /// Layout of a buffer with `len` cells, or [`None`] for empty buffers, which don't allocate.
+    fn layout(len: usize) -> Option<Layout> {
+        (len > 0).then(|| Layout::from_size_align(len, CACHE_LINE).expect("buffer too large"))
+    }
+
+    #[must_use]
+    /// Allocates a buffer of `len` copies of `cell`.
+    pub fn new(len: usize, cell: Cell) -> Self {
+        let Some(layout) = Self::layout(len) else {
+            return Self::empty();
+        };
+
+        // SAFETY: the layout has non-zero size, and dead cells are zero bytes
+        let ptr = unsafe {
+            match cell {
+                Cell::Dead => alloc::alloc_zeroed(layout),
+                Cell::Live => {
+                    let ptr = alloc::alloc(layout);
+                    if !ptr.is_null() {
+                        ptr.write_bytes(u8::from(cell), len);
+                    }
+                    ptr
+                },
+            }
+        };
+        let ptr = NonNull::new(ptr.cast::<Cell>()).unwrap_or_else(|| alloc::handle_alloc_error(layout));
+        Self { ptr, len }
+    }
+
+    #[inline]
+    #[must_use]
+    /// A buffer without cells.
+    pub const fn empty() -> Self {
+        // an aligned address that is never dereferenced
+        let ptr = CACHE_LINE as *mut Cell;
+        // SAFETY: the address is not zero
+        Self { ptr: unsafe { NonNull::new_unchecked(ptr) }, len: 0 }
+    }
+
+    /// Allocates a buffer of `len` cells and lets `init` write them.
+    ///
+    /// This keeps the pages untouched until `init` writes to them, so they are placed on the NUMA
+    /// node of the threads that first touch them.
+    ///
+    /// # Safety
+    ///
+    /// `init` must initialize every cell.
+    pub unsafe fn init_with(len: usize, init: impl FnOnce(&mut [MaybeUninit<Cell>])) -> Self {
+        let Some(layout) = Self::layout(len) else {
+            return Self::empty();
+        };
+
+        // SAFETY: the layout has non-zero size
+        let ptr = unsafe { alloc::alloc(layout) };
+        let ptr = NonNull::new(ptr.cast::<Cell>()).unwrap_or_else(|| alloc::handle_alloc_error(layout));
+        // SAFETY: the allocation has room for `len` cells, which may be uninitialized
+        init(unsafe { std::slice::from_raw_parts_mut(ptr.as_ptr().cast(), len) });
+        Self { ptr, len }
+    }
+
+    #[inline]
+    #[must_use]
+    /// Number of cells in the buffer.
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    #[inline]
+    #[must_use]
+    /// The cells of the buffer.
+    pub const fn as_slice(&self) -> &[Cell] {
+        // SAFETY: the pointer is aligned, and valid for `len` initialized cells
+        unsafe { std::slice::from_raw_parts(self.ptr.as_ptr(), self.len) }
+    }
+
+    #[inline]
+    #[must_use]
+    /// The cells of the buffer, mutably.
+    pub fn as_mut_slice(&mut self) -> &mut [Cell] {
+        // SAFETY: the pointer is aligned, valid for `len` initialized cells, and not aliased
+        unsafe { std::slice::from_raw_parts_mut(self.ptr.as_ptr(), self.len) }
+    }
+}
+
+impl Drop for CellBuffer {
+    fn drop(&mut self) {
+        if let Some(layout) = Self::layout(self.len) {
+            // SAFETY: the buffer was allocated with the same layout
+            unsafe { alloc::dealloc(self.ptr.as_ptr().cast(), layout) }
+        }
+    }
+}
+
+impl From<&[Cell]> for CellBuffer {
+    fn from(cells: &[Cell]) -> Self {
+        // SAFETY: every cell is copied from the slice
+        unsafe {
+            Self::init_with(cells.len(), |buffer| {
+                for (cell, &value) in buffer.iter_mut().zip(cells) {
+                    cell.write(value);
+                }
+            })
+        }
+    }
+}
+
+impl FromIterator<Cell> for CellBuffer {
+    #[inline]
+    fn from_iter<I: IntoIterator<Item = Cell>>(cells: I) -> Self {
+        Self::from(&cells.into_iter().collect::<Vec<_>>()[..])
+    }
+}
+
+impl Deref for CellBuffer {
+    type Target = [Cell];
+
+    #[inline]
+    fn deref(&self) -> &[Cell] {
+        self.as_slice()
+    }
+}
+
+impl DerefMut for CellBuffer {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut [Cell] {
+        self.as_mut_slice()
+    }
+}
+
+impl Clone for CellBuffer {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self::from(self.as_slice())
+    }
+
+    fn clone_from(&mut self, source: &Self) {
+        if self.len == source.len {
+            self.copy_from_slice(source);
+        } else {
+            *self = source.clone();
+        }
+    }
+}
+
+impl Debug for CellBuffer {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        self.as_slice().fmt(f)
+    }
+}
+
+impl PartialEq for CellBuffer {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.as_slice() == other.as_slice()
+    }
+}
+
+impl Eq for CellBuffer {}
+
+impl PartialOrd for CellBuffer {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for CellBuffer {
+    #[inline]
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.as_slice().cmp(other.as_slice())
+    }
+}
+
+impl Hash for CellBuffer {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_slice().hash(state);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn aligned_buffers() {
+        for len in [0, 1, 63, 64, 1000] {
+            for cell in [Cell::Dead, Cell::Live] {
+                let buffer = CellBuffer::new(len, cell);
+                assert_eq!(buffer.as_ptr() as usize % CACHE_LINE, 0);
+                assert_eq!(buffer.len(), len);
+                assert!(buffer.iter().all(|&value| value == cell));
+
+                let mut copy = buffer.clone();
+                assert_eq!(copy, buffer);
+                assert_eq!(copy.as_ptr() as usize % CACHE_LINE, 0);
+                copy.clone_from(&CellBuffer::new(len, !cell));
+                assert!(copy.iter().all(|&value| value != cell));
+            }
+        }
+        assert_eq!(CellBuffer::from(&[Cell::Live, Cell::Dead][..]).as_slice(), [Cell::Live, Cell::Dead]);
+    }
+}
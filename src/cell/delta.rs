@@ -0,0 +1,173 @@
+use crate::error::Error;
+
+use super::{Cell, Grid};
+
+/// The cells that changed between two grids of the same shape, from [`Grid::diff`].
+///
+/// Only the positions of the births and deaths are kept, which is much smaller than a grid after
+/// the first few generations of most patterns. Applying a delta sets the cells to their new
+/// state instead of flipping them, so applying it twice does nothing the second time.
+///
+/// # Example
+///
+/// ```
+/// # use vida::cell::{Cell, Grid};
+/// # use vida::engine::{Engine, SerialEngine};
+/// #
+/// let mut blinker = Grid::new(5, 5);
+/// blinker[2][1..4].fill(Cell::Live);
+/// let next = SerialEngine::default().update(&blinker);
+///
+/// let delta = blinker.diff(&next);
+/// assert_eq!(delta.births(), [(1, 2), (3, 2)]);
+/// assert_eq!(delta.deaths(), [(2, 1), (2, 3)]);
+///
+/// blinker.apply(&delta);
+/// assert_eq!(blinker, next);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct GridDelta {
+    rows: usize,
+    columns: usize,
+    /// Positions that became live, in row-major order.
+    births: Vec<(usize, usize)>,
+    /// Positions that became dead, in row-major order.
+    deaths: Vec<(usize, usize)>,
+}
+
+impl GridDelta {
+    #[inline]
+    #[must_use]
+    /// The shape `(rows, columns)` of the grids compared.
+    pub const fn shape(&self) -> (usize, usize) {
+        (self.rows, self.columns)
+    }
+
+    #[inline]
+    #[must_use]
+    /// Positions `(row, col)` of the cells that became live, in row-major order.
+    pub fn births(&self) -> &[(usize, usize)] {
+        &self.births
+    }
+
+    #[inline]
+    #[must_use]
+    /// Positions `(row, col)` of the cells that died, in row-major order.
+    pub fn deaths(&self) -> &[(usize, usize)] {
+        &self.deaths
+    }
+
+    #[inline]
+    /// Positions of every cell that changed, the births and then the deaths.
+    pub fn changed(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.births.iter().chain(&self.deaths).copied()
+    }
+
+    #[inline]
+    #[must_use]
+    /// Number of cells that changed.
+    pub fn len(&self) -> usize {
+        self.births.len() + self.deaths.len()
+    }
+
+    #[inline]
+    #[must_use]
+    /// Checks if both grids were the same.
+    pub fn is_empty(&self) -> bool {
+        self.births.is_empty() && self.deaths.is_empty()
+    }
+
+    /// Frees the memory left unused while the delta was built.
+    pub fn shrink_to_fit(&mut self) {
+        self.births.shrink_to_fit();
+        self.deaths.shrink_to_fit();
+    }
+
+    #[must_use]
+    /// The delta that undoes this one, from the newer grid back to the older.
+    pub fn reversed(&self) -> Self {
+        Self { births: self.deaths.clone(), deaths: self.births.clone(), ..*self }
+    }
+}
+
+impl Grid {
+    #[must_use]
+    /// The cells that change from this grid to `next`.
+    ///
+    /// # Panics
+    ///
+    /// If the grids have different shapes, see [`try_diff`](Grid::try_diff).
+    pub fn diff(&self, next: &Self) -> GridDelta {
+        self.try_diff(next).unwrap_or_else(|error| panic!("{error}"))
+    }
+
+    /// Like [`diff`](Grid::diff), but fails with [`Error::DimensionMismatch`] if the grids have
+    /// different shapes.
+    pub fn try_diff(&self, next: &Self) -> Result<GridDelta, Error> {
+        if self.shape() != next.shape() {
+            return Err(Error::DimensionMismatch { expected: self.shape(), found: next.shape() });
+        }
+
+        let (rows, columns) = self.shape();
+        let mut delta = GridDelta { rows, columns, births: Vec::new(), deaths: Vec::new() };
+
+        let changed = self.flat().iter().zip(next.flat()).enumerate().filter(|(_, (old, new))| old != new);
+        for (index, (_, &new)) in changed {
+            let position = (index / columns, index % columns);
+            match new {
+                Cell::Live => delta.births.push(position),
+                Cell::Dead => delta.deaths.push(position),
+            }
+        }
+        Ok(delta)
+    }
+
+    /// Sets the cells changed in a delta to their new state.
+    ///
+    /// # Panics
+    ///
+    /// If the delta was made for grids of another shape.
+    pub fn apply(&mut self, delta: &GridDelta) {
+        assert_eq!(self.shape(), delta.shape(), "delta made for grids of another shape");
+
+        for &position in &delta.births {
+            self[position] = Cell::Live;
+        }
+        for &position in &delta.deaths {
+            self[position] = Cell::Dead;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::engine::{Engine, SerialEngine};
+
+    use super::*;
+
+    #[test]
+    pub fn diff_and_apply() {
+        let mut grid = Grid::random_seeded(30, 40, 546);
+        for _ in 0..10 {
+            let next = SerialEngine::default().update(&grid);
+            let delta = grid.diff(&next);
+            assert_eq!(delta.len(), grid.flat().iter().zip(next.flat()).filter(|(old, new)| old != new).count());
+            assert_eq!(grid.population() + delta.births().len() - delta.deaths().len(), next.population());
+            assert!(delta.changed().all(|position| grid[position] != next[position]));
+
+            let previous = grid.clone();
+            grid.apply(&delta);
+            assert_eq!(grid, next);
+            // applying twice changes nothing
+            grid.apply(&delta);
+            assert_eq!(grid, next);
+
+            grid.apply(&delta.reversed());
+            assert_eq!(grid, previous);
+            grid = next;
+        }
+
+        assert!(grid.diff(&grid).is_empty());
+        assert_eq!(Grid::empty().diff(&Grid::empty()), GridDelta::default());
+    }
+}
@@ -1,23 +1,72 @@
 use std::fmt::{self, Display, Formatter, Write};
 use std::ops::{Index, IndexMut};
+use std::str::FromStr;
 
 use rand::{Fill, Rng, SeedableRng};
 use rand::rngs::SmallRng;
 
-use rayon::iter::IntoParallelIterator;
-use rayon::slice::{ParallelSlice, ParallelSliceMut};
+use crate::error::Error;
+use crate::par::*;
 
-use super::Cell;
+use super::{Cell, CellBuffer};
 
 pub type Iter<'a> =  std::slice::ChunksExact<'a, Cell>;
 pub type IterMut<'a> = std::slice::ChunksExactMut<'a, Cell>;
+#[cfg(feature = "parallel")]
 pub type ParIter<'a> = rayon::slice::ChunksExact<'a, Cell>;
+#[cfg(feature = "parallel")]
 pub type ParIterMut<'a> = rayon::slice::ChunksExactMut<'a, Cell>;
 
+/// What [`Grid::blit`] does with the cells of a pattern that fall outside of the grid.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+pub enum Overflow {
+    /// Leaves them out.
+    #[default]
+    Clip,
+    /// Fails with [`Error::OutOfBounds`], without changing the grid.
+    Error,
+    /// Wraps them around to the opposite edges, as in a torus.
+    Wrap,
+}
+
+/// Where [`Grid::resized`] keeps the existing cells.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+pub enum Anchor {
+    TopLeft,
+    Top,
+    TopRight,
+    Left,
+    #[default]
+    Center,
+    Right,
+    BottomLeft,
+    Bottom,
+    BottomRight,
+}
+
+impl Anchor {
+    /// Vertical and horizontal alignment, each 0 for the start, 1 for the middle or 2 for the end.
+    const fn alignment(self) -> (usize, usize) {
+        match self {
+            Self::TopLeft => (0, 0),
+            Self::Top => (0, 1),
+            Self::TopRight => (0, 2),
+            Self::Left => (1, 0),
+            Self::Center => (1, 1),
+            Self::Right => (1, 2),
+            Self::BottomLeft => (2, 0),
+            Self::Bottom => (2, 1),
+            Self::BottomRight => (2, 2),
+        }
+    }
+}
+
 /// A 2D matrix representing the current state in Conway's Game of Life.
+///
+/// The cells are stored in row-major order, starting at a cache line boundary.
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Grid {
-    cells: Box<[Cell]>,
+    cells: CellBuffer,
     columns: usize,
 }
 
@@ -43,7 +92,15 @@ impl Grid {
     pub fn new_with(rows: usize, columns: usize, cell: Cell) -> Self {
         let cells = rows.checked_mul(columns).expect("number of cells overflows usize");
 
-        Self { cells: vec![cell; cells].into(), columns }
+        Self { cells: CellBuffer::new(cells, cell), columns }
+    }
+
+    #[inline]
+    #[must_use]
+    /// Like [`new`](Grid::new), but returns [`None`] if `rows * columns` overflows an `usize`.
+    pub fn try_new(rows: usize, columns: usize) -> Option<Self> {
+        let cells = rows.checked_mul(columns)?;
+        Some(Self { cells: CellBuffer::new(cells, Cell::default()), columns })
     }
 
     #[inline]
@@ -53,6 +110,23 @@ impl Grid {
         Self::random_with(rows, columns, &mut rng)
     }
 
+    #[inline]
+    #[must_use]
+    /// Creates a random grid that is always the same for the same `seed`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use vida::cell::Grid;
+    /// #
+    /// assert_eq!(Grid::random_seeded(30, 40, 516), Grid::random_seeded(30, 40, 516));
+    /// assert_ne!(Grid::random_seeded(30, 40, 516), Grid::random_seeded(30, 40, 517));
+    /// ```
+    pub fn random_seeded(rows: usize, columns: usize, seed: u64) -> Self {
+        let mut rng = SmallRng::seed_from_u64(seed);
+        Self::random_with(rows, columns, &mut rng)
+    }
+
     #[inline]
     #[must_use]
     pub fn random_with<R: Rng + ?Sized>(rows: usize, columns: usize, rng: &mut R) -> Self {
@@ -62,6 +136,35 @@ impl Grid {
         grid
     }
 
+    #[must_use]
+    /// Creates a random grid where each cell is live with probability `density`.
+    ///
+    /// # Panics
+    ///
+    /// If `density` is not between 0 and 1.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use vida::cell::Grid;
+    /// # use rand::SeedableRng;
+    /// # use rand::rngs::SmallRng;
+    /// #
+    /// let grid = Grid::random_with_density(100, 100, 0.05, &mut SmallRng::seed_from_u64(517));
+    ///
+    /// assert!((400..600).contains(&grid.population()));
+    /// ```
+    pub fn random_with_density<R: Rng + ?Sized>(rows: usize, columns: usize, density: f64, rng: &mut R) -> Self {
+        let mut grid = Self::new(rows, columns);
+        for cell in grid.cells.iter_mut() {
+            if rng.gen_bool(density) {
+                *cell = Cell::Live;
+            }
+        }
+
+        grid
+    }
+
     #[inline]
     #[must_use]
     /// Creates an empty grid.
@@ -69,10 +172,9 @@ impl Grid {
         Self::new(0, 0)
     }
 
-    #[must_use]
     /// Creates a grid from a collection of cell slices.
     ///
-    /// Returns [`None`] when the slices have different lengths.
+    /// Fails with [`Error::DimensionMismatch`] when the slices have different lengths.
     ///
     /// # Example
     ///
@@ -89,7 +191,7 @@ impl Grid {
     /// assert_eq!(grid[(1, 2)], Cell::Live);
     /// assert_eq!(grid[1][2], Cell::Live);
     /// ```
-    pub fn try_from<T: AsRef<[Cell]>>(grid: impl IntoIterator<Item = T>) -> Option<Self> {
+    pub fn try_from<T: AsRef<[Cell]>>(grid: impl IntoIterator<Item = T>) -> Result<Self, Error> {
         let mut grid = grid.into_iter().peekable();
 
         let (expected_rows, _) = grid.size_hint();
@@ -99,13 +201,50 @@ impl Grid {
 
         for row in grid {
             if row.as_ref().len() != columns {
-                return None
+                return Err(Error::DimensionMismatch { expected: (1, columns), found: (1, row.as_ref().len()) })
             }
 
             cells.extend_from_slice(row.as_ref())
         };
 
-        Some(Grid { cells: cells.into(), columns })
+        Ok(Grid { cells: CellBuffer::from(&cells[..]), columns })
+    }
+
+    #[must_use]
+    /// A `rows` by `columns` grid with only the cells at the given `(row, col)` positions alive.
+    ///
+    /// # Panics
+    ///
+    /// If a position is outside of the grid, see [`try_from_live_cells`](Grid::try_from_live_cells).
+    pub fn from_live_cells(rows: usize, columns: usize, positions: impl IntoIterator<Item = (usize, usize)>) -> Self {
+        Self::try_from_live_cells(rows, columns, positions).unwrap_or_else(|error| panic!("{error}"))
+    }
+
+    /// Like [`from_live_cells`](Grid::from_live_cells), but fails with [`Error::OutOfBounds`] for
+    /// the first position outside of the grid.
+    pub fn try_from_live_cells(rows: usize, columns: usize, positions: impl IntoIterator<Item = (usize, usize)>) -> Result<Self, Error> {
+        let mut grid = Self::new(rows, columns);
+        for (row, col) in positions {
+            match grid.get_cell_mut(row, col) {
+                Some(cell) => *cell = Cell::Live,
+                None => return Err(Error::OutOfBounds { position: (row, col), shape: grid.shape() }),
+            }
+        }
+        Ok(grid)
+    }
+
+    #[inline]
+    #[must_use]
+    #[cfg(any(feature = "parallel", feature = "continuous"))]
+    /// Wraps an already filled buffer of cells, in row-major order.
+    ///
+    /// # Panics
+    ///
+    /// If the buffer length is not a multiple of `columns`.
+    pub(crate) fn from_cells(cells: CellBuffer, columns: usize) -> Self {
+        assert!(cells.len() % columns.max(1) == 0, "buffer with incomplete rows");
+
+        Self { cells, columns }
     }
 
     #[inline]
@@ -140,18 +279,68 @@ impl Grid {
         self.cells.len()
     }
 
+    #[must_use]
+    /// The number of live cells, counted in parallel with the `parallel` feature.
+    pub fn population(&self) -> usize {
+        self.cells.par_iter().filter(|cell| cell.is_live()).count()
+    }
+
+    #[must_use]
+    /// Smallest rectangle `(top, left, bottom, right)` containing all live cells, inclusive.
+    ///
+    /// Each row is scanned in parallel with the `parallel` feature. Returns [`None`] when there
+    /// are no live cells.
+    pub fn bounding_box(&self) -> Option<(usize, usize, usize, usize)> {
+        let spans: Vec<_> = self.cells.par_chunks_exact(self.columns.max(1))
+            .map(|cells| Some((cells.iter().position(|cell| cell.is_live())?, cells.iter().rposition(|cell| cell.is_live())?)))
+            .collect();
+
+        let top = spans.iter().position(Option::is_some)?;
+        let bottom = spans.iter().rposition(Option::is_some)?;
+        let left = spans.iter().flatten().map(|&(first, _)| first).min()?;
+        let right = spans.iter().flatten().map(|&(_, last)| last).max()?;
+        Some((top, left, bottom, right))
+    }
+
+    #[must_use]
+    /// A copy of the smallest rectangle containing all live cells, or an empty grid if there are
+    /// none.
+    pub fn trim(&self) -> Self {
+        match self.bounding_box() {
+            Some((top, left, bottom, right)) => self.crop(top..=bottom, left..=right),
+            None => Self::empty(),
+        }
+    }
+
     #[inline]
     #[must_use]
     /// A slice over all the cells in the grid, row-major order.
     pub const fn flat(&self) -> &[Cell] {
-        &self.cells
+        self.cells.as_slice()
+    }
+
+    #[inline]
+    #[must_use]
+    /// The cells in row-major order as bytes, `0` for dead and `1` for live, without copying.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use vida::cell::{Cell, Grid};
+    /// #
+    /// let grid = Grid::from([[Cell::Dead, Cell::Live], [Cell::Live, Cell::Live]]);
+    /// assert_eq!(grid.as_bytes(), [0, 1, 1, 1]);
+    /// ```
+    pub fn as_bytes(&self) -> &[u8] {
+        // SAFETY: `Cell` is `repr(u8)`, so a slice of cells has the same layout as the bytes
+        unsafe { std::slice::from_raw_parts(self.cells.as_ptr().cast(), self.cells.len()) }
     }
 
     #[inline]
     #[must_use]
     /// A mutable slice over all the cells in the grid, row-major order.
     pub fn flat_mut(&mut self) -> &mut [Cell] {
-        &mut self.cells
+        self.cells.as_mut_slice()
     }
 
     #[inline]
@@ -255,13 +444,262 @@ impl Grid {
     pub fn iter_mut(&mut self) -> IterMut<'_> {
         self.into_iter()
     }
+
+    #[inline]
+    #[cfg(feature = "parallel")]
+    /// A parallel iterator over every cell as `(row, col, &cell)`.
+    ///
+    /// Unlike nesting [`par_iter`](rayon::iter::IntoParallelRefIterator::par_iter) over rows and
+    /// then over cells, this is a single indexed iterator over the whole buffer, so rayon can split
+    /// the work evenly regardless of the grid shape.
+    pub fn par_iter_cells(&self) -> impl IndexedParallelIterator<Item = (usize, usize, &Cell)> + '_ {
+        let columns = self.columns;
+        self.cells.par_iter().enumerate().map(move |(index, cell)| (index / columns, index % columns, cell))
+    }
+
+    #[inline]
+    #[cfg(feature = "parallel")]
+    /// A parallel iterator over every cell as `(row, col, &mut cell)`.
+    ///
+    /// See [`Grid::par_iter_cells`].
+    pub fn par_iter_cells_mut(&mut self) -> impl IndexedParallelIterator<Item = (usize, usize, &mut Cell)> + '_ {
+        let columns = self.columns;
+        self.cells.par_iter_mut().enumerate().map(move |(index, cell)| (index / columns, index % columns, cell))
+    }
+
+    #[inline]
+    /// The `(row, col)` position of every live cell, in row-major order.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use vida::cell::Grid;
+    /// #
+    /// let glider = Grid::from_live_cells(3, 3, [(0, 1), (1, 2), (2, 0), (2, 1), (2, 2)]);
+    /// assert_eq!(glider.live_cells().nth(2), Some((2, 0)));
+    /// assert_eq!(Grid::from_live_cells(3, 3, glider.live_cells()), glider);
+    /// ```
+    pub fn live_cells(&self) -> impl DoubleEndedIterator<Item = (usize, usize)> + '_ {
+        let columns = self.columns;
+        self.cells.iter().enumerate().filter(|(_, cell)| cell.is_live()).map(move |(index, _)| (index / columns, index % columns))
+    }
+
+    #[inline]
+    #[cfg(feature = "parallel")]
+    /// A parallel iterator over the `(row, col)` position of every live cell.
+    ///
+    /// See [`Grid::live_cells`].
+    pub fn par_live_cells(&self) -> impl ParallelIterator<Item = (usize, usize)> + '_ {
+        self.par_iter_cells().filter(|(_, _, cell)| cell.is_live()).map(|(row, col, _)| (row, col))
+    }
+
+    #[must_use]
+    /// The cells in row-major order, packed eight per byte, with cell `i` in bit `i % 8` of byte
+    /// `i / 8`.
+    pub(crate) fn pack_bits(&self) -> Vec<u8> {
+        self.cells.chunks(8)
+            .map(|cells| cells.iter().enumerate().fold(0, |byte, (i, cell)| byte | (u8::from(cell.is_live()) << i)))
+            .collect()
+    }
+
+    #[must_use]
+    /// The inverse of [`pack_bits`](Grid::pack_bits), or [`None`] if there isn't exactly one
+    /// byte for every 8 cells.
+    pub(crate) fn from_packed_bits(rows: usize, columns: usize, bytes: &[u8]) -> Option<Self> {
+        let cells = rows.checked_mul(columns)?;
+        if bytes.len() != (cells + 7) / 8 {
+            return None;
+        }
+
+        let mut grid = Self::new(rows, columns);
+        for (index, cell) in grid.cells.iter_mut().enumerate() {
+            if bytes[index / 8] & (1 << (index % 8)) != 0 {
+                *cell = Cell::Live;
+            }
+        }
+        Some(grid)
+    }
+
+    #[must_use]
+    /// A larger grid of `(rows, columns)` dead cells, with this one copied at `(top, left)`.
+    ///
+    /// # Panics
+    ///
+    /// If this grid doesn't fit in the new one at that position, see [`try_expanded`](Grid::try_expanded).
+    pub fn expanded(&self, position: (usize, usize), shape: (usize, usize)) -> Self {
+        self.try_expanded(position, shape).unwrap_or_else(|error| panic!("{error}"))
+    }
+
+    /// Like [`expanded`](Grid::expanded), but fails with [`Error::DimensionMismatch`] if this grid
+    /// doesn't fit in the new one at that position.
+    pub fn try_expanded(&self, (top, left): (usize, usize), (rows, columns): (usize, usize)) -> Result<Self, Error> {
+        if top + self.rows() > rows || left + self.columns > columns {
+            return Err(Error::DimensionMismatch { expected: (rows, columns), found: (top + self.rows(), left + self.columns) });
+        }
+
+        let mut grid = Self::new(rows, columns);
+        for (cells, row) in self.iter().zip(top..) {
+            grid[row][left..left + cells.len()].copy_from_slice(cells);
+        }
+        Ok(grid)
+    }
+
+    /// Copies every cell of `pattern` into this grid, with its top-left corner at `(row, col)`.
+    ///
+    /// Dead cells of the pattern are copied too, so the whole rectangle under it is replaced.
+    /// Cells that fall outside of this grid are handled as chosen by `overflow`. When wrapping a
+    /// pattern larger than the grid, its later cells overwrite the earlier ones.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use vida::cell::{Cell, Grid, Overflow};
+    /// #
+    /// let mut board = Grid::new(3, 3);
+    /// let domino = Grid::from([[Cell::Live, Cell::Live]]);
+    ///
+    /// board.blit(&domino, 0, 2, Overflow::Wrap).unwrap();
+    /// assert!(board[(0, 2)].is_live() && board[(0, 0)].is_live());
+    /// assert!(board.blit(&domino, 1, 2, Overflow::Error).is_err());
+    /// ```
+    pub fn blit(&mut self, pattern: &Grid, row: usize, col: usize, overflow: Overflow) -> Result<(), Error> {
+        let (rows, columns) = pattern.shape();
+        if rows == 0 || columns == 0 {
+            return Ok(())
+        }
+
+        match overflow {
+            Overflow::Clip => {
+                let end = col.saturating_add(columns).min(self.columns);
+                for (cells, row) in pattern.iter().zip(row..self.rows()) {
+                    if let Some(target) = self[row].get_mut(col..end) {
+                        target.copy_from_slice(&cells[..end - col]);
+                    }
+                }
+            }
+            Overflow::Error => {
+                let bottom = row.checked_add(rows - 1).filter(|&bottom| bottom < self.rows());
+                let right = col.checked_add(columns - 1).filter(|&right| right < self.columns);
+                if bottom.is_none() || right.is_none() {
+                    let position = (row.saturating_add(rows - 1), col.saturating_add(columns - 1));
+                    return Err(Error::OutOfBounds { position, shape: self.shape() });
+                }
+                for (cells, row) in pattern.iter().zip(row..) {
+                    self[row][col..col + columns].copy_from_slice(cells);
+                }
+            }
+            Overflow::Wrap => {
+                let (height, width) = self.shape();
+                if height == 0 || width == 0 {
+                    return Ok(())
+                }
+                for (offset, cells) in pattern.iter().enumerate() {
+                    let target = (row % height + offset % height) % height;
+                    for (offset, &cell) in cells.iter().enumerate() {
+                        self[(target, (col % width + offset % width) % width)] = cell;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    #[must_use]
+    /// A grid of `(rows, columns)` cells with the existing ones kept at `anchor`.
+    ///
+    /// Growing adds dead cells on the sides away from the anchor, and shrinking cuts the cells on
+    /// those sides. With [`Anchor::Center`], an odd difference puts the extra row or column at the
+    /// bottom or right.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use vida::cell::{Anchor, Cell, Grid};
+    /// #
+    /// let mut grid = Grid::new(2, 2);
+    /// grid[(1, 1)] = Cell::Live;
+    ///
+    /// assert!(grid.resized(4, 4, Anchor::Center)[(2, 2)].is_live());
+    /// assert!(grid.resized(3, 5, Anchor::BottomRight)[(2, 4)].is_live());
+    /// assert_eq!(grid.resized(1, 1, Anchor::TopLeft).population(), 0);
+    /// ```
+    pub fn resized(&self, rows: usize, columns: usize, anchor: Anchor) -> Self {
+        // the first source and target positions on an axis, and how many cells are kept
+        let place = |old: usize, new: usize, alignment: usize| {
+            let kept = old.min(new);
+            let offset = (old.max(new) - kept) * alignment / 2;
+            if new >= old { (0, offset, kept) } else { (offset, 0, kept) }
+        };
+        let (vertical, horizontal) = anchor.alignment();
+        let (source_row, target_row, height) = place(self.rows(), rows, vertical);
+        let (source_col, target_col, width) = place(self.columns, columns, horizontal);
+
+        let mut grid = Self::new(rows, columns);
+        if width > 0 {
+            for row in 0..height {
+                let cells = &self[source_row + row][source_col..source_col + width];
+                grid[target_row + row][target_col..target_col + width].copy_from_slice(cells);
+            }
+        }
+        grid
+    }
+
+    #[must_use]
+    /// A grid of the same shape with every cell moved `dy` rows down and `dx` columns right.
+    ///
+    /// Negative offsets move up and left. Cells moved past an edge come back on the opposite one
+    /// with `wrap`, as in a torus, or are left out otherwise.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use vida::cell::{Cell, Grid};
+    /// #
+    /// let grid = Grid::from([[Cell::Live, Cell::Dead, Cell::Dead]]);
+    ///
+    /// assert_eq!(grid.translated(0, -1, true), Grid::from([[Cell::Dead, Cell::Dead, Cell::Live]]));
+    /// assert_eq!(grid.translated(0, -1, false).population(), 0);
+    /// ```
+    pub fn translated(&self, dy: isize, dx: isize, wrap: bool) -> Self {
+        let (rows, columns) = self.shape();
+        let mut grid = Self::new(rows, columns);
+        if rows == 0 || columns == 0 {
+            return grid;
+        }
+
+        if wrap {
+            let shift = dx.rem_euclid(columns as isize) as usize;
+            for (row, cells) in self.iter().enumerate() {
+                let target = &mut grid[(row as isize + dy).rem_euclid(rows as isize) as usize];
+                target.copy_from_slice(cells);
+                target.rotate_right(shift);
+            }
+            return grid;
+        }
+
+        // the columns that stay in the grid, before and after moving
+        let width = columns.saturating_sub(dx.unsigned_abs());
+        let (source, target) = if dx >= 0 { (0, columns - width) } else { (columns - width, 0) };
+        for (row, cells) in self.iter().enumerate() {
+            let moved = row.checked_add_signed(dy).filter(|&moved| moved < rows);
+            if let Some(moved) = moved {
+                grid[moved][target..target + width].copy_from_slice(&cells[source..source + width]);
+            }
+        }
+        grid
+    }
+
+    #[cold]
+    /// Panics for a position outside of the grid.
+    fn out_of_bounds(&self, position: (usize, usize)) -> ! {
+        panic!("{}", Error::OutOfBounds { position, shape: self.shape() })
+    }
 }
 
 impl<T: AsRef<[Cell]>, I: IntoIterator<Item = T>> From<I> for Grid {
     #[inline]
-    #[must_use]
     fn from(rows: I) -> Self {
-        Grid::try_from(rows).expect("rows with different lengths")
+        Grid::try_from(rows).unwrap_or_else(|error| panic!("{error}"))
     }
 }
 
@@ -269,17 +707,18 @@ impl Index<usize> for Grid {
     type Output = [Cell];
 
     #[inline]
-    #[must_use]
     fn index(&self, row: usize) -> &[Cell] {
-        self.get(row).expect("row out of bounds")
+        self.get(row).unwrap_or_else(|| self.out_of_bounds((row, 0)))
     }
 }
 
 impl IndexMut<usize> for Grid {
     #[inline]
-    #[must_use]
     fn index_mut(&mut self, row: usize) -> &mut [Cell] {
-        self.get_mut(row).expect("row out of bounds")
+        if row >= self.rows() {
+            self.out_of_bounds((row, 0))
+        }
+        &mut self.cells[row * self.columns..(row + 1) * self.columns]
     }
 }
 
@@ -287,17 +726,18 @@ impl Index<(usize, usize)> for Grid {
     type Output = Cell;
 
     #[inline]
-    #[must_use]
     fn index(&self, (row, col): (usize, usize)) -> &Cell {
-        &self[row][col]
+        self.get_cell(row, col).unwrap_or_else(|| self.out_of_bounds((row, col)))
     }
 }
 
 impl IndexMut<(usize, usize)> for Grid {
     #[inline]
-    #[must_use]
     fn index_mut(&mut self, (row, col): (usize, usize)) -> &mut Cell {
-        &mut self[row][col]
+        if row >= self.rows() || col >= self.columns {
+            self.out_of_bounds((row, col))
+        }
+        &mut self.cells[row * self.columns + col]
     }
 }
 
@@ -306,9 +746,8 @@ impl<'a> IntoIterator for &'a Grid {
     type IntoIter = Iter<'a>;
 
     #[inline]
-    #[must_use]
     fn into_iter(self) -> Self::IntoIter {
-        self.cells.chunks_exact(self.columns)
+        self.cells.chunks_exact(self.columns.max(1))
     }
 }
 
@@ -317,39 +756,40 @@ impl<'a> IntoIterator for &'a mut Grid {
     type IntoIter = IterMut<'a>;
 
     #[inline]
-    #[must_use]
     fn into_iter(self) -> Self::IntoIter {
-        self.cells.chunks_exact_mut(self.columns)
+        self.cells.chunks_exact_mut(self.columns.max(1))
     }
 }
 
+#[cfg(feature = "parallel")]
 impl<'a> IntoParallelIterator for &'a Grid {
     type Item = &'a [Cell];
     type Iter = ParIter<'a>;
 
     #[inline]
-    #[must_use]
     fn into_par_iter(self) -> Self::Iter {
-        self.cells.par_chunks_exact(self.columns)
+        self.cells.par_chunks_exact(self.columns.max(1))
     }
 }
 
+#[cfg(feature = "parallel")]
 impl<'a> IntoParallelIterator for &'a mut Grid {
     type Item = &'a mut [Cell];
     type Iter = ParIterMut<'a>;
 
     #[inline]
-    #[must_use]
     fn into_par_iter(self) -> Self::Iter {
-        self.cells.par_chunks_exact_mut(self.columns)
+        self.cells.par_chunks_exact_mut(self.columns.max(1))
     }
 }
 
 impl Display for Grid {
+    /// Writes one line per row, with `D` and `L` cells, or `.` and `#` in the alternate form
+    /// (`{:#}`).
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         for row in self.iter() {
-            for &cell in row {
-                write!(f, "{cell}")?
+            for cell in row {
+                cell.fmt(f)?
             }
             f.write_char('\n')?
         }
@@ -357,6 +797,37 @@ impl Display for Grid {
     }
 }
 
+impl FromStr for Grid {
+    type Err = Error;
+
+    /// Parses one row per line, in either form written by [`Display`], ignoring blank lines and
+    /// the spaces around each row.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use vida::cell::Grid;
+    /// #
+    /// let blinker: Grid = "
+    ///     .....
+    ///     .###.
+    ///     .....
+    /// ".parse().unwrap();
+    /// assert_eq!(blinker.population(), 3);
+    ///
+    /// let glider: Grid = ".#.\n..#\n###".parse().unwrap();
+    ///
+    /// assert_eq!(format!("{glider:#}"), ".#.\n..#\n###\n");
+    /// assert_eq!(glider.to_string().parse::<Grid>().unwrap(), glider);
+    /// ```
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let rows = text.lines().map(str::trim).filter(|line| !line.is_empty())
+            .map(|line| line.chars().map(Cell::try_from).collect::<Result<Vec<_>, _>>())
+            .collect::<Result<Vec<_>, _>>()?;
+        Self::try_from(rows)
+    }
+}
+
 impl Fill for Grid {
     #[inline]
     fn try_fill<R: Rng + ?Sized>(&mut self, rng: &mut R) -> Result<(), rand::Error> {
@@ -369,7 +840,6 @@ impl Fill for Grid {
 
 impl Default for Grid {
     #[inline]
-    #[must_use]
     fn default() -> Self {
         Self::empty()
     }
@@ -426,6 +896,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "parallel")]
     pub fn parallel_mutation() {
         use rayon::prelude::*;
 
@@ -447,4 +918,175 @@ mod tests {
 
         assert_eq!(dead_cells, grid.cells());
     }
+
+    #[test]
+    #[cfg(feature = "parallel")]
+    pub fn flat_parallel_iteration() {
+        use rayon::prelude::*;
+
+        let mut grid = Grid::new(7, 11);
+        grid.par_iter_cells_mut().for_each(|(row, col, cell)| {
+            if (row + col) % 3 == 0 {
+                *cell = Cell::Live
+            }
+        });
+
+        for (row, col, cell) in grid.par_iter_cells().collect::<Vec<_>>() {
+            assert_eq!(cell, &grid[(row, col)]);
+            assert_eq!(cell.is_live(), (row + col) % 3 == 0);
+        }
+        assert_eq!(grid.par_iter_cells().len(), grid.cells());
+    }
+
+    #[test]
+    pub fn bounding_box() {
+        assert_eq!(Grid::new(5, 5).bounding_box(), None);
+        assert_eq!(Grid::new(5, 5).trim(), Grid::empty());
+        assert_eq!(Grid::empty().bounding_box(), None);
+
+        let grid = Grid::from_live_cells(9, 12, [(2, 7), (3, 4), (6, 9)]);
+        assert_eq!(grid.bounding_box(), Some((2, 4, 6, 9)));
+
+        let trimmed = grid.trim();
+        assert_eq!(trimmed.shape(), (5, 6));
+        assert_eq!(trimmed.population(), 3);
+        assert!(trimmed[(0, 3)].is_live() && trimmed[(1, 0)].is_live() && trimmed[(4, 5)].is_live());
+        assert_eq!(trimmed.trim(), trimmed);
+    }
+
+    #[test]
+    pub fn resizing() {
+        let grid = Grid::random_seeded(5, 6, 562);
+        assert_eq!(grid.resized(5, 6, Anchor::BottomLeft), grid);
+
+        for anchor in [Anchor::TopLeft, Anchor::Center, Anchor::BottomRight, Anchor::Left] {
+            let grown = grid.resized(11, 9, anchor);
+            assert_eq!(grown.shape(), (11, 9));
+            assert_eq!(grown.population(), grid.population());
+            assert_eq!(grown.resized(5, 6, anchor), grid, "{anchor:?}");
+        }
+        assert_eq!(grid.resized(9, 10, Anchor::Center), grid.expanded((2, 2), (9, 10)));
+        assert_eq!(grid.resized(7, 6, Anchor::Bottom), grid.expanded((2, 0), (7, 6)));
+
+        let shrunk = grid.resized(3, 2, Anchor::Center);
+        assert_eq!(shrunk, grid.crop(1..4, 2..4));
+        assert_eq!(grid.resized(3, 2, Anchor::TopRight), grid.crop(0..3, 4..6));
+        assert_eq!(grid.resized(0, 4, Anchor::Center), Grid::new(0, 4));
+        assert_eq!(grid.resized(4, 0, Anchor::Center), Grid::new(4, 0));
+        assert_eq!(Grid::empty().resized(2, 3, Anchor::Center), Grid::new(2, 3));
+    }
+
+    #[test]
+    pub fn translation() {
+        let grid = Grid::random_seeded(7, 9, 563);
+        assert_eq!(grid.translated(0, 0, false), grid);
+        assert_eq!(grid.translated(7, -18, true), grid);
+        assert_eq!(grid.translated(2, -3, true).translated(-2, 3, true), grid);
+        assert_eq!(grid.translated(8, 0, false).population(), 0);
+        assert_eq!(grid.translated(0, isize::MIN, false).population(), 0);
+
+        for (dy, dx) in [(1, 2), (-3, 4), (5, -1), (-2, -8)] {
+            let wrapped = grid.translated(dy, dx, true);
+            let clipped = grid.translated(dy, dx, false);
+            for (row, col) in (0..7).flat_map(|row| (0..9).map(move |col| (row, col))) {
+                let target = ((row as isize + dy).rem_euclid(7) as usize, (col as isize + dx).rem_euclid(9) as usize);
+                assert_eq!(wrapped[target], grid[(row, col)]);
+
+                let inside = (0..7).contains(&(row as isize + dy)) && (0..9).contains(&(col as isize + dx));
+                if inside {
+                    assert_eq!(clipped[target], grid[(row, col)]);
+                }
+            }
+            assert!(clipped.population() <= grid.population());
+        }
+    }
+
+    #[test]
+    pub fn blit_overflow() {
+        let pattern = Grid::random_seeded(3, 4, 559);
+
+        let mut grid = Grid::new_with(6, 6, Cell::Live);
+        grid.blit(&pattern, 1, 2, Overflow::Error).unwrap();
+        for (row, col) in (0..6).flat_map(|row| (0..6).map(move |col| (row, col))) {
+            let inside = (1..4).contains(&row) && (2..6).contains(&col);
+            let expected = if inside { pattern[(row - 1, col - 2)] } else { Cell::Live };
+            assert_eq!(grid[(row, col)], expected, "at ({row}, {col})");
+        }
+
+        let mut grid = Grid::new(6, 6);
+        let error = grid.blit(&pattern, 4, 1, Overflow::Error).unwrap_err();
+        assert_eq!(error.to_string(), "position (6, 4) is outside of a 6x6 grid");
+        assert_eq!(grid, Grid::new(6, 6));
+
+        grid.blit(&pattern, 4, 4, Overflow::Clip).unwrap();
+        for (row, col) in [(4, 4), (4, 5), (5, 4), (5, 5)] {
+            assert_eq!(grid[(row, col)], pattern[(row - 4, col - 4)]);
+        }
+        assert_eq!(grid.population(), [(0, 0), (0, 1), (1, 0), (1, 1)].iter().filter(|&&position| pattern[position].is_live()).count());
+        grid.blit(&pattern, 6, usize::MAX, Overflow::Clip).unwrap();
+
+        let mut grid = Grid::new(6, 6);
+        grid.blit(&pattern, 4, 4, Overflow::Wrap).unwrap();
+        for (row, col) in (0..3).flat_map(|row| (0..4).map(move |col| (row, col))) {
+            assert_eq!(grid[((row + 4) % 6, (col + 4) % 6)], pattern[(row, col)]);
+        }
+        assert_eq!(grid.population(), pattern.population());
+    }
+
+    #[test]
+    pub fn live_cells() {
+        let grid = Grid::random_seeded(11, 7, 565);
+        let positions: Vec<_> = grid.live_cells().collect();
+        assert_eq!(positions.len(), grid.population());
+        assert!(positions.iter().all(|&position| grid[position].is_live()));
+        assert!(positions.windows(2).all(|pair| pair[0] < pair[1]));
+        assert_eq!(Grid::from_live_cells(11, 7, positions.iter().copied()), grid);
+
+        #[cfg(feature = "parallel")]
+        assert_eq!(grid.par_live_cells().collect::<Vec<_>>(), positions);
+
+        assert_eq!(Grid::new(4, 4).live_cells().next(), None);
+        let error = Grid::try_from_live_cells(4, 4, [(1, 1), (2, 4)]).unwrap_err();
+        assert_eq!(error.to_string(), "position (2, 4) is outside of a 4x4 grid");
+    }
+
+    #[test]
+    pub fn parse_and_display() {
+        let grid = Grid::random_seeded(6, 8, 567);
+        assert_eq!(grid.to_string().parse::<Grid>().unwrap(), grid);
+        assert_eq!(format!("{grid:#}").parse::<Grid>().unwrap(), grid);
+        assert_eq!(format!("{grid:#}"), grid.to_string().replace('D', ".").replace('L', "#"));
+
+        let blinker: Grid = "
+            .....
+            .###.
+            .....
+        ".parse().unwrap();
+        assert_eq!(blinker, Grid::from_live_cells(3, 5, [(1, 1), (1, 2), (1, 3)]));
+        assert_eq!(format!("{:#}", blinker.view(1..2, 1..)), "###.\n");
+        assert_eq!("".parse::<Grid>().unwrap(), Grid::empty());
+
+        let error = "..#\n.o.".parse::<Grid>().unwrap_err();
+        assert_eq!(error.to_string(), "invalid cell 'o', expected 'D' or '.' for dead cells and 'L' or '#' for live ones");
+        let error = "..#\n.#".parse::<Grid>().unwrap_err();
+        assert_eq!(error.to_string(), "expected 1x3 cells, found 1x2");
+    }
+
+    #[test]
+    pub fn byte_representation() {
+        let grid = Grid::random_seeded(5, 9, 569);
+        assert_eq!(grid.as_bytes().len(), grid.cells());
+        for (&byte, &cell) in grid.as_bytes().iter().zip(grid.flat()) {
+            assert_eq!(byte, u8::from(cell));
+            assert_eq!(Cell::from(byte), cell);
+            assert_eq!(bool::from(cell), byte == 1);
+        }
+        assert_eq!(Cell::from(7), Cell::Live);
+
+        let mut cell = Cell::Dead;
+        cell.toggle();
+        assert_eq!(cell, Cell::Live);
+        assert_eq!(!cell, Cell::Dead);
+        assert_eq!(std::mem::size_of::<Cell>(), 1);
+    }
 }
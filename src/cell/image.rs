@@ -0,0 +1,75 @@
+use image::{GrayImage, Luma};
+
+use super::{Cell, Grid};
+
+/// Brightness of live cells in [`Grid::to_image`].
+const LIVE: Luma<u8> = Luma([u8::MAX]);
+/// Brightness of dead cells in [`Grid::to_image`].
+const DEAD: Luma<u8> = Luma([0]);
+
+impl Grid {
+    #[must_use]
+    /// A grid with one cell for each pixel, live where the pixel is at least as bright as
+    /// `threshold`.
+    ///
+    /// Colored images can be converted first with [`DynamicImage::to_luma8`](image::DynamicImage::to_luma8),
+    /// and inverted to seed the dark parts of a photograph instead.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use vida::cell::{Cell, Grid};
+    /// # use image::GrayImage;
+    /// #
+    /// let gradient = GrayImage::from_fn(4, 1, |x, _| image::Luma([x as u8 * 80]));
+    /// let grid = Grid::from_image(&gradient, 128);
+    ///
+    /// assert_eq!(grid, Grid::from([[Cell::Dead, Cell::Dead, Cell::Live, Cell::Live]]));
+    /// ```
+    pub fn from_image(image: &GrayImage, threshold: u8) -> Self {
+        let (width, height) = image.dimensions();
+        let mut grid = Self::new(height as usize, width as usize);
+        for (cell, &Luma([value])) in grid.flat_mut().iter_mut().zip(image.pixels()) {
+            *cell = Cell::from(value >= threshold);
+        }
+        grid
+    }
+
+    #[must_use]
+    /// Draws the grid as a black and white image, with each cell `scale` by `scale` pixels and
+    /// live cells in white.
+    ///
+    /// # Panics
+    ///
+    /// If the image would be larger than `u32::MAX` pixels on a side.
+    pub fn to_image(&self, scale: u32) -> GrayImage {
+        let (rows, columns) = self.shape();
+        let side = |cells: usize| u32::try_from(cells).ok().and_then(|cells| cells.checked_mul(scale)).expect("image too large");
+
+        GrayImage::from_fn(side(columns), side(rows), |x, y| {
+            match self[((y / scale) as usize, (x / scale) as usize)] {
+                Cell::Live => LIVE,
+                Cell::Dead => DEAD,
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn image_round_trip() {
+        let grid = Grid::random_seeded(13, 21, 557);
+        assert_eq!(Grid::from_image(&grid.to_image(1), 128), grid);
+
+        let image = grid.to_image(3);
+        assert_eq!(image.dimensions(), (63, 39));
+        assert_eq!(image.get_pixel(5, 8), if grid[(2, 1)].is_live() { &LIVE } else { &DEAD });
+        assert_eq!(Grid::from_image(&image, 1).population(), 9 * grid.population());
+
+        assert_eq!(Grid::from_image(&GrayImage::new(0, 0), 0), Grid::empty());
+        assert_eq!(Grid::from_image(&GrayImage::new(2, 3), 0).population(), 6);
+    }
+}
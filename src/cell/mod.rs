@@ -3,18 +3,45 @@ use std::fmt::{self, Display, Formatter, Write};
 use rand::Rng;
 use rand::distributions::{Distribution, Standard};
 
+mod bits;
+mod buffer;
+mod delta;
 mod grid;
+#[cfg(feature = "image")]
+mod image;
+mod neighborhood;
+mod ops;
+mod padded;
+mod pool;
+mod quad;
+#[cfg(feature = "serde")]
+mod serialize;
+mod svg;
+mod view;
 
-pub use grid::{Grid, Iter, IterMut};
+pub use bits::{BitGrid, BitRow, CellMut};
+pub use buffer::CACHE_LINE;
+pub(crate) use buffer::CellBuffer;
+pub use delta::GridDelta;
+pub use grid::{Anchor, Grid, Iter, IterMut, Overflow};
+pub use neighborhood::{Boundary, Neighborhood, Neighborhoods, Topology};
+pub use padded::PaddedGrid;
+pub use pool::GridPool;
+pub use quad::QuadGrid;
+pub use view::GridView;
 
 /// Represents the state of a single cell in Conways's Game of Life.
+///
+/// A cell is a single byte, `0` for [`Dead`](Cell::Dead) and `1` for [`Live`](Cell::Live), so a
+/// slice of cells can be handed to other code as bytes, see [`Grid::as_bytes`].
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+#[repr(u8)]
 pub enum Cell {
     #[default]
     /// The cell is currently "unpopulated".
-    Dead,
+    Dead = 0,
     /// The cell is currently "populated".
-    Live,
+    Live = 1,
 }
 
 impl Cell {
@@ -31,21 +58,85 @@ impl Cell {
     pub const fn is_live(&self) -> bool {
         matches!(self, Self::Live)
     }
+
+    #[inline]
+    /// Flips the cell between [`Dead`](Cell::Dead) and [`Live`](Cell::Live).
+    pub fn toggle(&mut self) {
+        *self = !*self;
+    }
+}
+
+impl From<bool> for Cell {
+    #[inline]
+    fn from(live: bool) -> Self {
+        if live { Self::Live } else { Self::Dead }
+    }
+}
+
+impl From<Cell> for bool {
+    #[inline]
+    fn from(cell: Cell) -> Self {
+        cell.is_live()
+    }
+}
+
+impl From<u8> for Cell {
+    /// Any non-zero byte is a live cell.
+    #[inline]
+    fn from(byte: u8) -> Self {
+        Self::from(byte != 0)
+    }
+}
+
+impl From<Cell> for u8 {
+    #[inline]
+    fn from(cell: Cell) -> Self {
+        cell as u8
+    }
 }
 
 impl Display for Cell {
     #[inline]
+    /// Writes `D` or `L`, or `.` or `#` in the alternate form (`{:#}`).
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        match self {
-            Self::Dead => f.write_char('D'),
-            Self::Live => f.write_char('L'),
+        match (self, f.alternate()) {
+            (Self::Dead, false) => f.write_char('D'),
+            (Self::Live, false) => f.write_char('L'),
+            (Self::Dead, true) => f.write_char('.'),
+            (Self::Live, true) => f.write_char('#'),
+        }
+    }
+}
+
+impl TryFrom<char> for Cell {
+    type Error = ParseCellError;
+
+    /// Parses either form written by [`Display`], `D` or `.` for dead cells and `L` or `#` for
+    /// live ones.
+    #[inline]
+    fn try_from(char: char) -> Result<Self, Self::Error> {
+        match char {
+            'D' | '.' => Ok(Self::Dead),
+            'L' | '#' => Ok(Self::Live),
+            _ => Err(ParseCellError(char)),
         }
     }
 }
 
+/// Error returned when parsing a [`Cell`] fails, with the unexpected character.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParseCellError(pub char);
+
+impl Display for ParseCellError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid cell {:?}, expected 'D' or '.' for dead cells and 'L' or '#' for live ones", self.0)
+    }
+}
+
+impl std::error::Error for ParseCellError {}
+
 impl Distribution<Cell> for Standard {
     #[inline]
-    #[must_use]
     fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Cell {
         if <Self as Distribution<bool>>::sample(self, rng) {
             Cell::Live
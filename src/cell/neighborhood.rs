@@ -0,0 +1,259 @@
+use std::iter::FusedIterator;
+use std::ops::Range;
+
+#[cfg(feature = "parallel")]
+use crate::par::*;
+
+use super::{Cell, Grid, PaddedGrid};
+
+/// The 3 by 3 block of cells around a cell, with the cell itself at `[1][1]`.
+pub type Neighborhood = [[Cell; 3]; 3];
+
+/// What lies past the edges of a grid, when looking at the neighbors of the cells there.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub enum Boundary {
+    #[default]
+    /// The grid is surrounded by dead cells.
+    Dead,
+    /// The grid wraps around like a torus, so the last row is above the first one and the last
+    /// column is to the left of the first one.
+    Wrap,
+}
+
+/// Which cells of the 3 by 3 [`Neighborhood`] around a cell are its neighbors, which also decides
+/// how the cells are arranged.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub enum Topology {
+    #[default]
+    /// Square cells, each with the eight neighbors of its Moore neighborhood.
+    Moore,
+    /// Square cells, each with the four neighbors sharing an edge with it.
+    VonNeumann,
+    /// Hexagonal cells in offset coordinates, with odd rows shifted half a cell to the right, so
+    /// each cell has six neighbors: two in its own row, and two in each row above and below.
+    Hex,
+    /// Square cells with an explicit set of neighbors, where `mask[1 + dy][1 + dx]` marks the cell
+    /// at offset `(dy, dx)`. The center is never a neighbor. See [`Topology::from_offsets`].
+    Custom([[bool; 3]; 3]),
+}
+
+impl Topology {
+    #[must_use]
+    /// The neighborhood with the cells at each `(dy, dx)` offset as neighbors, or [`None`] if any
+    /// offset is outside of the 3 by 3 window or is the cell itself.
+    ///
+    /// Offsets further away can't be expressed as a topology. Moore and von Neumann neighborhoods
+    /// of a larger radius are run as [`LtlRule`](crate::rule::LtlRule)s instead.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use vida::cell::Topology;
+    /// #
+    /// let cross = Topology::from_offsets(&[(-1, 0), (0, -1), (0, 1), (1, 0)]);
+    /// assert_eq!(cross.map(|topology| topology.mask(0)), Some(Topology::VonNeumann.mask(0)));
+    ///
+    /// assert_eq!(Topology::from_offsets(&[(0, 0)]), None);
+    /// assert_eq!(Topology::from_offsets(&[(2, 1)]), None);
+    /// ```
+    pub fn from_offsets(offsets: &[(isize, isize)]) -> Option<Self> {
+        let mut mask = [[false; 3]; 3];
+        for &(dy, dx) in offsets {
+            if (dy, dx) == (0, 0) || !(-1..=1).contains(&dy) || !(-1..=1).contains(&dx) {
+                return None;
+            }
+            mask[(1 + dy) as usize][(1 + dx) as usize] = true;
+        }
+        Some(Self::Custom(mask))
+    }
+
+    #[inline]
+    #[must_use]
+    /// Which cells of the [`Neighborhood`] of a cell in `row` are its neighbors.
+    pub const fn mask(self, row: usize) -> [[bool; 3]; 3] {
+        match self {
+            Self::Moore => [[true, true, true], [true, false, true], [true, true, true]],
+            Self::VonNeumann => [[false, true, false], [true, false, true], [false, true, false]],
+            Self::Hex if row % 2 == 0 => [[true, true, false], [true, false, true], [true, true, false]],
+            Self::Hex => [[false, true, true], [true, false, true], [false, true, true]],
+            Self::Custom(mut mask) => {
+                mask[1][1] = false;
+                mask
+            },
+        }
+    }
+
+    /// The `(dy, dx)` offsets of the neighbors of a cell in `row`, in row-major order.
+    pub fn offsets(self, row: usize) -> impl Iterator<Item = (isize, isize)> {
+        let mask = self.mask(row);
+        (0..3).flat_map(|i| (0..3).map(move |j| (i, j)))
+            .filter(move |&(i, j)| mask[i][j])
+            .map(|(i, j)| (i as isize - 1, j as isize - 1))
+    }
+
+    #[inline]
+    #[must_use]
+    /// Counts the live neighbors in the [`Neighborhood`] of a cell in `row`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use vida::cell::{Boundary, Grid, Topology};
+    /// #
+    /// let grid = Grid::from_live_cells(3, 3, [(0, 0), (0, 2), (1, 0), (2, 0), (2, 2)]);
+    /// let neighborhood = grid.neighborhoods(Boundary::Dead).nth(4).unwrap().2;
+    ///
+    /// assert_eq!(Topology::Moore.count(&neighborhood, 1), 5);
+    /// assert_eq!(Topology::VonNeumann.count(&neighborhood, 1), 1);
+    /// // on odd rows, the neighbors above and below are the center and right cells
+    /// assert_eq!(Topology::Hex.count(&neighborhood, 1), 3);
+    /// ```
+    pub fn count(self, neighborhood: &Neighborhood, row: usize) -> usize {
+        let live = |cell: &Cell| usize::from(cell.is_live());
+        match self {
+            Self::Moore => neighborhood.iter().flatten().map(live).sum::<usize>() - live(&neighborhood[1][1]),
+            _ => self.offsets(row).map(|(dy, dx)| live(&neighborhood[(1 + dy) as usize][(1 + dx) as usize])).sum(),
+        }
+    }
+}
+
+impl Grid {
+    #[must_use]
+    /// The neighborhood of every cell as `(row, col, neighborhood)`, in row-major order.
+    ///
+    /// This lets custom rules and analyses look at the neighbors of each cell without any index
+    /// math at the edges, which are handled as set by `boundary`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use vida::cell::{Boundary, Cell, Grid};
+    /// #
+    /// let grid = Grid::from_live_cells(3, 4, [(0, 0), (2, 3)]);
+    ///
+    /// let (_, _, corner) = grid.neighborhoods(Boundary::Dead).next().unwrap();
+    /// assert_eq!(corner[0], [Cell::Dead; 3]);
+    ///
+    /// let (_, _, corner) = grid.neighborhoods(Boundary::Wrap).next().unwrap();
+    /// assert_eq!(corner[0][0], Cell::Live);
+    /// assert_eq!(corner[1][1], Cell::Live);
+    /// ```
+    pub fn neighborhoods(&self, boundary: Boundary) -> Neighborhoods {
+        Neighborhoods { grid: PaddedGrid::with_boundary(self, boundary), positions: 0..self.cells() }
+    }
+
+    #[cfg(feature = "parallel")]
+    /// A parallel iterator over the neighborhood of every cell as `(row, col, neighborhood)`.
+    ///
+    /// See [`Grid::neighborhoods`].
+    pub fn par_neighborhoods(&self, boundary: Boundary) -> impl IndexedParallelIterator<Item = (usize, usize, Neighborhood)> {
+        let grid = PaddedGrid::with_boundary(self, boundary);
+        (0..self.cells()).into_par_iter().map(move |index| neighborhood_at(&grid, index))
+    }
+}
+
+/// The neighborhood of the cell at `index` in row-major order.
+#[inline]
+fn neighborhood_at(grid: &PaddedGrid, index: usize) -> (usize, usize, Neighborhood) {
+    let (row, col) = (index / grid.columns(), index % grid.columns());
+    (row, col, grid.neighborhood(row, col))
+}
+
+/// Iterator over the neighborhoods of a grid, created by [`Grid::neighborhoods`].
+///
+/// It keeps a padded copy of the grid, so the grid itself can change while iterating, like when
+/// computing the next generation in place.
+#[derive(Debug, Clone)]
+pub struct Neighborhoods {
+    grid: PaddedGrid,
+    positions: Range<usize>,
+}
+
+impl Iterator for Neighborhoods {
+    type Item = (usize, usize, Neighborhood);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.positions.next().map(|index| neighborhood_at(&self.grid, index))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.positions.size_hint()
+    }
+
+    #[inline]
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        self.positions.nth(n).map(|index| neighborhood_at(&self.grid, index))
+    }
+}
+
+impl DoubleEndedIterator for Neighborhoods {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.positions.next_back().map(|index| neighborhood_at(&self.grid, index))
+    }
+}
+
+impl ExactSizeIterator for Neighborhoods {}
+
+impl FusedIterator for Neighborhoods {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn neighborhoods_at_the_edges() {
+        let grid = Grid::random_seeded(7, 9, 566);
+
+        for boundary in [Boundary::Dead, Boundary::Wrap] {
+            let neighborhoods: Vec<_> = grid.neighborhoods(boundary).collect();
+            assert_eq!(neighborhoods.len(), grid.cells());
+
+            for (row, col, neighborhood) in neighborhoods {
+                for (i, j) in (0..3).flat_map(|i| (0..3).map(move |j| (i, j))) {
+                    let (y, x) = ((row + 7 + i - 1) % 7, (col + 9 + j - 1) % 9);
+                    let inside = (row + i).checked_sub(1) == Some(y) && (col + j).checked_sub(1) == Some(x);
+                    let expected = match boundary {
+                        Boundary::Wrap => grid[(y, x)],
+                        Boundary::Dead if inside => grid[(y, x)],
+                        Boundary::Dead => Cell::Dead,
+                    };
+                    assert_eq!(neighborhood[i][j], expected, "at ({row}, {col}) with {boundary:?}");
+                }
+            }
+        }
+
+        let mut neighborhoods = grid.neighborhoods(Boundary::Dead);
+        assert_eq!(neighborhoods.len(), 63);
+        assert_eq!(neighborhoods.next_back().map(|(row, col, _)| (row, col)), Some((6, 8)));
+        assert_eq!(neighborhoods.nth(10).map(|(row, col, _)| (row, col)), Some((1, 1)));
+        assert_eq!(Grid::new(3, 0).neighborhoods(Boundary::Wrap).next(), None);
+
+        #[cfg(feature = "parallel")]
+        assert!(grid.par_neighborhoods(Boundary::Wrap).collect::<Vec<_>>().into_iter().eq(grid.neighborhoods(Boundary::Wrap)));
+    }
+
+    #[test]
+    pub fn hexagonal_neighbors() {
+        // the six neighbors of each cell are also its neighbors back
+        for row in 0..4 {
+            let neighbors: Vec<_> = (0..3).flat_map(|i| (0..3).map(move |j| (i, j)))
+                .filter(|&(i, j)| Topology::Hex.mask(row)[i][j])
+                .collect();
+            assert_eq!(neighbors.len(), 6);
+
+            for (i, j) in neighbors {
+                let other = (row + i + 1) % 2;
+                assert!(Topology::Hex.mask(other)[2 - i][2 - j], "({i}, {j}) from row {row}");
+            }
+        }
+
+        let neighborhood = [[Cell::Live; 3]; 3];
+        let counts = [Topology::Moore, Topology::VonNeumann, Topology::Hex].map(|topology| topology.count(&neighborhood, 3));
+        assert_eq!(counts, [8, 4, 6]);
+        assert_eq!(Topology::Custom([[true; 3]; 3]).count(&neighborhood, 0), 8);
+        assert_eq!(Topology::Hex.offsets(1).collect::<Vec<_>>(), [(-1, 0), (-1, 1), (0, -1), (0, 1), (1, 0), (1, 1)]);
+    }
+}
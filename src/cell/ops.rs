@@ -0,0 +1,160 @@
+//! Set operations on cells and grids, with live cells as members of the set.
+//!
+//! `&a | &b` is the union of two grids, `&a & &b` the intersection, `&a ^ &b` the cells that
+//! differ, like a change mask between generations, and `!&a` the complement. The grids must have
+//! the same shape, see [`Grid::try_zip_with`] for a version that doesn't panic.
+
+use std::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Not};
+
+use crate::error::Error;
+use crate::par::*;
+
+use super::{Cell, Grid};
+
+impl Not for Cell {
+    type Output = Self;
+
+    #[inline]
+    fn not(self) -> Self {
+        Self::from(self.is_dead())
+    }
+}
+
+impl Grid {
+    /// Combines the cells at the same position in both grids, in parallel with the `parallel`
+    /// feature.
+    ///
+    /// # Panics
+    ///
+    /// If the grids have different shapes, see [`try_zip_with`](Grid::try_zip_with).
+    pub fn zip_with(&self, other: &Self, combine: impl Fn(Cell, Cell) -> Cell + Send + Sync) -> Self {
+        self.try_zip_with(other, combine).unwrap_or_else(|error| panic!("{error}"))
+    }
+
+    /// Like [`zip_with`](Grid::zip_with), but fails with [`Error::DimensionMismatch`] if the grids
+    /// have different shapes.
+    pub fn try_zip_with(&self, other: &Self, combine: impl Fn(Cell, Cell) -> Cell + Send + Sync) -> Result<Self, Error> {
+        let mut grid = self.clone();
+        grid.try_zip_assign(other, combine)?;
+        Ok(grid)
+    }
+
+    /// Replaces each cell with its combination with the cell at the same position in `other`.
+    fn try_zip_assign(&mut self, other: &Self, combine: impl Fn(Cell, Cell) -> Cell + Send + Sync) -> Result<(), Error> {
+        if self.shape() != other.shape() {
+            return Err(Error::DimensionMismatch { expected: self.shape(), found: other.shape() });
+        }
+
+        self.flat_mut().par_iter_mut().zip(other.flat()).for_each(|(cell, &other)| *cell = combine(*cell, other));
+        Ok(())
+    }
+}
+
+/// Implements a binary operator for cells and for grids of the same shape, by reference and in
+/// place.
+macro_rules! set_operation {
+    ($Op:ident :: $op:ident, $OpAssign:ident :: $op_assign:ident, |$a:ident, $b:ident| $combine:expr) => {
+        impl $Op for Cell {
+            type Output = Self;
+
+            #[inline]
+            fn $op(self, other: Self) -> Self {
+                let ($a, $b) = (self.is_live(), other.is_live());
+                Self::from($combine)
+            }
+        }
+
+        impl $Op for &Grid {
+            type Output = Grid;
+
+            /// # Panics
+            ///
+            /// If the grids have different shapes.
+            #[inline]
+            fn $op(self, other: Self) -> Grid {
+                self.zip_with(other, Cell::$op)
+            }
+        }
+
+        impl $Op<&Grid> for Grid {
+            type Output = Grid;
+
+            /// # Panics
+            ///
+            /// If the grids have different shapes.
+            #[inline]
+            fn $op(mut self, other: &Grid) -> Grid {
+                self.$op_assign(other);
+                self
+            }
+        }
+
+        impl $OpAssign<&Grid> for Grid {
+            /// # Panics
+            ///
+            /// If the grids have different shapes.
+            #[inline]
+            fn $op_assign(&mut self, other: &Grid) {
+                self.try_zip_assign(other, Cell::$op).unwrap_or_else(|error| panic!("{error}"))
+            }
+        }
+    };
+}
+
+set_operation!(BitOr::bitor, BitOrAssign::bitor_assign, |a, b| a || b);
+set_operation!(BitAnd::bitand, BitAndAssign::bitand_assign, |a, b| a && b);
+set_operation!(BitXor::bitxor, BitXorAssign::bitxor_assign, |a, b| a != b);
+
+impl Not for &Grid {
+    type Output = Grid;
+
+    #[inline]
+    fn not(self) -> Grid {
+        !self.clone()
+    }
+}
+
+impl Not for Grid {
+    type Output = Self;
+
+    #[inline]
+    fn not(mut self) -> Self {
+        self.flat_mut().par_iter_mut().for_each(Cell::toggle);
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::engine::{Engine, SerialEngine};
+
+    use super::*;
+
+    #[test]
+    pub fn set_operations() {
+        let (a, b) = (Grid::random_seeded(9, 13, 564), Grid::random_seeded(9, 13, 565));
+        let (union, intersection, difference) = (&a | &b, &a & &b, &a ^ &b);
+
+        for (row, col) in (0..9).flat_map(|row| (0..13).map(move |col| (row, col))) {
+            let (x, y) = (a[(row, col)].is_live(), b[(row, col)].is_live());
+            assert_eq!(union[(row, col)].is_live(), x || y);
+            assert_eq!(intersection[(row, col)].is_live(), x && y);
+            assert_eq!(difference[(row, col)].is_live(), x != y);
+        }
+        assert_eq!(union.population() + intersection.population(), a.population() + b.population());
+        assert_eq!(!&union, &!a.clone() & &!b.clone());
+        assert_eq!((!&a).population(), a.cells() - a.population());
+
+        let mut c = a.clone();
+        c ^= &b;
+        assert_eq!(c, difference);
+        assert_eq!(a.clone() | &b, union);
+
+        // the change mask between generations
+        let next = SerialEngine::default().update(&a);
+        assert_eq!((&a ^ &next).population(), a.diff(&next).len());
+
+        let error = a.try_zip_with(&Grid::new(9, 12), Cell::bitor).unwrap_err();
+        assert_eq!(error.to_string(), "expected 9x13 cells, found 9x12");
+    }
+}
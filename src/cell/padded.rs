@@ -0,0 +1,199 @@
+use super::{Boundary, Cell, Grid, Neighborhood};
+
+/// A copy of a [`Grid`] surrounded by a border of dead cells (a halo).
+///
+/// Every cell of the original grid has eight neighbors inside the buffer, so counting them needs
+/// no bound checks or special cases for the edges.
+///
+/// # Example
+///
+/// ```
+/// # use vida::cell::{Cell, Grid, PaddedGrid};
+/// #
+/// let mut grid = Grid::new(3, 3);
+/// grid[0].fill(Cell::Live);
+///
+/// let padded = PaddedGrid::from(&grid);
+/// assert_eq!(padded.live_neighbors(0, 0), 1);
+/// assert_eq!(padded.live_neighbors(1, 1), 3);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct PaddedGrid {
+    cells: Box<[Cell]>,
+    rows: usize,
+    columns: usize,
+}
+
+impl PaddedGrid {
+    #[must_use]
+    /// Creates a padded grid of `(rows, columns)` dead cells, not counting the border.
+    pub fn new(rows: usize, columns: usize) -> Self {
+        let cells = vec![Cell::Dead; (rows + 2) * (columns + 2)].into_boxed_slice();
+        Self { cells, rows, columns }
+    }
+
+    /// Copies the cells of a grid, keeping the border dead.
+    ///
+    /// The buffer is only reallocated if the shape changes.
+    pub fn copy_from(&mut self, grid: &Grid) {
+        if self.shape() != grid.shape() {
+            *self = Self::new(grid.rows(), grid.columns());
+        }
+
+        let stride = self.stride();
+        for (row, cells) in grid.iter().enumerate() {
+            let start = (row + 1) * stride + 1;
+            self.cells[start..start + cells.len()].copy_from_slice(cells);
+        }
+    }
+
+    #[must_use]
+    /// Copies the cells of a grid, with the border filled as set by `boundary`.
+    pub fn with_boundary(grid: &Grid, boundary: Boundary) -> Self {
+        let mut padded = Self::from(grid);
+        if boundary == Boundary::Wrap {
+            padded.wrap_border();
+        }
+        padded
+    }
+
+    /// Fills the border with the cells on the opposite edges, so the grid wraps around like a
+    /// torus.
+    pub fn wrap_border(&mut self) {
+        let (rows, columns, stride) = (self.rows, self.columns, self.stride());
+        if rows * columns == 0 {
+            return;
+        }
+
+        for row in self.cells.chunks_exact_mut(stride).skip(1).take(rows) {
+            row[0] = row[columns];
+            row[columns + 1] = row[1];
+        }
+        self.cells.copy_within(rows * stride..(rows + 1) * stride, 0);
+        self.cells.copy_within(stride..2 * stride, (rows + 1) * stride);
+    }
+
+    #[inline]
+    #[must_use]
+    /// The number of rows, not counting the border.
+    pub const fn rows(&self) -> usize {
+        self.rows
+    }
+
+    #[inline]
+    #[must_use]
+    /// The number of columns, not counting the border.
+    pub const fn columns(&self) -> usize {
+        self.columns
+    }
+
+    #[inline]
+    #[must_use]
+    /// The shape `(rows, columns)`, not counting the border.
+    pub const fn shape(&self) -> (usize, usize) {
+        (self.rows, self.columns)
+    }
+
+    #[inline]
+    #[must_use]
+    const fn stride(&self) -> usize {
+        self.columns + 2
+    }
+
+    #[inline]
+    #[must_use]
+    /// A row including its two border cells, so cell `col` of the grid is at index `col + 1`.
+    ///
+    /// Rows `0` and `rows + 1` are the top and bottom borders.
+    ///
+    /// # Panics
+    ///
+    /// If `row` is greater than `rows + 1`.
+    pub fn padded_row(&self, row: usize) -> &[Cell] {
+        let stride = self.stride();
+        &self.cells[row * stride..(row + 1) * stride]
+    }
+
+    #[inline]
+    #[must_use]
+    /// The cell at `(row, col)` of the original grid.
+    ///
+    /// # Panics
+    ///
+    /// If the index is outside of the grid.
+    pub fn cell(&self, row: usize, col: usize) -> Cell {
+        assert!(row < self.rows && col < self.columns, "index outside of the grid");
+        self.padded_row(row + 1)[col + 1]
+    }
+
+    #[inline]
+    #[must_use]
+    /// Counts the live cells among the eight neighbors of `(row, col)` in the original grid.
+    ///
+    /// # Panics
+    ///
+    /// If the index is outside of the grid.
+    pub fn live_neighbors(&self, row: usize, col: usize) -> usize {
+        let cell = self.cell(row, col);
+        let block: usize = (row..row + 3)
+            .map(|row| self.padded_row(row)[col..col + 3].iter().filter(|cell| cell.is_live()).count())
+            .sum();
+
+        block - usize::from(cell.is_live())
+    }
+
+    #[inline]
+    #[must_use]
+    /// The 3 by 3 block centered on `(row, col)` of the original grid, including the border.
+    ///
+    /// # Panics
+    ///
+    /// If the index is outside of the grid.
+    pub fn neighborhood(&self, row: usize, col: usize) -> Neighborhood {
+        assert!(row < self.rows && col < self.columns, "index outside of the grid");
+        [0, 1, 2].map(|offset| {
+            let cells = &self.padded_row(row + offset)[col..col + 3];
+            [cells[0], cells[1], cells[2]]
+        })
+    }
+}
+
+impl From<&Grid> for PaddedGrid {
+    #[inline]
+    fn from(grid: &Grid) -> Self {
+        let mut padded = Self::new(grid.rows(), grid.columns());
+        padded.copy_from(grid);
+        padded
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand::rngs::SmallRng;
+
+    use super::*;
+
+    #[test]
+    pub fn same_neighbors_as_grid() {
+        let mut rng = SmallRng::seed_from_u64(524);
+        let grid = Grid::random_with(13, 17, &mut rng);
+        let padded = PaddedGrid::from(&grid);
+
+        for row in 0..grid.rows() {
+            for col in 0..grid.columns() {
+                let expected = (row.saturating_sub(1)..=row + 1)
+                    .flat_map(|i| (col.saturating_sub(1)..=col + 1).map(move |j| (i, j)))
+                    .filter(|&index| index != (row, col))
+                    .filter(|&(i, j)| grid.get_cell(i, j).is_some_and(Cell::is_live))
+                    .count();
+
+                assert_eq!(padded.cell(row, col), grid[(row, col)]);
+                assert_eq!(padded.live_neighbors(row, col), expected);
+            }
+        }
+
+        assert_eq!(padded.padded_row(0), vec![Cell::Dead; 19]);
+        assert_eq!(padded.padded_row(14), vec![Cell::Dead; 19]);
+    }
+}
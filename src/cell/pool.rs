@@ -0,0 +1,155 @@
+use std::sync::Arc;
+
+use super::Grid;
+
+/// Grids kept by a [`GridPool`] when no capacity is given.
+const DEFAULT_CAPACITY: usize = 4;
+
+/// Recycles grid buffers, so computing a generation doesn't allocate a new grid every time.
+///
+/// Grids are handed out as [`Arc`]s, which can be shared with other threads, like a renderer
+/// drawing the last generation. Once they are released back to the pool, they are reused as soon
+/// as every other copy of the `Arc` is dropped. [`Simulation`](crate::simulation::Simulation) keeps
+/// a pool for its generations, so this only needs to be used directly by custom loops.
+///
+/// # Example
+///
+/// ```
+/// # use std::sync::Arc;
+/// # use vida::cell::GridPool;
+/// #
+/// let mut pool = GridPool::default();
+/// let grid = pool.acquire(30, 40);
+/// let reader = Arc::clone(&grid);
+/// pool.release(grid);
+///
+/// // still shared with the reader, so it can't be reused yet
+/// assert!(!Arc::ptr_eq(&pool.acquire(30, 40), &reader));
+///
+/// let address = reader.flat().as_ptr();
+/// drop(reader);
+/// assert_eq!(pool.acquire(30, 40).flat().as_ptr(), address);
+/// ```
+#[derive(Debug)]
+pub struct GridPool {
+    grids: Vec<Arc<Grid>>,
+    capacity: usize,
+}
+
+impl GridPool {
+    #[inline]
+    #[must_use]
+    /// Creates an empty pool holding up to `capacity` grids.
+    pub const fn new(capacity: usize) -> Self {
+        Self { grids: Vec::new(), capacity }
+    }
+
+    #[inline]
+    #[must_use]
+    /// The most grids held by the pool.
+    pub const fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    #[inline]
+    #[must_use]
+    /// Number of grids held by the pool, shared or not.
+    pub fn len(&self) -> usize {
+        self.grids.len()
+    }
+
+    #[inline]
+    #[must_use]
+    /// Checks if the pool holds no grids.
+    pub fn is_empty(&self) -> bool {
+        self.grids.is_empty()
+    }
+
+    #[must_use]
+    /// A grid that isn't shared with anyone else, so [`Arc::get_mut`] always succeeds on it.
+    ///
+    /// Grids released with the same shape are preferred, then any other grid no longer shared,
+    /// which [`Engine::update_into`](crate::engine::Engine::update_into) resizes as needed. Only if
+    /// none is available, a new grid of `(rows, columns)` dead cells is allocated. The cells of a
+    /// recycled grid are left as they were.
+    pub fn acquire(&mut self, rows: usize, columns: usize) -> Arc<Grid> {
+        // the shape of grids that are not shared
+        let free = |grid: &mut Arc<Grid>| Arc::get_mut(grid).map(|grid| grid.shape());
+        let index = self.grids.iter_mut().position(|grid| free(grid) == Some((rows, columns)))
+            .or_else(|| self.grids.iter_mut().position(|grid| free(grid).is_some()));
+
+        match index {
+            Some(index) => self.grids.remove(index),
+            None => Arc::new(Grid::new(rows, columns)),
+        }
+    }
+
+    /// Gives a grid back to the pool, to be reused once it isn't shared anymore.
+    ///
+    /// When the pool is full, the oldest grid is dropped to make room.
+    pub fn release(&mut self, grid: Arc<Grid>) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.grids.len() >= self.capacity {
+            self.grids.remove(0);
+        }
+        self.grids.push(grid);
+    }
+
+    /// Drops every grid held by the pool.
+    pub fn clear(&mut self) {
+        self.grids.clear();
+    }
+}
+
+impl Default for GridPool {
+    #[inline]
+    fn default() -> Self {
+        Self::new(DEFAULT_CAPACITY)
+    }
+}
+
+impl Clone for GridPool {
+    /// An empty pool with the same capacity, since the grids themselves can't be reused by two
+    /// pools.
+    #[inline]
+    fn clone(&self) -> Self {
+        Self::new(self.capacity)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn recycles_free_grids() {
+        let mut pool = GridPool::new(2);
+        let mut grid = pool.acquire(5, 6);
+        assert!(Arc::get_mut(&mut grid).is_some());
+        let address = grid.flat().as_ptr();
+
+        pool.release(grid);
+        assert_eq!(pool.len(), 1);
+        let mut grid = pool.acquire(7, 7);
+        assert_eq!(grid.flat().as_ptr(), address);
+        assert!(Arc::get_mut(&mut grid).is_some());
+        assert!(pool.is_empty());
+
+        let shared = Arc::clone(&grid);
+        pool.release(grid);
+        assert_eq!(pool.acquire(5, 6).shape(), (5, 6));
+        drop(shared);
+
+        for _ in 0..3 {
+            pool.release(Arc::new(Grid::new(2, 2)));
+        }
+        assert_eq!(pool.len(), 2);
+        assert!(pool.clone().is_empty());
+
+        let mut disabled = GridPool::new(0);
+        disabled.release(Arc::new(Grid::new(2, 2)));
+        assert!(disabled.is_empty());
+    }
+}
@@ -0,0 +1,376 @@
+use std::collections::HashMap;
+use std::ops::Range;
+use std::sync::Arc;
+
+use super::{Cell, Grid};
+
+/// Level of the leaves, which are squares of 8 by 8 cells.
+const LEAF_LEVEL: u8 = 3;
+/// Smallest level of the root, with one quadrant on each side of the origin.
+const MIN_LEVEL: u8 = LEAF_LEVEL + 1;
+
+/// A square of `2^level` cells on each side.
+#[derive(Debug, PartialEq, Eq, Hash)]
+enum Node {
+    /// The cells of an 8 by 8 square, bit `8 row + col` being the cell at `(row, col)`.
+    Leaf(u64),
+    /// Quadrants in the order `[nw, ne, sw, se]`.
+    Branch { level: u8, population: u64, children: [Arc<Node>; 4] },
+}
+
+impl Node {
+    fn level(&self) -> u8 {
+        match self {
+            Self::Leaf(_) => LEAF_LEVEL,
+            Self::Branch { level, .. } => *level,
+        }
+    }
+
+    fn population(&self) -> u64 {
+        match self {
+            Self::Leaf(cells) => u64::from(cells.count_ones()),
+            Self::Branch { population, .. } => *population,
+        }
+    }
+
+    fn branch(children: [Arc<Self>; 4]) -> Arc<Self> {
+        let level = children[0].level() + 1;
+        let population = children.iter().map(|child| child.population()).sum();
+        Arc::new(Self::Branch { level, population, children })
+    }
+
+    /// A dead square of the given level, where every quadrant is the same node.
+    fn empty(level: u8) -> Arc<Self> {
+        if level == LEAF_LEVEL {
+            Arc::new(Self::Leaf(0))
+        } else {
+            Self::branch([(); 4].map({
+                let child = Self::empty(level - 1);
+                move |()| Arc::clone(&child)
+            }))
+        }
+    }
+}
+
+/// A quadtree of cells on a plane with no edges, where squares of cells can be shared.
+///
+/// Unlike [`Grid`], which stores every cell of a fixed rectangle, a quadtree grows as cells are
+/// set farther from the origin, and only stores the squares that have live cells. Dead squares
+/// are all the same node, and so are identical leaves built by [`QuadGrid::from`], so repetitive
+/// boards use much less memory than their area. Cloning a quadtree only clones its root, and
+/// changing a cell copies just the path to it.
+///
+/// Coordinates are signed, with the cells of a [`Grid`] starting at `(0, 0)`.
+///
+/// # Example
+///
+/// ```
+/// # use vida::cell::{Cell, Grid, QuadGrid};
+/// #
+/// let mut grid = Grid::new(3, 3);
+/// grid[1].fill(Cell::Live);
+///
+/// let mut quad = QuadGrid::from(&grid);
+/// quad.set(-1_000, 5_000, Cell::Live);
+/// assert_eq!(quad.population(), 4);
+/// assert_eq!(quad.get(1, 2), Cell::Live);
+///
+/// assert_eq!(quad.window(0, 0, 3, 3), grid);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct QuadGrid {
+    /// Centered on the origin, covering `-2^(level-1)..2^(level-1)` on both axes.
+    root: Arc<Node>,
+}
+
+impl QuadGrid {
+    #[must_use]
+    /// Creates a quadtree with only dead cells.
+    pub fn new() -> Self {
+        Self { root: Node::empty(MIN_LEVEL) }
+    }
+
+    #[inline]
+    #[must_use]
+    /// The number of live cells.
+    pub fn population(&self) -> u64 {
+        self.root.population()
+    }
+
+    #[inline]
+    #[must_use]
+    /// Cells on each side of the square currently covered by the tree, centered on the origin.
+    /// Every cell outside of it is dead.
+    pub fn size(&self) -> u64 {
+        1 << self.root.level()
+    }
+
+    #[must_use]
+    /// The cell at `(row, col)`, which may be anywhere on the plane.
+    pub fn get(&self, row: i64, col: i64) -> Cell {
+        let half = self.half();
+        if !(-half..half).contains(&row) || !(-half..half).contains(&col) {
+            return Cell::Dead;
+        }
+
+        let (mut node, mut top, mut left) = (&self.root, -half, -half);
+        loop {
+            match &**node {
+                Node::Leaf(cells) => {
+                    let bit = 8 * (row - top) + (col - left);
+                    return if cells & (1 << bit) != 0 { Cell::Live } else { Cell::Dead };
+                },
+                Node::Branch { level, children, .. } => {
+                    let half = 1_i64 << (level - 1);
+                    let (south, east) = (row >= top + half, col >= left + half);
+                    node = &children[2 * usize::from(south) + usize::from(east)];
+                    top += if south { half } else { 0 };
+                    left += if east { half } else { 0 };
+                },
+            }
+        }
+    }
+
+    /// Changes the cell at `(row, col)`, growing the tree if it is outside of it.
+    ///
+    /// # Panics
+    ///
+    /// If the tree would need more than 2^62 cells on each side.
+    pub fn set(&mut self, row: i64, col: i64, cell: Cell) {
+        while !(-self.half()..self.half()).contains(&row) || !(-self.half()..self.half()).contains(&col) {
+            if cell.is_dead() {
+                return;
+            }
+            assert!(self.root.level() < 62, "quadtree too large");
+            self.expand();
+        }
+
+        let half = self.half();
+        self.root = Self::with_cell(&self.root, (-half, -half), (row, col), cell);
+    }
+
+    #[must_use]
+    /// Positions `(row, col)` of all live cells, in no particular order.
+    pub fn live_cells(&self) -> Vec<(i64, i64)> {
+        let mut cells = Vec::new();
+        let half = self.half();
+        Self::collect(&self.root, (-half, -half), &mut |row, col| cells.push((row, col)));
+        cells
+    }
+
+    #[must_use]
+    /// Copies the cells in the rectangle of `(rows, columns)` cells starting at `(top, left)` into
+    /// a [`Grid`].
+    pub fn window(&self, top: i64, left: i64, rows: usize, columns: usize) -> Grid {
+        let mut grid = Grid::new(rows, columns);
+        let (bottom, right) = (top + rows as i64, left + columns as i64);
+
+        let half = self.half();
+        Self::collect_in(&self.root, (-half, -half), (top..bottom, left..right), &mut |row, col| {
+            grid[((row - top) as usize, (col - left) as usize)] = Cell::Live;
+        });
+        grid
+    }
+
+    fn half(&self) -> i64 {
+        1 << (self.root.level() - 1)
+    }
+
+    /// Doubles the size of the tree, keeping the cells in place.
+    fn expand(&mut self) {
+        let Node::Branch { level, children: [nw, ne, sw, se], .. } = &*self.root else {
+            unreachable!("the root is never a leaf");
+        };
+
+        let empty = Node::empty(level - 1);
+        let e = || Arc::clone(&empty);
+        self.root = Node::branch([
+            Node::branch([e(), e(), e(), Arc::clone(nw)]),
+            Node::branch([e(), e(), Arc::clone(ne), e()]),
+            Node::branch([e(), Arc::clone(sw), e(), e()]),
+            Node::branch([Arc::clone(se), e(), e(), e()]),
+        ]);
+    }
+
+    /// A copy of `node`, whose top-left corner is at `corner`, with one cell changed.
+    fn with_cell(node: &Arc<Node>, (top, left): (i64, i64), (row, col): (i64, i64), cell: Cell) -> Arc<Node> {
+        match &**node {
+            Node::Leaf(cells) => {
+                let bit = 1 << (8 * (row - top) + (col - left));
+                Arc::new(Node::Leaf(if cell.is_live() { cells | bit } else { cells & !bit }))
+            },
+            Node::Branch { level, children, .. } => {
+                let half = 1_i64 << (level - 1);
+                let (south, east) = (row >= top + half, col >= left + half);
+                let index = 2 * usize::from(south) + usize::from(east);
+                let corner = (top + if south { half } else { 0 }, left + if east { half } else { 0 });
+
+                let mut children = children.clone();
+                children[index] = Self::with_cell(&children[index], corner, (row, col), cell);
+                Node::branch(children)
+            },
+        }
+    }
+
+    /// Calls `live` with the position of each live cell in `node`.
+    fn collect(node: &Node, corner: (i64, i64), live: &mut impl FnMut(i64, i64)) {
+        Self::collect_in(node, corner, (i64::MIN..i64::MAX, i64::MIN..i64::MAX), live);
+    }
+
+    /// Calls `live` with the position of each live cell in `node` and inside `bounds`.
+    fn collect_in(node: &Node, (top, left): (i64, i64), bounds: (Range<i64>, Range<i64>), live: &mut impl FnMut(i64, i64)) {
+        let size = 1_i64 << node.level();
+        let (rows, columns) = &bounds;
+        if node.population() == 0 || top >= rows.end || left >= columns.end || top + size <= rows.start || left + size <= columns.start {
+            return;
+        }
+
+        match node {
+            Node::Leaf(cells) => {
+                for bit in (0..64).filter(|bit| cells & (1 << bit) != 0) {
+                    let (row, col) = (top + bit / 8, left + bit % 8);
+                    if rows.contains(&row) && columns.contains(&col) {
+                        live(row, col);
+                    }
+                }
+            },
+            Node::Branch { children, .. } => {
+                let half = size / 2;
+                for (index, child) in children.iter().enumerate() {
+                    let corner = (top + half * (index as i64 / 2), left + half * (index as i64 % 2));
+                    Self::collect_in(child, corner, bounds.clone(), live);
+                }
+            },
+        }
+    }
+
+    /// The node covering `2^level` cells of `grid` from `(top, left)`, reusing identical leaves.
+    fn build(grid: &Grid, level: u8, (top, left): (i64, i64), nodes: &mut Nodes) -> Arc<Node> {
+        let size = 1_i64 << level;
+        let (rows, columns) = (grid.rows() as i64, grid.columns() as i64);
+        if top >= rows || left >= columns || top + size <= 0 || left + size <= 0 {
+            return nodes.empty(level);
+        }
+
+        if level == LEAF_LEVEL {
+            let mut cells = 0;
+            for row in top.max(0)..(top + size).min(rows) {
+                for col in left.max(0)..(left + size).min(columns) {
+                    if grid[(row as usize, col as usize)].is_live() {
+                        cells |= 1 << (8 * (row - top) + (col - left));
+                    }
+                }
+            }
+            return nodes.leaf(cells);
+        }
+
+        let half = size / 2;
+        Node::branch([(0, 0), (0, half), (half, 0), (half, half)].map(|(row, col)| {
+            Self::build(grid, level - 1, (top + row, left + col), nodes)
+        }))
+    }
+}
+
+impl Default for QuadGrid {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl From<&Grid> for QuadGrid {
+    /// Places the grid with its top-left corner at the origin.
+    fn from(grid: &Grid) -> Self {
+        let mut level = MIN_LEVEL;
+        while (1_usize << (level - 1)) < grid.rows().max(grid.columns()) {
+            level += 1;
+        }
+
+        let half = 1_i64 << (level - 1);
+        Self { root: QuadGrid::build(grid, level, (-half, -half), &mut Nodes::default()) }
+    }
+}
+
+impl From<&QuadGrid> for Grid {
+    /// The smallest grid starting at the origin with all live cells, extended up and to the left
+    /// for cells at negative positions.
+    fn from(quad: &QuadGrid) -> Self {
+        let cells = quad.live_cells();
+        let (Some(top), Some(left)) = (cells.iter().map(|&(row, _)| row).min(), cells.iter().map(|&(_, col)| col).min()) else {
+            return Grid::empty();
+        };
+        let (top, left) = (top.min(0), left.min(0));
+
+        let bottom = cells.iter().map(|&(row, _)| row).max().unwrap_or(top);
+        let right = cells.iter().map(|&(_, col)| col).max().unwrap_or(left);
+        quad.window(top, left, (bottom - top + 1) as usize, (right - left + 1) as usize)
+    }
+}
+
+/// Nodes shared while building a tree.
+#[derive(Debug, Default)]
+struct Nodes {
+    leaves: HashMap<u64, Arc<Node>>,
+    empty: Vec<Arc<Node>>,
+}
+
+impl Nodes {
+    fn leaf(&mut self, cells: u64) -> Arc<Node> {
+        Arc::clone(self.leaves.entry(cells).or_insert_with(|| Arc::new(Node::Leaf(cells))))
+    }
+
+    fn empty(&mut self, level: u8) -> Arc<Node> {
+        let index = usize::from(level - LEAF_LEVEL);
+        while self.empty.len() <= index {
+            let node = match self.empty.last() {
+                None => self.leaf(0),
+                Some(child) => Node::branch([(); 4].map(|()| Arc::clone(child))),
+            };
+            self.empty.push(node);
+        }
+        Arc::clone(&self.empty[index])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn conversions() {
+        let grid = Grid::random_seeded(37, 70, 541);
+        let quad = QuadGrid::from(&grid);
+        assert_eq!(quad.population(), grid.population() as u64);
+        assert_eq!(quad.size(), 256);
+        assert_eq!(quad.window(0, 0, 37, 70), grid);
+        assert_eq!(quad.get(36, 69), grid[(36, 69)]);
+        assert_eq!(quad.get(-1, 0), Cell::Dead);
+
+        // a copy only shares the nodes
+        let mut copy = quad.clone();
+        copy.set(-500, 300, Cell::Live);
+        copy.set(0, 0, Cell::Dead);
+        copy.set(0, 0, Cell::Live);
+        assert_eq!(copy.size(), 1024);
+        assert_eq!(copy.population(), quad.population() + 1);
+        assert_eq!(copy.window(0, 0, 37, 70), grid);
+        assert_eq!(quad.get(-500, 300), Cell::Dead);
+
+        let window = copy.window(-501, 299, 3, 3);
+        assert_eq!(window.population(), 1);
+        assert_eq!(window[(1, 1)], Cell::Live);
+        assert_eq!(Grid::from(&copy).shape(), (537, 301));
+
+        // every dead leaf is the same node
+        let empty = QuadGrid::from(&Grid::new(100, 100));
+        let first_leaf = |mut node: &Arc<Node>| {
+            while let Node::Branch { children, .. } = &**node {
+                node = &children[0];
+            }
+            Arc::clone(node)
+        };
+        let Node::Branch { children: [nw, .., se], .. } = &*empty.root else { unreachable!() };
+        assert!(Arc::ptr_eq(&first_leaf(nw), &first_leaf(se)));
+        assert_eq!(Grid::from(&empty), Grid::empty());
+    }
+}
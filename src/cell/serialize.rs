@@ -0,0 +1,187 @@
+use std::fmt::{self, Formatter};
+
+use serde::de::{self, Deserialize, Deserializer, SeqAccess, Visitor};
+use serde::ser::{Serialize, SerializeTuple, Serializer};
+
+use super::{Cell, Grid};
+
+/// Characters of dead and live cells in human-readable formats, as in plaintext patterns.
+const DEAD: char = '.';
+const LIVE: char = 'O';
+
+impl Serialize for Cell {
+    /// A live cell is `true`.
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_bool(self.is_live())
+    }
+}
+
+impl<'de> Deserialize<'de> for Cell {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let live = bool::deserialize(deserializer)?;
+        Ok(if live { Self::Live } else { Self::Dead })
+    }
+}
+
+impl Serialize for Grid {
+    /// In human-readable formats, a grid is a list of rows, each a string like `".OO."`. Binary
+    /// formats get a tuple of rows, columns and the cells packed in bytes, eight cells per byte.
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            serializer.collect_seq(to_lines(self))
+        } else {
+            let mut tuple = serializer.serialize_tuple(3)?;
+            tuple.serialize_element(&(self.rows() as u64))?;
+            tuple.serialize_element(&(self.columns() as u64))?;
+            tuple.serialize_element(&Bytes(&self.pack_bits()))?;
+            tuple.end()
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Grid {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_seq(LinesVisitor)
+        } else {
+            deserializer.deserialize_tuple(3, PackedVisitor)
+        }
+    }
+}
+
+/// Each row of a grid, as text.
+fn to_lines(grid: &Grid) -> impl Iterator<Item = String> + '_ {
+    grid.iter().map(|cells| cells.iter().map(|cell| if cell.is_live() { LIVE } else { DEAD }).collect())
+}
+
+/// Reads a grid from its rows, which must all have the same length.
+fn from_lines<E: de::Error>(lines: impl IntoIterator<Item = String>) -> Result<Grid, E> {
+    let rows = lines.into_iter().map(|line| {
+        line.chars()
+            .map(|char| match char {
+                DEAD => Ok(Cell::Dead),
+                LIVE => Ok(Cell::Live),
+                _ => Err(E::invalid_value(de::Unexpected::Char(char), &"'.' or 'O'")),
+            })
+            .collect::<Result<Vec<_>, _>>()
+    });
+
+    let rows = rows.collect::<Result<Vec<_>, _>>()?;
+    Grid::try_from(rows).map_err(E::custom)
+}
+
+/// Reads the cells packed by [`Grid::pack_bits`].
+fn unpack<E: de::Error>(rows: u64, columns: u64, bytes: &[u8]) -> Result<Grid, E> {
+    let too_large = || E::custom("grid too large");
+    let rows = usize::try_from(rows).map_err(|_| too_large())?;
+    let columns = usize::try_from(columns).map_err(|_| too_large())?;
+    Grid::from_packed_bits(rows, columns, bytes).ok_or_else(|| E::invalid_length(bytes.len(), &"one byte for every 8 cells"))
+}
+
+/// Serializes as a byte string, instead of a sequence of integers.
+struct Bytes<'a>(&'a [u8]);
+
+impl Serialize for Bytes<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_bytes(self.0)
+    }
+}
+
+/// Deserializes a byte string, or a sequence of bytes for formats without byte strings.
+struct ByteBuf(Vec<u8>);
+
+impl<'de> Deserialize<'de> for ByteBuf {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct BytesVisitor;
+
+        impl<'de> Visitor<'de> for BytesVisitor {
+            type Value = ByteBuf;
+
+            fn expecting(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+                formatter.write_str("packed cells")
+            }
+
+            fn visit_bytes<E: de::Error>(self, bytes: &[u8]) -> Result<ByteBuf, E> {
+                Ok(ByteBuf(bytes.to_vec()))
+            }
+
+            fn visit_byte_buf<E: de::Error>(self, bytes: Vec<u8>) -> Result<ByteBuf, E> {
+                Ok(ByteBuf(bytes))
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<ByteBuf, A::Error> {
+                let mut bytes = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+                while let Some(byte) = seq.next_element()? {
+                    bytes.push(byte);
+                }
+                Ok(ByteBuf(bytes))
+            }
+        }
+
+        deserializer.deserialize_byte_buf(BytesVisitor)
+    }
+}
+
+struct LinesVisitor;
+
+impl<'de> Visitor<'de> for LinesVisitor {
+    type Value = Grid;
+
+    fn expecting(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        formatter.write_str("a list of rows like \".OO.\"")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Grid, A::Error> {
+        let mut lines = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+        while let Some(line) = seq.next_element()? {
+            lines.push(line);
+        }
+        from_lines(lines)
+    }
+}
+
+struct PackedVisitor;
+
+impl<'de> Visitor<'de> for PackedVisitor {
+    type Value = Grid;
+
+    fn expecting(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        formatter.write_str("rows, columns and packed cells")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Grid, A::Error> {
+        let rows = seq.next_element()?.ok_or_else(|| de::Error::invalid_length(0, &self))?;
+        let columns = seq.next_element()?.ok_or_else(|| de::Error::invalid_length(1, &self))?;
+        let ByteBuf(bytes) = seq.next_element()?.ok_or_else(|| de::Error::invalid_length(2, &self))?;
+        unpack(rows, columns, &bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::de::value::{Error, SeqDeserializer};
+
+    use super::*;
+
+    #[test]
+    pub fn both_representations() {
+        let grid = Grid::random_seeded(5, 11, 543);
+
+        let lines: Vec<_> = to_lines(&grid).collect();
+        assert_eq!(lines.len(), 5);
+        assert!(lines.iter().all(|line| line.len() == 11));
+
+        let deserializer = SeqDeserializer::<_, Error>::new(lines.into_iter());
+        assert_eq!(Grid::deserialize(deserializer).unwrap(), grid);
+
+        let packed = grid.pack_bits();
+        assert_eq!(packed.len(), 7);
+        assert_eq!(unpack::<Error>(5, 11, &packed).unwrap(), grid);
+        assert!(unpack::<Error>(5, 12, &packed).is_err());
+
+        let uneven = SeqDeserializer::<_, Error>::new([".O", "O"].into_iter().map(String::from));
+        assert!(Grid::deserialize(uneven).is_err());
+        let invalid = SeqDeserializer::<_, Error>::new(["..x"].into_iter().map(String::from));
+        assert!(Grid::deserialize(invalid).is_err());
+    }
+}
@@ -0,0 +1,113 @@
+use std::fmt::Write;
+
+use crate::theme::{self, Theme};
+
+use super::Grid;
+
+impl Grid {
+    #[must_use]
+    /// Draws the grid as a standalone SVG document, with each cell `scale` units wide.
+    ///
+    /// Runs of live cells in a row are merged into a single rectangle, and identical runs in
+    /// consecutive rows are merged vertically, so the output stays small and has no seams between
+    /// neighboring cells.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use vida::cell::{Cell, Grid};
+    /// # use vida::theme::Theme;
+    /// #
+    /// let grid = Grid::from([[Cell::Live, Cell::Live], [Cell::Live, Cell::Live]]);
+    /// let svg = grid.to_svg(10.0, Theme::LIGHT);
+    ///
+    /// assert!(svg.contains(r#"<rect x="0" y="0" width="2" height="2"/>"#));
+    /// ```
+    pub fn to_svg(&self, scale: f64, theme: Theme) -> String {
+        let (rows, columns) = self.shape();
+        let mut svg = String::new();
+
+        let _ = writeln!(svg, r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" viewBox="0 0 {columns} {rows}" shape-rendering="crispEdges">"#,
+            columns as f64 * scale, rows as f64 * scale);
+        let _ = writeln!(svg, r#"<rect width="{columns}" height="{rows}"{}/>"#, fill(theme.dead));
+        let _ = writeln!(svg, "<g{}>", fill(theme.live));
+
+        // open runs as `(start column, length, first row)`
+        let mut open: Vec<(usize, usize, usize)> = Vec::new();
+        for row in 0..=rows {
+            let runs = if row < rows { live_runs(&self[row]) } else { Vec::new() };
+
+            open.retain(|&(start, len, first)| {
+                let continues = runs.contains(&(start, len));
+                if !continues {
+                    let _ = writeln!(svg, r#"<rect x="{start}" y="{first}" width="{len}" height="{}"/>"#, row - first);
+                }
+                continues
+            });
+            for (start, len) in runs {
+                if !open.iter().any(|&(s, l, _)| (s, l) == (start, len)) {
+                    open.push((start, len, row));
+                }
+            }
+        }
+
+        svg.push_str("</g>\n</svg>\n");
+        svg
+    }
+}
+
+#[must_use]
+/// Maximal runs of live cells in a row, as `(start, length)`.
+fn live_runs(row: &[super::Cell]) -> Vec<(usize, usize)> {
+    let mut runs = Vec::new();
+    let mut start = None;
+
+    for (col, cell) in row.iter().enumerate() {
+        match (start, cell.is_live()) {
+            (None, true) => start = Some(col),
+            (Some(first), false) => {
+                runs.push((first, col - first));
+                start = None;
+            },
+            _ => (),
+        }
+    }
+    if let Some(first) = start {
+        runs.push((first, row.len() - first));
+    }
+    runs
+}
+
+#[must_use]
+fn fill(color: theme::Color) -> String {
+    let opacity = color[3].clamp(0.0, 1.0);
+    if opacity < 1.0 {
+        format!(r#" fill="{}" fill-opacity="{opacity}""#, theme::to_hex(color))
+    } else {
+        format!(r#" fill="{}""#, theme::to_hex(color))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cell::Cell;
+
+    use super::*;
+
+    #[test]
+    pub fn merged_rectangles() {
+        let grid = Grid::from([
+            [Cell::Live, Cell::Live, Cell::Dead, Cell::Live],
+            [Cell::Live, Cell::Live, Cell::Dead, Cell::Dead],
+            [Cell::Dead, Cell::Live, Cell::Live, Cell::Dead],
+        ]);
+        let svg = grid.to_svg(2.0, Theme::DARK);
+
+        assert!(svg.contains(r#"width="8" height="6""#));
+        assert!(svg.contains(r##"<g fill="#ffffff">"##));
+        assert!(svg.contains(r#"<rect x="0" y="0" width="2" height="2"/>"#));
+        assert!(svg.contains(r#"<rect x="3" y="0" width="1" height="1"/>"#));
+        assert!(svg.contains(r#"<rect x="1" y="2" width="2" height="1"/>"#));
+        assert_eq!(svg.matches("<rect").count(), 4);
+    }
+}
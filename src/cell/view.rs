@@ -0,0 +1,254 @@
+use std::fmt::{self, Display, Formatter, Write};
+use std::ops::{Bound, Index, Range, RangeBounds};
+
+use crate::error::Error;
+
+use super::{Cell, Grid};
+
+/// A rectangular region of a [`Grid`], borrowed instead of copied.
+///
+/// It has the same read API as a grid, with rows and columns counted from the top-left corner
+/// of the region, so analysis code can work on part of a board without copying the whole of it.
+///
+/// # Example
+///
+/// ```
+/// # use vida::cell::{Cell, Grid};
+/// #
+/// let mut grid = Grid::new(10, 10);
+/// grid[(4, 5)] = Cell::Live;
+///
+/// let view = grid.view(3..6, 4..);
+/// assert_eq!(view.shape(), (3, 6));
+/// assert!(view[(1, 1)].is_live());
+/// assert_eq!(view.to_grid(), grid.crop(3..6, 4..));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct GridView<'a> {
+    grid: &'a Grid,
+    rows: Range<usize>,
+    columns: Range<usize>,
+}
+
+impl Grid {
+    #[inline]
+    #[must_use]
+    /// A view of the cells in `rows` and `columns`, like `2..5` or `..`.
+    ///
+    /// # Panics
+    ///
+    /// If the ranges go past the grid, see [`try_view`](Grid::try_view).
+    pub fn view(&self, rows: impl RangeBounds<usize>, columns: impl RangeBounds<usize>) -> GridView<'_> {
+        self.try_view(rows, columns).unwrap_or_else(|error| panic!("{error}"))
+    }
+
+    /// Like [`view`](Grid::view), but fails with [`Error::OutOfBounds`] if the ranges go past the
+    /// grid.
+    pub fn try_view(&self, rows: impl RangeBounds<usize>, columns: impl RangeBounds<usize>) -> Result<GridView<'_>, Error> {
+        let out_of_bounds = |position| Error::OutOfBounds { position, shape: self.shape() };
+        let rows = resolve(rows, self.rows()).ok_or_else(|| out_of_bounds((self.rows(), 0)))?;
+        let columns = resolve(columns, self.columns()).ok_or_else(|| out_of_bounds((0, self.columns())))?;
+        Ok(GridView { grid: self, rows, columns })
+    }
+
+    #[must_use]
+    /// A copy of the cells in `rows` and `columns`.
+    ///
+    /// # Panics
+    ///
+    /// If the ranges go past the grid.
+    pub fn crop(&self, rows: impl RangeBounds<usize>, columns: impl RangeBounds<usize>) -> Self {
+        self.view(rows, columns).to_grid()
+    }
+}
+
+impl<'a> GridView<'a> {
+    #[inline]
+    #[must_use]
+    /// The grid this view borrows from.
+    pub const fn grid(&self) -> &'a Grid {
+        self.grid
+    }
+
+    #[inline]
+    #[must_use]
+    /// Position of the top-left corner of the view in its grid, as `(row, col)`.
+    pub const fn origin(&self) -> (usize, usize) {
+        (self.rows.start, self.columns.start)
+    }
+
+    #[inline]
+    #[must_use]
+    /// Number of rows in the view.
+    pub fn rows(&self) -> usize {
+        self.rows.len()
+    }
+
+    #[inline]
+    #[must_use]
+    /// Number of columns in the view.
+    pub fn columns(&self) -> usize {
+        self.columns.len()
+    }
+
+    #[inline]
+    #[must_use]
+    /// Number of `(rows, columns)` in the view.
+    pub fn shape(&self) -> (usize, usize) {
+        (self.rows(), self.columns())
+    }
+
+    #[inline]
+    #[must_use]
+    /// The total number of cells in the view.
+    pub fn cells(&self) -> usize {
+        self.rows() * self.columns()
+    }
+
+    #[must_use]
+    /// The number of live cells in the view.
+    pub fn population(&self) -> usize {
+        self.iter().map(|row| row.iter().filter(|cell| cell.is_live()).count()).sum()
+    }
+
+    #[inline]
+    #[must_use]
+    /// A row of cells, or [`None`] if it is outside of the view.
+    pub fn get(&self, row: usize) -> Option<&'a [Cell]> {
+        let row = self.rows.start.checked_add(row).filter(|row| self.rows.contains(row))?;
+        Some(&self.grid[row][self.columns.clone()])
+    }
+
+    #[inline]
+    #[must_use]
+    /// A cell, or [`None`] if it is outside of the view.
+    pub fn get_cell(&self, row: usize, col: usize) -> Option<&'a Cell> {
+        self.get(row).and_then(|cells| cells.get(col))
+    }
+
+    #[inline]
+    /// The rows of the view, from top to bottom.
+    pub fn iter(&self) -> impl ExactSizeIterator<Item = &'a [Cell]> + DoubleEndedIterator + '_ {
+        self.rows.clone().map(|row| &self.grid[row][self.columns.clone()])
+    }
+
+    #[inline]
+    #[must_use]
+    /// A smaller view inside this one, with ranges relative to this view.
+    ///
+    /// # Panics
+    ///
+    /// If the ranges go past this view.
+    pub fn view(&self, rows: impl RangeBounds<usize>, columns: impl RangeBounds<usize>) -> GridView<'a> {
+        let out_of_bounds = || Error::OutOfBounds { position: self.shape(), shape: self.shape() };
+        let rows = resolve(rows, self.rows()).unwrap_or_else(|| panic!("{}", out_of_bounds()));
+        let columns = resolve(columns, self.columns()).unwrap_or_else(|| panic!("{}", out_of_bounds()));
+
+        let (top, left) = self.origin();
+        GridView { grid: self.grid, rows: top + rows.start..top + rows.end, columns: left + columns.start..left + columns.end }
+    }
+
+    #[must_use]
+    /// Copies the cells of the view to a new grid.
+    pub fn to_grid(&self) -> Grid {
+        if self.cells() == 0 {
+            return Grid::new(self.rows(), self.columns());
+        }
+        Grid::try_from(self.iter()).expect("rows of a view have the same length")
+    }
+}
+
+impl<'a> From<&'a Grid> for GridView<'a> {
+    #[inline]
+    fn from(grid: &'a Grid) -> Self {
+        grid.view(.., ..)
+    }
+}
+
+impl From<GridView<'_>> for Grid {
+    #[inline]
+    fn from(view: GridView<'_>) -> Self {
+        view.to_grid()
+    }
+}
+
+impl Index<usize> for GridView<'_> {
+    type Output = [Cell];
+
+    #[inline]
+    fn index(&self, row: usize) -> &[Cell] {
+        self.get(row).unwrap_or_else(|| panic!("{}", Error::OutOfBounds { position: (row, 0), shape: self.shape() }))
+    }
+}
+
+impl Index<(usize, usize)> for GridView<'_> {
+    type Output = Cell;
+
+    #[inline]
+    fn index(&self, (row, col): (usize, usize)) -> &Cell {
+        self.get_cell(row, col).unwrap_or_else(|| panic!("{}", Error::OutOfBounds { position: (row, col), shape: self.shape() }))
+    }
+}
+
+impl Display for GridView<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for row in self.iter() {
+            for cell in row {
+                cell.fmt(f)?
+            }
+            f.write_char('\n')?
+        }
+        Ok(())
+    }
+}
+
+/// The range of `0..len` chosen by `range`, or [`None`] if it goes past `len`.
+fn resolve(range: impl RangeBounds<usize>, len: usize) -> Option<Range<usize>> {
+    let start = match range.start_bound() {
+        Bound::Included(&start) => start,
+        Bound::Excluded(&start) => start.checked_add(1)?,
+        Bound::Unbounded => 0,
+    };
+    let end = match range.end_bound() {
+        Bound::Included(&end) => end.checked_add(1)?,
+        Bound::Excluded(&end) => end,
+        Bound::Unbounded => len,
+    };
+    (start <= end && end <= len).then_some(start..end)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn views_and_crops() {
+        let grid = Grid::random_seeded(12, 17, 560);
+        let view = grid.view(2..9, 3..=10);
+        assert_eq!(view.shape(), (7, 8));
+        assert_eq!(view.origin(), (2, 3));
+
+        for row in 0..7 {
+            assert_eq!(&view[row], &grid[row + 2][3..11]);
+            for col in 0..8 {
+                assert_eq!(view[(row, col)], grid[(row + 2, col + 3)]);
+            }
+        }
+        assert_eq!(view.get(7), None);
+        assert_eq!(view.get_cell(0, 8), None);
+        assert_eq!(view.iter().len(), 7);
+
+        let cropped = grid.crop(2..9, 3..=10);
+        assert_eq!(view.to_grid(), cropped);
+        assert_eq!(view.population(), cropped.population());
+        assert_eq!(view.to_string(), cropped.to_string());
+        assert_eq!(view.view(1..3, 2..), grid.view(3..5, 5..11));
+
+        assert_eq!(GridView::from(&grid).to_grid(), grid);
+        assert_eq!(grid.crop(4..4, ..).shape(), (0, 17));
+        assert_eq!(grid.crop(.., 5..5), Grid::new(12, 0));
+        assert!(grid.try_view(..13, ..).is_err());
+        let (start, end) = (10, 5);
+        assert!(grid.try_view(.., start..end).is_err());
+    }
+}
@@ -0,0 +1,172 @@
+//! Saving the state of long runs, to resume them later.
+//!
+//! A checkpoint file is binary, starting with the magic bytes `VIDACKPT` and a version number,
+//! followed by the engine, rule, generation and seed, and then the grid with eight cells per
+//! byte. Integers are little-endian and strings are prefixed by their length. Unlike a
+//! [`Session`](crate::session::Session), it has nothing about the display, and it stays small for
+//! large boards.
+
+use std::fs::{self, File};
+use std::io::{self, BufReader, BufWriter, ErrorKind, Read, Write};
+use std::path::Path;
+
+use crate::cell::Grid;
+use crate::io::MAX_CELLS;
+use crate::rule::Rule;
+
+const MAGIC: &[u8; 8] = b"VIDACKPT";
+const VERSION: u32 = 1;
+
+/// The state of a [`Simulation`](crate::simulation::Simulation) at some generation.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Checkpoint {
+    /// Name of the engine in use, from [`Engine::name`](crate::engine::Engine::name).
+    pub engine: String,
+    /// The rule of the engine, from [`Engine::life_rule`](crate::engine::Engine::life_rule).
+    pub rule: Option<Rule>,
+    /// Generation number of the board.
+    pub generation: usize,
+    /// Seed of the initial random board, if there was one.
+    pub seed: Option<u64>,
+    /// The current board.
+    pub grid: Grid,
+}
+
+impl Checkpoint {
+    /// Writes the checkpoint to a file, replacing it if it already exists.
+    ///
+    /// The checkpoint is first written next to the file and then renamed over it, so an
+    /// interrupted write never loses the previous checkpoint.
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let path = path.as_ref();
+        let mut partial = path.as_os_str().to_owned();
+        partial.push(".partial");
+
+        let mut file = BufWriter::new(File::create(&partial)?);
+        self.write_to(&mut file)?;
+        file.into_inner()?.sync_all()?;
+        fs::rename(partial, path)
+    }
+
+    /// Reads a checkpoint from a file.
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        Self::read_from(BufReader::new(File::open(path)?))
+    }
+
+    /// Writes the checkpoint in the binary format.
+    pub fn write_to<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        writer.write_all(MAGIC)?;
+        writer.write_all(&VERSION.to_le_bytes())?;
+        write_str(&mut writer, &self.engine)?;
+        write_str(&mut writer, &self.rule.map(|rule| rule.to_string()).unwrap_or_default())?;
+        writer.write_all(&(self.generation as u64).to_le_bytes())?;
+        writer.write_all(&[u8::from(self.seed.is_some())])?;
+        writer.write_all(&self.seed.unwrap_or(0).to_le_bytes())?;
+        writer.write_all(&(self.grid.rows() as u64).to_le_bytes())?;
+        writer.write_all(&(self.grid.columns() as u64).to_le_bytes())?;
+        writer.write_all(&self.grid.pack_bits())
+    }
+
+    /// Reads a checkpoint in the binary format.
+    pub fn read_from<R: Read>(mut reader: R) -> io::Result<Self> {
+        let mut magic = [0; MAGIC.len()];
+        reader.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            return Err(invalid("not a vida checkpoint file"));
+        }
+        let version = u32::from_le_bytes(read_array(&mut reader)?);
+        if version != VERSION {
+            return Err(invalid(format!("unsupported checkpoint version {version}")));
+        }
+
+        let engine = read_str(&mut reader)?;
+        let rule = read_str(&mut reader)?;
+        let rule = if rule.is_empty() { None } else { Some(rule.parse().map_err(|_| invalid(format!("invalid rule {rule:?}")))?) };
+        let generation = read_usize(&mut reader)?;
+        let [has_seed] = read_array(&mut reader)?;
+        let seed = u64::from_le_bytes(read_array(&mut reader)?);
+        let (rows, columns) = (read_usize(&mut reader)?, read_usize(&mut reader)?);
+
+        let cells = rows.checked_mul(columns)
+            .filter(|&cells| cells <= MAX_CELLS)
+            .ok_or_else(|| invalid(format!("grid of {rows}x{columns} cells is too large")))?;
+        let mut bytes = vec![0; (cells + 7) / 8];
+        reader.read_exact(&mut bytes)?;
+        let grid = Grid::from_packed_bits(rows, columns, &bytes).ok_or_else(|| invalid("invalid grid"))?;
+
+        Ok(Self { engine, rule, generation, seed: (has_seed != 0).then_some(seed), grid })
+    }
+}
+
+#[inline]
+#[must_use]
+pub(crate) fn invalid(message: impl Into<String>) -> io::Error {
+    io::Error::new(ErrorKind::InvalidData, message.into())
+}
+
+pub(crate) fn write_str<W: Write>(writer: &mut W, string: &str) -> io::Result<()> {
+    let length = u16::try_from(string.len()).map_err(|_| io::Error::new(ErrorKind::InvalidInput, "string too long"))?;
+    writer.write_all(&length.to_le_bytes())?;
+    writer.write_all(string.as_bytes())
+}
+
+pub(crate) fn read_array<R: Read, const N: usize>(reader: &mut R) -> io::Result<[u8; N]> {
+    let mut bytes = [0; N];
+    reader.read_exact(&mut bytes)?;
+    Ok(bytes)
+}
+
+pub(crate) fn read_usize<R: Read>(reader: &mut R) -> io::Result<usize> {
+    usize::try_from(u64::from_le_bytes(read_array(reader)?)).map_err(|_| invalid("number too large"))
+}
+
+pub(crate) fn read_str<R: Read>(reader: &mut R) -> io::Result<String> {
+    let length = u16::from_le_bytes(read_array(reader)?);
+    let mut bytes = vec![0; length.into()];
+    reader.read_exact(&mut bytes)?;
+    String::from_utf8(bytes).map_err(|_| invalid("invalid string"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn round_trip() {
+        let checkpoint = Checkpoint {
+            engine: "sparse".to_owned(),
+            rule: Some(Rule::HIGHLIFE),
+            generation: 1_000_000,
+            seed: Some(544),
+            grid: Grid::random_seeded(9, 21, 544),
+        };
+
+        let mut buffer = Vec::new();
+        checkpoint.write_to(&mut buffer).unwrap();
+        assert_eq!(Checkpoint::read_from(buffer.as_slice()).unwrap(), checkpoint);
+
+        // truncated files and newer versions are rejected
+        assert!(Checkpoint::read_from(&buffer[..buffer.len() - 1]).is_err());
+        buffer[MAGIC.len()] += 1;
+        assert!(Checkpoint::read_from(buffer.as_slice()).is_err());
+
+        let ant = Checkpoint { engine: "ant".to_owned(), rule: None, seed: None, ..checkpoint };
+        let mut buffer = Vec::new();
+        ant.write_to(&mut buffer).unwrap();
+        assert_eq!(Checkpoint::read_from(buffer.as_slice()).unwrap(), ant);
+    }
+
+    #[test]
+    pub fn rejects_huge_grids() {
+        let checkpoint = Checkpoint { engine: "serial".to_owned(), rule: None, generation: 0, seed: None, grid: Grid::new(1, 1) };
+        let mut buffer = Vec::new();
+        checkpoint.write_to(&mut buffer).unwrap();
+
+        // rows and columns are the last numbers before the single byte of cells
+        let shape = buffer.len() - 17;
+        buffer[shape..shape + 8].copy_from_slice(&(1_u64 << 31).to_le_bytes());
+        buffer[shape + 8..shape + 16].copy_from_slice(&(1_u64 << 31).to_le_bytes());
+        let error = Checkpoint::read_from(buffer.as_slice()).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::InvalidData);
+    }
+}
@@ -1,4 +1,15 @@
-use clap::{Parser, ValueEnum};
+use std::path::PathBuf;
+use std::time::Duration;
+
+use clap::builder::{PossibleValuesParser, TypedValueParser};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+
+use vida::bench;
+use vida::engine::Turmite;
+use vida::methuselah::Fitness;
+use vida::patterns::Preset;
+use vida::renderer::MAX_UPDATE_INTERVAL;
+use vida::rule::{BlockRule, LtlRule, Rule, Rule3d};
 
 const CELL_SIZE: f64 = 2.0;
 const GRID_WIDTH: usize = 768;
@@ -7,14 +18,423 @@ const GRID_HEIGHT: usize = 432;
 #[derive(Parser)]
 #[command(author, version, about, long_about = None)]
 pub struct Cli {
-    /// What mode to run the program in.
-    #[arg(value_enum)]
+    #[command(subcommand)]
+    pub command: Command,
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Run a simulation, in a window or without rendering.
+    Run(Box<RunArgs>),
+    /// Time a run without rendering.
+    Bench(Box<BenchArgs>),
+    /// Convert a pattern file to another format.
+    Convert(ConvertArgs),
+    /// Print statistics about a saved session or pattern file.
+    Analyze(AnalyzeArgs),
+    /// Play back a run saved with `run --save-replay`.
+    Replay(ReplayArgs),
+    /// Run many random soups without rendering and report how each one settled, as CSV.
+    Soup(SoupArgs),
+    /// Evolve small patterns into methuselahs that take many generations to settle.
+    Methuselah(MethuselahArgs),
+}
+
+#[derive(Args)]
+pub struct RunArgs {
+    /// What mode to run the program in. Defaults to the engine of the loaded session or checkpoint.
+    #[arg(value_enum, required_unless_present_any = ["load_session", "resume"])]
+    pub mode: Option<Mode>,
+
+    /// Size of the cell.
+    #[arg(short, long, default_value_t = CELL_SIZE)]
+    pub cell_size: f64,
+
+    #[command(flatten)]
+    pub board: BoardArgs,
+
+    #[command(flatten)]
+    pub engine: EngineArgs,
+
+    /// Generations per second while rendering.
+    #[arg(long, conflicts_with = "interval_ms", value_parser = parse_fps)]
+    pub fps: Option<f64>,
+
+    /// Milliseconds between generations while rendering. Defaults to one second.
+    #[arg(long, value_name = "MS")]
+    pub interval_ms: Option<u64>,
+
+    /// Where to draw the simulation.
+    #[arg(long, value_enum, default_value_t = RendererKind::Window)]
+    pub renderer: RendererKind,
+
+    /// Write each generation as a PNG image to this directory, instead of rendering.
+    #[arg(long, value_name = "DIR")]
+    pub record: Option<PathBuf>,
+
+    /// Pixels per cell side in the images written by `--record` and `--heatmap-out`.
+    #[arg(long, default_value_t = 1, value_name = "PIXELS", value_parser = clap::value_parser!(u16).range(1..))]
+    pub record_scale: u16,
+
+    /// Write the run as an animated GIF, instead of rendering. Needs `--iterations`.
+    #[arg(long, value_name = "PATH", requires = "iterations", conflicts_with = "record")]
+    pub gif: Option<PathBuf>,
+
+    /// Pixels per cell side in the GIF.
+    #[arg(long, default_value_t = 2, value_name = "PIXELS", value_parser = clap::value_parser!(u16).range(1..))]
+    pub gif_scale: u16,
+
+    /// Milliseconds between frames of the GIF, in steps of 10.
+    #[arg(long, default_value_t = 100, value_name = "MS")]
+    pub gif_delay: u64,
+
+    /// Keep only one of every N generations in the GIF.
+    #[arg(long, default_value_t = 1, value_name = "N", value_parser = clap::value_parser!(u32).range(1..))]
+    pub gif_every: u32,
+
+    /// Run without rendering, like `--renderer none`.
+    #[arg(short = 'r', long, default_value_t = false)]
+    pub no_render: bool,
+
+    /// Maximum number of iterations.
+    #[arg(short, long, required = false)]
+    pub iterations: Option<usize>,
+
+    /// Stop when there are no live cells left.
+    #[arg(long, default_value_t = false)]
+    pub stop_on_extinction: bool,
+
+    /// Stop when a generation is the same as the previous one.
+    #[arg(long, default_value_t = false)]
+    pub stop_on_stable: bool,
+
+    /// Stop when a generation repeats a recent one, printing the period.
+    #[arg(long, default_value_t = false)]
+    pub stop_on_cycle: bool,
+
+    /// How many recent generations are checked by `--stop-on-cycle`.
+    #[arg(long, default_value_t = 64, value_name = "GENERATIONS")]
+    pub cycle_window: usize,
+
+    /// Grow the board when live cells reach its edges, instead of losing them at the wall.
+    #[arg(long, default_value_t = false, conflicts_with = "gif")]
+    pub expand: bool,
+
+    /// Probability of each cell flipping on every generation, like 0.0001, from a generator seeded
+    /// by `--seed`.
+    #[arg(long, default_value_t = 0.0, hide_default_value = true, value_name = "PROBABILITY", value_parser = parse_probability)]
+    pub noise: f64,
+
+    /// Print a summary of population, births and deaths at the end of a run without rendering.
+    #[arg(long, default_value_t = false)]
+    pub stats: bool,
+
+    /// Write the population, births and deaths of every generation to this CSV file at the end of
+    /// the run, with any renderer.
+    #[arg(long, value_name = "PATH")]
+    pub stats_out: Option<PathBuf>,
+
+    /// Plot the population of the last generations in a strip at the bottom of the window.
+    #[arg(long, default_value_t = false)]
+    pub graph: bool,
+
+    /// Save a PNG heatmap of how often each cell has been alive to this file at the end of the run.
+    /// In the window, `H` shows the same heatmap over the board.
+    #[arg(long, value_name = "PATH")]
+    pub heatmap_out: Option<PathBuf>,
+
+    /// Pin each worker thread to a distinct physical core.
+    #[arg(long, default_value_t = false)]
+    pub pin_threads: bool,
+
+    /// Color live cells by how many generations they have been alive, young ones brighter.
+    #[arg(long, default_value_t = false)]
+    pub color_by_age: bool,
+
+    /// Draw cells that died in the last GENERATIONS generations in a fading gray, leaving trails
+    /// behind moving patterns.
+    #[arg(long, value_name = "GENERATIONS", value_parser = clap::value_parser!(u16).range(1..))]
+    pub trails: Option<u16>,
+
+    /// Generations kept to step back with `B` or the left arrow while paused, in the window or the
+    /// terminal. `0` keeps none.
+    #[arg(long, default_value_t = 256, value_name = "GENERATIONS")]
+    pub rewind: usize,
+
+    /// Memory used by the generations kept for `--rewind`, in MiB. Only the cells that changed are
+    /// kept, so sparse boards fit many more generations.
+    #[arg(long, default_value_t = 64, value_name = "MIB")]
+    pub rewind_memory: usize,
+
+    /// Cells each player places per turn in the immigration game.
+    #[arg(long, default_value_t = 20)]
+    pub turn_cells: usize,
+
+    /// Placement turns for each player in the immigration game.
+    #[arg(long, default_value_t = 3)]
+    pub turns: usize,
+
+    /// Resume from a saved session file.
+    #[arg(long, value_name = "PATH")]
+    pub load_session: Option<PathBuf>,
+
+    /// Where to save the session, with `S` in the window or at the end of a run without rendering.
+    #[arg(long, value_name = "PATH")]
+    pub save_session: Option<PathBuf>,
+
+    /// Write a binary checkpoint here at the end of the run, to continue it with `--resume`.
+    #[arg(long, value_name = "PATH")]
+    pub checkpoint: Option<PathBuf>,
+
+    /// Also write the checkpoint every N generations without rendering, replacing the last one.
+    #[arg(long, value_name = "N", requires = "checkpoint", value_parser = clap::value_parser!(u64).range(1..))]
+    pub checkpoint_every: Option<u64>,
+
+    /// Continue a run from a checkpoint written by `--checkpoint`.
+    #[arg(long, value_name = "PATH", conflicts_with = "load_session")]
+    pub resume: Option<PathBuf>,
+
+    /// Save a replay of the run at its end, to watch it again with `vida replay`.
+    #[arg(long, value_name = "PATH")]
+    pub save_replay: Option<PathBuf>,
+
+    /// Run the engine of MODE side by side with this one, from the same board, with the cells
+    /// where they disagree in red. Without rendering, checks that they agree for `--iterations`
+    /// generations, 100 by default.
+    #[arg(long, value_enum, value_name = "MODE")]
+    pub compare: Option<Mode>,
+}
+
+impl RunArgs {
+    #[must_use]
+    /// The interval between generations chosen with `--fps` or `--interval-ms`.
+    pub fn update_interval(&self) -> Option<Duration> {
+        self.fps.map(|fps| Duration::from_secs_f64(1.0 / fps)).or(self.interval_ms.map(Duration::from_millis))
+    }
+
+    #[must_use]
+    /// The renderer chosen with `--renderer` or `--no-render`.
+    pub fn renderer(&self) -> RendererKind {
+        if self.no_render { RendererKind::None } else { self.renderer }
+    }
+}
+
+#[derive(Args)]
+pub struct BenchArgs {
+    /// The engine to time.
+    #[arg(value_enum, required_unless_present = "matrix")]
+    pub mode: Option<Mode>,
+
+    /// Time every Life engine on standard boards instead: a random soup, a glider gun and an
+    /// empty board, of each size in `--sizes`.
+    #[arg(long, default_value_t = false, conflicts_with = "mode")]
+    pub matrix: bool,
+
+    /// Sides of the square boards timed by `--matrix`.
+    #[arg(long, value_delimiter = ',', default_values_t = bench::SIZES, requires = "matrix")]
+    pub sizes: Vec<usize>,
+
+    #[command(flatten)]
+    pub board: BoardArgs,
+
+    #[command(flatten)]
+    pub engine: EngineArgs,
+
+    /// Number of updates to time.
+    #[arg(short, long, default_value_t = 100)]
+    pub iterations: usize,
+
+    /// How the results are printed.
+    #[arg(long, value_enum, default_value_t = ReportFormat::Text)]
+    pub format: ReportFormat,
+
+    /// Leave out the CSV header, for appending to an existing file.
+    #[arg(long, default_value_t = false)]
+    pub no_header: bool,
+
+    /// Pin each worker thread to a distinct physical core.
+    #[arg(long, default_value_t = false)]
+    pub pin_threads: bool,
+}
+
+#[derive(Args)]
+pub struct SoupArgs {
+    /// The engine running each soup. Soups run concurrently, so a serial engine is usually the
+    /// fastest.
+    #[arg(value_enum, default_value = "bit")]
     pub mode: Mode,
 
+    /// Number of soups to run.
+    #[arg(short = 'n', long, default_value_t = 1000)]
+    pub soups: usize,
+
+    /// Seed of the first soup. The others take the next seeds, so searches can be split in ranges.
+    #[arg(long, default_value_t = 0)]
+    pub seed: u64,
+
+    /// Width of each soup.
+    #[arg(short, long, default_value_t = 64)]
+    pub width: usize,
+
+    /// Height of each soup.
+    #[arg(short = 'H', long, default_value_t = 64)]
+    pub height: usize,
+
+    /// Fraction of live cells in each soup, from 0 to 1.
+    #[arg(long, default_value_t = 0.5, value_parser = parse_probability)]
+    pub density: f64,
+
+    /// Rule of the soups, for the engines that support other rules.
+    #[arg(long, default_value = "B3/S23")]
+    pub rule: Rule,
+
+    /// Soups that haven't settled after this many generations are reported as unsettled.
+    #[arg(long, default_value_t = 10_000)]
+    pub max_generations: usize,
+
+    /// Longest oscillator period detected. Soups with longer periods are reported as unsettled.
+    #[arg(long, default_value_t = 60, value_parser = clap::value_parser!(u16).range(1..))]
+    pub cycle_window: u16,
+
+    /// Where to write the CSV report. Defaults to the standard output.
+    #[arg(short, long, value_name = "PATH")]
+    pub output: Option<PathBuf>,
+
+    /// Leave out the CSV header, for appending to an existing file.
+    #[arg(long, default_value_t = false)]
+    pub no_header: bool,
+
+    /// Also count the blocks, blinkers, gliders and other objects left by the soups.
+    #[arg(long, default_value_t = false)]
+    pub census: bool,
+}
+
+#[derive(Args)]
+pub struct MethuselahArgs {
+    /// The engine evaluating each pattern. Patterns are evaluated concurrently, so a serial
+    /// engine is usually the fastest.
+    #[arg(value_enum, default_value = "sparse")]
+    pub mode: Mode,
+
+    /// Side of the square box the patterns must fit in.
+    #[arg(short, long, default_value_t = 5, value_parser = clap::value_parser!(u16).range(1..))]
+    pub size: u16,
+
+    /// Rounds of mutations.
+    #[arg(short, long, default_value_t = 100)]
+    pub rounds: usize,
+
+    /// Number of patterns kept from one round to the next.
+    #[arg(long, default_value_t = 32)]
+    pub population: usize,
+
+    /// Most cells flipped in each mutation.
+    #[arg(long, default_value_t = 3)]
+    pub mutations: usize,
+
+    /// How patterns are ranked: by the generations until they settle, by the cells left, or by
+    /// the generations for each initial cell.
+    #[arg(long, default_value = "lifespan", value_parser = PossibleValuesParser::new(Fitness::NAMES).map(|name| name.parse::<Fitness>().unwrap()))]
+    pub fitness: Fitness,
+
+    /// Width of the board where patterns are evaluated.
+    #[arg(short, long, default_value_t = 256)]
+    pub width: usize,
+
+    /// Height of the board where patterns are evaluated.
+    #[arg(short = 'H', long, default_value_t = 256)]
+    pub height: usize,
+
+    /// Rule of the patterns, for the engines that support other rules.
+    #[arg(long, default_value = "B3/S23")]
+    pub rule: Rule,
+
+    /// Patterns that haven't settled after this many generations stop there.
+    #[arg(long, default_value_t = 5000)]
+    pub max_generations: usize,
+
+    /// Longest oscillator period detected when patterns settle.
+    #[arg(long, default_value_t = 60, value_parser = clap::value_parser!(u16).range(1..))]
+    pub cycle_window: u16,
+
+    /// Seed of the search, for reproducible runs. Defaults to a random one, which is printed.
+    #[arg(long)]
+    pub seed: Option<u64>,
+
+    /// Directory where the best patterns are written as RLE files.
+    #[arg(short, long, value_name = "DIR", default_value = "methuselahs")]
+    pub output: PathBuf,
+
+    /// Number of patterns written, best first.
+    #[arg(long, default_value_t = 10)]
+    pub keep: usize,
+}
+
+#[derive(Args)]
+pub struct ConvertArgs {
+    /// The pattern file to read, in any supported format.
+    pub input: PathBuf,
+
+    /// Where to write the converted pattern. Defaults to the standard output.
+    #[arg(short, long, value_name = "PATH")]
+    pub output: Option<PathBuf>,
+
+    /// The output format. Defaults to the one matching the output extension, or RLE.
+    #[arg(long, value_enum)]
+    pub to: Option<Format>,
+}
+
+#[derive(Args)]
+pub struct AnalyzeArgs {
+    /// A session file, or a pattern file in any supported format.
+    pub input: PathBuf,
+
+    /// Also simulate this many generations and summarize them.
+    #[arg(short, long, default_value_t = 0)]
+    pub generations: usize,
+
+    /// Rule used for the simulated generations.
+    #[arg(long, default_value = "B3/S23")]
+    pub rule: Rule,
+}
+
+#[derive(Args)]
+pub struct ReplayArgs {
+    /// The replay file.
+    pub input: PathBuf,
+
+    /// Engine that computes the generations again. Defaults to the one of the recorded run.
+    #[arg(long, value_enum)]
+    pub mode: Option<Mode>,
+
     /// Size of the cell.
     #[arg(short, long, default_value_t = CELL_SIZE)]
     pub cell_size: f64,
 
+    /// Generations per second.
+    #[arg(long, conflicts_with = "interval_ms", value_parser = parse_fps)]
+    pub fps: Option<f64>,
+
+    /// Milliseconds between generations. Defaults to one second.
+    #[arg(long, value_name = "MS")]
+    pub interval_ms: Option<u64>,
+
+    /// Where to draw the replay. Without rendering, only the last generation is printed.
+    #[arg(long, value_enum, default_value_t = RendererKind::Window)]
+    pub renderer: RendererKind,
+}
+
+impl ReplayArgs {
+    #[must_use]
+    /// The interval between generations chosen with `--fps` or `--interval-ms`.
+    pub fn update_interval(&self) -> Option<Duration> {
+        self.fps.map(|fps| Duration::from_secs_f64(1.0 / fps)).or(self.interval_ms.map(Duration::from_millis))
+    }
+}
+
+/// How the initial board is made.
+#[derive(Args)]
+pub struct BoardArgs {
     /// Width of the grid.
     #[arg(short, long, default_value_t = GRID_WIDTH)]
     pub width: usize,
@@ -23,13 +443,82 @@ pub struct Cli {
     #[arg(short = 'H', long, default_value_t = GRID_HEIGHT)]
     pub height: usize,
 
-    /// Open window for rendering the game.
-    #[arg(short = 'r', long, default_value_t = false)]
-    pub no_render: bool,
+    /// Number of layers of the space in the life3d mode.
+    #[arg(long, default_value_t = 32)]
+    pub depth: usize,
 
-    /// Maximum number of iterations.
-    #[arg(short, long, required = false)]
-    pub iterations: Option<usize>,
+    /// Start from a pattern file (RLE, plaintext or Life 1.05/1.06) centered on an empty board.
+    #[arg(long, value_name = "PATH")]
+    pub pattern: Option<PathBuf>,
+
+    /// Start from a built-in pattern centered on an empty board.
+    #[arg(long, conflicts_with = "pattern", value_parser = PossibleValuesParser::new(Preset::NAMES).map(|name| name.parse::<Preset>().unwrap()))]
+    pub preset: Option<Preset>,
+
+    /// Moves the pattern from the center of the board by `ROW,COL` cells.
+    #[arg(long, value_name = "ROW,COL", allow_hyphen_values = true, value_parser = parse_offset, default_value = "0,0")]
+    pub offset: (isize, isize),
+
+    /// Fraction of live cells in the random initial board, from 0 to 1.
+    #[arg(long, default_value_t = 0.5, value_parser = parse_probability)]
+    pub density: f64,
+
+    /// Seed for the random initial board, for reproducible runs.
+    #[arg(long)]
+    pub seed: Option<u64>,
+}
+
+/// Settings for the engines of each mode.
+#[derive(Args)]
+pub struct EngineArgs {
+    /// Rule for the serial, parallel, numa, sparse and generations modes, like `B3/S23` or `B36/S23` (HighLife).
+    #[arg(long, default_value = "B3/S23")]
+    pub rule: Rule,
+
+    /// Rule for the ltl mode, like `R5,C0,M1,S34..58,B34..45` (Bosco's Rule).
+    #[arg(long, value_name = "RULE", default_value = "R5,C0,M1,S34..58,B34..45,NM")]
+    pub ltl_rule: LtlRule,
+
+    /// Rule for the margolus mode, `critters`, `billiard-ball`, `tron` or a table of the next
+    /// 2x2 blocks like `M15,14,13,3,11,5,6,1,7,9,10,2,12,4,8,0`.
+    #[arg(long, value_name = "RULE", default_value = "critters")]
+    pub block_rule: BlockRule,
+
+    /// Rule for the life3d mode, like `5766` or `4555`, or `5,7,6,6` for bounds past 9.
+    #[arg(long = "rule-3d", value_name = "RULE", default_value = "5766")]
+    pub rule_3d: Rule3d,
+
+    /// Number of states in the generations mode, with the dying ones fading out, at least 2.
+    #[arg(long, value_name = "N", default_value_t = 4, value_parser = clap::value_parser!(u8).range(2..))]
+    pub states: u8,
+
+    /// Number of colors in the quadlife mode, 2 for Immigration or 4 for QuadLife.
+    #[arg(long, value_name = "N", default_value_t = 4, value_parser = clap::value_parser!(u8).range(2..=4))]
+    pub colors: u8,
+
+    /// Rule for the ant mode, like `RL` (Langton's Ant) or `{{{1,2,1},{0,8,0}},{{1,2,1},{1,1,0}}}`.
+    #[arg(long, default_value = "RL")]
+    pub turmite: Turmite,
+
+    /// Number of ants in the ant mode, placed side by side at the center.
+    #[arg(long, default_value_t = 1)]
+    pub ants: usize,
+
+    /// Steps taken by each ant on each generation.
+    #[arg(long, default_value_t = 100)]
+    pub ant_steps: usize,
+
+    /// Generations advanced on each update by the hashlife mode.
+    #[arg(long, default_value_t = 1)]
+    pub step: usize,
+
+    /// Worker threads for the parallel mode, in a dedicated pool instead of the global one.
+    #[arg(long, value_name = "N")]
+    pub threads: Option<usize>,
+
+    /// Rows in each band of work of the parallel mode. By default, each thread gets a few bands.
+    #[arg(long, value_name = "ROWS", default_value_t = 0, hide_default_value = true)]
+    pub chunk_rows: usize,
 }
 
 #[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
@@ -38,4 +527,118 @@ pub enum Mode {
     Serial,
     /// Parallel Mode
     Parallel,
+    /// Parallel Mode, with one band of rows per NUMA node
+    Numa,
+    /// Serial Mode, on bit-packed rows
+    Bit,
+    /// Serial Mode, only around live cells, fast for mostly empty boards
+    Sparse,
+    /// Gosper's Hashlife algorithm, fast for long runs
+    #[value(name = "hashlife")]
+    HashLife,
+    /// Larger than Life rules, with neighborhoods of any radius
+    Ltl,
+    /// Block automata on the Margolus neighborhood, like Critters
+    Margolus,
+    /// Langton's Ant, or another turmite
+    Ant,
+    /// Two-player Immigration game
+    Immigration,
+    /// Continuous states with Lenia rules
+    Lenia,
+    /// Continuous states with SmoothLife rules
+    #[value(name = "smoothlife")]
+    SmoothLife,
+    /// Multi-state Generations rules, with dying cells fading out
+    Generations,
+    /// Electrons flowing along wires, in Wireworld circuits
+    Wireworld,
+    /// Brian's Brain, with firing cells resting before they can fire again
+    BriansBrain,
+    /// Life with colored cells, newborn ones taking the color of most of their parents
+    #[value(name = "quadlife")]
+    QuadLife,
+    /// Life in three dimensions, shown one slice at a time
+    #[value(name = "life3d")]
+    Life3d,
+}
+
+impl Mode {
+    #[inline]
+    #[must_use]
+    /// Whether the mode runs a two-state rule like Life, including Larger than Life and block
+    /// rules, on a [`Simulation`](vida::simulation::Simulation). The ant mode also runs on a
+    /// simulation, but without a rule for the cells.
+    pub const fn has_life_rule(self) -> bool {
+        matches!(self, Self::Serial | Self::Parallel | Self::Numa | Self::Bit | Self::Sparse | Self::HashLife | Self::Ltl | Self::Margolus)
+    }
+}
+
+/// Ways to show a running simulation.
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
+pub enum RendererKind {
+    /// A window, with mouse and keyboard controls
+    Window,
+    /// Text in the terminal, which also works over SSH
+    Terminal,
+    /// Nothing, just run as fast as possible
+    None,
+}
+
+/// Output formats of the benchmark results.
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
+pub enum ReportFormat {
+    /// Aligned for reading
+    Text,
+    /// One line of comma separated values, after a header
+    Csv,
+    /// A single JSON object
+    Json,
+}
+
+/// Pattern file formats.
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
+pub enum Format {
+    /// Run Length Encoded (`.rle`)
+    Rle,
+    /// Plaintext (`.cells`)
+    Plaintext,
+    /// Life 1.05
+    #[value(name = "life105")]
+    Life105,
+    /// Life 1.06 (`.lif`)
+    #[value(name = "life106")]
+    Life106,
+    /// SVG image, which can't be read back
+    Svg,
+    /// PNG image, white for live cells, which can't be read back
+    #[cfg(feature = "image")]
+    Png,
+}
+
+/// Parses a positive rate of generations per second, no slower than one every
+/// [`MAX_UPDATE_INTERVAL`], so its interval always fits a [`Duration`].
+fn parse_fps(fps: &str) -> Result<f64, String> {
+    let min = 1.0 / MAX_UPDATE_INTERVAL.as_secs_f64();
+    match fps.parse() {
+        Ok(fps) if fps <= 0.0 || !f64::is_finite(fps) => Err("must be a positive number".to_owned()),
+        Ok(fps) if fps < min => Err(format!("must be at least {min}, one generation every {MAX_UPDATE_INTERVAL:?}")),
+        Ok(fps) => Ok(fps),
+        Err(error) => Err(format!("{error}")),
+    }
+}
+
+fn parse_probability(probability: &str) -> Result<f64, String> {
+    match probability.parse() {
+        Ok(probability @ 0.0..=1.0) => Ok(probability),
+        Ok(_) => Err("must be between 0 and 1".to_owned()),
+        Err(error) => Err(format!("{error}")),
+    }
+}
+
+fn parse_offset(offset: &str) -> Result<(isize, isize), String> {
+    let error = || format!("expected ROW,COL, found {offset:?}");
+    let (row, col) = offset.split_once(',').ok_or_else(error)?;
+
+    Ok((row.trim().parse().map_err(|_| error())?, col.trim().parse().map_err(|_| error())?))
 }
@@ -0,0 +1,460 @@
+//! The subcommands other than `run`.
+
+use std::fmt::{self, Display, Formatter};
+use std::fs;
+use std::io::{self as stdio, Write};
+use std::path::Path;
+use std::process::exit;
+use std::time::{Duration, Instant};
+
+use vida::cell::Grid;
+use clap::ValueEnum;
+
+use vida::affinity;
+use vida::analysis::census::Census;
+use vida::bench::{self, Workload};
+use vida::engine::{BitEngine, Engine, HashLifeEngine, LtlEngine, MargolusEngine, ParallelEngine, SerialEngine, SparseEngine};
+use vida::io::{self, life105, life106, plaintext, rle};
+use vida::methuselah::MethuselahSearch;
+use vida::renderer::{Player, TerminalRenderer, WindowRenderer};
+use vida::replay::Replay;
+use vida::rule::Rule;
+use vida::session::Session;
+use vida::simulation::Simulation;
+use vida::soup::{self, Fate, SoupSearch, Tally};
+use vida::theme::Theme;
+
+use crate::cli::{AnalyzeArgs, BenchArgs, ConvertArgs, Format, MethuselahArgs, Mode, RendererKind, ReplayArgs, ReportFormat, SoupArgs};
+use crate::{ant_engine, initial_grid, numa_engine, parallel_engine, report_stop, warn_unsupported, UPDATE_INTERVAL};
+
+/// Scale of each cell when converting to SVG.
+const SVG_CELL_SIZE: f64 = 10.0;
+/// Pixels on each side of a cell when converting to PNG.
+#[cfg(feature = "image")]
+const PNG_CELL_SIZE: u32 = 10;
+
+pub fn bench(args: &BenchArgs) {
+    if args.pin_threads {
+        if let Err(error) = affinity::pin_global_pool() {
+            eprintln!("could not pin worker threads: {error}");
+        }
+    }
+    let Some(mode) = args.mode else {
+        return bench_matrix(args);
+    };
+    warn_unsupported(mode, &args.engine);
+
+    let rule = args.engine.rule;
+    match mode {
+        Mode::Serial => bench_engine(SerialEngine::new(rule), args),
+        Mode::Parallel => bench_engine(parallel_engine(&args.engine, args.pin_threads), args),
+        Mode::Numa => bench_engine(numa_engine().with_rule(rule), args),
+        Mode::Bit => bench_engine(BitEngine, args),
+        Mode::Sparse => bench_engine(SparseEngine::new(rule), args),
+        Mode::Ltl => bench_engine(LtlEngine::new(args.engine.ltl_rule), args),
+        Mode::Margolus => bench_engine(MargolusEngine::new(args.engine.block_rule), args),
+        Mode::HashLife => bench_engine(HashLifeEngine::new().with_step(args.engine.step), args),
+        Mode::Ant => bench_engine(ant_engine(&args.engine, (args.board.height, args.board.width)), args),
+        Mode::Immigration | Mode::Lenia | Mode::SmoothLife | Mode::Generations | Mode::Wireworld | Mode::BriansBrain | Mode::QuadLife | Mode::Life3d => {
+            eprintln!("only the engines of 2D discrete automata can be benchmarked");
+            exit(1)
+        }
+    }
+}
+
+/// Times every engine on the standard boards, see [`bench::run_matrix`].
+fn bench_matrix(args: &BenchArgs) {
+    let engines = bench::standard_engines();
+    let measurements = bench::run_matrix(&engines, &args.sizes, &Workload::ALL, args.iterations);
+
+    if args.format == ReportFormat::Csv && !args.no_header {
+        println!("workload,{}", Report::CSV_HEADER);
+    }
+    for (i, measurement) in measurements.iter().enumerate() {
+        let size = (measurement.size, measurement.size);
+        let report = Report::new(measurement.engine, size, 1, measurement.times.clone()).with_workload(measurement.workload.name());
+        match args.format {
+            ReportFormat::Text if i == 0 => print!("{report}"),
+            ReportFormat::Text => print!("\n{report}"),
+            ReportFormat::Csv => println!("{}", report.to_csv()),
+            ReportFormat::Json => println!("{}", report.to_json()),
+        }
+    }
+}
+
+fn bench_engine<E: Engine>(engine: E, args: &BenchArgs) {
+    let (grid, _) = initial_grid(&engine, &args.board, args.engine.rule);
+    let shape = grid.shape();
+    let mut simulation = Simulation::new(engine, grid);
+
+    let mut times = Vec::with_capacity(args.iterations);
+    for _ in 0..args.iterations {
+        let start = Instant::now();
+        simulation.step();
+        times.push(start.elapsed());
+    }
+
+    let generations_per_update = if args.mode == Some(Mode::HashLife) { args.engine.step } else { 1 };
+    let report = Report::new(simulation.engine().name(), shape, generations_per_update, times);
+    match args.format {
+        ReportFormat::Text => print!("{report}"),
+        ReportFormat::Csv => {
+            if !args.no_header {
+                println!("{}", Report::CSV_HEADER);
+            }
+            println!("{}", report.to_csv());
+        }
+        ReportFormat::Json => println!("{}", report.to_json()),
+    }
+}
+
+/// Timing results of a benchmark.
+struct Report<'a> {
+    engine: &'a str,
+    /// The standard board timed, for reports of `--matrix`.
+    workload: Option<&'a str>,
+    rows: usize,
+    columns: usize,
+    updates: usize,
+    generations: usize,
+    total: Duration,
+    min: Duration,
+    p50: Duration,
+    p90: Duration,
+    p99: Duration,
+    max: Duration,
+}
+
+impl<'a> Report<'a> {
+    const CSV_HEADER: &'static str = "engine,rows,columns,updates,generations,total_s,min_s,p50_s,p90_s,p99_s,max_s,cells_per_s";
+
+    fn new(engine: &'a str, (rows, columns): (usize, usize), generations_per_update: usize, mut times: Vec<Duration>) -> Self {
+        times.sort_unstable();
+        // nearest-rank percentile
+        let percentile = |p: usize| {
+            let rank = (p * times.len()).saturating_sub(1) / 100;
+            times.get(rank).copied().unwrap_or_default()
+        };
+
+        Self {
+            engine,
+            workload: None,
+            rows,
+            columns,
+            updates: times.len(),
+            generations: times.len().saturating_mul(generations_per_update),
+            total: times.iter().sum(),
+            min: times.first().copied().unwrap_or_default(),
+            p50: percentile(50),
+            p90: percentile(90),
+            p99: percentile(99),
+            max: times.last().copied().unwrap_or_default(),
+        }
+    }
+
+    /// Reports the results of a standard board, with its name first.
+    fn with_workload(mut self, workload: &'a str) -> Self {
+        self.workload = Some(workload);
+        self
+    }
+
+    /// Cells computed per second, over all generations.
+    fn throughput(&self) -> f64 {
+        let cells = self.rows as f64 * self.columns as f64 * self.generations as f64;
+        cells / self.total.as_secs_f64().max(f64::MIN_POSITIVE)
+    }
+
+    fn to_csv(&self) -> String {
+        let workload = self.workload.map(|workload| format!("{workload},")).unwrap_or_default();
+        format!(
+            "{workload}{},{},{},{},{},{},{},{},{},{},{},{:.0}",
+            self.engine, self.rows, self.columns, self.updates, self.generations,
+            self.total.as_secs_f64(), self.min.as_secs_f64(), self.p50.as_secs_f64(),
+            self.p90.as_secs_f64(), self.p99.as_secs_f64(), self.max.as_secs_f64(), self.throughput(),
+        )
+    }
+
+    fn to_json(&self) -> String {
+        let workload = self.workload.map(|workload| format!(r#""workload":"{workload}","#)).unwrap_or_default();
+        format!(
+            r#"{{{workload}"engine":"{}","rows":{},"columns":{},"updates":{},"generations":{},"total_s":{},"min_s":{},"p50_s":{},"p90_s":{},"p99_s":{},"max_s":{},"cells_per_s":{:.0}}}"#,
+            self.engine, self.rows, self.columns, self.updates, self.generations,
+            self.total.as_secs_f64(), self.min.as_secs_f64(), self.p50.as_secs_f64(),
+            self.p90.as_secs_f64(), self.p99.as_secs_f64(), self.max.as_secs_f64(), self.throughput(),
+        )
+    }
+}
+
+impl Display for Report<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if let Some(workload) = self.workload {
+            writeln!(f, "workload     {workload}")?;
+        }
+        writeln!(f, "engine       {}", self.engine)?;
+        writeln!(f, "grid         {}x{}", self.rows, self.columns)?;
+        writeln!(f, "updates      {} ({} generations)", self.updates, self.generations)?;
+        writeln!(f, "total        {:?}", self.total)?;
+        writeln!(f, "per update   min {:?}, p50 {:?}, p90 {:?}, p99 {:?}, max {:?}", self.min, self.p50, self.p90, self.p99, self.max)?;
+        writeln!(f, "throughput   {:.3e} cells/s", self.throughput())
+    }
+}
+
+pub fn convert(args: &ConvertArgs) {
+    let pattern = io::load(&args.input).unwrap_or_else(|error| {
+        eprintln!("could not load pattern from {}: {error}", args.input.display());
+        exit(1)
+    });
+
+    let format = args.to
+        .or_else(|| args.output.as_deref().and_then(format_of))
+        .unwrap_or(Format::Rle);
+    let output = match format {
+        Format::Rle => rle::encode(&pattern.grid, pattern.rule.as_deref()).into_bytes(),
+        Format::Plaintext => plaintext::encode(&pattern.grid, pattern.name.as_deref()).into_bytes(),
+        Format::Life105 => life105::encode(&pattern.grid).into_bytes(),
+        Format::Life106 => life106::encode(&pattern.grid).into_bytes(),
+        Format::Svg => pattern.grid.to_svg(SVG_CELL_SIZE, Theme::default()).into_bytes(),
+        #[cfg(feature = "image")]
+        Format::Png => {
+            let mut png = stdio::Cursor::new(Vec::new());
+            if let Err(error) = pattern.grid.to_image(PNG_CELL_SIZE).write_to(&mut png, image::ImageOutputFormat::Png) {
+                eprintln!("could not encode PNG: {error}");
+                exit(1)
+            }
+            png.into_inner()
+        }
+    };
+
+    let result = match &args.output {
+        Some(path) => fs::write(path, output),
+        None => stdio::stdout().write_all(&output),
+    };
+    if let Err(error) = result {
+        eprintln!("could not write converted pattern: {error}");
+        exit(1)
+    }
+}
+
+/// Guesses the format from a file extension.
+fn format_of(path: &Path) -> Option<Format> {
+    match path.extension()?.to_str()?.to_ascii_lowercase().as_str() {
+        "rle" => Some(Format::Rle),
+        "cells" | "txt" => Some(Format::Plaintext),
+        "lif" | "life" => Some(Format::Life106),
+        "svg" => Some(Format::Svg),
+        #[cfg(feature = "image")]
+        "png" => Some(Format::Png),
+        _ => None,
+    }
+}
+
+pub fn analyze(args: &AnalyzeArgs) {
+    // session files are recognized by their header, anything else is read as a pattern
+    let (grid, generation) = match Session::load(&args.input) {
+        Ok(session) => {
+            println!("engine      {}", session.engine);
+            (session.grid, session.generation)
+        }
+        Err(_) => match io::load(&args.input) {
+            Ok(pattern) => (pattern.grid, 0),
+            Err(error) => {
+                eprintln!("could not load {}: {error}", args.input.display());
+                exit(1)
+            }
+        },
+    };
+
+    describe(&grid, generation);
+    if args.generations == 0 {
+        return;
+    }
+
+    let mut simulation = Simulation::new(SerialEngine::new(args.rule), grid)
+        .with_generation(generation)
+        .with_max_generations(generation.saturating_add(args.generations))
+        .stop_on_extinction(true)
+        .stop_on_cycle(Some(args.generations.min(1024)))
+        .collect_stats(true);
+    simulation.run();
+
+    println!();
+    report_stop(&simulation);
+    if let Some(stats) = simulation.stats() {
+        print!("{}", stats.summary());
+    }
+    println!("census      {}", Census::new(simulation.grid()));
+}
+
+fn describe(grid: &Grid, generation: usize) {
+    let (rows, columns) = grid.shape();
+    let population = grid.population();
+    let density = population as f64 / (rows * columns).max(1) as f64;
+
+    println!("generation  {generation}");
+    println!("grid        {rows}x{columns}");
+    println!("population  {population}");
+    println!("density     {density:.4}");
+    println!("census      {}", Census::new(grid));
+}
+
+pub fn replay(args: &ReplayArgs) {
+    let replay = Replay::load(&args.input).unwrap_or_else(|error| {
+        eprintln!("could not load replay from {}: {error}", args.input.display());
+        exit(1)
+    });
+
+    let Some(mode) = args.mode.or_else(|| Mode::from_str(&replay.start.engine, true).ok()) else {
+        eprintln!("unknown engine in replay file, choose a mode explicitly");
+        exit(1)
+    };
+    let rule = replay.start.rule.unwrap_or(Rule::CONWAY);
+    if rule != Rule::CONWAY && matches!(mode, Mode::Bit | Mode::HashLife) {
+        eprintln!("the run used {rule}, which the bit and hashlife modes don't support");
+        exit(1)
+    }
+
+    match mode {
+        Mode::Serial => play_replay(SerialEngine::new(rule), &replay, args),
+        Mode::Parallel => play_replay(ParallelEngine::new(rule), &replay, args),
+        Mode::Numa => play_replay(numa_engine().with_rule(rule), &replay, args),
+        Mode::Bit => play_replay(BitEngine, &replay, args),
+        Mode::Sparse => play_replay(SparseEngine::new(rule), &replay, args),
+        Mode::HashLife => play_replay(HashLifeEngine::new(), &replay, args),
+        Mode::Ltl | Mode::Margolus | Mode::Ant | Mode::Immigration | Mode::Lenia | Mode::SmoothLife | Mode::Generations | Mode::Wireworld | Mode::BriansBrain | Mode::QuadLife | Mode::Life3d => {
+            eprintln!("only runs of Life-like rules can be replayed");
+            exit(1)
+        }
+    }
+}
+
+/// Runs the soups of `--seed` onward and writes their outcomes as CSV, with a summary on the
+/// standard error.
+pub fn soup(args: &SoupArgs) {
+    if args.rule != Rule::CONWAY && args.mode == Mode::Bit {
+        eprintln!("the bit mode only runs {}, choose another mode for {}", Rule::CONWAY, args.rule);
+        exit(1)
+    }
+
+    match args.mode {
+        Mode::Serial => search_soups(SerialEngine::new(args.rule), args),
+        Mode::Parallel => search_soups(ParallelEngine::new(args.rule), args),
+        Mode::Bit => search_soups(BitEngine, args),
+        Mode::Sparse => search_soups(SparseEngine::new(args.rule), args),
+        Mode::Numa | Mode::HashLife | Mode::Ltl | Mode::Margolus | Mode::Ant | Mode::Immigration | Mode::Lenia | Mode::SmoothLife | Mode::Generations | Mode::Wireworld | Mode::BriansBrain | Mode::QuadLife | Mode::Life3d => {
+            eprintln!("soups can only be searched with the serial, parallel, bit and sparse modes");
+            exit(1)
+        }
+    }
+}
+
+fn search_soups<E: Engine + Clone + Sync>(engine: E, args: &SoupArgs) {
+    let search = SoupSearch::new(engine, args.height, args.width)
+        .with_density(args.density)
+        .with_max_generations(args.max_generations)
+        .with_cycle_window(args.cycle_window.into());
+
+    let seeds: Vec<_> = (args.seed..).take(args.soups).collect();
+    let start = Instant::now();
+    let (soups, census) = if args.census { search.run_all_with_census(&seeds) } else { (search.run_all(&seeds), Census::default()) };
+    let elapsed = start.elapsed();
+
+    let written = match &args.output {
+        Some(path) => fs::File::create(path).and_then(|file| soup::write_csv(&soups, !args.no_header, stdio::BufWriter::new(file))),
+        None => soup::write_csv(&soups, !args.no_header, stdio::stdout().lock()),
+    };
+    if let Err(error) = written {
+        eprintln!("could not write the report: {error}");
+        exit(1)
+    }
+
+    eprint!("{}", Tally::new(&soups));
+    if args.census {
+        eprintln!("census       {census}");
+    }
+    eprintln!("time         {elapsed:?}");
+}
+
+/// Evolves methuselahs and writes the best ones as RLE files, listing them on the standard output.
+pub fn methuselah(args: &MethuselahArgs) {
+    if args.rule != Rule::CONWAY && args.mode == Mode::Bit {
+        eprintln!("the bit mode only runs {}, choose another mode for {}", Rule::CONWAY, args.rule);
+        exit(1)
+    }
+
+    match args.mode {
+        Mode::Serial => search_methuselahs(SerialEngine::new(args.rule), args),
+        Mode::Parallel => search_methuselahs(ParallelEngine::new(args.rule), args),
+        Mode::Bit => search_methuselahs(BitEngine, args),
+        Mode::Sparse => search_methuselahs(SparseEngine::new(args.rule), args),
+        Mode::Numa | Mode::HashLife | Mode::Ltl | Mode::Margolus | Mode::Ant | Mode::Immigration | Mode::Lenia | Mode::SmoothLife | Mode::Generations | Mode::Wireworld | Mode::BriansBrain | Mode::QuadLife | Mode::Life3d => {
+            eprintln!("methuselahs can only be searched with the serial, parallel, bit and sparse modes");
+            exit(1)
+        }
+    }
+}
+
+fn search_methuselahs<E: Engine + Clone + Sync>(engine: E, args: &MethuselahArgs) {
+    let search = MethuselahSearch::new(engine, args.size.into())
+        .with_board(args.height, args.width)
+        .with_fitness(args.fitness)
+        .with_max_generations(args.max_generations)
+        .with_cycle_window(args.cycle_window.into())
+        .with_population(args.population)
+        .with_mutations(args.mutations);
+
+    let seed = args.seed.unwrap_or_else(rand::random);
+    eprintln!("searching with seed {seed}");
+    let start = Instant::now();
+    let best = search.search(args.rounds, seed);
+    eprintln!("{} rounds in {:?}", args.rounds, start.elapsed());
+
+    if let Err(error) = fs::create_dir_all(&args.output) {
+        eprintln!("could not create {}: {error}", args.output.display());
+        exit(1)
+    }
+
+    println!("rank  lifespan  cells  final  fate         file");
+    let rule = args.rule.to_string();
+    for (rank, candidate) in best.iter().take(args.keep).enumerate().map(|(rank, candidate)| (rank + 1, candidate)) {
+        let fate = match candidate.fate {
+            Fate::Oscillating(period) => format!("p{period}"),
+            fate => fate.name().to_owned(),
+        };
+        let path = args.output.join(format!("{rank:03}.rle"));
+        let rle = format!(
+            "#N methuselah {rank}\n#C lifespan {}, {} cells, {} at the end, {fate}, seed {seed}\n{}",
+            candidate.lifespan, candidate.initial_population, candidate.final_population, rle::encode(&candidate.pattern, Some(&rule)),
+        );
+        if let Err(error) = fs::write(&path, rle) {
+            eprintln!("could not write {}: {error}", path.display());
+            exit(1)
+        }
+
+        println!(
+            "{rank:>4}  {:>8}  {:>5}  {:>5}  {fate:<11}  {}",
+            candidate.lifespan, candidate.initial_population, candidate.final_population, path.display(),
+        );
+    }
+}
+
+fn play_replay<E: Engine + Send + 'static>(engine: E, replay: &Replay, args: &ReplayArgs) {
+    let mut simulation = replay.simulation(engine);
+    let update_interval = args.update_interval().unwrap_or(UPDATE_INTERVAL);
+
+    let played = match args.renderer {
+        RendererKind::Window => Player::new(WindowRenderer::new(args.cell_size), simulation, update_interval).run(),
+        RendererKind::Terminal => Player::new(TerminalRenderer::new(), simulation, update_interval).run(),
+        RendererKind::None => {
+            simulation.run();
+            Ok(simulation)
+        }
+    };
+
+    match played {
+        Ok(simulation) => describe(simulation.grid(), simulation.generation()),
+        Err(error) => {
+            eprintln!("could not render the replay: {error}");
+            exit(1)
+        }
+    }
+}
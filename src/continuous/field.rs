@@ -0,0 +1,136 @@
+use std::ops::{Index, IndexMut};
+
+use rand::Rng;
+
+use crate::cell::{Cell, Grid};
+
+/// A 2D matrix of continuous cell states, each in `0.0 ..= 1.0`.
+///
+/// The layout mirrors [`Grid`]: cells are stored in row-major order in a single buffer.
+#[derive(Debug, Clone, PartialEq, PartialOrd)]
+pub struct Field {
+    values: Box<[f32]>,
+    columns: usize,
+}
+
+impl Field {
+    #[inline]
+    #[must_use]
+    /// Creates a field of `(rows, columns)` empty cells.
+    ///
+    /// # Panics
+    ///
+    /// If `rows * columns` overflows an `usize`.
+    pub fn new(rows: usize, columns: usize) -> Self {
+        let cells = rows.checked_mul(columns).expect("number of cells overflows usize");
+
+        Self { values: vec![0.0; cells].into(), columns }
+    }
+
+    #[must_use]
+    /// Creates a field of `(rows, columns)` cells with uniformly random states.
+    pub fn random_with<R: Rng + ?Sized>(rows: usize, columns: usize, rng: &mut R) -> Self {
+        let mut field = Self::new(rows, columns);
+        for value in field.values.iter_mut() {
+            *value = rng.gen()
+        }
+        field
+    }
+
+    #[inline]
+    #[must_use]
+    /// Wraps an already filled buffer of cells, in row-major order.
+    pub(crate) fn from_values(values: Box<[f32]>, columns: usize) -> Self {
+        assert!(values.len() % columns.max(1) == 0, "buffer with incomplete rows");
+
+        Self { values, columns }
+    }
+
+    #[inline]
+    #[must_use]
+    /// The number of rows in the field.
+    pub const fn rows(&self) -> usize {
+        match self.values.len().checked_div(self.columns) {
+            Some(rows) => rows,
+            None => 0,
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    /// The number of columns in each row of the field.
+    pub const fn columns(&self) -> usize {
+        self.columns
+    }
+
+    #[inline]
+    #[must_use]
+    /// The shape `(rows, columns)` of the field.
+    pub const fn shape(&self) -> (usize, usize) {
+        (self.rows(), self.columns())
+    }
+
+    #[inline]
+    #[must_use]
+    /// A slice over all the cells in the field, row-major order.
+    pub const fn flat(&self) -> &[f32] {
+        &self.values
+    }
+
+    #[inline]
+    #[must_use]
+    /// A mutable slice over all the cells in the field, row-major order.
+    pub fn flat_mut(&mut self) -> &mut [f32] {
+        &mut self.values
+    }
+
+    #[inline]
+    /// Iterates over the rows of the field.
+    pub fn iter(&self) -> std::slice::ChunksExact<'_, f32> {
+        self.values.chunks_exact(self.columns.max(1))
+    }
+
+    #[inline]
+    #[must_use]
+    /// The total mass of the field, the sum of all states.
+    pub fn mass(&self) -> f32 {
+        self.values.iter().sum()
+    }
+
+    #[must_use]
+    /// Converts into a binary grid, with cells at or above `threshold` considered live.
+    pub fn to_grid(&self, threshold: f32) -> Grid {
+        let cells = self.values.iter()
+            .map(|&value| if value >= threshold { Cell::Live } else { Cell::Dead })
+            .collect();
+
+        Grid::from_cells(cells, self.columns)
+    }
+}
+
+impl From<&Grid> for Field {
+    #[inline]
+    fn from(grid: &Grid) -> Self {
+        let values = grid.flat().iter().map(|cell| if cell.is_live() { 1.0 } else { 0.0 }).collect();
+
+        Self { values, columns: grid.columns() }
+    }
+}
+
+impl Index<(usize, usize)> for Field {
+    type Output = f32;
+
+    #[inline]
+    fn index(&self, (row, col): (usize, usize)) -> &f32 {
+        assert!(col < self.columns, "column out of bounds");
+        &self.values[row * self.columns + col]
+    }
+}
+
+impl IndexMut<(usize, usize)> for Field {
+    #[inline]
+    fn index_mut(&mut self, (row, col): (usize, usize)) -> &mut f32 {
+        assert!(col < self.columns, "column out of bounds");
+        &mut self.values[row * self.columns + col]
+    }
+}
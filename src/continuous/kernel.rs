@@ -0,0 +1,91 @@
+use crate::par::*;
+
+use super::Field;
+
+/// A convolution kernel, stored as the list of its non-zero weights.
+///
+/// Radially symmetric kernels are mostly empty outside a ring, so keeping only the non-zero
+/// offsets makes direct convolution practical for the radii used by Lenia and SmoothLife.
+#[derive(Debug, Clone, PartialEq, PartialOrd)]
+pub struct Kernel {
+    weights: Vec<(isize, isize, f32)>,
+}
+
+impl Kernel {
+    #[must_use]
+    /// Builds a radially symmetric kernel of the given radius.
+    ///
+    /// `profile` maps the distance from the center, normalized to `0.0 ..= 1.0`, into a weight.
+    /// The weights are normalized to sum to one, unless they are all zero.
+    pub fn radial(radius: usize, profile: impl Fn(f32) -> f32) -> Self {
+        let reach = radius as isize;
+        let mut weights = Vec::new();
+
+        for dy in -reach ..= reach {
+            for dx in -reach ..= reach {
+                let distance = ((dy * dy + dx * dx) as f32).sqrt() / radius.max(1) as f32;
+                let weight = profile(distance);
+                if weight > 0.0 {
+                    weights.push((dy, dx, weight));
+                }
+            }
+        }
+
+        let total: f32 = weights.iter().map(|&(_, _, weight)| weight).sum();
+        if total > 0.0 {
+            for (_, _, weight) in weights.iter_mut() {
+                *weight /= total;
+            }
+        }
+        Self { weights }
+    }
+
+    #[must_use]
+    /// A disk with anti-aliased border, covering distances `inner ..= outer` in cells.
+    pub fn ring(inner: f32, outer: f32) -> Self {
+        let radius = outer.ceil() as usize + 1;
+        Self::radial(radius, |distance| {
+            let distance = distance * radius as f32;
+            let outside = (outer + 0.5 - distance).clamp(0.0, 1.0);
+            let inside = if inner > 0.0 { (distance - inner + 0.5).clamp(0.0, 1.0) } else { 1.0 };
+            outside * inside
+        })
+    }
+
+    #[inline]
+    #[must_use]
+    /// The non-zero weights, as `(row offset, column offset, weight)`.
+    pub fn weights(&self) -> &[(isize, isize, f32)] {
+        &self.weights
+    }
+
+    #[must_use]
+    /// Convolves the field with this kernel, wrapping around the edges.
+    pub fn convolve(&self, field: &Field) -> Field {
+        let (rows, columns) = field.shape();
+        let mut output = vec![0.0; rows * columns].into_boxed_slice();
+        if output.is_empty() {
+            return Field::from_values(output, columns);
+        }
+
+        let (irows, icolumns) = (rows as isize, columns as isize);
+        output.par_chunks_exact_mut(columns).enumerate().for_each(|(row, values)| {
+            for &(dy, dx, weight) in &self.weights {
+                let source = (row as isize + dy).rem_euclid(irows) as usize;
+                let source = &field.flat()[source * columns..][..columns];
+                let shift = dx.rem_euclid(icolumns) as usize;
+
+                // output[col] += weight * source[(col + shift) % columns], without the modulo
+                let (head, tail) = values.split_at_mut(columns - shift);
+                for (value, &input) in head.iter_mut().zip(&source[shift..]) {
+                    *value += weight * input;
+                }
+                for (value, &input) in tail.iter_mut().zip(&source[..shift]) {
+                    *value += weight * input;
+                }
+            }
+        });
+
+        Field::from_values(output, columns)
+    }
+}
@@ -0,0 +1,197 @@
+//! Continuous-state cellular automata, like Lenia and SmoothLife.
+//!
+//! Cells hold a real value in `0.0 ..= 1.0` instead of being dead or live, and the neighborhood is
+//! a smooth convolution [`Kernel`] instead of the 3×3 Moore window. Space wraps around the edges.
+
+use crate::par::*;
+
+mod field;
+mod kernel;
+
+pub use field::Field;
+pub use kernel::Kernel;
+
+/// Computes generations of a continuous automaton.
+pub trait ContinuousEngine {
+    #[must_use]
+    fn update(&self, field: &Field) -> Field;
+
+    #[must_use]
+    /// The field after `generations` updates, or the same field with zero.
+    fn advance(&self, mut field: Field, generations: usize) -> Field {
+        for _ in 0..generations {
+            field = self.update(&field);
+        }
+        field
+    }
+
+    #[must_use]
+    /// Short identifier for the engine, as used in the command line.
+    fn name(&self) -> &'static str;
+}
+
+/// Lenia, with a single ring kernel and gaussian growth.
+///
+/// Each step computes the potential `U = K * A` and moves every cell towards
+/// `A + dt * G(U)`, clipped to `0.0 ..= 1.0`, where `G` is a bell curve centered at `mu`.
+#[derive(Debug, Clone, PartialEq, PartialOrd)]
+pub struct Lenia {
+    kernel: Kernel,
+    mu: f32,
+    sigma: f32,
+    dt: f32,
+}
+
+impl Lenia {
+    #[must_use]
+    /// Creates the Lenia automaton with a kernel of the given radius and growth parameters.
+    pub fn new(radius: usize, mu: f32, sigma: f32, dt: f32) -> Self {
+        let kernel = Kernel::radial(radius, |r| {
+            if 0.0 < r && r < 1.0 {
+                (4.0 - 1.0 / (r * (1.0 - r))).exp()
+            } else {
+                0.0
+            }
+        });
+
+        Self { kernel, mu, sigma, dt }
+    }
+
+    #[inline]
+    #[must_use]
+    /// The growth mapping, from potential to rate of change in `-1.0 ..= 1.0`.
+    pub fn growth(&self, potential: f32) -> f32 {
+        let x = (potential - self.mu) / self.sigma;
+        2.0 * (-x * x / 2.0).exp() - 1.0
+    }
+}
+
+impl Default for Lenia {
+    #[inline]
+    /// The parameters of *Orbium*, the best known Lenia glider.
+    fn default() -> Self {
+        Self::new(13, 0.15, 0.015, 0.1)
+    }
+}
+
+impl ContinuousEngine for Lenia {
+    fn update(&self, field: &Field) -> Field {
+        let mut next = self.kernel.convolve(field);
+
+        next.flat_mut().par_iter_mut().zip(field.flat()).for_each(|(value, &current)| {
+            *value = (current + self.dt * self.growth(*value)).clamp(0.0, 1.0);
+        });
+        next
+    }
+
+    #[inline]
+    fn name(&self) -> &'static str {
+        "lenia"
+    }
+}
+
+/// SmoothLife, from Rafler's "Generalization of Conway's Game of Life to a continuous domain".
+///
+/// The inner disk plays the role of the cell and the outer ring the role of its neighbors. The new
+/// state is a smooth version of the birth and survival intervals of Life.
+#[derive(Debug, Clone, PartialEq, PartialOrd)]
+pub struct SmoothLife {
+    inner: Kernel,
+    outer: Kernel,
+    birth: (f32, f32),
+    survival: (f32, f32),
+    alpha_n: f32,
+    alpha_m: f32,
+}
+
+impl SmoothLife {
+    #[must_use]
+    /// Creates SmoothLife with outer radius `radius` and the given birth and survival intervals.
+    pub fn new(radius: f32, birth: (f32, f32), survival: (f32, f32)) -> Self {
+        let inner_radius = radius / 3.0;
+        let inner = Kernel::ring(0.0, inner_radius);
+        let outer = Kernel::ring(inner_radius, radius);
+
+        Self { inner, outer, birth, survival, alpha_n: 0.028, alpha_m: 0.147 }
+    }
+
+    #[inline]
+    #[must_use]
+    /// The transition function, from ring filling `n` and disk filling `m` into the new state.
+    pub fn transition(&self, n: f32, m: f32) -> f32 {
+        let sigma = |x: f32, a: f32, alpha: f32| 1.0 / (1.0 + (-(x - a) * 4.0 / alpha).exp());
+        let sigma_n = |x: f32, a: f32, b: f32| sigma(x, a, self.alpha_n) * (1.0 - sigma(x, b, self.alpha_n));
+        let sigma_m = |x: f32, y: f32| {
+            let alive = sigma(m, 0.5, self.alpha_m);
+            x * (1.0 - alive) + y * alive
+        };
+
+        sigma_n(n, sigma_m(self.birth.0, self.survival.0), sigma_m(self.birth.1, self.survival.1))
+    }
+}
+
+impl Default for SmoothLife {
+    #[inline]
+    fn default() -> Self {
+        Self::new(12.0, (0.278, 0.365), (0.267, 0.445))
+    }
+}
+
+impl ContinuousEngine for SmoothLife {
+    fn update(&self, field: &Field) -> Field {
+        let mut next = self.outer.convolve(field);
+        let inner = self.inner.convolve(field);
+
+        next.flat_mut().par_iter_mut().zip(inner.flat()).for_each(|(value, &m)| {
+            *value = self.transition(*value, m).clamp(0.0, 1.0);
+        });
+        next
+    }
+
+    #[inline]
+    fn name(&self) -> &'static str {
+        "smoothlife"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand::rngs::SmallRng;
+
+    use super::*;
+
+    #[test]
+    pub fn convolution_wraps_around() {
+        let mut field = Field::new(9, 11);
+        field[(0, 0)] = 1.0;
+
+        let kernel = Kernel::ring(0.0, 1.0);
+        let total: f32 = kernel.weights().iter().map(|&(_, _, weight)| weight).sum();
+        assert!((total - 1.0).abs() < 1e-5);
+
+        let blurred = kernel.convolve(&field);
+        assert!((blurred.mass() - 1.0).abs() < 1e-5);
+        assert!(blurred[(8, 10)] > 0.0);
+        assert!(blurred[(0, 10)] > 0.0);
+        assert_eq!(blurred[(4, 5)], 0.0);
+    }
+
+    #[test]
+    pub fn empty_field_stays_empty() {
+        let field = Field::new(32, 32);
+
+        assert_eq!(Lenia::default().update(&field), field);
+        assert!(SmoothLife::default().update(&field).mass() < 1e-6);
+    }
+
+    #[test]
+    pub fn states_stay_in_range() {
+        let mut rng = SmallRng::seed_from_u64(494);
+        let field = Field::random_with(40, 50, &mut rng);
+
+        for next in [Lenia::default().update(&field), SmoothLife::default().update(&field)] {
+            assert!(next.flat().iter().all(|value| (0.0..=1.0).contains(value)));
+        }
+    }
+}
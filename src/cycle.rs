@@ -0,0 +1,107 @@
+//! Detecting when a simulation settles into still lifes and oscillators.
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::VecDeque;
+use std::hash::{Hash, Hasher};
+
+use crate::cell::Grid;
+
+/// Remembers the hashes of the last few generations, to find when one of them repeats.
+///
+/// Only the hashes are kept, so a long history is cheap, but a hash collision could report a cycle
+/// that doesn't exist. With 64-bit hashes, that is very unlikely.
+///
+/// # Example
+///
+/// ```
+/// # use vida::cell::{Cell, Grid};
+/// # use vida::cycle::CycleDetector;
+/// # use vida::engine::{Engine, SerialEngine};
+/// #
+/// let mut blinker = Grid::new(5, 5);
+/// blinker[2][1..4].fill(Cell::Live);
+///
+/// let mut detector = CycleDetector::new(10);
+/// assert_eq!(detector.push(&blinker), None);
+///
+/// let blinker = SerialEngine::default().update(&blinker);
+/// assert_eq!(detector.push(&blinker), None);
+///
+/// let blinker = SerialEngine::default().update(&blinker);
+/// assert_eq!(detector.push(&blinker), Some(2));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CycleDetector {
+    window: usize,
+    history: VecDeque<u64>,
+}
+
+impl CycleDetector {
+    #[inline]
+    #[must_use]
+    /// Creates a detector for cycles with period up to `window` generations.
+    pub fn new(window: usize) -> Self {
+        Self { window, history: VecDeque::with_capacity(window) }
+    }
+
+    #[inline]
+    #[must_use]
+    /// The longest period that can be detected.
+    pub const fn window(&self) -> usize {
+        self.window
+    }
+
+    /// Forgets all generations seen so far.
+    pub fn clear(&mut self) {
+        self.history.clear();
+    }
+
+    /// Records the next generation.
+    ///
+    /// Returns the period when the grid repeats one of the last `window` generations, which is 1
+    /// for still lifes.
+    pub fn push(&mut self, grid: &Grid) -> Option<usize> {
+        if self.window == 0 {
+            return None;
+        }
+
+        let mut hasher = DefaultHasher::new();
+        grid.hash(&mut hasher);
+        let hash = hasher.finish();
+
+        let period = self.history.iter().rev().position(|&previous| previous == hash).map(|index| index + 1);
+        if self.history.len() >= self.window {
+            self.history.pop_front();
+        }
+        self.history.push_back(hash);
+        period
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cell::Cell;
+    use crate::engine::{Engine, SerialEngine};
+    use crate::simulation::{Simulation, StopReason};
+
+    use super::*;
+
+    #[test]
+    pub fn periods_up_to_the_window() {
+        // a block and a toad, with periods 1 and 2, and a lone cell that dies first
+        let mut grid = Grid::new(12, 12);
+        for (row, col) in [(1, 1), (1, 2), (2, 1), (2, 2), (7, 6), (7, 7), (7, 8), (8, 5), (8, 6), (8, 7), (10, 1)] {
+            grid[(row, col)] = Cell::Live;
+        }
+
+        let mut simulation = Simulation::new(SerialEngine::default(), grid.clone()).stop_on_cycle(Some(8));
+        assert_eq!(simulation.run(), StopReason::Cycle(2));
+        assert_eq!(simulation.generation(), 3);
+
+        let mut detector = CycleDetector::new(1);
+        let toad = SerialEngine::default().update(&grid);
+        assert_eq!(detector.push(&toad), None);
+        assert_eq!(detector.push(&SerialEngine::default().update(&toad)), None);
+        assert_eq!(detector.push(&toad), None);
+    }
+}
@@ -0,0 +1,211 @@
+//! Changes made to a board by hand, which can be undone and redone.
+
+use crate::cell::{Grid, Overflow};
+
+/// A rectangle of cells replaced on a board, like a toggled cell, a pasted pattern or a cleared
+/// region, with what was there before.
+///
+/// # Example
+///
+/// ```
+/// # use vida::cell::{Cell, Grid};
+/// # use vida::edit::Edit;
+/// #
+/// let mut board = Grid::new(3, 3);
+/// let edit = Edit::new(&board, Grid::new_with(2, 2, Cell::Live), (2, 1)).unwrap();
+///
+/// // the cells past the edges are left out
+/// assert_eq!(edit.after().shape(), (1, 2));
+/// edit.apply(&mut board);
+/// assert_eq!(board.population(), 2);
+/// edit.revert(&mut board);
+/// assert_eq!(board.population(), 0);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Edit {
+    position: (usize, usize),
+    before: Grid,
+    after: Grid,
+}
+
+impl Edit {
+    #[must_use]
+    /// Replaces the cells of `grid` under `pattern`, with its top-left corner at `(row, col)`, or
+    /// [`None`] if no cell of the pattern falls inside the grid.
+    pub fn new(grid: &Grid, pattern: Grid, (row, col): (usize, usize)) -> Option<Self> {
+        let rows = pattern.rows().min(grid.rows().saturating_sub(row));
+        let columns = pattern.columns().min(grid.columns().saturating_sub(col));
+        if rows == 0 || columns == 0 {
+            return None;
+        }
+
+        let after = if (rows, columns) == pattern.shape() { pattern } else { pattern.crop(..rows, ..columns) };
+        let before = grid.crop(row..row + rows, col..col + columns);
+        Some(Self { position: (row, col), before, after })
+    }
+
+    #[inline]
+    #[must_use]
+    /// Position `(row, col)` of the top-left corner of the rectangle.
+    pub const fn position(&self) -> (usize, usize) {
+        self.position
+    }
+
+    #[inline]
+    #[must_use]
+    /// The cells in the rectangle before the edit.
+    pub const fn before(&self) -> &Grid {
+        &self.before
+    }
+
+    #[inline]
+    #[must_use]
+    /// The cells in the rectangle after the edit.
+    pub const fn after(&self) -> &Grid {
+        &self.after
+    }
+
+    /// Makes the edit on a board, where the rectangle is left out if it doesn't fit anymore.
+    pub fn apply(&self, grid: &mut Grid) {
+        let _ = grid.blit(&self.after, self.position.0, self.position.1, Overflow::Clip);
+    }
+
+    /// Puts back the cells from before the edit.
+    pub fn revert(&self, grid: &mut Grid) {
+        let _ = grid.blit(&self.before, self.position.0, self.position.1, Overflow::Clip);
+    }
+}
+
+/// The edits made to a board, in order, and the ones undone since the last edit.
+///
+/// This is separate from the generations of a simulation: it only makes sense while the board is
+/// the one left by the last edit, so it should be [cleared](EditHistory::clear) when the board
+/// changes in any other way.
+///
+/// # Example
+///
+/// ```
+/// # use vida::cell::{Cell, Grid};
+/// # use vida::edit::{Edit, EditHistory};
+/// #
+/// let mut board = Grid::new(4, 4);
+/// let mut history = EditHistory::default();
+///
+/// for col in 0..3 {
+///     let edit = Edit::new(&board, Grid::new_with(1, 1, Cell::Live), (1, col)).unwrap();
+///     edit.apply(&mut board);
+///     history.push(edit);
+/// }
+///
+/// history.undo().unwrap().revert(&mut board);
+/// history.undo().unwrap().revert(&mut board);
+/// history.redo().unwrap().apply(&mut board);
+/// assert_eq!(board.population(), 2);
+/// assert_eq!((history.undo_len(), history.redo_len()), (2, 1));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct EditHistory {
+    done: Vec<Edit>,
+    undone: Vec<Edit>,
+}
+
+impl EditHistory {
+    /// Records an edit that was just made, forgetting the ones undone before it.
+    pub fn push(&mut self, edit: Edit) {
+        self.done.push(edit);
+        self.undone.clear();
+    }
+
+    /// The last edit made, which should now be [reverted](Edit::revert), or [`None`] if there is
+    /// nothing to undo.
+    pub fn undo(&mut self) -> Option<&Edit> {
+        let edit = self.done.pop()?;
+        self.undone.push(edit);
+        self.undone.last()
+    }
+
+    /// The last edit undone, which should now be [applied](Edit::apply) again, or [`None`] if
+    /// there is nothing to redo.
+    pub fn redo(&mut self) -> Option<&Edit> {
+        let edit = self.undone.pop()?;
+        self.done.push(edit);
+        self.done.last()
+    }
+
+    /// Forgets every edit.
+    pub fn clear(&mut self) {
+        self.done.clear();
+        self.undone.clear();
+    }
+
+    #[inline]
+    #[must_use]
+    /// Number of edits that can be undone.
+    pub fn undo_len(&self) -> usize {
+        self.done.len()
+    }
+
+    #[inline]
+    #[must_use]
+    /// Number of edits that can be redone.
+    pub fn redo_len(&self) -> usize {
+        self.undone.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cell::Cell;
+    use crate::patterns;
+
+    use super::*;
+
+    #[test]
+    pub fn undo_and_redo_in_order() {
+        let mut board = Grid::new(6, 6);
+        let mut history = EditHistory::default();
+        let mut edit = |board: &mut Grid, pattern: Grid, position| {
+            let edit = Edit::new(board, pattern, position).unwrap();
+            edit.apply(board);
+            history.push(edit);
+        };
+
+        edit(&mut board, patterns::glider(), (0, 0));
+        let glider = board.clone();
+        edit(&mut board, Grid::new_with(2, 2, Cell::Live), (1, 1));
+        edit(&mut board, Grid::new(6, 6), (0, 0));
+        assert_eq!(board.population(), 0);
+
+        while let Some(edit) = history.undo() {
+            edit.revert(&mut board);
+            if history.undo_len() == 1 {
+                assert_eq!(board, glider);
+            }
+        }
+        assert_eq!(board.population(), 0);
+        assert_eq!((history.undo_len(), history.redo_len()), (0, 3));
+
+        history.redo().unwrap().apply(&mut board);
+        assert_eq!(board, glider);
+
+        // a new edit can't be followed by the ones undone before it
+        let toggle = Edit::new(&board, Grid::new_with(1, 1, Cell::Live), (5, 5)).unwrap();
+        assert_eq!(toggle.before()[(0, 0)], Cell::Dead);
+        history.push(toggle);
+        assert_eq!((history.undo_len(), history.redo_len()), (2, 0));
+        assert!(history.redo().is_none());
+
+        history.clear();
+        assert!(history.undo().is_none());
+    }
+
+    #[test]
+    pub fn outside_of_the_board() {
+        let board = Grid::new(3, 3);
+        assert!(Edit::new(&board, Grid::new(2, 2), (3, 0)).is_none());
+        assert!(Edit::new(&board, Grid::new(0, 2), (0, 0)).is_none());
+
+        let edit = Edit::new(&board, Grid::new_with(4, 4, Cell::Live), (1, 0)).unwrap();
+        assert_eq!((edit.position(), edit.before().shape(), edit.after().shape()), ((1, 0), (2, 3), (2, 3)));
+    }
+}
@@ -0,0 +1,358 @@
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+use std::sync::Mutex;
+
+use crate::cell::{Cell, Grid};
+
+use super::Engine;
+
+/// Heading of an ant on the grid.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub enum Direction {
+    #[default]
+    Up,
+    Right,
+    Down,
+    Left,
+}
+
+impl Direction {
+    #[inline]
+    #[must_use]
+    /// The direction after turning.
+    pub const fn turn(self, turn: Turn) -> Self {
+        let quarters = match turn {
+            Turn::None => 0,
+            Turn::Right => 1,
+            Turn::UTurn => 2,
+            Turn::Left => 3,
+        };
+
+        match (self as u8 + quarters) % 4 {
+            0 => Self::Up,
+            1 => Self::Right,
+            2 => Self::Down,
+            _ => Self::Left,
+        }
+    }
+}
+
+/// A relative turn.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub enum Turn {
+    #[default]
+    None,
+    Right,
+    UTurn,
+    Left,
+}
+
+/// What a turmite does when it finds a cell, in a given internal state.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Transition {
+    /// New value for the cell.
+    pub write: Cell,
+    /// Turn taken before moving forward.
+    pub turn: Turn,
+    /// Next internal state of the turmite.
+    pub next_state: usize,
+}
+
+/// The transition table of a two-color turmite, indexed by internal state and then by cell.
+///
+/// Rules can be parsed from the relative turn notation of Langton's Ant (`RL`, one turn for each
+/// cell state) or from the notation used by Golly and Ed Pegg Jr., where
+/// `{{{1,2,0},{0,8,0}}}` lists `{write, turn, next state}` for each state and color, with turns
+/// encoded as 1 (none), 2 (right), 4 (u-turn) and 8 (left).
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Turmite {
+    table: Vec<[Transition; 2]>,
+}
+
+impl Turmite {
+    #[must_use]
+    /// Creates a turmite from its transition table.
+    ///
+    /// Returns [`None`] when the table is empty or refers to a state that doesn't exist.
+    pub fn new(table: Vec<[Transition; 2]>) -> Option<Self> {
+        let valid = table.iter().flatten().all(|transition| transition.next_state < table.len());
+
+        (valid && !table.is_empty()).then_some(Self { table })
+    }
+
+    #[must_use]
+    /// Langton's Ant: turn right on dead cells, left on live ones, flipping the cell.
+    pub fn langton() -> Self {
+        Self {
+            table: vec![[
+                Transition { write: Cell::Live, turn: Turn::Right, next_state: 0 },
+                Transition { write: Cell::Dead, turn: Turn::Left, next_state: 0 },
+            ]],
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    /// The number of internal states.
+    pub fn states(&self) -> usize {
+        self.table.len()
+    }
+
+    #[inline]
+    #[must_use]
+    /// What to do on a cell in a given state.
+    ///
+    /// # Panics
+    ///
+    /// If the state doesn't exist.
+    pub fn transition(&self, state: usize, cell: Cell) -> Transition {
+        self.table[state][cell as usize]
+    }
+}
+
+impl Default for Turmite {
+    #[inline]
+    fn default() -> Self {
+        Self::langton()
+    }
+}
+
+/// Error returned when parsing a [`Turmite`] fails.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseTurmiteError(String);
+
+impl Display for ParseTurmiteError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid turmite rule: {}", self.0)
+    }
+}
+
+impl Error for ParseTurmiteError {}
+
+impl FromStr for Turmite {
+    type Err = ParseTurmiteError;
+
+    fn from_str(rule: &str) -> Result<Self, Self::Err> {
+        let error = |message: &str| ParseTurmiteError(message.to_owned());
+        let rule = rule.trim();
+
+        if !rule.starts_with('{') {
+            let turns: Vec<_> = rule.chars()
+                .map(|char| match char.to_ascii_uppercase() {
+                    'L' => Ok(Turn::Left),
+                    'R' => Ok(Turn::Right),
+                    'N' => Ok(Turn::None),
+                    'U' => Ok(Turn::UTurn),
+                    _ => Err(error("expected only L, R, N or U")),
+                })
+                .collect::<Result<_, _>>()?;
+
+            let [dead, live] = turns[..] else {
+                return Err(error("relative rules need exactly one turn for each of the two cell states"));
+            };
+            return Ok(Self {
+                table: vec![[
+                    Transition { write: Cell::Live, turn: dead, next_state: 0 },
+                    Transition { write: Cell::Dead, turn: live, next_state: 0 },
+                ]],
+            });
+        }
+
+        let numbers: Vec<usize> = rule.split(|char: char| !char.is_ascii_digit())
+            .filter(|number| !number.is_empty())
+            .map(|number| number.parse().map_err(|_| error("number too large")))
+            .collect::<Result<_, _>>()?;
+
+        if numbers.is_empty() || numbers.len() % 6 != 0 {
+            return Err(error("expected two {write, turn, state} triples for each state"));
+        }
+
+        let transition = |triple: &[usize]| {
+            let write = match triple[0] {
+                0 => Cell::Dead,
+                1 => Cell::Live,
+                _ => return Err(error("only two colors are supported")),
+            };
+            let turn = match triple[1] {
+                1 => Turn::None,
+                2 => Turn::Right,
+                4 => Turn::UTurn,
+                8 => Turn::Left,
+                _ => return Err(error("turns must be 1, 2, 4 or 8")),
+            };
+            Ok(Transition { write, turn, next_state: triple[2] })
+        };
+
+        let table = numbers.chunks_exact(6)
+            .map(|state| Ok([transition(&state[..3])?, transition(&state[3..])?]))
+            .collect::<Result<_, _>>()?;
+
+        Self::new(table).ok_or_else(|| error("transition to a state that doesn't exist"))
+    }
+}
+
+/// A turmite walking on the grid.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Ant {
+    pub row: usize,
+    pub col: usize,
+    pub direction: Direction,
+    pub state: usize,
+}
+
+impl Ant {
+    #[inline]
+    #[must_use]
+    /// An ant facing up, in the initial state.
+    pub const fn new(row: usize, col: usize) -> Self {
+        Self { row, col, direction: Direction::Up, state: 0 }
+    }
+
+    /// Applies the turmite rule on the current cell, then moves one cell forward.
+    ///
+    /// The grid wraps around at the edges, and ants past them, as when the grid was replaced by a
+    /// smaller one, are wrapped back into it first.
+    pub fn step(&mut self, turmite: &Turmite, grid: &mut Grid) {
+        let (rows, columns) = grid.shape();
+        if rows == 0 || columns == 0 {
+            return;
+        }
+        (self.row, self.col) = (self.row % rows, self.col % columns);
+
+        let cell = &mut grid[(self.row, self.col)];
+        let transition = turmite.transition(self.state, *cell);
+
+        *cell = transition.write;
+        self.state = transition.next_state;
+        self.direction = self.direction.turn(transition.turn);
+
+        match self.direction {
+            Direction::Up => self.row = (self.row + rows - 1) % rows,
+            Direction::Right => self.col = (self.col + 1) % columns,
+            Direction::Down => self.row = (self.row + 1) % rows,
+            Direction::Left => self.col = (self.col + columns - 1) % columns,
+        }
+    }
+}
+
+/// Runs Langton's Ant, or any two-color turmite, with one or more ants.
+///
+/// The ants are kept apart from the grid, which only stores cell colors. In each step, every ant
+/// moves once, in the order they were added, so ants that meet interact deterministically. Each
+/// generation is made of a configurable number of steps.
+#[derive(Debug)]
+pub struct AntEngine {
+    turmite: Turmite,
+    ants: Mutex<Vec<Ant>>,
+    steps_per_generation: usize,
+}
+
+impl AntEngine {
+    #[must_use]
+    /// Creates the engine with the given ants, advancing one step per generation.
+    pub fn new(turmite: Turmite, ants: impl IntoIterator<Item = Ant>) -> Self {
+        let ants = Mutex::new(ants.into_iter().collect());
+        Self { turmite, ants, steps_per_generation: 1 }
+    }
+
+    #[must_use]
+    /// Sets how many steps each ant takes on each generation.
+    pub fn with_steps_per_generation(mut self, steps: usize) -> Self {
+        self.steps_per_generation = steps;
+        self
+    }
+
+    #[inline]
+    #[must_use]
+    /// The rule followed by every ant.
+    pub const fn turmite(&self) -> &Turmite {
+        &self.turmite
+    }
+
+    #[must_use]
+    /// The current position and state of each ant.
+    pub fn ants(&self) -> Vec<Ant> {
+        self.ants.lock().unwrap_or_else(|error| error.into_inner()).clone()
+    }
+}
+
+impl Engine for AntEngine {
+    fn update(&self, grid: &Grid) -> Grid {
+        let mut next = grid.clone();
+        let mut ants = self.ants.lock().unwrap_or_else(|error| error.into_inner());
+
+        for _ in 0..self.steps_per_generation {
+            for ant in ants.iter_mut() {
+                ant.step(&self.turmite, &mut next);
+            }
+        }
+        next
+    }
+
+    #[inline]
+    fn name(&self) -> &'static str {
+        "ant"
+    }
+
+    fn agents(&self) -> Vec<(usize, usize)> {
+        self.ants().iter().map(|ant| (ant.row, ant.col)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn parse_rules() {
+        assert_eq!("RL".parse(), Ok(Turmite::langton()));
+        assert_eq!("{{{1, 2, 0}, {0, 8, 0}}}".parse(), Ok(Turmite::langton()));
+        assert_eq!("{{{1,2,1},{0,8,0}},{{1,2,1},{1,1,0}}}".parse::<Turmite>().unwrap().states(), 2);
+
+        assert!("RLR".parse::<Turmite>().is_err());
+        assert!("{{{1,2,1},{0,8,0}}}".parse::<Turmite>().is_err());
+    }
+
+    #[test]
+    pub fn langton_highway_period() {
+        // after the chaotic phase, the ant builds a highway repeating every 104 steps
+        let engine = AntEngine::new(Turmite::langton(), [Ant::new(64, 64)]).with_steps_per_generation(11_000);
+        let grid = engine.update(&Grid::new(128, 128));
+        let before = engine.ants()[0];
+
+        let engine = AntEngine::new(Turmite::langton(), [before]).with_steps_per_generation(104);
+        let after_grid = engine.update(&grid);
+        let after = engine.ants()[0];
+
+        assert_eq!(after.direction, before.direction);
+        assert_eq!(after.row.abs_diff(before.row), 2);
+        assert_eq!(after.col.abs_diff(before.col), 2);
+        assert_ne!(after_grid, grid);
+    }
+
+    #[test]
+    pub fn ants_move_in_order() {
+        let grid = Grid::new(5, 5);
+        let engine = AntEngine::new(Turmite::langton(), [Ant::new(2, 2), Ant::new(2, 3)]);
+
+        let next = engine.update(&grid);
+        assert_eq!(engine.agents(), [(2, 3), (2, 4)]);
+        assert_eq!(next[(2, 2)], Cell::Live);
+        assert_eq!(next[(2, 3)], Cell::Live);
+
+        // the first ant arrives on the cell flipped by the second one and turns left
+        let next = engine.update(&next);
+        assert_eq!(engine.agents(), [(1, 3), (3, 4)]);
+        assert_eq!(next[(2, 3)], Cell::Dead);
+    }
+
+    #[test]
+    pub fn ants_wrap_into_smaller_grids() {
+        let engine = AntEngine::new(Turmite::langton(), [Ant::new(64, 70)]);
+
+        let next = engine.update(&Grid::new(5, 5));
+        assert_eq!(next[(4, 0)], Cell::Live);
+        assert_eq!(engine.agents(), [(4, 1)]);
+    }
+}
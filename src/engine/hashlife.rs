@@ -0,0 +1,414 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use crate::cell::{Cell, Grid};
+use crate::rule::Rule;
+
+use super::Engine;
+
+/// Index of a node in the [`Store`].
+type NodeId = u32;
+
+/// The dead and live cells, the leaves of every tree.
+const DEAD: NodeId = 0;
+const LIVE: NodeId = 1;
+
+/// Nodes kept before the store is compacted, around 100 MB.
+const NODE_LIMIT: usize = 1 << 22;
+
+/// A square of `2^level` cells on each side, split in four quadrants.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+struct Node {
+    level: u8,
+    /// Quadrants in the order `[nw, ne, sw, se]`, unused on leaves.
+    children: [NodeId; 4],
+}
+
+/// Canonical nodes, so that equal squares share the same id, and their memoized results.
+#[derive(Debug)]
+struct Store {
+    nodes: Vec<Node>,
+    population: Vec<u64>,
+    ids: HashMap<Node, NodeId>,
+    /// Center of a node after `2^j` generations, keyed by `(node, j)`.
+    results: HashMap<(NodeId, u8), NodeId>,
+    /// The empty node of each level.
+    empty: Vec<NodeId>,
+}
+
+impl Store {
+    fn new() -> Self {
+        let leaf = Node { level: 0, children: [DEAD; 4] };
+
+        Self {
+            nodes: vec![leaf, leaf],
+            population: vec![0, 1],
+            ids: HashMap::new(),
+            results: HashMap::new(),
+            empty: vec![DEAD],
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    fn level(&self, id: NodeId) -> u8 {
+        self.nodes[id as usize].level
+    }
+
+    fn population(&self, id: NodeId) -> u64 {
+        self.population[id as usize]
+    }
+
+    fn children(&self, id: NodeId) -> [NodeId; 4] {
+        self.nodes[id as usize].children
+    }
+
+    /// The unique node with the given quadrants, which must all have the same level.
+    fn join(&mut self, children: [NodeId; 4]) -> NodeId {
+        let node = Node { level: self.level(children[0]) + 1, children };
+        if let Some(&id) = self.ids.get(&node) {
+            return id;
+        }
+
+        let id = NodeId::try_from(self.nodes.len()).expect("too many nodes for hashlife");
+        self.nodes.push(node);
+        self.population.push(children.iter().map(|&child| self.population(child)).sum());
+        self.ids.insert(node, id);
+        id
+    }
+
+    fn empty(&mut self, level: u8) -> NodeId {
+        while self.empty.len() <= level as usize {
+            let child = self.empty[self.empty.len() - 1];
+            let empty = self.join([child; 4]);
+            self.empty.push(empty);
+        }
+        self.empty[level as usize]
+    }
+
+    /// The node of the given level with its top-left corner at `(top, left)`, relative to the
+    /// top-left corner of the grid.
+    fn build(&mut self, grid: &Grid, level: u8, top: i128, left: i128) -> NodeId {
+        let size = 1_i128 << level;
+        let (rows, columns) = (grid.rows() as i128, grid.columns() as i128);
+        if top >= rows || left >= columns || top + size <= 0 || left + size <= 0 {
+            return self.empty(level);
+        }
+        if level == 0 {
+            return if grid[(top as usize, left as usize)].is_live() { LIVE } else { DEAD };
+        }
+
+        let half = size / 2;
+        let nw = self.build(grid, level - 1, top, left);
+        let ne = self.build(grid, level - 1, top, left + half);
+        let sw = self.build(grid, level - 1, top + half, left);
+        let se = self.build(grid, level - 1, top + half, left + half);
+        self.join([nw, ne, sw, se])
+    }
+
+    /// Writes the live cells of a node into the grid, ignoring cells outside of it.
+    ///
+    /// Coordinates are 128 bits wide, since steps of up to `2^64` generations grow the root past
+    /// what fits in 64 bits.
+    fn extract(&self, id: NodeId, top: i128, left: i128, grid: &mut Grid) {
+        let size = 1_i128 << self.level(id);
+        let (rows, columns) = (grid.rows() as i128, grid.columns() as i128);
+        if self.population(id) == 0 || top >= rows || left >= columns || top + size <= 0 || left + size <= 0 {
+            return;
+        }
+        if id == LIVE {
+            grid[(top as usize, left as usize)] = Cell::Live;
+            return;
+        }
+
+        let half = size / 2;
+        let [nw, ne, sw, se] = self.children(id);
+        self.extract(nw, top, left, grid);
+        self.extract(ne, top, left + half, grid);
+        self.extract(sw, top + half, left, grid);
+        self.extract(se, top + half, left + half, grid);
+    }
+
+    /// The same pattern in a node one level up, centered.
+    fn expand(&mut self, id: NodeId) -> NodeId {
+        let empty = self.empty(self.level(id) - 1);
+        let [nw, ne, sw, se] = self.children(id);
+
+        let nw = self.join([empty, empty, empty, nw]);
+        let ne = self.join([empty, empty, ne, empty]);
+        let sw = self.join([empty, sw, empty, empty]);
+        let se = self.join([se, empty, empty, empty]);
+        self.join([nw, ne, sw, se])
+    }
+
+    /// Checks if all live cells are in the central square, half the size of the node.
+    fn is_padded(&self, id: NodeId) -> bool {
+        if self.level(id) < 3 {
+            return false;
+        }
+
+        let [nw, ne, sw, se] = self.children(id);
+        let inner = [(nw, 3), (ne, 2), (sw, 1), (se, 0)].map(|(child, corner)| {
+            let quadrant = self.children(child)[corner];
+            self.population(self.children(quadrant)[corner])
+        });
+        inner.iter().sum::<u64>() == self.population(id)
+    }
+
+    /// The central quadrant of a node, without advancing it.
+    fn center(&mut self, id: NodeId) -> NodeId {
+        let [nw, ne, sw, se] = self.children(id);
+        self.join([self.children(nw)[3], self.children(ne)[2], self.children(sw)[1], self.children(se)[0]])
+    }
+
+    /// The central quadrant of a node of level `k`, advanced by `2^j` generations, with `j <= k - 2`.
+    fn successor(&mut self, id: NodeId, j: u8) -> NodeId {
+        let level = self.level(id);
+        if self.population(id) == 0 {
+            return self.empty(level - 1);
+        }
+        if let Some(&result) = self.results.get(&(id, j)) {
+            return result;
+        }
+
+        let result = if level == 2 {
+            self.base_case(id)
+        } else {
+            let [nw, ne, sw, se] = self.children(id);
+            let [_, nw_ne, nw_sw, nw_se] = self.children(nw);
+            let [ne_nw, _, ne_sw, ne_se] = self.children(ne);
+            let [sw_nw, sw_ne, _, sw_se] = self.children(sw);
+            let [se_nw, se_ne, se_sw, _] = self.children(se);
+
+            // nine overlapping squares, half the size of the node
+            let squares = [
+                nw,
+                self.join([nw_ne, ne_nw, nw_se, ne_sw]),
+                ne,
+                self.join([nw_sw, nw_se, sw_nw, sw_ne]),
+                self.join([nw_se, ne_sw, sw_ne, se_nw]),
+                self.join([ne_sw, ne_se, se_nw, se_ne]),
+                sw,
+                self.join([sw_ne, se_nw, sw_se, se_sw]),
+                se,
+            ];
+
+            // at full speed, both halves advance `2^(j-1)` generations, otherwise only the second
+            let full_speed = j + 2 == level;
+            let c = squares.map(|square| if full_speed { self.successor(square, j - 1) } else { self.center(square) });
+            let next = if full_speed { j - 1 } else { j };
+
+            let nw = self.join([c[0], c[1], c[3], c[4]]);
+            let ne = self.join([c[1], c[2], c[4], c[5]]);
+            let sw = self.join([c[3], c[4], c[6], c[7]]);
+            let se = self.join([c[4], c[5], c[7], c[8]]);
+
+            let quadrants = [nw, ne, sw, se].map(|quadrant| self.successor(quadrant, next));
+            self.join(quadrants)
+        };
+
+        self.results.insert((id, j), result);
+        result
+    }
+
+    /// The next generation of the central 2x2 cells of a 4x4 node.
+    fn base_case(&mut self, id: NodeId) -> NodeId {
+        let mut cells = [[false; 4]; 4];
+        for (quadrant, child) in self.children(id).into_iter().enumerate() {
+            for (position, leaf) in self.children(child).into_iter().enumerate() {
+                let row = 2 * (quadrant / 2) + position / 2;
+                let col = 2 * (quadrant % 2) + position % 2;
+                cells[row][col] = leaf == LIVE;
+            }
+        }
+
+        let next = |row: usize, col: usize| {
+            let square = cells[row-1..=row+1].iter().flat_map(|cells| &cells[col-1..=col+1]);
+            let live_cells = square.filter(|&&live| live).count() - usize::from(cells[row][col]);
+
+            if live_cells == 3 || (live_cells == 2 && cells[row][col]) { LIVE } else { DEAD }
+        };
+        self.join([next(1, 1), next(1, 2), next(2, 1), next(2, 2)])
+    }
+
+    /// Advances a centered tree by some number of generations, returning the new centered tree.
+    fn advance(&mut self, mut root: NodeId, generations: usize) -> NodeId {
+        for j in 0..usize::BITS as u8 {
+            if generations & (1 << j) == 0 {
+                continue;
+            }
+
+            // the pattern moves at most one cell per generation, so it needs some room around it
+            while self.level(root) < j + 3 || !self.is_padded(root) {
+                root = self.expand(root);
+            }
+            root = self.successor(root, j);
+
+            if self.len() > NODE_LIMIT {
+                root = self.compact(root);
+            }
+        }
+        root
+    }
+
+    /// Drops every node not used by the tree and all memoized results.
+    fn compact(&mut self, root: NodeId) -> NodeId {
+        let mut store = Self::new();
+        let mut copies = HashMap::from([(DEAD, DEAD), (LIVE, LIVE)]);
+        let root = self.copy_into(root, &mut store, &mut copies);
+
+        *self = store;
+        root
+    }
+
+    fn copy_into(&self, id: NodeId, store: &mut Self, copies: &mut HashMap<NodeId, NodeId>) -> NodeId {
+        if let Some(&copy) = copies.get(&id) {
+            return copy;
+        }
+
+        let children = self.children(id).map(|child| self.copy_into(child, store, copies));
+        let copy = store.join(children);
+        copies.insert(id, copy);
+        copy
+    }
+}
+
+/// Runs the Game of Life with Gosper's Hashlife algorithm.
+///
+/// The grid is turned into a quadtree where identical squares are shared, and the future of each
+/// square is memoized. Repetitive patterns then advance exponentially faster as the step grows:
+/// an update can skip millions of generations at once.
+///
+/// The cache is kept between updates. With a single generation per update, the result is the same
+/// as the other engines. With larger steps, the pattern evolves on an unbounded plane and only the
+/// cells inside the grid are kept at the end, so cells leaving the grid may still affect it.
+#[derive(Debug)]
+pub struct HashLifeEngine {
+    store: Mutex<Store>,
+    step: usize,
+}
+
+impl HashLifeEngine {
+    #[must_use]
+    /// Creates the engine, advancing one generation per update.
+    pub fn new() -> Self {
+        Self { store: Mutex::new(Store::new()), step: 1 }
+    }
+
+    #[must_use]
+    /// Sets how many generations each update advances.
+    pub fn with_step(mut self, generations: usize) -> Self {
+        self.step = generations;
+        self
+    }
+
+    #[inline]
+    #[must_use]
+    /// Generations advanced on each update.
+    pub const fn step(&self) -> usize {
+        self.step
+    }
+}
+
+impl Default for HashLifeEngine {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Engine for HashLifeEngine {
+    fn update(&self, grid: &Grid) -> Grid {
+        let mut store = self.store.lock().unwrap_or_else(|error| error.into_inner());
+        if store.len() > NODE_LIMIT {
+            *store = Store::new();
+        }
+
+        // the root is centered on the top-left corner of the grid
+        let (rows, columns) = grid.shape();
+        let mut level = 3;
+        while (1_usize << (level - 1)) < rows.max(columns) {
+            level += 1;
+        }
+        let half = 1_i128 << (level - 1);
+
+        let root = store.build(grid, level, -half, -half);
+        let root = store.advance(root, self.step);
+
+        let half = 1_i128 << (store.level(root) - 1);
+        let mut next = Grid::new(rows, columns);
+        store.extract(root, -half, -half, &mut next);
+        next
+    }
+
+    #[inline]
+    fn name(&self) -> &'static str {
+        "hashlife"
+    }
+
+    #[inline]
+    fn life_rule(&self) -> Option<Rule> {
+        Some(Rule::CONWAY)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::rngs::SmallRng;
+    use rand::SeedableRng;
+
+    use crate::engine::SerialEngine;
+
+    use super::*;
+
+    #[test]
+    pub fn same_as_serial() {
+        let mut rng = SmallRng::seed_from_u64(505);
+        let engine = HashLifeEngine::new();
+
+        for (rows, columns) in [(1, 1), (7, 13), (40, 3), (64, 64)] {
+            let mut grid = Grid::random_with(rows, columns, &mut rng);
+            for _ in 0..20 {
+                let next = engine.update(&grid);
+                assert_eq!(next, SerialEngine::default().update(&grid));
+                grid = next;
+            }
+        }
+    }
+
+    #[test]
+    pub fn glider_over_large_steps() {
+        let mut grid = Grid::new(100, 100);
+        for (row, col) in [(0, 1), (1, 2), (2, 0), (2, 1), (2, 2)] {
+            grid[(row, col)] = Cell::Live;
+        }
+
+        // a glider moves one cell diagonally every 4 generations
+        let next = HashLifeEngine::new().with_step(4 * 90).update(&grid);
+        assert_eq!(next.population(), 5);
+        assert_eq!(next[(90, 91)], Cell::Live);
+        assert_eq!(next[(92, 92)], Cell::Live);
+
+        // and then leaves the grid
+        let next = HashLifeEngine::new().with_step(1_000_000).update(&grid);
+        assert_eq!(next, Grid::new(100, 100));
+    }
+
+    #[test]
+    pub fn oscillators_after_many_generations() {
+        let mut grid = Grid::new(5, 5);
+        grid[2][1..4].fill(Cell::Live);
+
+        let engine = HashLifeEngine::new().with_step(1 << 40);
+        assert_eq!(engine.update(&grid), grid);
+
+        let engine = HashLifeEngine::new().with_step((1 << 40) + 1);
+        assert_eq!(engine.update(&grid), SerialEngine::default().update(&grid));
+
+        // the root grows past 64 bits of coordinates
+        let engine = HashLifeEngine::new().with_step(usize::MAX);
+        assert_eq!(engine.update(&grid), SerialEngine::default().update(&grid));
+    }
+}
@@ -0,0 +1,179 @@
+use crate::cell::Grid;
+use crate::par::*;
+use crate::rule::LtlRule;
+
+use super::Engine;
+
+/// Runs a Larger than Life rule, counting neighbors in a summed-area table.
+///
+/// The table holds, for each position, the number of live cells above and to the left of it, so
+/// the count over any square is found with four lookups, whatever its radius. Building it is a
+/// single pass over the grid, making each generation linear in the area even for neighborhoods
+/// of hundreds of cells. Von Neumann neighborhoods are diamonds instead, counted with two lookups
+/// for each of their rows. Cells past the edges of the grid are dead. With the `parallel` feature,
+/// the rows of the table and of the next generation are computed in parallel.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+#[repr(transparent)]
+pub struct LtlEngine {
+    rule: LtlRule,
+}
+
+impl LtlEngine {
+    #[inline]
+    #[must_use]
+    /// Creates an engine running the given rule.
+    pub const fn new(rule: LtlRule) -> Self {
+        Self { rule }
+    }
+
+    #[inline]
+    #[must_use]
+    /// The rule used to compute each generation.
+    pub const fn rule(&self) -> LtlRule {
+        self.rule
+    }
+
+    #[must_use]
+    /// The summed-area table of the grid, with an extra row and column of zeros at the start, so
+    /// position `(row, col)` holds the live cells in `grid[..row][..col]`.
+    fn summed_area(grid: &Grid) -> Vec<u32> {
+        let width = grid.columns() + 1;
+        let mut table = vec![0; (grid.rows() + 1) * width];
+
+        table[width..].par_chunks_exact_mut(width).zip(grid.flat().par_chunks_exact(grid.columns().max(1))).for_each(|(sums, cells)| {
+            for (col, cell) in cells.iter().enumerate() {
+                sums[col + 1] = sums[col] + u32::from(cell.is_live());
+            }
+        });
+        for row in 1..grid.rows() {
+            let (above, below) = table.split_at_mut((row + 1) * width);
+            for (sum, &previous) in below[..width].iter_mut().zip(&above[row * width..]) {
+                *sum += previous;
+            }
+        }
+        table
+    }
+
+    fn fill_next_grid(&self, grid: &Grid, next: &mut Grid) {
+        let (rows, columns) = grid.shape();
+        if next.shape() != grid.shape() {
+            *next = Grid::new(rows, columns);
+        }
+
+        let table = Self::summed_area(grid);
+        let (width, radius) = (columns + 1, self.rule.radius());
+        let sum = |row: usize, col: usize| table[row * width + col] as usize;
+
+        next.flat_mut().par_chunks_exact_mut(columns.max(1)).enumerate().for_each(|(row, cells)| {
+            let (top, bottom) = (row.saturating_sub(radius), (row + radius + 1).min(rows));
+            for (col, cell) in cells.iter_mut().enumerate() {
+                let (left, right) = (col.saturating_sub(radius), (col + radius + 1).min(columns));
+
+                let current = grid[(row, col)];
+                let live = if self.rule.von_neumann() {
+                    // each row of the diamond is the difference of two strips of the table
+                    (top..bottom).map(|i| {
+                        let reach = radius - i.abs_diff(row);
+                        let (left, right) = (col.saturating_sub(reach), (col + reach + 1).min(columns));
+                        sum(i + 1, right) + sum(i, left) - sum(i, right) - sum(i + 1, left)
+                    }).sum()
+                } else {
+                    sum(bottom, right) + sum(top, left) - sum(top, right) - sum(bottom, left)
+                };
+                let neighbors = if self.rule.middle() { live } else { live - usize::from(current.is_live()) };
+                *cell = self.rule.next(current, neighbors);
+            }
+        });
+    }
+}
+
+impl Engine for LtlEngine {
+    #[inline]
+    fn update(&self, grid: &Grid) -> Grid {
+        let mut next = Grid::empty();
+        self.fill_next_grid(grid, &mut next);
+        next
+    }
+
+    #[inline]
+    fn update_into(&self, src: &Grid, dst: &mut Grid) {
+        self.fill_next_grid(src, dst);
+    }
+
+    #[inline]
+    fn name(&self) -> &'static str {
+        "ltl"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cell::{Cell, Topology};
+    use crate::engine::SerialEngine;
+    use crate::rule::Rule;
+
+    use super::*;
+
+    /// Counts the neighbors of every cell one by one.
+    fn naive_update(rule: LtlRule, grid: &Grid) -> Grid {
+        let (rows, columns) = grid.shape();
+        let radius = rule.radius();
+        let mut next = Grid::new(rows, columns);
+
+        for row in 0..rows {
+            for col in 0..columns {
+                let mut live = 0;
+                for i in row.saturating_sub(radius)..(row + radius + 1).min(rows) {
+                    for j in col.saturating_sub(radius)..(col + radius + 1).min(columns) {
+                        let inside = !rule.von_neumann() || i.abs_diff(row) + j.abs_diff(col) <= radius;
+                        if inside && ((i, j) != (row, col) || rule.middle()) {
+                            live += usize::from(grid[(i, j)].is_live());
+                        }
+                    }
+                }
+                next[(row, col)] = rule.next(grid[(row, col)], live);
+            }
+        }
+        next
+    }
+
+    #[test]
+    pub fn matches_naive_counts() {
+        let rules = [
+            LtlRule::BOSCO,
+            LtlRule::new(2, false, 3..=8, 4..=6),
+            LtlRule::new(7, true, 50..=120, 40..=90),
+            LtlRule::new(3, true, 6..=12, 5..=9).with_von_neumann(true),
+        ];
+        for rule in rules {
+            for (rows, columns) in [(30, 41), (1, 20), (12, 1), (0, 5), (5, 0)] {
+                let grid = Grid::random_seeded(rows, columns, 573);
+                assert_eq!(LtlEngine::new(rule).update(&grid), naive_update(rule, &grid), "{rule} on {rows}x{columns}");
+            }
+        }
+    }
+
+    #[test]
+    pub fn radius_one_is_life() {
+        let life = LtlEngine::new(LtlRule::new(1, false, 2..=3, 3..=3));
+        let mut grid = Grid::random_seeded(25, 25, 574);
+        let mut expected = grid.clone();
+
+        for _ in 0..10 {
+            life.update_into(&grid.clone(), &mut grid);
+            expected = SerialEngine::default().update(&expected);
+            assert_eq!(grid, expected);
+        }
+        assert!(grid.flat().contains(&Cell::Live));
+    }
+
+    #[test]
+    pub fn von_neumann_radius_one() {
+        let ltl: LtlRule = "R1,C0,M0,S1..2,B1..1,NN".parse().unwrap();
+        assert_eq!((ltl.neighbors(), ltl.to_string()), (4, "R1,C0,M0,S1..2,B1..1,NN".to_owned()));
+
+        let rule = Rule::new(&[1], &[1, 2]).with_topology(Topology::VonNeumann);
+        let grid = Grid::random_seeded(25, 31, 576);
+        assert_eq!(LtlEngine::new(ltl).update(&grid), SerialEngine::new(rule).update(&grid));
+    }
+}
@@ -0,0 +1,187 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::cell::{Cell, Grid};
+use crate::par::*;
+use crate::rule::BlockRule;
+
+use super::Engine;
+
+/// Runs a block cellular automaton on the Margolus neighborhood, like Critters.
+///
+/// The grid is split into 2x2 blocks, and each generation replaces every block by the next one in
+/// a [`BlockRule`]. The partition alternates between generations: blocks start at even rows and
+/// columns on one, and at odd rows and columns on the next, so cells interact with all of their
+/// neighbors over two generations. Blocks that don't fit in the grid on the odd partition are left
+/// as they are, which keeps reversible rules reversible, see [`MargolusEngine::step_back`].
+///
+/// The partition of the next generation is kept behind interior mutability, like the ants of
+/// [`AntEngine`](super::AntEngine). With the `parallel` feature, pairs of rows are computed in
+/// parallel.
+#[derive(Debug, Default)]
+pub struct MargolusEngine {
+    rule: BlockRule,
+    /// Whether the next generation uses blocks starting at odd rows and columns.
+    odd: AtomicBool,
+}
+
+impl MargolusEngine {
+    #[inline]
+    #[must_use]
+    /// Creates an engine running the given rule, starting from blocks at even rows and columns.
+    pub const fn new(rule: BlockRule) -> Self {
+        Self { rule, odd: AtomicBool::new(false) }
+    }
+
+    #[inline]
+    #[must_use]
+    /// The rule used to compute each generation.
+    pub const fn rule(&self) -> BlockRule {
+        self.rule
+    }
+
+    #[inline]
+    #[must_use]
+    /// Offset of the blocks in the next generation, `0` for blocks at even rows and columns and `1`
+    /// for odd ones.
+    pub fn offset(&self) -> usize {
+        usize::from(self.odd.load(Ordering::Relaxed))
+    }
+
+    #[must_use]
+    /// Undoes the last generation, going back to the one before `grid`, or returns [`None`] if
+    /// the rule is not reversible.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use vida::cell::Grid;
+    /// # use vida::engine::{Engine, MargolusEngine};
+    /// # use vida::rule::BlockRule;
+    /// #
+    /// let start = Grid::random_seeded(20, 30, 583);
+    /// let engine = MargolusEngine::new(BlockRule::CRITTERS);
+    ///
+    /// let grid = engine.update(&engine.update(&start));
+    /// let back = engine.step_back(&engine.step_back(&grid).unwrap()).unwrap();
+    /// assert_eq!(back, start);
+    /// ```
+    pub fn step_back(&self, grid: &Grid) -> Option<Grid> {
+        let inverse = self.rule.inverse()?;
+        // the partition of the last generation is the opposite of the next one
+        let odd = !self.odd.fetch_xor(true, Ordering::Relaxed);
+
+        let mut previous = grid.clone();
+        Self::apply(inverse, &mut previous, usize::from(odd));
+        Some(previous)
+    }
+
+    #[must_use]
+    /// An engine running backwards from the current generation, with the inverse rule and the
+    /// partitions in reverse order, or [`None`] if the rule is not reversible.
+    pub fn reversed(&self) -> Option<Self> {
+        let inverse = self.rule.inverse()?;
+        Some(Self { rule: inverse, odd: AtomicBool::new(self.offset() == 0) })
+    }
+
+    /// Replaces each block at `offset` by its next state, in place.
+    fn apply(rule: BlockRule, grid: &mut Grid, offset: usize) {
+        let (rows, columns) = grid.shape();
+        if rows <= offset || columns == 0 {
+            return;
+        }
+
+        grid.flat_mut()[offset * columns..].par_chunks_exact_mut(2 * columns).for_each(|pair| {
+            let (top, bottom) = pair.split_at_mut(columns);
+            for col in (offset..columns - 1).step_by(2) {
+                let block = u8::from(top[col])
+                    | u8::from(top[col + 1]) << 1
+                    | u8::from(bottom[col]) << 2
+                    | u8::from(bottom[col + 1]) << 3;
+
+                let next = rule.next(block);
+                top[col] = Cell::from(next & 1);
+                top[col + 1] = Cell::from(next & 2);
+                bottom[col] = Cell::from(next & 4);
+                bottom[col + 1] = Cell::from(next & 8);
+            }
+        });
+    }
+}
+
+impl Clone for MargolusEngine {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self { rule: self.rule, odd: AtomicBool::new(self.odd.load(Ordering::Relaxed)) }
+    }
+}
+
+impl Engine for MargolusEngine {
+    #[inline]
+    fn update(&self, grid: &Grid) -> Grid {
+        let mut next = grid.clone();
+        self.update_into(grid, &mut next);
+        next
+    }
+
+    fn update_into(&self, src: &Grid, dst: &mut Grid) {
+        if dst.shape() == src.shape() {
+            dst.flat_mut().copy_from_slice(src.flat());
+        } else {
+            *dst = src.clone();
+        }
+
+        let odd = self.odd.fetch_xor(true, Ordering::Relaxed);
+        Self::apply(self.rule, dst, usize::from(odd));
+    }
+
+    #[inline]
+    fn name(&self) -> &'static str {
+        "margolus"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn reversible_rules() {
+        for (_, rule) in BlockRule::NAMES {
+            for (rows, columns) in [(31, 40), (20, 25), (1, 7), (0, 3)] {
+                let start = Grid::random_seeded(rows, columns, 583);
+                let engine = MargolusEngine::new(rule);
+
+                let mut grid = start.clone();
+                for _ in 0..15 {
+                    grid = engine.update(&grid);
+                }
+                assert_eq!(engine.offset(), 1);
+
+                let backwards = engine.reversed().unwrap();
+                let mut reversed = grid.clone();
+                for _ in 0..15 {
+                    reversed = backwards.update(&reversed);
+                    grid = engine.step_back(&grid).unwrap();
+                }
+                assert_eq!(grid, start, "{rule} on {rows}x{columns}");
+                assert_eq!(reversed, start);
+                assert_eq!(engine.offset(), 0);
+            }
+        }
+
+        let irreversible = MargolusEngine::new(BlockRule::new([0; 16]));
+        assert_eq!(irreversible.step_back(&Grid::new(4, 4)), None);
+        assert_eq!(irreversible.offset(), 0);
+    }
+
+    #[test]
+    pub fn billiard_balls_move_diagonally() {
+        let engine = MargolusEngine::new(BlockRule::BILLIARD_BALL);
+        let mut grid = Grid::from_live_cells(10, 10, [(2, 2)]);
+
+        for step in 1..=4 {
+            grid = engine.update(&grid);
+            assert_eq!(grid, Grid::from_live_cells(10, 10, [(2 + step, 2 + step)]));
+        }
+    }
+}
@@ -1,12 +1,93 @@
 use crate::cell::Grid;
+use crate::rule::Rule;
 
 mod serial;
+#[cfg(feature = "parallel")]
 mod parallel;
+#[cfg(feature = "parallel")]
+mod numa;
+mod ant;
+mod hashlife;
+mod bit;
+mod sparse;
+mod ltl;
+mod margolus;
+pub mod neighbors;
 
 pub use serial::SerialEngine;
+#[cfg(feature = "parallel")]
 pub use parallel::ParallelEngine;
+#[cfg(feature = "parallel")]
+pub use numa::NumaEngine;
+pub use ant::{Ant, AntEngine, Direction, ParseTurmiteError, Transition, Turmite, Turn};
+pub use hashlife::HashLifeEngine;
+pub use bit::BitEngine;
+pub use sparse::SparseEngine;
+pub use ltl::LtlEngine;
+pub use margolus::MargolusEngine;
 
+/// A way to compute the next generation of a grid.
+///
+/// Every engine, serial or parallel, follows the same stateless API: the grid is owned by the
+/// caller, who passes the current generation in and gets the next one back, so engines can be
+/// swapped without changing how the board is stored or rendered. Engines that need to remember
+/// something between generations, like the ants of [`AntEngine`] or the cache of
+/// [`HashLifeEngine`], keep it behind interior mutability.
 pub trait Engine {
     #[must_use]
+    /// Computes the generation after `grid`, with the same shape.
     fn update(&self, grid: &Grid) -> Grid;
+
+    /// Computes the generation after `src` into `dst`, reusing its buffer when it has the same
+    /// shape.
+    ///
+    /// This lets callers alternate between two grids instead of allocating a new one on every
+    /// generation. The default implementation just replaces `dst` with [`update`](Engine::update).
+    fn update_into(&self, src: &Grid, dst: &mut Grid) {
+        *dst = self.update(src);
+    }
+
+    #[must_use]
+    /// Short identifier for the engine, as used in the command line.
+    fn name(&self) -> &'static str;
+
+    #[must_use]
+    /// The Life-like rule computed by the engine, if it runs one.
+    fn life_rule(&self) -> Option<Rule> {
+        None
+    }
+
+    #[must_use]
+    /// Positions `(row, col)` of agents moving over the grid, for engines that have them.
+    fn agents(&self) -> Vec<(usize, usize)> {
+        Vec::new()
+    }
+}
+
+/// Engines chosen at runtime, like the ones from [`standard_engines`](crate::bench::standard_engines).
+impl<E: Engine + ?Sized> Engine for Box<E> {
+    #[inline]
+    fn update(&self, grid: &Grid) -> Grid {
+        (**self).update(grid)
+    }
+
+    #[inline]
+    fn update_into(&self, src: &Grid, dst: &mut Grid) {
+        (**self).update_into(src, dst);
+    }
+
+    #[inline]
+    fn name(&self) -> &'static str {
+        (**self).name()
+    }
+
+    #[inline]
+    fn life_rule(&self) -> Option<Rule> {
+        (**self).life_rule()
+    }
+
+    #[inline]
+    fn agents(&self) -> Vec<(usize, usize)> {
+        (**self).agents()
+    }
 }
@@ -0,0 +1,82 @@
+//! Neighbor counting shared by the engines.
+
+use crate::cell::{Cell, Neighborhood};
+
+/// Counts the live neighbors of each cell in a row, given the rows above and below it.
+///
+/// The three rows must be padded with one extra cell on each side, like the rows of
+/// [`PaddedGrid`](crate::cell::PaddedGrid), so the counts are for `current[1..len - 1]`. A 3-wide
+/// window slides over the column sums, so each cell is read three times instead of nine.
+///
+/// # Panics
+///
+/// If the rows don't have the same length.
+///
+/// # Example
+///
+/// ```
+/// # use vida::cell::Cell::{Dead as D, Live as L};
+/// # use vida::engine::neighbors;
+/// #
+/// let above = [D, L, L, L, D];
+/// let current = [D, D, L, D, D];
+/// let below = [D, D, D, L, D];
+///
+/// let counts: Vec<_> = neighbors::count_row(&above, &current, &below).collect();
+/// assert_eq!(counts, [3, 4, 4]);
+/// ```
+pub fn count_row<'a>(above: &'a [Cell], current: &'a [Cell], below: &'a [Cell]) -> impl Iterator<Item = usize> + 'a {
+    assert!(above.len() == current.len() && current.len() == below.len(), "rows with different lengths");
+
+    let live = |cell: &Cell| usize::from(cell.is_live());
+    let mut columns = above.iter().zip(current).zip(below).map(move |((up, mid), down)| live(up) + live(mid) + live(down));
+    let left = columns.next().unwrap_or(0);
+    let middle = columns.next().unwrap_or(0);
+
+    columns
+        .scan((left, middle), |(left, middle), right| {
+            let block = *left + *middle + right;
+            (*left, *middle) = (*middle, right);
+            Some(block)
+        })
+        .zip(&current[1.min(current.len())..])
+        .map(move |(block, cell)| block - live(cell))
+}
+
+#[inline]
+#[must_use]
+/// Counts the live cells in a neighborhood, not counting the cell at its center.
+pub fn count(neighborhood: &Neighborhood) -> usize {
+    let block: usize = neighborhood.iter().flatten().map(|cell| usize::from(cell.is_live())).sum();
+    block - usize::from(neighborhood[1][1].is_live())
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand::rngs::SmallRng;
+
+    use crate::cell::{Boundary, Grid, PaddedGrid};
+
+    use super::*;
+
+    #[test]
+    pub fn same_as_padded_grid() {
+        let mut rng = SmallRng::seed_from_u64(525);
+        let grid = Grid::random_with(11, 23, &mut rng);
+        let padded = PaddedGrid::from(&grid);
+
+        for row in 0..grid.rows() {
+            let counts: Vec<_> = count_row(padded.padded_row(row), padded.padded_row(row + 1), padded.padded_row(row + 2)).collect();
+            let expected: Vec<_> = (0..grid.columns()).map(|col| padded.live_neighbors(row, col)).collect();
+            assert_eq!(counts, expected);
+        }
+
+        for (row, col, neighborhood) in grid.neighborhoods(Boundary::Dead) {
+            assert_eq!(count(&neighborhood), padded.live_neighbors(row, col));
+        }
+
+        assert_eq!(count_row(&[], &[], &[]).count(), 0);
+        assert_eq!(count_row(&[Cell::Live], &[Cell::Live], &[Cell::Live]).count(), 0);
+    }
+}
@@ -0,0 +1,185 @@
+use std::mem::MaybeUninit;
+use std::thread;
+
+use rayon::prelude::*;
+use rayon::{ThreadPool, ThreadPoolBuildError};
+
+use crate::affinity::{self, NumaNode};
+use crate::cell::{Cell, CellBuffer, Grid, PaddedGrid, Topology};
+use crate::rule::Rule;
+
+use super::{neighbors, Engine};
+
+/// Parallel engine that splits the grid into one band of rows per NUMA node.
+///
+/// Each node has its own thread pool, pinned to the node's physical cores. The next generation is
+/// written through uninitialized memory by those node-local threads, so the operating system
+/// places each band's pages on the node that works on it (first-touch policy). The padded copy
+/// used to count neighbors is made by the calling thread, so those reads may cross sockets.
+#[derive(Debug)]
+pub struct NumaEngine {
+    bands: Vec<Band>,
+    rule: Rule,
+}
+
+#[derive(Debug)]
+struct Band {
+    weight: usize,
+    pool: ThreadPool,
+}
+
+impl NumaEngine {
+    /// Creates one band for each NUMA node detected in the machine.
+    pub fn new() -> Result<Self, ThreadPoolBuildError> {
+        Self::with_nodes(affinity::numa_nodes())
+    }
+
+    /// Creates one band for each of the given nodes, with band sizes proportional to their cores.
+    ///
+    /// When `nodes` is empty, a single unpinned band using all cores is used instead.
+    pub fn with_nodes(nodes: impl IntoIterator<Item = NumaNode>) -> Result<Self, ThreadPoolBuildError> {
+        let mut bands = nodes.into_iter()
+            .map(|node| {
+                let weight = node.cores().len();
+                let pool = affinity::pinned_pool_builder(node.cores().to_vec()).build()?;
+                Ok(Band { weight, pool })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if bands.is_empty() {
+            let pool = rayon::ThreadPoolBuilder::new().build()?;
+            bands.push(Band { weight: 1, pool });
+        }
+
+        Ok(Self { bands, rule: Rule::CONWAY })
+    }
+
+    #[must_use]
+    /// Sets the rule used to compute each generation.
+    pub fn with_rule(mut self, rule: Rule) -> Self {
+        self.rule = rule;
+        self
+    }
+
+    #[inline]
+    #[must_use]
+    /// The number of bands the grid is split into.
+    pub fn nodes(&self) -> usize {
+        self.bands.len()
+    }
+
+    #[must_use]
+    /// Row where each band starts, plus the total number of rows at the end.
+    fn band_limits(&self, rows: usize) -> Vec<usize> {
+        let total: usize = self.bands.iter().map(|band| band.weight).sum();
+
+        let mut limits = vec![0];
+        let mut weight = 0;
+        for band in &self.bands {
+            weight += band.weight;
+            limits.push(rows * weight / total);
+        }
+        limits
+    }
+
+    #[must_use]
+    fn prepare_next_grid(&self, grid: &Grid) -> Grid {
+        let (rows, columns) = grid.shape();
+        if grid.cells() == 0 {
+            return Grid::new(rows, columns);
+        }
+
+        let limits = self.band_limits(rows);
+        let padded = PaddedGrid::from(grid);
+        let padded = &padded;
+
+        // SAFETY: every band is fully initialized by `fill_band`, and the bands cover the grid.
+        let cells = unsafe {
+            CellBuffer::init_with(grid.cells(), |cells| {
+                thread::scope(|scope| {
+                    let mut rest = cells;
+                    for (band, range) in self.bands.iter().zip(limits.windows(2)) {
+                        let (start, end) = (range[0], range[1]);
+                        let (current, tail) = rest.split_at_mut((end - start) * columns);
+                        rest = tail;
+
+                        scope.spawn(move || band.pool.install(|| Self::fill_band(self.rule, padded, start, current)));
+                    }
+                });
+            })
+        };
+        Grid::from_cells(cells, columns)
+    }
+
+    #[inline]
+    /// The next state of each cell in a row of the grid.
+    fn next_row(rule: Rule, grid: &PaddedGrid, row: usize) -> impl Iterator<Item = Cell> + '_ {
+        let current = grid.padded_row(row + 1);
+        neighbors::count_row(grid.padded_row(row), current, grid.padded_row(row + 2))
+            .zip(&current[1..])
+            .map(move |(count, &cell)| rule.next(cell, count))
+    }
+
+    fn fill_band(rule: Rule, grid: &PaddedGrid, start: usize, band: &mut [MaybeUninit<Cell>]) {
+        band.par_chunks_mut(grid.columns()).enumerate().for_each(|(offset, cells)| {
+            let row = start + offset;
+            match rule.topology() {
+                Topology::Moore => {
+                    for (cell, state) in cells.iter_mut().zip(Self::next_row(rule, grid, row)) {
+                        cell.write(state);
+                    }
+                },
+                _ => {
+                    for (col, cell) in cells.iter_mut().enumerate() {
+                        let neighborhood = grid.neighborhood(row, col);
+                        cell.write(rule.next(neighborhood[1][1], rule.count(&neighborhood, row)));
+                    }
+                },
+            }
+        });
+    }
+}
+
+impl Engine for NumaEngine {
+    #[inline]
+    fn update(&self, grid: &Grid) -> Grid {
+        self.prepare_next_grid(grid)
+    }
+
+    #[inline]
+    fn name(&self) -> &'static str {
+        "numa"
+    }
+
+    #[inline]
+    fn life_rule(&self) -> Option<Rule> {
+        Some(self.rule)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand::rngs::SmallRng;
+
+    use crate::engine::SerialEngine;
+
+    use super::*;
+
+    #[test]
+    pub fn same_as_serial() {
+        let mut rng = SmallRng::seed_from_u64(489);
+        let grid = Grid::random_with(67, 45, &mut rng);
+
+        let bands = [1, 3, 2].into_iter()
+            .map(|weight| Band { weight, pool: rayon::ThreadPoolBuilder::new().num_threads(2).build().unwrap() })
+            .collect();
+        let engine = NumaEngine { bands, rule: Rule::CONWAY };
+
+        assert_eq!(engine.update(&grid), SerialEngine::default().update(&grid));
+        assert_eq!(NumaEngine::with_nodes([]).unwrap().update(&grid), SerialEngine::default().update(&grid));
+
+        let hex = Rule::new(&[2], &[3, 4]).with_topology(Topology::Hex);
+        assert_eq!(engine.with_rule(hex).update(&grid), SerialEngine::new(hex).update(&grid));
+    }
+}
@@ -1,56 +1,180 @@
+use std::sync::Arc;
+
 use rayon::prelude::*;
+use rayon::{ThreadPool, ThreadPoolBuildError, ThreadPoolBuilder};
 
-use crate::cell::{Cell, Grid};
+use crate::cell::{Boundary, Grid};
+use crate::rule::Rule;
 
 use super::Engine;
 
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
-#[repr(transparent)]
-pub struct ParallelEngine;
+/// Bands given to each worker thread when the band size is chosen automatically, so that faster
+/// threads can pick up the work of slower ones.
+const BANDS_PER_THREAD: usize = 4;
+
+/// Parallel engine splitting the grid into bands of rows, each computed serially by a rayon worker.
+///
+/// By default the global rayon pool is used, so its size is set by `RAYON_NUM_THREADS`. An engine
+/// can also own a dedicated pool, which makes it easy to compare thread counts in a single process.
+#[derive(Debug, Clone, Default)]
+pub struct ParallelEngine {
+    rule: Rule,
+    pool: Option<Arc<ThreadPool>>,
+    chunk_rows: usize,
+}
 
 impl ParallelEngine {
+    #[inline]
     #[must_use]
-    fn next_cell_at(grid: &Grid, row: usize, col: usize) -> Cell {
-        let start_row = row.saturating_sub(1);
-        let start_col = col.saturating_sub(1);
+    /// Creates an engine running the given rule, on the global thread pool.
+    pub const fn new(rule: Rule) -> Self {
+        Self { rule, pool: None, chunk_rows: 0 }
+    }
 
-        let mut live_cells = 0;
+    /// Creates an engine with a dedicated pool of `threads` workers, running Conway's rule.
+    ///
+    /// With zero threads, rayon picks the number of threads as it does for the global pool.
+    pub fn with_threads(threads: usize) -> Result<Self, ThreadPoolBuildError> {
+        Ok(Self::with_pool(ThreadPoolBuilder::new().num_threads(threads).build()?))
+    }
 
-        for i in start_row..start_row+3 {
-            for j in start_col..start_col+3 {
-                if (i, j) != (row, col) && grid.get_cell(i, j) == Some(&Cell::Live) {
-                    live_cells += 1
-                }
-            }
-        }
+    #[must_use]
+    /// Creates an engine running Conway's rule on the given pool, like a pool of pinned threads.
+    pub fn with_pool(pool: ThreadPool) -> Self {
+        Self { rule: Rule::CONWAY, pool: Some(Arc::new(pool)), chunk_rows: 0 }
+    }
+
+    #[must_use]
+    /// Sets the rule used to compute each generation.
+    pub fn with_rule(mut self, rule: Rule) -> Self {
+        self.rule = rule;
+        self
+    }
+
+    #[must_use]
+    /// Sets how many rows are in each band of work, or `0` to split the grid into a few bands per
+    /// thread.
+    pub fn with_chunk_rows(mut self, rows: usize) -> Self {
+        self.chunk_rows = rows;
+        self
+    }
 
-        if live_cells == 3 || (live_cells == 2 && grid[row][col].is_live()) {
-            Cell::Live
-        } else {
-            Cell::Dead
+    #[inline]
+    #[must_use]
+    /// Rows in each band of work, or `0` if chosen from the grid size and number of threads.
+    pub const fn chunk_rows(&self) -> usize {
+        self.chunk_rows
+    }
+
+    #[must_use]
+    /// Rows in each band of work for a grid with `rows` rows.
+    fn band_rows(&self, rows: usize) -> usize {
+        if self.chunk_rows > 0 {
+            return self.chunk_rows;
         }
+        let bands = self.threads() * BANDS_PER_THREAD;
+        ((rows + bands - 1) / bands).max(1)
+    }
+
+    #[inline]
+    #[must_use]
+    /// The number of worker threads computing each generation.
+    pub fn threads(&self) -> usize {
+        self.pool.as_ref().map_or_else(rayon::current_num_threads, |pool| pool.current_num_threads())
     }
 
+    #[inline]
     #[must_use]
-    fn prepare_next_grid(grid: &Grid) -> Grid {
-        let mut next = Grid::new_with(grid.rows(), grid.columns(), Cell::Dead);
+    /// The rule used to compute each generation.
+    pub const fn rule(&self) -> Rule {
+        self.rule
+    }
 
-        next.par_iter_mut().enumerate().for_each(|(row, cells)| {
-            cells.par_iter_mut().enumerate().for_each(|(col, cell)| {
-                if Self::next_cell_at(grid, row, col).is_live() {
-                    *cell = Cell::Live
-                }
-            })
-        });
+    fn fill_next_grid(&self, grid: &Grid, next: &mut Grid) {
+        if next.shape() != grid.shape() {
+            *next = Grid::new(grid.rows(), grid.columns());
+        }
 
-        next
+        match &self.pool {
+            Some(pool) => pool.install(|| self.fill_cells(grid, next)),
+            None => self.fill_cells(grid, next),
+        }
+    }
+
+    fn fill_cells(&self, grid: &Grid, next: &mut Grid) {
+        let (rows, columns) = grid.shape();
+        if rows * columns == 0 {
+            return;
+        }
+
+        let band = self.band_rows(rows) * columns;
+        next.flat_mut().par_iter_mut()
+            .zip(grid.par_neighborhoods(Boundary::Dead))
+            .with_min_len(band)
+            .with_max_len(band)
+            .for_each(|(cell, (row, _, neighborhood))| *cell = self.rule.next(neighborhood[1][1], self.rule.count(&neighborhood, row)));
     }
 }
 
 impl Engine for ParallelEngine {
     #[inline]
-    #[must_use]
     fn update(&self, grid: &Grid) -> Grid {
-        Self::prepare_next_grid(grid)
+        let mut next = Grid::empty();
+        self.fill_next_grid(grid, &mut next);
+        next
+    }
+
+    #[inline]
+    fn update_into(&self, src: &Grid, dst: &mut Grid) {
+        self.fill_next_grid(src, dst);
+    }
+
+    #[inline]
+    fn name(&self) -> &'static str {
+        "parallel"
+    }
+
+    #[inline]
+    fn life_rule(&self) -> Option<Rule> {
+        Some(self.rule)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand::rngs::SmallRng;
+
+    use crate::cell::Topology;
+    use crate::engine::SerialEngine;
+
+    use super::*;
+
+    #[test]
+    pub fn dedicated_pool() {
+        let mut rng = SmallRng::seed_from_u64(522);
+        let grid = Grid::random_with(53, 71, &mut rng);
+        let expected = SerialEngine::new(Rule::HIGHLIFE).update(&grid);
+
+        for threads in [1, 3] {
+            let engine = ParallelEngine::with_threads(threads).unwrap().with_rule(Rule::HIGHLIFE);
+            assert_eq!(engine.threads(), threads);
+            assert_eq!(engine.update(&grid), expected);
+        }
+    }
+
+    #[test]
+    pub fn row_bands() {
+        let mut rng = SmallRng::seed_from_u64(523);
+        let grid = Grid::random_with(53, 71, &mut rng);
+        let expected = SerialEngine::default().update(&grid);
+
+        for rows in [0, 1, 7, 53, 100] {
+            assert_eq!(ParallelEngine::default().with_chunk_rows(rows).update(&grid), expected);
+        }
+
+        let hex = Rule::new(&[2], &[3, 4]).with_topology(Topology::Hex);
+        assert_eq!(ParallelEngine::new(hex).with_chunk_rows(7).update(&grid), SerialEngine::new(hex).update(&grid));
+        assert_eq!(ParallelEngine::default().update(&Grid::new(0, 5)), Grid::new(0, 5));
     }
 }
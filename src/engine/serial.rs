@@ -1,54 +1,120 @@
-use crate::cell::{Cell, Grid};
+use crate::cell::{Boundary, Grid};
+use crate::rule::Rule;
 
 use super::Engine;
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
 #[repr(transparent)]
-pub struct SerialEngine;
+pub struct SerialEngine {
+    rule: Rule,
+}
 
 impl SerialEngine {
+    #[inline]
     #[must_use]
-    fn next_cell_at(grid: &Grid, row: usize, col: usize) -> Cell {
-        let start_row = row.saturating_sub(1);
-        let start_col = col.saturating_sub(1);
-
-        let mut live_cells = 0;
-
-        for i in start_row..start_row+3 {
-            for j in start_col..start_col+3 {
-                if (i, j) != (row, col) && grid.get_cell(i, j) == Some(&Cell::Live) {
-                    live_cells += 1
-                }
-            }
-        }
-
-        if live_cells == 3 || (live_cells == 2 && grid[row][col].is_live()) {
-            Cell::Live
-        } else {
-            Cell::Dead
-        }
+    /// Creates an engine running the given rule.
+    pub const fn new(rule: Rule) -> Self {
+        Self { rule }
     }
 
+    #[inline]
     #[must_use]
-    fn prepare_next_grid(grid: &Grid) -> Grid {
-        let mut next = Grid::new_with(grid.rows(), grid.columns(), Cell::Dead);
-
-        for (row, cells) in next.iter_mut().enumerate() {
-            for (col, cell) in cells.iter_mut().enumerate() {
-                if Self::next_cell_at(grid, row, col).is_live() {
-                    *cell = Cell::Live
-                }
-            }
+    /// The rule used to compute each generation.
+    pub const fn rule(&self) -> Rule {
+        self.rule
+    }
+
+    fn fill_next_grid(&self, grid: &Grid, next: &mut Grid) {
+        if next.shape() != grid.shape() {
+            *next = Grid::new(grid.rows(), grid.columns());
         }
 
-        next
+        for (cell, (row, _, neighborhood)) in next.flat_mut().iter_mut().zip(grid.neighborhoods(Boundary::Dead)) {
+            *cell = self.rule.next(neighborhood[1][1], self.rule.count(&neighborhood, row));
+        }
     }
 }
 
 impl Engine for SerialEngine {
     #[inline]
-    #[must_use]
     fn update(&self, grid: &Grid) -> Grid {
-        Self::prepare_next_grid(grid)
+        let mut next = Grid::empty();
+        self.fill_next_grid(grid, &mut next);
+        next
+    }
+
+    #[inline]
+    fn update_into(&self, src: &Grid, dst: &mut Grid) {
+        self.fill_next_grid(src, dst);
+    }
+
+    #[inline]
+    fn name(&self) -> &'static str {
+        "serial"
+    }
+
+    #[inline]
+    fn life_rule(&self) -> Option<Rule> {
+        Some(self.rule)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cell::{Cell, Topology};
+    use crate::engine::SparseEngine;
+
+    use super::*;
+
+    #[test]
+    pub fn hexagonal_grid() {
+        let grid = Grid::random_seeded(20, 30, 575);
+        let rule = Rule::new(&[2], &[3, 4]).with_topology(Topology::Hex);
+        let next = SerialEngine::new(rule).update(&grid);
+
+        for (row, col) in (0..20_usize).flat_map(|row| (0..30_usize).map(move |col| (row, col))) {
+            // odd rows are shifted to the right, so their diagonal neighbors are on the right
+            let shift = (row % 2) as isize;
+            let neighbors = [(0, -1), (0, 1), (-1, shift - 1), (-1, shift), (1, shift - 1), (1, shift)];
+            let live = neighbors.iter()
+                .filter_map(|&(i, j)| Some((row.checked_add_signed(i)?, col.checked_add_signed(j)?)))
+                .filter(|&(y, x)| y < 20 && x < 30 && grid[(y, x)].is_live())
+                .count();
+            assert_eq!(next[(row, col)], rule.next(grid[(row, col)], live), "at ({row}, {col})");
+        }
+    }
+
+    #[test]
+    pub fn other_neighborhoods() {
+        let grid = Grid::random_seeded(15, 18, 576);
+        let glider = Topology::from_offsets(&[(-1, -1), (-1, 1), (1, 0)]).unwrap();
+
+        for topology in [Topology::VonNeumann, glider] {
+            let rule = Rule::new(&[1], &[1, 2]).with_topology(topology);
+            let next = SerialEngine::new(rule).update(&grid);
+
+            for (row, col) in (0..15_usize).flat_map(|row| (0..18_usize).map(move |col| (row, col))) {
+                let live = topology.offsets(row)
+                    .filter_map(|(dy, dx)| Some((row.checked_add_signed(dy)?, col.checked_add_signed(dx)?)))
+                    .filter(|&(y, x)| y < 15 && x < 18 && grid[(y, x)].is_live())
+                    .count();
+                assert_eq!(next[(row, col)], rule.next(grid[(row, col)], live), "at ({row}, {col}) with {topology:?}");
+            }
+            assert_eq!(SparseEngine::new(rule).update(&grid), next);
+            #[cfg(feature = "parallel")]
+            assert_eq!(crate::engine::ParallelEngine::new(rule).update(&grid), next);
+        }
+    }
+
+    #[test]
+    pub fn top_left_edges() {
+        // cells two rows or columns away from the top-left corner are not its neighbors
+        let grid: Grid = "..#.\n....\n#.#.\n....".parse().unwrap();
+        let next = SerialEngine::default().update(&grid);
+
+        assert_eq!(next[(0, 0)], Cell::Dead);
+        assert_eq!(next, SparseEngine::default().update(&grid));
+        #[cfg(feature = "parallel")]
+        assert_eq!(crate::engine::ParallelEngine::default().update(&grid), next);
     }
 }
@@ -0,0 +1,104 @@
+//! The errors of the library, in a single type.
+
+use std::io;
+
+use crate::cell::ParseCellError;
+use crate::engine::ParseTurmiteError;
+use crate::io::PatternError;
+use crate::rule::ParseRuleError;
+use crate::wireworld::ParseWireError;
+
+/// A boxed error from another crate, that can be sent between threads.
+type BoxedError = Box<dyn std::error::Error + Send + Sync>;
+
+/// Something that went wrong in the library.
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    /// Rows, grids or deltas of shapes that don't fit together, as `(rows, columns)`.
+    #[error("expected {}x{} cells, found {}x{}", expected.0, expected.1, found.0, found.1)]
+    DimensionMismatch { expected: (usize, usize), found: (usize, usize) },
+    /// A position `(row, col)` outside of a grid of the given shape.
+    #[error("position ({}, {}) is outside of a {}x{} grid", position.0, position.1, shape.0, shape.1)]
+    OutOfBounds { position: (usize, usize), shape: (usize, usize) },
+    /// Malformed text, like a pattern file, a rule or a turmite.
+    #[error(transparent)]
+    ParseError(BoxedError),
+    /// A frontend couldn't show the simulation.
+    #[error("could not render: {0}")]
+    RenderError(BoxedError),
+    /// Reading or writing a file failed.
+    #[error(transparent)]
+    IoError(#[from] io::Error),
+}
+
+impl Error {
+    #[inline]
+    #[must_use]
+    /// A [`RenderError`](Error::RenderError) from any error or message.
+    pub fn render(error: impl Into<BoxedError>) -> Self {
+        Self::RenderError(error.into())
+    }
+}
+
+impl From<PatternError> for Error {
+    fn from(error: PatternError) -> Self {
+        match error {
+            PatternError::Io(error) => Self::IoError(error),
+            error @ PatternError::Parse { .. } => Self::ParseError(Box::new(error)),
+        }
+    }
+}
+
+impl From<ParseCellError> for Error {
+    #[inline]
+    fn from(error: ParseCellError) -> Self {
+        Self::ParseError(Box::new(error))
+    }
+}
+
+impl From<ParseRuleError> for Error {
+    #[inline]
+    fn from(error: ParseRuleError) -> Self {
+        Self::ParseError(Box::new(error))
+    }
+}
+
+impl From<ParseTurmiteError> for Error {
+    #[inline]
+    fn from(error: ParseTurmiteError) -> Self {
+        Self::ParseError(Box::new(error))
+    }
+}
+
+impl From<ParseWireError> for Error {
+    #[inline]
+    fn from(error: ParseWireError) -> Self {
+        Self::ParseError(Box::new(error))
+    }
+}
+
+/// A result with the library [`Error`].
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+#[cfg(test)]
+mod tests {
+    use crate::cell::{Cell, Grid};
+
+    use super::*;
+
+    #[test]
+    pub fn messages() {
+        let uneven = Grid::try_from([vec![Cell::Live; 3], vec![Cell::Dead; 2]]).unwrap_err();
+        assert_eq!(uneven.to_string(), "expected 1x3 cells, found 1x2");
+
+        let outside = Error::OutOfBounds { position: (4, 0), shape: (3, 5) };
+        assert_eq!(outside.to_string(), "position (4, 0) is outside of a 3x5 grid");
+
+        let rule = Error::from("B3/X".parse::<crate::rule::Rule>().unwrap_err());
+        assert!(matches!(rule, Error::ParseError(_)));
+        let pattern = Error::from(crate::io::load("/nonexistent/pattern.rle").unwrap_err());
+        assert!(matches!(pattern, Error::IoError(_)));
+
+        assert_eq!(Error::render("no display").to_string(), "could not render: no display");
+    }
+}
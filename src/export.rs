@@ -0,0 +1,271 @@
+//! Exporting simulations as animated GIFs.
+
+use std::fs::File;
+use std::io::{self, BufWriter, ErrorKind, Write};
+use std::path::PathBuf;
+use std::sync::mpsc::{self, SyncSender};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use gif::{Encoder, EncodingError, Frame, Repeat};
+
+use crate::cell::Grid;
+use crate::par::*;
+use crate::theme::{self, Theme};
+
+/// Frames waiting to be encoded before [`GifWriter::capture`] blocks, which bounds the memory used
+/// when encoding is slower than the simulation.
+const BUFFERED_FRAMES: usize = 16;
+
+/// Palette indices of the colors in each frame.
+const DEAD: u8 = 0;
+const LIVE: u8 = 1;
+const AGENT: u8 = 2;
+
+/// Writes generations into an animated GIF that loops forever.
+///
+/// Frames are compressed and written on a background thread, so capturing a generation only
+/// draws it. The file is created on the first capture, when the size of the board is known.
+///
+/// # Example
+///
+/// ```no_run
+/// # use std::time::Duration;
+/// # use vida::cell::Grid;
+/// # use vida::engine::SerialEngine;
+/// # use vida::export::GifWriter;
+/// # use vida::simulation::Simulation;
+/// #
+/// let mut simulation = Simulation::new(SerialEngine::default(), Grid::random(100, 100));
+/// let mut gif = GifWriter::new("life.gif").with_scale(4).with_delay(Duration::from_millis(50));
+///
+/// for _ in 0..100 {
+///     gif.capture(simulation.grid(), &[]).unwrap();
+///     simulation.step();
+/// }
+/// let frames = gif.finish().unwrap();
+/// assert_eq!(frames, 100);
+/// ```
+#[derive(Debug)]
+pub struct GifWriter {
+    path: PathBuf,
+    scale: usize,
+    delay: Duration,
+    every: usize,
+    theme: Theme,
+    /// Calls to [`capture`](GifWriter::capture) so far, to keep one of every `every` generations.
+    captures: usize,
+    frames: usize,
+    worker: Option<Worker>,
+}
+
+/// The background thread encoding the frames.
+#[derive(Debug)]
+struct Worker {
+    sender: SyncSender<Vec<u8>>,
+    thread: JoinHandle<io::Result<()>>,
+    shape: (usize, usize),
+}
+
+impl GifWriter {
+    #[must_use]
+    /// Writes to `path`, with one pixel per cell, every generation and 100ms between frames.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            scale: 1,
+            delay: Duration::from_millis(100),
+            every: 1,
+            theme: Theme::default(),
+            captures: 0,
+            frames: 0,
+            worker: None,
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    /// Draws each cell as a square of `scale` by `scale` pixels.
+    pub fn with_scale(mut self, scale: usize) -> Self {
+        self.scale = scale.max(1);
+        self
+    }
+
+    #[inline]
+    #[must_use]
+    /// Time between frames, rounded down to hundredths of a second, as stored in the format.
+    pub const fn with_delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    #[inline]
+    #[must_use]
+    /// Keeps only one of every `every` captured generations, starting from the first one.
+    pub fn with_every(mut self, every: usize) -> Self {
+        self.every = every.max(1);
+        self
+    }
+
+    #[inline]
+    #[must_use]
+    /// Colors used in the frames. Only the dead, live and agent colors are used.
+    pub const fn with_theme(mut self, theme: Theme) -> Self {
+        self.theme = theme;
+        self
+    }
+
+    #[inline]
+    #[must_use]
+    /// The number of frames written so far.
+    pub const fn frames(&self) -> usize {
+        self.frames
+    }
+
+    /// Adds a generation to the animation, with agents drawn over the cells.
+    ///
+    /// Fails if the file can't be written, if the image would be too large for a GIF, or if the
+    /// grid has a different shape from the first one.
+    pub fn capture(&mut self, grid: &Grid, agents: &[(usize, usize)]) -> io::Result<()> {
+        if self.worker.as_ref().is_some_and(|worker| worker.shape != grid.shape()) {
+            return Err(io::Error::new(ErrorKind::InvalidInput, "all generations in a GIF must have the same shape"));
+        }
+
+        let skip = self.captures % self.every != 0;
+        self.captures += 1;
+        if skip {
+            return Ok(());
+        }
+
+        let worker = match &mut self.worker {
+            Some(worker) => worker,
+            None => self.worker.insert(self.start(grid.shape())?),
+        };
+
+        let pixels = indexed_pixels(grid, agents, self.scale);
+        if worker.sender.send(pixels).is_err() {
+            // the worker only stops early on errors
+            return self.finish_worker().and(Err(io::Error::new(ErrorKind::BrokenPipe, "GIF encoder stopped")));
+        }
+        self.frames += 1;
+        Ok(())
+    }
+
+    /// Waits for all frames to be written, returning how many there are.
+    pub fn finish(mut self) -> io::Result<usize> {
+        self.finish_worker()?;
+        Ok(self.frames)
+    }
+
+    fn start(&self, (rows, columns): (usize, usize)) -> io::Result<Worker> {
+        let too_large = || io::Error::new(ErrorKind::InvalidInput, "board too large for a GIF");
+        let width = u16::try_from(columns * self.scale).map_err(|_| too_large())?;
+        let height = u16::try_from(rows * self.scale).map_err(|_| too_large())?;
+        let delay = u16::try_from(self.delay.as_millis() / 10).unwrap_or(u16::MAX);
+        let palette: Vec<u8> = [self.theme.dead, self.theme.live, self.theme.agent].into_iter().flat_map(|color| {
+            let [r, g, b, _] = theme::to_rgba8(color);
+            [r, g, b]
+        }).collect();
+
+        let mut encoder = Encoder::new(BufWriter::new(File::create(&self.path)?), width, height, &palette).map_err(into_io)?;
+        encoder.set_repeat(Repeat::Infinite).map_err(into_io)?;
+
+        let (sender, receiver) = mpsc::sync_channel::<Vec<u8>>(BUFFERED_FRAMES);
+        let thread = thread::spawn(move || {
+            for pixels in receiver {
+                let mut frame = Frame::from_indexed_pixels(width, height, pixels, None);
+                frame.delay = delay;
+                encoder.write_frame(&frame).map_err(into_io)?;
+            }
+            encoder.into_inner()?.flush()
+        });
+        Ok(Worker { sender, thread, shape: (rows, columns) })
+    }
+
+    fn finish_worker(&mut self) -> io::Result<()> {
+        let Some(Worker { sender, thread, .. }) = self.worker.take() else {
+            return Ok(());
+        };
+
+        drop(sender);
+        thread.join().unwrap_or_else(|_| Err(io::Error::new(ErrorKind::Other, "GIF encoder panicked")))
+    }
+}
+
+impl Drop for GifWriter {
+    /// Finishes the file, ignoring errors. Use [`GifWriter::finish`] to check them.
+    fn drop(&mut self) {
+        let _ = self.finish_worker();
+    }
+}
+
+fn into_io(error: EncodingError) -> io::Error {
+    match error {
+        EncodingError::Io(error) => error,
+        EncodingError::Format(error) => io::Error::new(ErrorKind::InvalidData, error),
+    }
+}
+
+/// Palette indices of a grid, with `scale` by `scale` pixels for each cell.
+fn indexed_pixels(grid: &Grid, agents: &[(usize, usize)], scale: usize) -> Vec<u8> {
+    let (rows, columns) = grid.shape();
+    let line = columns * scale;
+    let mut pixels = vec![DEAD; rows * scale * line];
+    if line == 0 {
+        return pixels;
+    }
+
+    pixels.par_chunks_exact_mut(line * scale).zip(grid.flat().par_chunks(columns)).for_each(|(block, cells)| {
+        let (first, others) = block.split_at_mut(line);
+        for (pixels, cell) in first.chunks_exact_mut(scale).zip(cells) {
+            pixels.fill(if cell.is_live() { LIVE } else { DEAD });
+        }
+        for other in others.chunks_exact_mut(line) {
+            other.copy_from_slice(first);
+        }
+    });
+
+    for &(row, col) in agents.iter().filter(|&&(row, col)| row < rows && col < columns) {
+        for y in row * scale..(row + 1) * scale {
+            pixels[y * line + col * scale..][..scale].fill(AGENT);
+        }
+    }
+    pixels
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cell::Cell;
+
+    use super::*;
+
+    #[test]
+    pub fn animation() {
+        let path = std::env::temp_dir().join(format!("vida-export-{}.gif", std::process::id()));
+        let mut grid = Grid::new(3, 4);
+        grid[(1, 2)] = Cell::Live;
+
+        let mut gif = GifWriter::new(&path).with_scale(2).with_every(2).with_delay(Duration::from_millis(250));
+        for _ in 0..5 {
+            gif.capture(&grid, &[(0, 0)]).unwrap();
+        }
+        assert!(gif.capture(&Grid::new(4, 4), &[]).is_err());
+        assert_eq!(gif.finish().unwrap(), 3);
+
+        let mut options = gif::DecodeOptions::new();
+        options.set_color_output(gif::ColorOutput::Indexed);
+        let mut decoder = options.read_info(File::open(&path).unwrap()).unwrap();
+        assert_eq!((decoder.width(), decoder.height()), (8, 6));
+
+        let mut frames = 0;
+        while let Some(frame) = decoder.read_next_frame().unwrap() {
+            assert_eq!(frame.delay, 25);
+            assert_eq!(frame.buffer[..8], [AGENT, AGENT, DEAD, DEAD, DEAD, DEAD, DEAD, DEAD]);
+            assert_eq!(frame.buffer[2 * 8..3 * 8], [DEAD, DEAD, DEAD, DEAD, LIVE, LIVE, DEAD, DEAD]);
+            frames += 1;
+        }
+        assert_eq!(frames, 3);
+
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,306 @@
+//! A competitive two-player version of Life, based on the Immigration variant.
+//!
+//! Live cells belong to one of two players. Cells survive and are born as in Conway's Game of
+//! Life, and a newborn cell belongs to the player owning the majority of its three parents.
+//! Players take turns placing cells on the board, then the simulation runs until one of them is
+//! wiped out or the generation limit is reached, and the largest population wins.
+
+use rand::Rng;
+
+use crate::cell::{Cell, Grid};
+use crate::generations::{MultiStateEngine, States};
+use crate::multicolor::MultiColorEngine;
+
+/// One of the two competing players.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub enum Player {
+    #[default]
+    Red,
+    Blue,
+}
+
+impl Player {
+    #[inline]
+    #[must_use]
+    /// The other player.
+    pub const fn opponent(self) -> Self {
+        match self {
+            Self::Red => Self::Blue,
+            Self::Blue => Self::Red,
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    /// Index of the player, for per-player arrays.
+    pub const fn index(self) -> usize {
+        self as usize
+    }
+}
+
+/// Settings for a match.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct GameRules {
+    /// How many cells a player can place in each turn.
+    pub cells_per_turn: usize,
+    /// How many placement turns each player gets.
+    pub turns_per_player: usize,
+    /// Generations simulated before the populations are compared.
+    pub max_generations: usize,
+}
+
+impl Default for GameRules {
+    #[inline]
+    fn default() -> Self {
+        Self { cells_per_turn: 20, turns_per_player: 3, max_generations: 500 }
+    }
+}
+
+/// How a match ended.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Outcome {
+    Winner(Player),
+    Draw,
+}
+
+/// The current stage of a match.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Phase {
+    /// A player is placing cells, with `remaining` cells left in this turn.
+    Placing { player: Player, remaining: usize, turn: usize },
+    /// The board is evolving.
+    Running,
+    /// The match is over.
+    Finished(Outcome),
+}
+
+/// A match of the Immigration game.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ImmigrationGame {
+    grid: Grid,
+    owners: Box<[Player]>,
+    rules: GameRules,
+    phase: Phase,
+    generation: usize,
+}
+
+impl ImmigrationGame {
+    #[must_use]
+    /// Starts a match on an empty board, with the red player placing first.
+    pub fn new(rows: usize, columns: usize, rules: GameRules) -> Self {
+        let grid = Grid::new(rows, columns);
+        let owners = vec![Player::Red; grid.cells()].into();
+        let phase = Phase::Placing { player: Player::Red, remaining: rules.cells_per_turn, turn: 0 };
+
+        Self { grid, owners, rules, phase, generation: 0 }
+    }
+
+    #[must_use]
+    /// Starts a match already running, from a random board where live cells get random owners.
+    pub fn random_with<R: Rng + ?Sized>(rows: usize, columns: usize, rules: GameRules, rng: &mut R) -> Self {
+        let mut game = Self::new(rows, columns, rules);
+        game.grid = Grid::random_with(rows, columns, rng);
+        for owner in game.owners.iter_mut() {
+            *owner = if rng.gen() { Player::Red } else { Player::Blue };
+        }
+
+        game.phase = Phase::Running;
+        game
+    }
+
+    #[inline]
+    #[must_use]
+    /// The board, without ownership.
+    pub const fn grid(&self) -> &Grid {
+        &self.grid
+    }
+
+    #[inline]
+    #[must_use]
+    /// The current stage of the match.
+    pub const fn phase(&self) -> Phase {
+        self.phase
+    }
+
+    #[inline]
+    #[must_use]
+    /// Generations simulated so far.
+    pub const fn generation(&self) -> usize {
+        self.generation
+    }
+
+    #[inline]
+    #[must_use]
+    /// The settings for this match.
+    pub const fn rules(&self) -> &GameRules {
+        &self.rules
+    }
+
+    #[inline]
+    #[must_use]
+    /// The player owning a cell, or [`None`] if it is dead or out of bounds.
+    pub fn owner(&self, row: usize, col: usize) -> Option<Player> {
+        let cell = self.grid.get_cell(row, col)?;
+        cell.is_live().then(|| self.owners[row * self.grid.columns() + col])
+    }
+
+    #[must_use]
+    /// The population of each player, indexed by [`Player::index`].
+    pub fn scores(&self) -> [usize; 2] {
+        let mut scores = [0, 0];
+        for (cell, owner) in self.grid.flat().iter().zip(self.owners.iter()) {
+            if cell.is_live() {
+                scores[owner.index()] += 1;
+            }
+        }
+        scores
+    }
+
+    /// Places a cell for the current player.
+    ///
+    /// Returns `false` if no one is placing cells right now or the position is out of bounds or
+    /// already live. When the player runs out of cells, the turn passes automatically.
+    pub fn place(&mut self, row: usize, col: usize) -> bool {
+        let Phase::Placing { player, remaining, turn } = self.phase else {
+            return false;
+        };
+        if remaining == 0 {
+            return false;
+        }
+        match self.grid.get_cell_mut(row, col) {
+            Some(cell) if cell.is_dead() => *cell = Cell::Live,
+            _ => return false,
+        }
+
+        self.owners[row * self.grid.columns() + col] = player;
+        self.phase = Phase::Placing { player, remaining: remaining - 1, turn };
+        if remaining <= 1 {
+            self.end_turn();
+        }
+        true
+    }
+
+    /// Passes the turn to the other player, or starts the simulation after the last turn.
+    pub fn end_turn(&mut self) {
+        let Phase::Placing { player, turn, .. } = self.phase else {
+            return;
+        };
+
+        let turn = if player == Player::Blue { turn + 1 } else { turn };
+        self.phase = if turn >= self.rules.turns_per_player {
+            Phase::Running
+        } else {
+            Phase::Placing { player: player.opponent(), remaining: self.rules.cells_per_turn, turn }
+        };
+    }
+
+    /// Computes the next generation, if the simulation is running, and checks if someone won.
+    pub fn step(&mut self) {
+        if self.phase != Phase::Running {
+            return;
+        }
+
+        // player `i` is color `i + 1`, so the majority of the parents picks the owner of newborns
+        let mut states = States::new(self.grid.rows(), self.grid.columns());
+        for ((state, cell), owner) in states.flat_mut().iter_mut().zip(self.grid.flat()).zip(self.owners.iter()) {
+            if cell.is_live() {
+                *state = owner.index() as u8 + 1;
+            }
+        }
+
+        let next = MultiColorEngine::IMMIGRATION.update(&states);
+        for ((cell, owner), &state) in self.grid.flat_mut().iter_mut().zip(self.owners.iter_mut()).zip(next.flat()) {
+            *cell = Cell::from(state > 0);
+            if state > 0 {
+                *owner = if state == 1 { Player::Red } else { Player::Blue };
+            }
+        }
+
+        self.generation += 1;
+        self.check_winner();
+    }
+
+    fn check_winner(&mut self) {
+        let [red, blue] = self.scores();
+
+        let outcome = if red == 0 && blue == 0 {
+            Outcome::Draw
+        } else if blue == 0 {
+            Outcome::Winner(Player::Red)
+        } else if red == 0 {
+            Outcome::Winner(Player::Blue)
+        } else if self.generation < self.rules.max_generations {
+            return;
+        } else if red == blue {
+            Outcome::Draw
+        } else if red > blue {
+            Outcome::Winner(Player::Red)
+        } else {
+            Outcome::Winner(Player::Blue)
+        };
+
+        self.phase = Phase::Finished(outcome);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn alternating_turns() {
+        let rules = GameRules { cells_per_turn: 2, turns_per_player: 2, max_generations: 10 };
+        let mut game = ImmigrationGame::new(10, 10, rules);
+
+        assert!(game.place(0, 0));
+        assert!(!game.place(0, 0));
+        assert!(game.place(0, 1));
+        assert_eq!(game.phase(), Phase::Placing { player: Player::Blue, remaining: 2, turn: 0 });
+
+        game.end_turn();
+        assert_eq!(game.phase(), Phase::Placing { player: Player::Red, remaining: 2, turn: 1 });
+        game.end_turn();
+        game.end_turn();
+        assert_eq!(game.phase(), Phase::Running);
+        assert!(!game.place(5, 5));
+        assert_eq!(game.scores(), [2, 0]);
+    }
+
+    #[test]
+    pub fn births_take_majority_color() {
+        let rules = GameRules { cells_per_turn: 3, turns_per_player: 1, max_generations: 10 };
+        let mut game = ImmigrationGame::new(5, 5, rules);
+
+        // a blinker with two red cells and one blue cell in the middle
+        game.place(2, 1);
+        game.place(2, 3);
+        game.end_turn();
+        game.place(2, 2);
+        game.end_turn();
+
+        game.step();
+        assert_eq!(game.owner(1, 2), Some(Player::Red));
+        assert_eq!(game.owner(3, 2), Some(Player::Red));
+        assert_eq!(game.owner(2, 2), Some(Player::Blue));
+        assert_eq!(game.scores(), [2, 1]);
+
+        game.step();
+        assert_eq!(game.owner(2, 1), Some(Player::Red));
+        assert_eq!(game.owner(2, 2), Some(Player::Blue));
+        assert_eq!(game.phase(), Phase::Running);
+    }
+
+    #[test]
+    pub fn extinction_ends_the_game() {
+        let rules = GameRules { cells_per_turn: 1, turns_per_player: 1, max_generations: 10 };
+        let mut game = ImmigrationGame::new(5, 5, rules);
+
+        game.place(0, 0);
+        game.place(4, 4);
+        game.step();
+
+        assert_eq!(game.phase(), Phase::Finished(Outcome::Draw));
+        game.step();
+        assert_eq!(game.generation(), 1);
+    }
+}
@@ -0,0 +1,346 @@
+//! Multi-state automata with [`GenerationsRule`]s, like Star Wars or Brian's Brain.
+//!
+//! Cells hold a small state number instead of being dead or live, see [`GenerationsRule`] for how
+//! they change. Neighbors are counted as in Life-like rules, in the neighborhood given by the
+//! [`Topology`] of the rule, with dead cells past the edges.
+
+use std::ops::{Index, IndexMut};
+
+use rand::Rng;
+
+use crate::cell::{Cell, Grid, PaddedGrid, Topology};
+use crate::engine::neighbors;
+use crate::par::*;
+use crate::rule::GenerationsRule;
+use crate::theme::{Color, Theme};
+
+/// A 2D matrix of cell states, `0` for dead, `1` for live and higher numbers for dying cells.
+///
+/// The layout mirrors [`Grid`]: cells are stored in row-major order in a single buffer.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct States {
+    states: Box<[u8]>,
+    columns: usize,
+}
+
+impl States {
+    #[inline]
+    #[must_use]
+    /// Creates a board of `(rows, columns)` dead cells.
+    ///
+    /// # Panics
+    ///
+    /// If `rows * columns` overflows an `usize`.
+    pub fn new(rows: usize, columns: usize) -> Self {
+        let cells = rows.checked_mul(columns).expect("number of cells overflows usize");
+
+        Self { states: vec![0; cells].into(), columns }
+    }
+
+    #[must_use]
+    /// Creates a board of `(rows, columns)` randomly dead or live cells, without dying ones.
+    pub fn random_with<R: Rng + ?Sized>(rows: usize, columns: usize, rng: &mut R) -> Self {
+        Self::from(&Grid::random_with(rows, columns, rng))
+    }
+
+    #[inline]
+    #[must_use]
+    /// The number of rows in the board.
+    pub const fn rows(&self) -> usize {
+        match self.states.len().checked_div(self.columns) {
+            Some(rows) => rows,
+            None => 0,
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    /// The number of columns in each row of the board.
+    pub const fn columns(&self) -> usize {
+        self.columns
+    }
+
+    #[inline]
+    #[must_use]
+    /// The shape `(rows, columns)` of the board.
+    pub const fn shape(&self) -> (usize, usize) {
+        (self.rows(), self.columns())
+    }
+
+    #[inline]
+    #[must_use]
+    /// A slice over all the cells in the board, row-major order.
+    pub const fn flat(&self) -> &[u8] {
+        &self.states
+    }
+
+    #[inline]
+    #[must_use]
+    /// A mutable slice over all the cells in the board, row-major order.
+    pub fn flat_mut(&mut self) -> &mut [u8] {
+        &mut self.states
+    }
+
+    #[inline]
+    /// Iterates over the rows of the board.
+    pub fn iter(&self) -> std::slice::ChunksExact<'_, u8> {
+        self.states.chunks_exact(self.columns.max(1))
+    }
+
+    #[must_use]
+    /// The number of live cells, not counting the dying ones.
+    pub fn population(&self) -> usize {
+        self.states.par_iter().filter(|&&state| state == 1).count()
+    }
+
+    #[must_use]
+    /// Converts into a binary grid, where only the cells in state `1` are live.
+    pub fn to_grid(&self) -> Grid {
+        let mut grid = Grid::new(self.rows(), self.columns());
+        for (cell, &state) in grid.flat_mut().iter_mut().zip(self.states.iter()) {
+            *cell = Cell::from(state == 1);
+        }
+        grid
+    }
+}
+
+impl From<&Grid> for States {
+    #[inline]
+    fn from(grid: &Grid) -> Self {
+        Self { states: grid.as_bytes().into(), columns: grid.columns() }
+    }
+}
+
+impl Index<(usize, usize)> for States {
+    type Output = u8;
+
+    #[inline]
+    fn index(&self, (row, col): (usize, usize)) -> &u8 {
+        assert!(col < self.columns, "column out of bounds");
+        &self.states[row * self.columns + col]
+    }
+}
+
+impl IndexMut<(usize, usize)> for States {
+    #[inline]
+    fn index_mut(&mut self, (row, col): (usize, usize)) -> &mut u8 {
+        assert!(col < self.columns, "column out of bounds");
+        &mut self.states[row * self.columns + col]
+    }
+}
+
+/// An automaton whose cells hold a small state number, see [`States`].
+pub trait MultiStateEngine {
+    #[must_use]
+    fn update(&self, states: &States) -> States;
+
+    #[must_use]
+    /// The board after `generations` updates, or the same board with zero.
+    fn advance(&self, mut states: States, generations: usize) -> States {
+        for _ in 0..generations {
+            states = self.update(&states);
+        }
+        states
+    }
+
+    #[must_use]
+    /// Short identifier for the engine, as used in the command line.
+    fn name(&self) -> &'static str;
+
+    #[must_use]
+    /// Color of a cell in the given `state`, or [`None`] for cells drawn as background.
+    fn color(&self, theme: &Theme, state: u8) -> Option<Color>;
+}
+
+/// Computes generations of a [`GenerationsRule`], one row per task with the `parallel` feature.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(transparent)]
+pub struct GenerationsEngine {
+    rule: GenerationsRule,
+}
+
+impl GenerationsEngine {
+    #[inline]
+    #[must_use]
+    /// Creates an engine running the given rule.
+    pub const fn new(rule: GenerationsRule) -> Self {
+        Self { rule }
+    }
+
+    #[inline]
+    #[must_use]
+    /// The rule used to compute each generation.
+    pub const fn rule(&self) -> GenerationsRule {
+        self.rule
+    }
+}
+
+impl Default for GenerationsEngine {
+    #[inline]
+    fn default() -> Self {
+        Self::new(GenerationsRule::STAR_WARS)
+    }
+}
+
+impl MultiStateEngine for GenerationsEngine {
+    /// Computes the generation after `states`, with the same shape.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use vida::generations::{GenerationsEngine, MultiStateEngine, States};
+    /// # use vida::rule::GenerationsRule;
+    /// #
+    /// let mut states = States::new(3, 4);
+    /// states[(1, 1)] = 1;
+    /// states[(1, 2)] = 1;
+    ///
+    /// let engine = GenerationsEngine::new(GenerationsRule::BRIANS_BRAIN);
+    /// let next = engine.update(&states);
+    /// assert_eq!(next.flat(), [0, 1, 1, 0, 0, 2, 2, 0, 0, 1, 1, 0]);
+    /// ```
+    fn update(&self, states: &States) -> States {
+        let live = PaddedGrid::from(&states.to_grid());
+        let mut next = States::new(states.rows(), states.columns());
+
+        let rule = self.rule.rule();
+
+        next.states.par_chunks_exact_mut(states.columns().max(1)).enumerate().for_each(|(row, cells)| {
+            let current = &states.flat()[row * states.columns()..];
+            match rule.topology() {
+                Topology::Moore => {
+                    let counts = neighbors::count_row(live.padded_row(row), live.padded_row(row + 1), live.padded_row(row + 2));
+                    for ((cell, &state), count) in cells.iter_mut().zip(current).zip(counts) {
+                        *cell = self.rule.next(state, count);
+                    }
+                },
+                _ => {
+                    for (col, (cell, &state)) in cells.iter_mut().zip(current).enumerate() {
+                        *cell = self.rule.next(state, rule.count(&live.neighborhood(row, col), row));
+                    }
+                },
+            }
+        });
+        next
+    }
+
+    #[inline]
+    fn name(&self) -> &'static str {
+        "generations"
+    }
+
+    #[inline]
+    fn color(&self, theme: &Theme, state: u8) -> Option<Color> {
+        theme.by_state(state, self.rule.states())
+    }
+}
+
+/// Brian's Brain, where ready cells fire next to exactly two firing ones and then rest for a
+/// generation, drawn with its own palette, see [`Theme::by_brain`].
+///
+/// Runs [`GenerationsRule::BRIANS_BRAIN`], with state `1` for firing cells and `2` for refractory
+/// ones. Nearly every cell changes on each generation, so all the work is spread evenly across rows.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct BriansBrainEngine;
+
+impl MultiStateEngine for BriansBrainEngine {
+    #[inline]
+    fn update(&self, states: &States) -> States {
+        GenerationsEngine::new(GenerationsRule::BRIANS_BRAIN).update(states)
+    }
+
+    #[inline]
+    fn name(&self) -> &'static str {
+        "brians-brain"
+    }
+
+    #[inline]
+    fn color(&self, theme: &Theme, state: u8) -> Option<Color> {
+        theme.by_brain(state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand::rngs::SmallRng;
+
+    use crate::engine::{Engine, SerialEngine};
+    use crate::rule::Rule;
+
+    use super::*;
+
+    #[test]
+    pub fn two_states_are_life() {
+        let mut rng = SmallRng::seed_from_u64(571);
+        let grid = Grid::random_with(17, 23, &mut rng);
+        let engine = GenerationsEngine::new(GenerationsRule::new(Rule::HIGHLIFE, 2));
+
+        let next = engine.update(&States::from(&grid));
+        assert_eq!(next.to_grid(), SerialEngine::new(Rule::HIGHLIFE).update(&grid));
+        assert_eq!(next.population(), next.to_grid().population());
+    }
+
+    #[test]
+    pub fn dying_cells_fade() {
+        let mut rng = SmallRng::seed_from_u64(572);
+        let mut states = States::random_with(20, 30, &mut rng);
+        let engine = GenerationsEngine::default();
+
+        for _ in 0..10 {
+            let next = engine.update(&states);
+            for (&state, &after) in states.flat().iter().zip(next.flat()) {
+                match state {
+                    0 => assert!(after <= 1),
+                    1 => assert!(after <= 2),
+                    _ => assert_eq!(after, (state + 1) % 4),
+                }
+            }
+            states = next;
+        }
+        assert!(states.flat().iter().all(|&state| state < 4));
+        assert_eq!(engine.update(&States::new(0, 5)), States::new(0, 5));
+    }
+
+    #[test]
+    pub fn brians_brain() {
+        let mut rng = SmallRng::seed_from_u64(578);
+        let states = States::random_with(20, 30, &mut rng);
+
+        let next = BriansBrainEngine.update(&states);
+        assert_eq!(next, GenerationsEngine::new(GenerationsRule::BRIANS_BRAIN).update(&states));
+        for (&state, &after) in states.flat().iter().zip(next.flat()) {
+            assert_eq!(after == 2, state == 1);
+        }
+
+        let theme = Theme::default();
+        assert_eq!(BriansBrainEngine.color(&theme, 0), None);
+        assert_eq!(BriansBrainEngine.color(&theme, 1), Some(theme.brain[0]));
+        assert_eq!(BriansBrainEngine.color(&theme, 2), Some(theme.brain[1]));
+    }
+
+    /// Counts its own generations in the first cell.
+    struct Counter;
+
+    impl MultiStateEngine for Counter {
+        fn update(&self, states: &States) -> States {
+            let mut next = states.clone();
+            next[(0, 0)] += 1;
+            next
+        }
+
+        fn name(&self) -> &'static str {
+            "counter"
+        }
+
+        fn color(&self, _: &Theme, _: u8) -> Option<Color> {
+            None
+        }
+    }
+
+    #[test]
+    pub fn advances_exact_generations() {
+        assert_eq!(Counter.advance(States::new(1, 1), 0)[(0, 0)], 0);
+        assert_eq!(Counter.advance(States::new(1, 1), 5)[(0, 0)], 5);
+    }
+}
@@ -0,0 +1,236 @@
+//! How often each cell has been alive over a run.
+
+use std::ops::Index;
+
+#[cfg(feature = "image")]
+use image::{Rgba, RgbaImage};
+
+use crate::cell::Grid;
+use crate::par::*;
+#[cfg(feature = "image")]
+use crate::theme::{self, Theme};
+
+/// The number of generations each cell of a grid has been live, counting the current one.
+///
+/// Unlike [`Ages`](crate::age::Ages), the counts never go back to zero, so cells that blink on and
+/// off keep getting hotter. Counts saturate at [`u32::MAX`].
+///
+/// # Example
+///
+/// ```
+/// # use vida::cell::{Cell, Grid};
+/// # use vida::engine::{Engine, SerialEngine};
+/// # use vida::heatmap::Heatmap;
+/// #
+/// let mut blinker = Grid::new(5, 5);
+/// blinker[2][1..4].fill(Cell::Live);
+/// let mut heatmap = Heatmap::new(&blinker);
+///
+/// for _ in 0..3 {
+///     blinker = SerialEngine::default().update(&blinker);
+///     heatmap.update(&blinker);
+/// }
+///
+/// assert_eq!(heatmap[(2, 2)], 4);
+/// assert_eq!(heatmap[(1, 2)], 2);
+/// assert_eq!(heatmap[(2, 1)], 2);
+/// assert_eq!(heatmap.fraction(1, 2), 0.5);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Heatmap {
+    counts: Box<[u32]>,
+    rows: usize,
+    columns: usize,
+    /// The largest count, kept so the colors of every cell are cheap to find.
+    max: u32,
+}
+
+impl Heatmap {
+    #[must_use]
+    /// Starts counting from a grid, where every live cell was alive once.
+    pub fn new(grid: &Grid) -> Self {
+        let counts: Box<[u32]> = grid.flat().par_iter().map(|cell| u32::from(cell.is_live())).collect();
+        let max = counts.par_iter().copied().max().unwrap_or(0);
+        Self { counts, rows: grid.rows(), columns: grid.columns(), max }
+    }
+
+    /// Moves on to the next generation, counting the cells that are live in it.
+    ///
+    /// If the grid has a different shape, counting starts again.
+    pub fn update(&mut self, grid: &Grid) {
+        if self.shape() != grid.shape() {
+            *self = Self::new(grid);
+            return;
+        }
+
+        self.counts.par_iter_mut().zip(grid.flat().par_iter()).for_each(|(count, cell)| {
+            *count = count.saturating_add(u32::from(cell.is_live()));
+        });
+        self.max = self.counts.par_iter().copied().max().unwrap_or(0);
+    }
+
+    /// Goes back to the previous generation, no longer counting the cells live in `grid`, the
+    /// generation left.
+    ///
+    /// If the grid has a different shape, nothing changes.
+    pub fn step_back(&mut self, grid: &Grid) {
+        if self.shape() != grid.shape() {
+            return;
+        }
+
+        self.counts.par_iter_mut().zip(grid.flat().par_iter()).for_each(|(count, cell)| {
+            *count = count.saturating_sub(u32::from(cell.is_live()));
+        });
+        self.max = self.counts.par_iter().copied().max().unwrap_or(0);
+    }
+
+    #[inline]
+    #[must_use]
+    /// The shape `(rows, columns)` of the grid.
+    pub const fn shape(&self) -> (usize, usize) {
+        (self.rows, self.columns)
+    }
+
+    #[inline]
+    #[must_use]
+    /// The counts of all cells, in row-major order.
+    pub const fn flat(&self) -> &[u32] {
+        &self.counts
+    }
+
+    #[inline]
+    #[must_use]
+    /// The count of a cell, or [`None`] if the index is out-of-bounds.
+    pub fn get(&self, row: usize, col: usize) -> Option<u32> {
+        if col < self.columns {
+            self.counts.get(row.checked_mul(self.columns)?.checked_add(col)?).copied()
+        } else {
+            None
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    /// The count of the most active cell.
+    pub const fn max(&self) -> u32 {
+        self.max
+    }
+
+    #[inline]
+    #[must_use]
+    /// The count of a cell relative to the most active one, from `0.0` for cells that were never
+    /// alive to `1.0`.
+    pub fn fraction(&self, row: usize, col: usize) -> f32 {
+        if self.max == 0 {
+            0.0
+        } else {
+            (f64::from(self[(row, col)]) / f64::from(self.max)) as f32
+        }
+    }
+
+    #[must_use]
+    /// The counts of a larger grid of `(rows, columns)` cells, where this one was copied at
+    /// `(top, left)`, like [`Grid::expanded`].
+    ///
+    /// # Panics
+    ///
+    /// If this grid doesn't fit in the new one at that position.
+    pub fn expanded(&self, (top, left): (usize, usize), (rows, columns): (usize, usize)) -> Self {
+        assert!(top + self.rows <= rows && left + self.columns <= columns, "heatmap doesn't fit in the expanded grid");
+
+        let mut counts = vec![0; rows * columns].into_boxed_slice();
+        if self.columns > 0 {
+            for (old, row) in self.counts.chunks_exact(self.columns).zip(top..) {
+                let start = row * columns + left;
+                counts[start..start + self.columns].copy_from_slice(old);
+            }
+        }
+        Self { counts, rows, columns, max: self.max }
+    }
+
+    #[must_use]
+    #[cfg(feature = "image")]
+    /// Draws the heatmap as an image, with each cell `scale` by `scale` pixels colored by
+    /// [`Theme::by_activity`], and cells that were never alive in the background color.
+    ///
+    /// # Panics
+    ///
+    /// If the image would be larger than `u32::MAX` pixels on a side.
+    pub fn to_image(&self, theme: &Theme, scale: u32) -> RgbaImage {
+        let side = |cells: usize| u32::try_from(cells).ok().and_then(|cells| cells.checked_mul(scale)).expect("image too large");
+
+        RgbaImage::from_fn(side(self.columns), side(self.rows), |x, y| {
+            let fraction = self.fraction((y / scale) as usize, (x / scale) as usize);
+            Rgba(theme::to_rgba8(theme.by_activity(fraction).unwrap_or(theme.dead)))
+        })
+    }
+}
+
+impl Index<(usize, usize)> for Heatmap {
+    type Output = u32;
+
+    #[inline]
+    fn index(&self, (row, col): (usize, usize)) -> &u32 {
+        assert!(col < self.columns, "column out of bounds");
+        &self.counts[row * self.columns + col]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cell::Cell;
+    use crate::engine::SerialEngine;
+    use crate::patterns;
+    use crate::simulation::Simulation;
+
+    use super::*;
+
+    #[test]
+    pub fn glider_trail() {
+        let mut simulation = Simulation::new(SerialEngine::default(), patterns::glider().expanded((0, 0), (16, 16))).track_activity(true);
+        for _ in 0..20 {
+            simulation.step();
+        }
+        let heatmap = simulation.activity().unwrap();
+        // every generation has five live cells
+        assert_eq!(heatmap.flat().iter().map(|&count| u64::from(count)).sum::<u64>(), 21 * 5);
+        assert_eq!(heatmap.get(0, 0), Some(0));
+        assert_eq!(heatmap.get(0, 16), None);
+        assert!(heatmap.max() < 21);
+
+        // the block is always alive, so it is the hottest part of the board
+        simulation.reset(Grid::new_with(2, 2, Cell::Live), 0);
+        simulation.step();
+        let heatmap = simulation.activity().unwrap();
+        assert_eq!((heatmap.shape(), heatmap.max()), ((2, 2), 2));
+        assert_eq!(heatmap.fraction(1, 1), 1.0);
+    }
+
+    #[test]
+    pub fn expands_in_place() {
+        let mut grid = Grid::new(2, 3);
+        grid[(1, 2)] = Cell::Live;
+        let heatmap = Heatmap::new(&grid).expanded((1, 2), (4, 6));
+
+        assert_eq!(heatmap.shape(), (4, 6));
+        assert_eq!(heatmap[(2, 4)], 1);
+        assert_eq!((heatmap.flat().iter().sum::<u32>(), heatmap.max()), (1, 1));
+    }
+
+    #[test]
+    #[cfg(feature = "image")]
+    pub fn image_colors() {
+        let theme = Theme::default();
+        let mut grid = Grid::new(1, 3);
+        grid[(0, 0)] = Cell::Live;
+        let mut heatmap = Heatmap::new(&grid);
+        grid[(0, 1)] = Cell::Live;
+        heatmap.update(&grid);
+
+        let image = heatmap.to_image(&theme, 2);
+        assert_eq!(image.dimensions(), (6, 2));
+        assert_eq!(image.get_pixel(1, 1).0, theme::to_rgba8(theme.heat[1]));
+        assert_eq!(image.get_pixel(5, 0).0, theme::to_rgba8(theme.dead));
+        assert_eq!(image.get_pixel(2, 0).0, theme::to_rgba8(theme.by_activity(0.5).unwrap()));
+    }
+}
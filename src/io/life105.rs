@@ -0,0 +1,160 @@
+//! The Life 1.05 format, with cells drawn as blocks of text.
+//!
+//! After the `#Life 1.05` header, the file has optional `#D` descriptions, the rule as `#N`
+//! (Conway's) or `#R survival/birth`, and then blocks of cells. Each block starts with `#P x y`,
+//! the position of its top-left cell relative to the center, followed by rows of `.` (dead) and
+//! `*` (live) characters.
+
+use std::fmt::Write;
+
+use crate::cell::Grid;
+
+use super::{life106, Pattern, PatternError};
+
+/// First line of every Life 1.05 file.
+pub const HEADER: &str = "#Life 1.05";
+
+/// Maximum line length in Life 1.05 files, wider patterns are split into blocks.
+const LINE_LENGTH: usize = 80;
+
+/// Parses a pattern in Life 1.05 format, into a grid just large enough to hold it.
+///
+/// # Example
+///
+/// ```
+/// # use vida::cell::Cell;
+/// # use vida::io::life105;
+/// #
+/// let glider = life105::parse("#Life 1.05\n#D Glider\n#N\n#P -1 -1\n.*\n..*\n***\n").unwrap();
+///
+/// assert_eq!(glider.grid.shape(), (3, 3));
+/// assert_eq!(glider.rule.as_deref(), Some("B3/S23"));
+/// assert_eq!(glider.grid[(0, 1)], Cell::Live);
+/// ```
+pub fn parse(text: &str) -> Result<Pattern, PatternError> {
+    let mut lines = text.lines().enumerate().map(|(index, line)| (index + 1, line.trim()));
+    if lines.next().map(|(_, line)| line) != Some(HEADER) {
+        return Err(PatternError::parse(1, format!("missing {HEADER:?} header")));
+    }
+
+    let mut rule = None;
+    let mut comments = Vec::new();
+    let mut cells = Vec::new();
+    let mut block: Option<(isize, isize)> = None;
+    let mut row = 0;
+
+    for (line, text) in lines {
+        if let Some(description) = text.strip_prefix("#D") {
+            comments.push(description.trim().to_owned());
+        } else if text == "#N" {
+            rule = Some("B3/S23".to_owned());
+        } else if let Some(survival_birth) = text.strip_prefix("#R") {
+            let Some((survival, birth)) = survival_birth.trim().split_once('/') else {
+                return Err(PatternError::parse(line, "expected rule as `#R survival/birth`"));
+            };
+            rule = Some(format!("B{birth}/S{survival}"));
+        } else if let Some(position) = text.strip_prefix("#P") {
+            let mut numbers = position.split_whitespace().map(str::parse::<isize>);
+            let (Some(Ok(x)), Some(Ok(y)), None) = (numbers.next(), numbers.next(), numbers.next()) else {
+                return Err(PatternError::parse(line, "expected block position as `#P x y`"));
+            };
+            block = Some((x, y));
+            row = 0;
+        } else if text.starts_with('#') || text.is_empty() {
+            continue;
+        } else {
+            let Some((x, y)) = block else {
+                return Err(PatternError::parse(line, "cells before the first `#P` block"));
+            };
+
+            for (col, char) in text.chars().enumerate() {
+                match char {
+                    '.' => (),
+                    '*' => {
+                        let cell = isize::try_from(col).ok().and_then(|col| x.checked_add(col)).zip(y.checked_add(row));
+                        let (x, y) = cell.ok_or_else(|| PatternError::parse(line, "pattern too large"))?;
+                        cells.push((line, x, y));
+                    },
+                    _ => return Err(PatternError::parse(line, format!("unexpected character {char:?}"))),
+                }
+            }
+            row += 1;
+        }
+    }
+
+    let pattern = life106::fit(cells)?;
+    Ok(Pattern { rule, comments, ..pattern })
+}
+
+#[must_use]
+/// Encodes a grid in Life 1.05 format, trimming the dead cells around the live ones.
+///
+/// The pattern is centered on the origin, and patterns wider than 80 cells are split into blocks.
+pub fn encode(grid: &Grid) -> String {
+    let mut output = format!("{HEADER}\n#N\n");
+    let Some((top, left, bottom, right)) = grid.bounding_box() else {
+        return output;
+    };
+
+    let (height, width) = (bottom - top + 1, right - left + 1);
+    let (y, x) = (-(height as isize / 2), -(width as isize / 2));
+
+    for start in (left..=right).step_by(LINE_LENGTH) {
+        let end = (start + LINE_LENGTH - 1).min(right);
+        let _ = writeln!(output, "#P {} {y}", x + (start - left) as isize);
+
+        for row in top..=bottom {
+            let cells = &grid[row][start..=end];
+            let last = cells.iter().rposition(|cell| cell.is_live()).map_or(0, |last| last + 1);
+            let line: String = cells[..last].iter().map(|cell| if cell.is_live() { '*' } else { '.' }).collect();
+
+            output.push_str(if line.is_empty() { "." } else { &line });
+            output.push('\n');
+        }
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cell::Cell;
+
+    use super::*;
+
+    #[test]
+    pub fn wide_patterns_round_trip() {
+        let mut grid = Grid::new(5, 200);
+        grid[(1, 10)] = Cell::Live;
+        grid[(3, 190)] = Cell::Live;
+        grid[(2, 100)] = Cell::Live;
+
+        let text = encode(&grid);
+        assert!(text.lines().all(|line| line.len() <= LINE_LENGTH));
+        assert_eq!(text.matches("#P").count(), 3);
+
+        let pattern = parse(&text).unwrap();
+        assert_eq!(pattern.grid.shape(), (3, 181));
+        assert_eq!(pattern.grid[(0, 0)], Cell::Live);
+        assert_eq!(pattern.grid[(1, 90)], Cell::Live);
+        assert_eq!(pattern.grid[(2, 180)], Cell::Live);
+    }
+
+    #[test]
+    pub fn rules_and_errors() {
+        let pattern = parse("#Life 1.05\n#R 23/36\n#P 0 0\n*\n").unwrap();
+        assert_eq!(pattern.rule.as_deref(), Some("B36/S23"));
+
+        assert!(parse("#Life 1.05\n*\n").is_err());
+        assert!(parse("#Life 1.05\n#P 0\n*\n").is_err());
+        assert!(parse("#Life 1.05\n#P 0 0\n*o\n").is_err());
+    }
+
+    #[test]
+    pub fn too_large() {
+        let text = "#Life 1.05\n#P -1000000000 0\n*\n#P 1000000000 0\n*\n";
+        assert_eq!(parse(text).unwrap_err().to_string(), "invalid pattern at line 1: pattern too large");
+
+        let text = "#Life 1.05\n#P 9223372036854775807 0\n.*\n";
+        assert_eq!(parse(text).unwrap_err().to_string(), "invalid pattern at line 3: pattern too large");
+    }
+}
@@ -0,0 +1,135 @@
+//! The Life 1.06 format, a list of live cell coordinates.
+//!
+//! After the `#Life 1.06` header, each line has the `x y` coordinates of a live cell. Coordinates
+//! are unbounded and may be negative, so they need an origin to be placed in a [`Grid`].
+
+use std::fmt::Write;
+
+use crate::cell::{Cell, Grid};
+
+use super::{pattern_grid, Pattern, PatternError};
+
+/// First line of every Life 1.06 file.
+pub const HEADER: &str = "#Life 1.06";
+
+/// Parses a pattern in Life 1.06 format, into a grid just large enough to hold it.
+///
+/// The top-left live cell ends up at `(0, 0)`.
+pub fn parse(text: &str) -> Result<Pattern, PatternError> {
+    fit(coordinates(text)?)
+}
+
+/// Places `(line, x, y)` coordinates into a grid just large enough to hold them, failing if it
+/// would have more than [`MAX_CELLS`](super::MAX_CELLS) cells.
+pub(super) fn fit(cells: Vec<(usize, isize, isize)>) -> Result<Pattern, PatternError> {
+    let min_x = cells.iter().map(|&(_, x, _)| x).min().unwrap_or(0);
+    let min_y = cells.iter().map(|&(_, _, y)| y).min().unwrap_or(0);
+    let max_x = cells.iter().map(|&(_, x, _)| x).max().unwrap_or(-1);
+    let max_y = cells.iter().map(|&(_, _, y)| y).max().unwrap_or(-1);
+
+    let too_large = || PatternError::parse(1, "pattern too large");
+    let side = |min: isize, max: isize| max.checked_sub(min)?.checked_add(1).and_then(|side| usize::try_from(side).ok());
+    let rows = side(min_y, max_y).ok_or_else(too_large)?;
+    let columns = side(min_x, max_x).ok_or_else(too_large)?;
+
+    place(cells, pattern_grid(1, rows, columns)?, (-min_y, -min_x))
+}
+
+/// Parses a pattern in Life 1.06 format into a `(rows, columns)` grid.
+///
+/// The cell at coordinates `x y` is placed at row `y + origin.0` and column `x + origin.1`. Cells
+/// that fall outside of the grid are reported as errors.
+///
+/// # Example
+///
+/// ```
+/// # use vida::cell::Cell;
+/// # use vida::io::life106;
+/// #
+/// let text = "#Life 1.06\n0 -1\n1 0\n-1 1\n0 1\n1 1\n";
+/// let glider = life106::parse_with_origin(text, 10, 10, (5, 5)).unwrap();
+///
+/// assert_eq!(glider.grid[(4, 5)], Cell::Live);
+/// assert_eq!(glider.grid[(6, 4)], Cell::Live);
+/// ```
+pub fn parse_with_origin(text: &str, rows: usize, columns: usize, origin: (isize, isize)) -> Result<Pattern, PatternError> {
+    place(coordinates(text)?, Grid::new(rows, columns), origin)
+}
+
+/// Reads the `(line, x, y)` of each live cell.
+fn coordinates(text: &str) -> Result<Vec<(usize, isize, isize)>, PatternError> {
+    let mut lines = text.lines().enumerate().map(|(index, line)| (index + 1, line.trim()));
+    if lines.next().map(|(_, line)| line) != Some(HEADER) {
+        return Err(PatternError::parse(1, format!("missing {HEADER:?} header")));
+    }
+
+    let mut cells = Vec::new();
+    for (line, text) in lines {
+        if text.is_empty() || text.starts_with('#') {
+            continue;
+        }
+
+        let mut numbers = text.split_whitespace().map(str::parse::<isize>);
+        match (numbers.next(), numbers.next(), numbers.next()) {
+            (Some(Ok(x)), Some(Ok(y)), None) => cells.push((line, x, y)),
+            _ => return Err(PatternError::parse(line, format!("expected two coordinates, found {text:?}"))),
+        }
+    }
+    Ok(cells)
+}
+
+/// Places `(line, x, y)` coordinates into an empty grid, shifted by `origin`.
+fn place(cells: Vec<(usize, isize, isize)>, mut grid: Grid, origin: (isize, isize)) -> Result<Pattern, PatternError> {
+    for (line, x, y) in cells {
+        let row = y.checked_add(origin.0).and_then(|row| usize::try_from(row).ok());
+        let col = x.checked_add(origin.1).and_then(|col| usize::try_from(col).ok());
+
+        match row.zip(col).and_then(|(row, col)| grid.get_cell_mut(row, col)) {
+            Some(cell) => *cell = Cell::Live,
+            None => return Err(PatternError::parse(line, format!("cell ({x}, {y}) is outside of the grid"))),
+        }
+    }
+
+    Ok(Pattern { grid, ..Pattern::default() })
+}
+
+#[must_use]
+/// Encodes a grid in Life 1.06 format, with the top-left corner of the grid at `(0, 0)`.
+pub fn encode(grid: &Grid) -> String {
+    let mut output = format!("{HEADER}\n");
+
+    for (row, col) in grid.live_cells() {
+        let _ = writeln!(output, "{col} {row}");
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn negative_coordinates() {
+        let pattern = parse("#Life 1.06\n-3 -2\n2 0\n").unwrap();
+
+        assert_eq!(pattern.grid.shape(), (3, 6));
+        assert_eq!(pattern.grid[(0, 0)], Cell::Live);
+        assert_eq!(pattern.grid[(2, 5)], Cell::Live);
+        assert_eq!(encode(&pattern.grid), "#Life 1.06\n0 0\n5 2\n");
+    }
+
+    #[test]
+    pub fn outside_of_grid() {
+        assert!(parse_with_origin("#Life 1.06\n0 0\n-1 0\n", 5, 5, (0, 0)).is_err());
+        assert!(parse_with_origin("#Life 1.06\n0 0\n1 x\n", 5, 5, (0, 0)).is_err());
+        assert!(parse("0 0\n").is_err());
+    }
+
+    #[test]
+    pub fn too_large() {
+        for text in ["#Life 1.06\n-1000000000 0\n1000000000 0\n", "#Life 1.06\n0 0\n0 9223372036854775807\n"] {
+            let error = parse(text).unwrap_err();
+            assert_eq!(error.to_string(), "invalid pattern at line 1: pattern too large");
+        }
+    }
+}
@@ -0,0 +1,99 @@
+//! Reading and writing patterns in the file formats used by other Life programs.
+
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+use std::fs;
+use std::path::Path;
+
+use crate::cell::Grid;
+
+pub mod life105;
+pub mod life106;
+pub mod plaintext;
+pub mod rle;
+
+/// A pattern read from a file, with its metadata.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct Pattern {
+    /// The cells of the pattern, sized to the dimensions declared in the file.
+    pub grid: Grid,
+    /// The rule the pattern was made for, like `B3/S23`, if the file declares one.
+    pub rule: Option<String>,
+    /// The name of the pattern, if the file declares one.
+    pub name: Option<String>,
+    /// Other comment lines, without their markers.
+    pub comments: Vec<String>,
+}
+
+/// Error returned when a pattern can't be loaded.
+#[derive(Debug)]
+pub enum PatternError {
+    /// The file couldn't be read.
+    Io(std::io::Error),
+    /// The contents are malformed, at the given line (starting from 1).
+    Parse { line: usize, message: String },
+}
+
+impl PatternError {
+    #[inline]
+    #[must_use]
+    pub(crate) fn parse(line: usize, message: impl Into<String>) -> Self {
+        Self::Parse { line, message: message.into() }
+    }
+}
+
+/// Most cells in the grid of a pattern, so a malformed file fails instead of exhausting memory.
+pub const MAX_CELLS: usize = 1 << 30;
+
+/// An empty grid of `(rows, columns)` for a pattern, failing at `line` if it would have more than
+/// [`MAX_CELLS`] cells.
+pub(crate) fn pattern_grid(line: usize, rows: usize, columns: usize) -> Result<Grid, PatternError> {
+    rows.checked_mul(columns)
+        .filter(|&cells| cells <= MAX_CELLS)
+        .and_then(|_| Grid::try_new(rows, columns))
+        .ok_or_else(|| PatternError::parse(line, "pattern too large"))
+}
+
+impl Display for PatternError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(error) => write!(f, "could not read pattern: {error}"),
+            Self::Parse { line, message } => write!(f, "invalid pattern at line {line}: {message}"),
+        }
+    }
+}
+
+impl Error for PatternError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Io(error) => Some(error),
+            Self::Parse { .. } => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for PatternError {
+    #[inline]
+    fn from(error: std::io::Error) -> Self {
+        Self::Io(error)
+    }
+}
+
+/// Reads a pattern file.
+///
+/// The format is detected from the contents: Life 1.05 and 1.06 files start with their `#Life`
+/// header, plaintext files start with a `!` comment or a row of `.` and `O` cells, and anything
+/// else is read as RLE.
+pub fn load(path: impl AsRef<Path>) -> Result<Pattern, PatternError> {
+    parse(&fs::read_to_string(path)?)
+}
+
+/// Parses a pattern in any of the supported formats, detected as in [`load`].
+pub fn parse(text: &str) -> Result<Pattern, PatternError> {
+    match text.lines().next().map(str::trim) {
+        Some(life105::HEADER) => life105::parse(text),
+        Some(life106::HEADER) => life106::parse(text),
+        _ if plaintext::detect(text) => plaintext::parse(text),
+        _ => rle::parse(text),
+    }
+}
@@ -0,0 +1,108 @@
+//! The plaintext format (`.cells`), with one character per cell.
+//!
+//! Lines starting with `!` are comments, the first one usually being `!Name: <name>`. Every other
+//! line is a row of the pattern, with `.` for dead cells and `O` for live ones. Rows may be shorter
+//! than the pattern, with the missing cells being dead. See <https://conwaylife.com/wiki/Plaintext>.
+
+use crate::cell::{Cell, Grid};
+
+use super::{Pattern, PatternError};
+
+/// Checks if a text looks like a plaintext pattern, instead of RLE.
+pub(super) fn detect(text: &str) -> bool {
+    let first = text.lines().map(str::trim).find(|line| !line.is_empty());
+    first.is_some_and(|line| line.starts_with('!') || line.chars().all(|char| matches!(char, '.' | 'O' | '*')))
+}
+
+/// Parses a pattern in plaintext format, sized to its longest row.
+///
+/// Live cells may also be written as `*`, as in some older files.
+///
+/// # Example
+///
+/// ```
+/// # use vida::cell::Cell;
+/// # use vida::io::plaintext;
+/// #
+/// let glider = plaintext::parse("!Name: Glider\n.O\n..O\nOOO\n").unwrap();
+///
+/// assert_eq!(glider.name.as_deref(), Some("Glider"));
+/// assert_eq!(glider.grid.shape(), (3, 3));
+/// assert_eq!(glider.grid[(0, 1)], Cell::Live);
+/// ```
+pub fn parse(text: &str) -> Result<Pattern, PatternError> {
+    let mut pattern = Pattern::default();
+    let mut rows = Vec::new();
+
+    for (index, line) in text.lines().enumerate() {
+        let line = line.trim_end();
+        if let Some(comment) = line.strip_prefix('!') {
+            match comment.strip_prefix("Name:") {
+                Some(name) => pattern.name = Some(name.trim().to_owned()),
+                None => pattern.comments.push(comment.trim().to_owned()),
+            }
+            continue;
+        }
+
+        let row = line.chars()
+            .map(|char| match char {
+                '.' => Ok(Cell::Dead),
+                'O' | '*' => Ok(Cell::Live),
+                _ => Err(PatternError::parse(index + 1, format!("unexpected character {char:?}"))),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        rows.push(row);
+    }
+
+    // trailing empty lines are not part of the pattern
+    while rows.last().is_some_and(Vec::is_empty) {
+        rows.pop();
+    }
+
+    let columns = rows.iter().map(Vec::len).max().unwrap_or(0);
+    for row in &mut rows {
+        row.resize(columns, Cell::Dead);
+    }
+
+    pattern.grid = Grid::try_from(rows).expect("rows were padded to the same length");
+    Ok(pattern)
+}
+
+#[must_use]
+/// Encodes a grid in plaintext format, trimming the dead cells around the live ones and at the end
+/// of each row.
+pub fn encode(grid: &Grid, name: Option<&str>) -> String {
+    let mut output = name.map(|name| format!("!Name: {name}\n")).unwrap_or_default();
+    let Some((top, left, bottom, right)) = grid.bounding_box() else {
+        return output;
+    };
+
+    for row in top ..= bottom {
+        let cells = &grid[row][left ..= right];
+        let last = cells.iter().rposition(|cell| cell.is_live()).map_or(0, |last| last + 1);
+
+        output.extend(cells[..last].iter().map(|cell| if cell.is_live() { 'O' } else { '.' }));
+        output.push('\n');
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn round_trip() {
+        let text = "!Name: Beacon\n!Period 2 oscillator.\nOO\nO\n...O\n..OO\n\n";
+        let pattern = parse(text).unwrap();
+
+        assert_eq!(pattern.grid.shape(), (4, 4));
+        assert_eq!(pattern.comments, ["Period 2 oscillator."]);
+        assert_eq!(pattern.grid.population(), 6);
+        assert_eq!(encode(&pattern.grid, Some("Beacon")), "!Name: Beacon\nOO\nO\n...O\n..OO\n");
+
+        assert!(detect(text));
+        assert!(!detect("x = 2, y = 2\n2o$2o!"));
+        assert!(parse("OO\nOx\n").is_err());
+    }
+}
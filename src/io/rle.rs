@@ -0,0 +1,279 @@
+//! The Run Length Encoded format (`.rle`), used by Golly and LifeWiki.
+//!
+//! A file has optional `#` comment lines, a header like `x = 3, y = 3, rule = B3/S23` and then the
+//! cells as runs of `<count><tag>`, where `b` is a dead cell, `o` a live one, `$` ends a row and
+//! `!` ends the pattern. See <https://conwaylife.com/wiki/Run_Length_Encoded>.
+
+use std::fmt::Write;
+
+use crate::cell::{Cell, Grid};
+
+use super::{pattern_grid, Pattern, PatternError};
+
+/// Maximum line length in the encoded output, the same used by Golly.
+const LINE_LENGTH: usize = 70;
+
+/// Parses a pattern in RLE format.
+///
+/// # Example
+///
+/// ```
+/// # use vida::cell::Cell;
+/// # use vida::io::rle;
+/// #
+/// let glider = rle::parse("#N Glider\nx = 3, y = 3, rule = B3/S23\nbob$2bo$3o!").unwrap();
+///
+/// assert_eq!(glider.name.as_deref(), Some("Glider"));
+/// assert_eq!(glider.grid.shape(), (3, 3));
+/// assert_eq!(glider.grid[(0, 1)], Cell::Live);
+/// ```
+pub fn parse(text: &str) -> Result<Pattern, PatternError> {
+    let mut pattern = Pattern::default();
+    let mut lines = text.lines().enumerate().map(|(index, line)| (index + 1, line.trim()));
+
+    let (header_line, header) = loop {
+        match lines.next() {
+            None => return Err(PatternError::parse(1, "missing header line")),
+            Some((_, "")) => continue,
+            Some((_, line)) if line.starts_with('#') => read_comment(&line[1..], &mut pattern),
+            Some(header) => break header,
+        }
+    };
+
+    let (rows, columns) = read_header(header_line, header, &mut pattern)?;
+    pattern.grid = pattern_grid(header_line, rows, columns)?;
+
+    let (mut row, mut col): (usize, usize) = (0, 0);
+    let mut count: Option<usize> = None;
+
+    for (line_number, line) in lines {
+        if line.starts_with('#') {
+            continue;
+        }
+
+        for char in line.chars() {
+            if let Some(digit) = char.to_digit(10) {
+                let value = count.unwrap_or(0).checked_mul(10).and_then(|count| count.checked_add(digit as usize));
+                count = Some(value.ok_or_else(|| PatternError::parse(line_number, "run count too large"))?);
+                continue;
+            } else if char.is_whitespace() {
+                continue;
+            }
+
+            let run = count.take().unwrap_or(1);
+            let too_large = || PatternError::parse(line_number, "pattern too large");
+            match char {
+                'b' | '.' => col = col.checked_add(run).ok_or_else(too_large)?,
+                'o' | 'A'..='X' => {
+                    let end = col.checked_add(run).ok_or_else(too_large)?;
+                    if row >= rows || end > columns {
+                        return Err(PatternError::parse(line_number, "live cells outside of the declared size"));
+                    }
+                    pattern.grid[row][col..end].fill(Cell::Live);
+                    col = end;
+                },
+                '$' => {
+                    row = row.checked_add(run).ok_or_else(too_large)?;
+                    col = 0;
+                },
+                '!' => return Ok(pattern),
+                char => return Err(PatternError::parse(line_number, format!("unexpected character {char:?}"))),
+            }
+        }
+    }
+
+    Ok(pattern)
+}
+
+#[must_use]
+/// Encodes a grid in RLE format, trimming the dead cells around the live ones.
+///
+/// The `rule` goes in the header, defaulting to `B3/S23`. Lines are wrapped at 70 characters,
+/// without splitting a run.
+///
+/// # Example
+///
+/// ```
+/// # use vida::cell::Cell;
+/// # use vida::io::rle;
+/// #
+/// let glider = rle::parse("x = 3, y = 3\nbob$2bo$3o!").unwrap().grid;
+///
+/// assert_eq!(rle::encode(&glider, None), "x = 3, y = 3, rule = B3/S23\nbo$2bo$3o!\n");
+/// ```
+pub fn encode(grid: &Grid, rule: Option<&str>) -> String {
+    let rule = rule.unwrap_or("B3/S23");
+    let Some((top, left, bottom, right)) = grid.bounding_box() else {
+        return format!("x = 0, y = 0, rule = {rule}\n!\n");
+    };
+
+    let mut output = String::new();
+    let _ = writeln!(output, "x = {}, y = {}, rule = {rule}", right - left + 1, bottom - top + 1);
+
+    let mut line = String::new();
+    let mut push = |count: usize, tag: char| {
+        let token = if count > 1 { format!("{count}{tag}") } else { tag.to_string() };
+        if line.len() + token.len() > LINE_LENGTH {
+            output.push_str(&line);
+            output.push('\n');
+            line.clear();
+        }
+        line.push_str(&token);
+    };
+
+    let mut empty_rows = 0;
+    for row in top ..= bottom {
+        let cells = &grid[row][left ..= right];
+        let Some(last) = cells.iter().rposition(|cell| cell.is_live()) else {
+            empty_rows += 1;
+            continue;
+        };
+
+        if row > top {
+            push(empty_rows + 1, '$');
+        }
+        empty_rows = 0;
+
+        let mut start = 0;
+        while start <= last {
+            let cell = cells[start];
+            let len = cells[start..=last].iter().take_while(|&&other| other == cell).count();
+            push(len, if cell.is_live() { 'o' } else { 'b' });
+            start += len;
+        }
+    }
+    push(1, '!');
+
+    output.push_str(&line);
+    output.push('\n');
+    output
+}
+
+impl Grid {
+    #[inline]
+    #[must_use]
+    /// Encodes the grid in RLE format, as in [`encode`].
+    pub fn to_rle(&self) -> String {
+        encode(self, None)
+    }
+}
+
+/// Reads a `#` line, without the `#`.
+fn read_comment(comment: &str, pattern: &mut Pattern) {
+    let (kind, content) = comment.split_at(comment.chars().next().map_or(0, char::len_utf8));
+    let content = content.trim();
+
+    match kind {
+        "N" => pattern.name = Some(content.to_owned()),
+        "r" => pattern.rule = Some(content.to_owned()),
+        "C" | "c" | "O" => pattern.comments.push(content.to_owned()),
+        _ => (),
+    }
+}
+
+/// Reads the `x = m, y = n, rule = abc` line, returning `(rows, columns)`.
+fn read_header(line: usize, header: &str, pattern: &mut Pattern) -> Result<(usize, usize), PatternError> {
+    let mut width = None;
+    let mut height = None;
+
+    for item in header.split(',') {
+        let Some((key, value)) = item.split_once('=') else {
+            return Err(PatternError::parse(line, format!("expected `key = value` in header, found {:?}", item.trim())));
+        };
+
+        let value = value.trim();
+        let size = || value.parse::<usize>()
+            .map_err(|_| PatternError::parse(line, format!("invalid size {value:?} in header")));
+
+        match key.trim() {
+            "x" => width = Some(size()?),
+            "y" => height = Some(size()?),
+            "rule" => pattern.rule = Some(value.to_owned()),
+            _ => (),
+        }
+    }
+
+    match (height, width) {
+        (Some(height), Some(width)) => Ok((height, width)),
+        _ => Err(PatternError::parse(line, "header must declare both x and y")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn multiline_runs() {
+        let text = "\
+            #N Gosper glider gun\n\
+            #C A true period 30 glider gun.\n\
+            x = 36, y = 9, rule = B3/S23\n\
+            24bo$22bobo$12b2o6b2o12b2o$11bo3bo4b2o12b2o$2o8bo5bo3b2o$2o8bo3bob2o4b\n\
+            obo$10bo5bo7bo$11bo3bo$12b2o!\n";
+
+        let pattern = parse(text).unwrap();
+        let live = pattern.grid.population();
+
+        assert_eq!(pattern.grid.shape(), (9, 36));
+        assert_eq!(pattern.rule.as_deref(), Some("B3/S23"));
+        assert_eq!(pattern.comments, ["A true period 30 glider gun."]);
+        assert_eq!(live, 36);
+        assert_eq!(pattern.grid[(5, 24)], Cell::Live);
+        assert_eq!(pattern.grid[(8, 12)], Cell::Live);
+    }
+
+    #[test]
+    pub fn empty_rows_and_large_counts() {
+        let pattern = parse("x = 12, y = 4\n12o3$o11b!").unwrap();
+
+        assert!(pattern.grid[0].iter().all(|cell| cell.is_live()));
+        assert!(pattern.grid[1].iter().all(|cell| cell.is_dead()));
+        assert_eq!(pattern.grid[(3, 0)], Cell::Live);
+        assert_eq!(pattern.grid[(3, 1)], Cell::Dead);
+    }
+
+    #[test]
+    pub fn encode_round_trip() {
+        let mut grid = Grid::new(20, 200);
+        for col in (3..190).step_by(3) {
+            grid[(4, col)] = Cell::Live;
+            grid[(9, col + 1)] = Cell::Live;
+        }
+
+        let text = grid.to_rle();
+        assert!(text.lines().all(|line| line.len() <= LINE_LENGTH));
+        assert!(text.starts_with("x = 188, y = 6, rule = B3/S23\n"));
+
+        let decoded = parse(&text).unwrap().grid;
+        assert_eq!(decoded[(0, 0)], Cell::Live);
+        assert_eq!(decoded[(5, 1)], Cell::Live);
+        assert_eq!(decoded.population(), 2 * 63);
+        assert_eq!(encode(&decoded, None), text);
+
+        assert_eq!(encode(&Grid::new(3, 3), Some("B36/S23")), "x = 0, y = 0, rule = B36/S23\n!\n");
+    }
+
+    #[test]
+    pub fn malformed_input() {
+        let error = |text| match parse(text) {
+            Err(PatternError::Parse { line, .. }) => line,
+            other => panic!("expected parse error, found {other:?}"),
+        };
+
+        assert_eq!(error("#C only a comment\n"), 1);
+        assert_eq!(error("x = 3\nobo!"), 1);
+        assert_eq!(error("x = 3, y = 1\nobo$\n3oz!"), 3);
+        assert_eq!(error("x = 2, y = 1\n3o!"), 2);
+
+        // sizes and runs that overflow fail instead of panicking
+        let message = |text| match parse(text) {
+            Err(PatternError::Parse { message, .. }) => message,
+            other => panic!("expected parse error, found {other:?}"),
+        };
+        assert_eq!(message("x = 99999999999, y = 99999999999\n!"), "pattern too large");
+        assert_eq!(message("x = 3, y = 1\n18446744073709551615bo!"), "pattern too large");
+        assert_eq!(message("x = 3, y = 1\n2b18446744073709551615o!"), "pattern too large");
+        assert_eq!(message("x = 3, y = 1\n$18446744073709551615$o!"), "pattern too large");
+    }
+}
@@ -1,5 +1,41 @@
-#[warn(unsafe_op_in_unsafe_fn)]
+#![warn(unsafe_op_in_unsafe_fn)]
 
+#[cfg(feature = "parallel")]
+pub mod affinity;
+pub mod age;
+pub mod analysis;
+pub mod bench;
 pub mod cell;
+pub mod checkpoint;
+#[cfg(feature = "continuous")]
+pub mod continuous;
+pub mod cycle;
+pub mod edit;
 pub mod engine;
+pub mod error;
+pub mod export;
+pub mod game;
+pub mod generations;
+pub mod heatmap;
+pub mod io;
+pub mod life3d;
+pub mod methuselah;
+pub mod multicolor;
+pub mod noise;
+mod par;
+pub mod patterns;
 pub mod renderer;
+pub mod replay;
+pub mod rewind;
+pub mod rule;
+pub mod session;
+pub mod simulation;
+pub mod soup;
+pub mod stats;
+pub mod testing;
+pub mod theme;
+pub mod trail;
+pub mod verify;
+pub mod wireworld;
+
+pub use error::{Error, Result};
@@ -0,0 +1,273 @@
+//! Life in three dimensions, with Carter Bays' totalistic rules like 5766.
+//!
+//! A [`Grid3`] is a stack of 2D [`Grid`] layers, so each `z`-slice can be drawn, saved and
+//! inspected with the same tools as a board of 2D Life. Cells have the 26 neighbors of a 3x3x3
+//! cube, and cells past the edges of the space are dead.
+
+use std::ops::{Index, IndexMut};
+
+use rand::Rng;
+
+use crate::cell::{Cell, Grid};
+use crate::par::*;
+use crate::rule::Rule3d;
+
+/// A space of `depth` layers of `(rows, columns)` cells.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Grid3 {
+    layers: Vec<Grid>,
+    rows: usize,
+    columns: usize,
+}
+
+impl Grid3 {
+    #[inline]
+    #[must_use]
+    /// Creates a space of dead cells.
+    pub fn new(depth: usize, rows: usize, columns: usize) -> Self {
+        Self { layers: vec![Grid::new(rows, columns); depth], rows, columns }
+    }
+
+    #[must_use]
+    /// Creates a space where each cell is live with probability `density`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use rand::SeedableRng;
+    /// # use rand::rngs::SmallRng;
+    /// # use vida::life3d::Grid3;
+    /// #
+    /// let space = Grid3::random_with(8, 20, 30, 0.2, &mut SmallRng::seed_from_u64(584));
+    /// assert_eq!(space.shape(), (8, 20, 30));
+    /// assert!((600..1300).contains(&space.population()));
+    /// ```
+    pub fn random_with<R: Rng + ?Sized>(depth: usize, rows: usize, columns: usize, density: f64, rng: &mut R) -> Self {
+        let layers = (0..depth).map(|_| Grid::random_with_density(rows, columns, density, rng)).collect();
+        Self { layers, rows, columns }
+    }
+
+    #[inline]
+    #[must_use]
+    /// Number of layers.
+    pub fn depth(&self) -> usize {
+        self.layers.len()
+    }
+
+    #[inline]
+    #[must_use]
+    /// Number of rows in each layer.
+    pub const fn rows(&self) -> usize {
+        self.rows
+    }
+
+    #[inline]
+    #[must_use]
+    /// Number of columns in each layer.
+    pub const fn columns(&self) -> usize {
+        self.columns
+    }
+
+    #[inline]
+    #[must_use]
+    /// The `(depth, rows, columns)` of the space.
+    pub fn shape(&self) -> (usize, usize, usize) {
+        (self.depth(), self.rows, self.columns)
+    }
+
+    #[inline]
+    #[must_use]
+    /// The `z`-slice of the space, or [`None`] past the last layer.
+    pub fn layer(&self, z: usize) -> Option<&Grid> {
+        self.layers.get(z)
+    }
+
+    #[inline]
+    #[must_use]
+    /// The `z`-slice of the space, for editing.
+    pub fn layer_mut(&mut self, z: usize) -> Option<&mut Grid> {
+        self.layers.get_mut(z)
+    }
+
+    #[inline]
+    #[must_use]
+    /// All layers, from `z = 0` up.
+    pub fn layers(&self) -> &[Grid] {
+        &self.layers
+    }
+
+    #[must_use]
+    /// Number of live cells in the whole space.
+    pub fn population(&self) -> usize {
+        self.layers.iter().map(Grid::population).sum()
+    }
+}
+
+impl Index<(usize, usize, usize)> for Grid3 {
+    type Output = Cell;
+
+    #[inline]
+    fn index(&self, (z, row, col): (usize, usize, usize)) -> &Self::Output {
+        &self.layers[z][(row, col)]
+    }
+}
+
+impl IndexMut<(usize, usize, usize)> for Grid3 {
+    #[inline]
+    fn index_mut(&mut self, (z, row, col): (usize, usize, usize)) -> &mut Self::Output {
+        &mut self.layers[z][(row, col)]
+    }
+}
+
+/// Computes generations of 3D Life, one layer per task with the `parallel` feature.
+///
+/// Live neighbors are counted from the sums of each 3x3 square in every layer, which are computed
+/// once and shared by the layers above and below, instead of visiting all 26 neighbors of a cell.
+///
+/// # Example
+///
+/// ```
+/// # use vida::cell::Cell;
+/// # use vida::life3d::{Engine3d, Grid3};
+/// # use vida::rule::Rule3d;
+/// #
+/// // a 2x2x2 cube is a still life in 5766: each cell has 7 neighbors
+/// let mut space = Grid3::new(4, 4, 4);
+/// for z in 1..=2 {
+///     for row in 1..=2 {
+///         for col in 1..=2 {
+///             space[(z, row, col)] = Cell::Live;
+///         }
+///     }
+/// }
+/// assert_eq!(Engine3d::new(Rule3d::LIFE_5766).update(&space), space);
+/// ```
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Engine3d {
+    rule: Rule3d,
+}
+
+impl Engine3d {
+    #[inline]
+    #[must_use]
+    /// Creates an engine running the given rule.
+    pub const fn new(rule: Rule3d) -> Self {
+        Self { rule }
+    }
+
+    #[inline]
+    #[must_use]
+    /// The rule used to compute each generation.
+    pub const fn rule(&self) -> Rule3d {
+        self.rule
+    }
+
+    #[must_use]
+    /// Computes the next generation of the space.
+    pub fn update(&self, space: &Grid3) -> Grid3 {
+        let (rows, columns) = (space.rows, space.columns);
+        let sums: Vec<_> = space.layers.par_iter().map(square_sums).collect();
+
+        let mut next = Grid3::new(space.depth(), rows, columns);
+        next.layers.par_iter_mut().enumerate().for_each(|(z, layer)| {
+            let below = z.checked_sub(1).map(|z| &sums[z]);
+            let above = sums.get(z + 1);
+            for (index, cell) in layer.flat_mut().iter_mut().enumerate() {
+                let current = space.layers[z].flat()[index];
+                let count = sums[z][index] + below.map_or(0, |sum| sum[index]) + above.map_or(0, |sum| sum[index])
+                    - u8::from(current);
+                *cell = self.rule.next(current, count);
+            }
+        });
+        next
+    }
+
+    #[must_use]
+    /// The space after `generations` updates, or the same space with zero.
+    pub fn advance(&self, mut space: Grid3, generations: usize) -> Grid3 {
+        for _ in 0..generations {
+            space = self.update(&space);
+        }
+        space
+    }
+}
+
+/// Number of live cells in the 3x3 square around each cell of a layer, itself included.
+fn square_sums(layer: &Grid) -> Vec<u8> {
+    let (rows, columns) = layer.shape();
+
+    // sums of each horizontal run of three cells, then of three runs stacked vertically
+    let mut runs = vec![0_u8; rows * columns];
+    for (row, cells) in layer.flat().chunks_exact(columns.max(1)).enumerate() {
+        for col in 0..columns {
+            let start = col.saturating_sub(1);
+            let end = (col + 2).min(columns);
+            runs[row * columns + col] = cells[start..end].iter().map(|&cell| u8::from(cell)).sum();
+        }
+    }
+
+    let mut sums = vec![0_u8; rows * columns];
+    for row in 0..rows {
+        for near in row.saturating_sub(1)..(row + 2).min(rows) {
+            for col in 0..columns {
+                sums[row * columns + col] += runs[near * columns + col];
+            }
+        }
+    }
+    sums
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand::rngs::SmallRng;
+
+    use super::*;
+
+    fn naive(rule: Rule3d, space: &Grid3) -> Grid3 {
+        let (depth, rows, columns) = space.shape();
+        let mut next = Grid3::new(depth, rows, columns);
+        for z in 0..depth {
+            for row in 0..rows {
+                for col in 0..columns {
+                    let mut count = 0;
+                    for nz in z.saturating_sub(1)..(z + 2).min(depth) {
+                        for nrow in row.saturating_sub(1)..(row + 2).min(rows) {
+                            for ncol in col.saturating_sub(1)..(col + 2).min(columns) {
+                                if (nz, nrow, ncol) != (z, row, col) {
+                                    count += u8::from(space[(nz, nrow, ncol)]);
+                                }
+                            }
+                        }
+                    }
+                    next[(z, row, col)] = rule.next(space[(z, row, col)], count);
+                }
+            }
+        }
+        next
+    }
+
+    #[test]
+    pub fn same_as_naive_count() {
+        let mut rng = SmallRng::seed_from_u64(584);
+        for rule in [Rule3d::LIFE_5766, Rule3d::LIFE_4555, Rule3d::new(2..=10, 3..=4)] {
+            for (depth, rows, columns) in [(6, 11, 13), (1, 5, 7), (3, 1, 1)] {
+                let mut space = Grid3::random_with(depth, rows, columns, 0.3, &mut rng);
+                let engine = Engine3d::new(rule);
+                for _ in 0..3 {
+                    let next = engine.update(&space);
+                    assert_eq!(next, naive(rule, &space), "{rule} on {depth}x{rows}x{columns}");
+                    space = next;
+                }
+            }
+        }
+    }
+
+    #[test]
+    pub fn empty_spaces() {
+        let engine = Engine3d::default();
+        assert_eq!(engine.update(&Grid3::new(5, 6, 7)), Grid3::new(5, 6, 7));
+        assert_eq!(engine.update(&Grid3::new(0, 6, 7)), Grid3::new(0, 6, 7));
+        assert_eq!(engine.update(&Grid3::new(3, 0, 0)).shape(), (3, 0, 0));
+    }
+}
@@ -1,52 +1,756 @@
+use std::path::Path;
+use std::process::exit;
 use std::time::{Duration, Instant};
 
-use clap::Parser;
+use clap::{Parser, ValueEnum};
+use rand::SeedableRng;
+use rand::rngs::SmallRng;
 
-use vida::cell::Grid;
-use vida::engine::{Engine, ParallelEngine, SerialEngine};
-use vida::renderer::Renderer;
+use vida::{affinity, io, patterns, wireworld};
+use vida::cell::{Cell, Grid};
+use vida::checkpoint::Checkpoint;
+#[cfg(feature = "continuous")]
+use vida::continuous::{ContinuousEngine, Field, Lenia, SmoothLife};
+use vida::engine::{Ant, AntEngine, BitEngine, Engine, HashLifeEngine, LtlEngine, MargolusEngine, NumaEngine, ParallelEngine, SerialEngine, SparseEngine};
+use vida::export::GifWriter;
+use vida::game::{GameRules, ImmigrationGame, Phase};
+use vida::generations::{BriansBrainEngine, GenerationsEngine, MultiStateEngine, States};
+use vida::life3d::{Engine3d, Grid3};
+use vida::multicolor::MultiColorEngine;
+use vida::noise::Noise;
+#[cfg(feature = "continuous")]
+use vida::renderer::FieldRenderer;
+use vida::renderer::{CompareRenderer, FrameRecorder, GameRenderer, LayersRenderer, Player, Renderer, StatesRenderer, TerminalRenderer, WindowConfig, WindowRenderer};
+use vida::rule::{BlockRule, GenerationsRule, LtlRule, Rule, Rule3d};
+use vida::replay::Replay;
+use vida::rewind::Rewind;
+use vida::session::Session;
+use vida::simulation::{Simulation, StopReason};
+#[cfg(feature = "image")]
+use vida::theme::Theme;
+use vida::verify::{compare_engines, Comparison};
+use vida::wireworld::WireworldEngine;
 
 mod cli;
+mod commands;
 
-use cli::{Cli, Mode};
+use cli::{BoardArgs, Cli, Command, EngineArgs, Mode, RendererKind, RunArgs};
 
 const UPDATE_INTERVAL: Duration = Duration::from_secs(1);
+/// Largest board reachable with `--expand`.
+const MAX_EXPANDED_SHAPE: (usize, usize) = (4096, 4096);
+/// Generations checked by `--compare` without rendering, unless set with `--iterations`.
+const COMPARE_GENERATIONS: usize = 100;
 
 fn main() {
-    let cli = Cli::parse();
+    match Cli::parse().command {
+        Command::Run(args) => run_command(&args),
+        Command::Bench(args) => commands::bench(&args),
+        Command::Convert(args) => commands::convert(&args),
+        Command::Analyze(args) => commands::analyze(&args),
+        Command::Replay(args) => commands::replay(&args),
+        Command::Soup(args) => commands::soup(&args),
+        Command::Methuselah(args) => commands::methuselah(&args),
+    }
+}
 
-    let grid = Grid::random(cli.height, cli.width);
-    let max_iter = cli.iterations.unwrap_or(usize::MAX);
+fn run_command(cli: &RunArgs) {
+    if cli.pin_threads {
+        if let Err(error) = affinity::pin_global_pool() {
+            eprintln!("could not pin worker threads: {error}");
+        }
+    }
+
+    let checkpoint = cli.resume.as_ref().map(|path| {
+        Checkpoint::load(path).unwrap_or_else(|error| {
+            eprintln!("could not load checkpoint from {}: {error}", path.display());
+            exit(1)
+        })
+    });
+
+    let session = match checkpoint {
+        Some(checkpoint) => Some(resumed_session(checkpoint, cli)),
+        None => cli.load_session.as_ref().map(|path| {
+            Session::load(path).unwrap_or_else(|error| {
+                eprintln!("could not load session from {}: {error}", path.display());
+                exit(1)
+            })
+        }),
+    };
+    let rule = session.as_ref().and_then(|session| session.rule).unwrap_or(cli.engine.rule);
+    let seed = session.as_ref().and_then(|session| session.seed);
+
+    let session_mode = session.as_ref().and_then(|session| Mode::from_str(&session.engine, true).ok());
+    let Some(mode) = cli.mode.or(session_mode) else {
+        eprintln!("unknown engine in session or checkpoint file, choose a mode explicitly");
+        exit(1)
+    };
+    if rule != cli.engine.rule && cli.engine.rule != Rule::CONWAY {
+        let source = if cli.resume.is_some() { "checkpoint" } else { "session" };
+        eprintln!("{source} was saved running {rule}, ignoring --rule");
+    }
+
+    warn_unsupported(mode, &cli.engine);
+    if cli.renderer() == RendererKind::Terminal && !mode.has_life_rule() && mode != Mode::Ant {
+        eprintln!("the terminal renderer only supports discrete automata, opening a window");
+    }
+    if (cli.record.is_some() || cli.gif.is_some()) && !mode.has_life_rule() && !matches!(mode, Mode::Ant | Mode::Life3d) {
+        eprintln!("--record and --gif only support discrete automata, ignoring");
+    }
+    if cli.gif.is_some() && mode == Mode::Life3d {
+        eprintln!("--gif is not supported by the life3d mode, use --record for slice stacks, ignoring");
+    }
+    if cli.expand && !mode.has_life_rule() {
+        eprintln!("--expand is not supported by the ant, immigration, lenia, smoothlife, generations, wireworld, brians-brain, quadlife and life3d modes, ignoring");
+    }
+    if (cli.checkpoint.is_some() || cli.resume.is_some()) && !mode.has_life_rule() && mode != Mode::Ant {
+        eprintln!("checkpoints are not supported by the immigration, lenia, smoothlife, generations, wireworld, brians-brain, quadlife and life3d modes, ignoring");
+    }
+    if cli.noise > 0.0 && !mode.has_life_rule() && mode != Mode::Ant {
+        eprintln!("--noise only supports the engines of Life-like automata, ignoring");
+    }
+    if (cli.stats_out.is_some() || cli.graph) && !mode.has_life_rule() && mode != Mode::Ant {
+        eprintln!("--stats-out and --graph are not supported by the immigration, lenia, smoothlife, generations, wireworld, brians-brain, quadlife and life3d modes, ignoring");
+    }
+    if cli.heatmap_out.is_some() && !mode.has_life_rule() && mode != Mode::Ant {
+        eprintln!("--heatmap-out is not supported by the immigration, lenia, smoothlife, generations, wireworld, brians-brain, quadlife and life3d modes, ignoring");
+    }
+    if cli.trails.is_some() && !mode.has_life_rule() && mode != Mode::Ant {
+        eprintln!("--trails is not supported by the immigration, lenia, smoothlife, generations, wireworld, brians-brain, quadlife and life3d modes, ignoring");
+    }
+    if cli.graph && cli.renderer() != RendererKind::Window {
+        eprintln!("--graph is only drawn in the window renderer, ignoring");
+    }
+    if cli.save_replay.is_some() && cli.noise > 0.0 {
+        eprintln!("replays can't repeat the flips of --noise, ignoring --save-replay");
+    }
+    if cli.save_replay.is_some() && !mode.has_life_rule() {
+        eprintln!("--save-replay is not supported by the ant, immigration, lenia, smoothlife, generations, wireworld, brians-brain, quadlife and life3d modes, ignoring");
+    }
+
+    if let Some(other) = cli.compare {
+        return run_compare(mode, other, rule, cli);
+    }
+
+    match mode {
+        Mode::Serial => run(SerialEngine::new(rule), cli, session, seed),
+        Mode::Parallel => run(parallel_engine(&cli.engine, cli.pin_threads).with_rule(rule), cli, session, seed),
+        Mode::Numa => run(numa_engine().with_rule(rule), cli, session, seed),
+        Mode::Bit => run(BitEngine, cli, session, seed),
+        Mode::Sparse => run(SparseEngine::new(rule), cli, session, seed),
+        Mode::Ltl => run(LtlEngine::new(cli.engine.ltl_rule), cli, session, seed),
+        Mode::Margolus => run(MargolusEngine::new(cli.engine.block_rule), cli, session, seed),
+        Mode::HashLife => run_hashlife(cli, session, seed),
+        Mode::Ant => {
+            let shape = session.as_ref().map_or((cli.board.height, cli.board.width), |session| session.grid.shape());
+            run(ant_engine(&cli.engine, shape), cli, session, seed)
+        },
+        Mode::Immigration => run_game(cli),
+        #[cfg(feature = "continuous")]
+        Mode::Lenia => run_continuous(Lenia::default(), cli, session),
+        #[cfg(feature = "continuous")]
+        Mode::SmoothLife => run_continuous(SmoothLife::default(), cli, session),
+        #[cfg(not(feature = "continuous"))]
+        Mode::Lenia | Mode::SmoothLife => {
+            eprintln!("the lenia and smoothlife modes need the continuous feature");
+            exit(1)
+        },
+        Mode::Generations => run_generations(GenerationsRule::new(rule, cli.engine.states), cli, session),
+        Mode::Wireworld => run_wireworld(cli, session),
+        Mode::BriansBrain => run_brians_brain(cli, session),
+        Mode::QuadLife => run_quadlife(MultiColorEngine::new(rule, cli.engine.colors), cli, session),
+        Mode::Life3d => run_3d(Engine3d::new(cli.engine.rule_3d), cli, session),
+    }
+}
+
+/// A checkpoint as a session, drawn with the cell size and speed from the command line.
+fn resumed_session(checkpoint: Checkpoint, cli: &RunArgs) -> Session {
+    Session {
+        grid: checkpoint.grid,
+        engine: checkpoint.engine,
+        generation: checkpoint.generation,
+        cell_size: cli.cell_size,
+        update_interval: cli.update_interval().unwrap_or(UPDATE_INTERVAL),
+        rule: checkpoint.rule,
+        seed: checkpoint.seed,
+        noise: None,
+        cycle_window: None,
+        view: None,
+    }
+}
+
+/// Warns about engine options that the chosen mode doesn't use.
+fn warn_unsupported(mode: Mode, engine: &EngineArgs) {
+    if engine.rule != Rule::CONWAY && !matches!(mode, Mode::Serial | Mode::Parallel | Mode::Numa | Mode::Sparse | Mode::Generations | Mode::QuadLife) {
+        eprintln!("--rule is only supported by the serial, parallel, numa, sparse, generations and quadlife modes, ignoring");
+    }
+    if engine.ltl_rule != LtlRule::BOSCO && mode != Mode::Ltl {
+        eprintln!("--ltl-rule is only supported by the ltl mode, ignoring");
+    }
+    if engine.block_rule != BlockRule::CRITTERS && mode != Mode::Margolus {
+        eprintln!("--block-rule is only supported by the margolus mode, ignoring");
+    }
+    if engine.rule_3d != Rule3d::LIFE_5766 && mode != Mode::Life3d {
+        eprintln!("--rule-3d is only supported by the life3d mode, ignoring");
+    }
+    if engine.states != 4 && mode != Mode::Generations {
+        eprintln!("--states is only supported by the generations mode, ignoring");
+    }
+    if engine.colors != 4 && mode != Mode::QuadLife {
+        eprintln!("--colors is only supported by the quadlife mode, ignoring");
+    }
+    if (engine.threads.is_some() || engine.chunk_rows > 0) && mode != Mode::Parallel {
+        eprintln!("--threads and --chunk-rows are only supported by the parallel mode, ignoring");
+    }
+}
+
+/// Uses the global pool, unless `--threads` asks for a dedicated one.
+fn parallel_engine(engine: &EngineArgs, pin_threads: bool) -> ParallelEngine {
+    let Some(threads) = engine.threads else {
+        return ParallelEngine::new(engine.rule).with_chunk_rows(engine.chunk_rows);
+    };
+
+    let cores = if pin_threads { affinity::physical_cores().into_iter().take(threads).collect() } else { Vec::new() };
+    let pool = affinity::pinned_pool_builder(cores).num_threads(threads).build().expect("could not build thread pool");
+    ParallelEngine::with_pool(pool).with_rule(engine.rule).with_chunk_rows(engine.chunk_rows)
+}
+
+fn numa_engine() -> NumaEngine {
+    NumaEngine::new().expect("could not build NUMA thread pools")
+}
+
+/// The engine of a mode that computes generations of a plain [`Grid`], for `--compare`.
+fn grid_engine(mode: Mode, cli: &RunArgs, rule: Rule) -> Option<Box<dyn Engine>> {
+    Some(match mode {
+        Mode::Serial => Box::new(SerialEngine::new(rule)),
+        Mode::Parallel => Box::new(parallel_engine(&cli.engine, cli.pin_threads).with_rule(rule)),
+        Mode::Numa => Box::new(numa_engine().with_rule(rule)),
+        Mode::Bit => Box::new(BitEngine),
+        Mode::Sparse => Box::new(SparseEngine::new(rule)),
+        Mode::HashLife => Box::new(HashLifeEngine::new()),
+        Mode::Ltl => Box::new(LtlEngine::new(cli.engine.ltl_rule)),
+        Mode::Margolus => Box::new(MargolusEngine::new(cli.engine.block_rule)),
+        _ => return None,
+    })
+}
+
+fn ant_engine(engine: &EngineArgs, (rows, columns): (usize, usize)) -> AntEngine {
+    let (row, col) = (rows / 2, columns / 2);
+    let start = col.saturating_sub(engine.ants);
+    let ants = (0..engine.ants).map(|i| Ant::new(row, (start + 2 * i) % columns.max(1)));
+
+    AntEngine::new(engine.turmite.clone(), ants).with_steps_per_generation(engine.ant_steps)
+}
+
+/// Random number generator for the initial state, seeded by `--seed` if given.
+fn rng(board: &BoardArgs) -> SmallRng {
+    board.seed.map_or_else(SmallRng::from_entropy, SmallRng::seed_from_u64)
+}
+
+/// Initial grid when not resuming a session, with the seed of random grids.
+///
+/// Random grids always get a seed, even without `--seed`, so checkpoints can tell how they started.
+fn initial_grid<E: Engine>(engine: &E, board: &BoardArgs, rule: Rule) -> (Grid, Option<u64>) {
+    if let Some(path) = &board.pattern {
+        (place_pattern(&load_pattern(path, rule), board), None)
+    } else if let Some(preset) = board.preset {
+        (place_pattern(&preset.grid(), board), None)
+    } else if engine.agents().is_empty() {
+        let seed = board.seed.unwrap_or_else(rand::random);
+        let mut rng = SmallRng::seed_from_u64(seed);
+        (Grid::random_with_density(board.height, board.width, board.density, &mut rng), Some(seed))
+    } else {
+        (Grid::new(board.height, board.width), None)
+    }
+}
+
+fn load_pattern(path: &Path, running: Rule) -> Grid {
+    let pattern = io::load(path).unwrap_or_else(|error| {
+        eprintln!("could not load pattern from {}: {error}", path.display());
+        exit(1)
+    });
+    if let Some(rule) = pattern.rule.as_deref().and_then(|rule| rule.parse::<Rule>().ok()) {
+        if rule != running {
+            eprintln!("pattern was made for {rule}, running {running}");
+        }
+    }
+
+    pattern.grid
+}
+
+/// Places the live cells of a pattern at the center of an empty board, moved by `--offset`.
+fn place_pattern(pattern: &Grid, board: &BoardArgs) -> Grid {
+    let pattern = pattern.trim();
+    let (rows, columns) = pattern.shape();
+    let top = (board.height as isize - rows as isize) / 2 + board.offset.0;
+    let left = (board.width as isize - columns as isize) / 2 + board.offset.1;
+
+    let mut grid = Grid::new(board.height, board.width);
+    let mut clipped = false;
+    for (row, col) in pattern.live_cells() {
+        let row = usize::try_from(top + row as isize).ok();
+        let col = usize::try_from(left + col as isize).ok();
+
+        match row.zip(col).and_then(|(row, col)| grid.get_cell_mut(row, col)) {
+            Some(cell) => *cell = Cell::Live,
+            None => clipped = true,
+        }
+    }
+
+    if clipped {
+        eprintln!("pattern doesn't fit in the board, some cells were left out");
+    }
+    grid
+}
+
+/// Runs a discrete automaton, from a new board or a resumed one. The `seed` is the one of the
+/// resumed run, if it had one, and its noise, cycle detection and view are kept unless given
+/// on the command line.
+fn run<E: Engine + Send + 'static>(engine: E, cli: &RunArgs, session: Option<Session>, seed: Option<u64>) {
+    let (grid, generation, seed) = match &session {
+        Some(session) => (session.grid.clone(), session.generation, seed),
+        None => {
+            let (grid, seed) = initial_grid(&engine, &cli.board, cli.engine.rule);
+            (grid, 0, seed)
+        },
+    };
+
+    let noise = (cli.noise > 0.0).then(|| Noise::new(cli.noise, cli.board.seed.or(seed).unwrap_or_else(rand::random)));
+    let cycle_window = cli.stop_on_cycle.then_some(cli.cycle_window);
+    let mut simulation = Simulation::new(engine, grid)
+        .with_generation(generation)
+        .with_seed(seed)
+        .with_density(cli.board.density)
+        .with_noise(noise.or_else(|| session.as_ref().and_then(|session| session.noise.clone())))
+        .stop_on_extinction(cli.stop_on_extinction)
+        .stop_on_stable(cli.stop_on_stable)
+        .stop_on_cycle(cycle_window.or_else(|| session.as_ref().and_then(|session| session.cycle_window)))
+        .expand_up_to(cli.expand.then_some(MAX_EXPANDED_SHAPE))
+        .collect_stats((cli.stats && cli.renderer() == RendererKind::None) || cli.stats_out.is_some())
+        .track_ages(cli.color_by_age && (cli.record.is_some() || cli.renderer() == RendererKind::Window))
+        .track_trails(cli.trails.map(usize::from).filter(|_| cli.record.is_some() || cli.renderer() == RendererKind::Window))
+        .track_activity(cli.heatmap_out.is_some() || (cli.record.is_none() && cli.gif.is_none() && cli.renderer() == RendererKind::Window))
+        .track_changes(cli.record.is_none() && cli.gif.is_none() && cli.renderer() == RendererKind::Window)
+        .with_rewind((cli.rewind > 0 && cli.record.is_none() && cli.gif.is_none() && cli.renderer() != RendererKind::None)
+            .then(|| Rewind::new(cli.rewind, cli.rewind_memory.saturating_mul(1 << 20))));
+    if let Some(iterations) = cli.iterations {
+        simulation = simulation.with_max_generations(generation.saturating_add(iterations));
+    }
+
+    let view = session.as_ref().and_then(|session| session.view);
+    let (cell_size, update_interval) = session.map_or((cli.cell_size, UPDATE_INTERVAL), |session| (session.cell_size, session.update_interval));
+    let update_interval = cli.update_interval().unwrap_or(update_interval);
+    if let Some(path) = &cli.gif {
+        let simulation = record_gif(simulation, path, cli);
+        return save_run(cli, simulation);
+    }
+    if let Some(directory) = &cli.record {
+        let simulation = play(FrameRecorder::new(directory).with_scale(cli.record_scale.into()), simulation, update_interval, cli);
+        return save_run(cli, simulation);
+    }
+
+    match cli.renderer() {
+        RendererKind::None => {
+            let simulation = run_non_stop(simulation, cli);
+            save_run(cli, simulation);
+        },
+        RendererKind::Terminal => {
+            let simulation = play(TerminalRenderer::new(), simulation, update_interval, cli);
+            save_run(cli, simulation);
+        },
+        RendererKind::Window => {
+            let mut renderer = WindowRenderer::new(cell_size).with_graph(cli.graph);
+            if let Some(view) = view {
+                renderer.set_view(view);
+            }
+            let simulation = play(renderer, simulation, update_interval, cli);
+            save_checkpoint(cli, &simulation.checkpoint());
+            save_replay(cli, &simulation.replay());
+            save_stats(cli, &simulation);
+            save_heatmap(cli, &simulation);
+        },
+    }
+}
+
+/// Saves the session, checkpoint and replay at the end of a run, if asked to.
+fn save_run<E: Engine>(cli: &RunArgs, simulation: Simulation<E>) {
+    let checkpoint = simulation.checkpoint();
+    save_checkpoint(cli, &checkpoint);
+    save_replay(cli, &simulation.replay());
+    save_stats(cli, &simulation);
+    save_heatmap(cli, &simulation);
+    let (noise, cycle_window) = (simulation.noise().cloned(), simulation.cycle_window());
+    save_session(cli, checkpoint, noise, cycle_window);
+}
+
+/// Writes the statistics of every generation to `--stats-out`, if given.
+fn save_stats<E: Engine>(cli: &RunArgs, simulation: &Simulation<E>) {
+    let (Some(path), Some(stats)) = (&cli.stats_out, simulation.stats()) else {
+        return;
+    };
+
+    if let Err(error) = std::fs::File::create(path).and_then(|file| stats.write_csv(std::io::BufWriter::new(file))) {
+        eprintln!("could not save statistics to {}: {error}", path.display());
+    }
+}
+
+/// Saves the heatmap of the run to `--heatmap-out`, if given.
+fn save_heatmap<E: Engine>(cli: &RunArgs, simulation: &Simulation<E>) {
+    let (Some(path), Some(activity)) = (&cli.heatmap_out, simulation.activity()) else {
+        return;
+    };
+
+    #[cfg(feature = "image")]
+    if let Err(error) = activity.to_image(&Theme::default(), cli.record_scale.into()).save(path) {
+        eprintln!("could not save heatmap to {}: {error}", path.display());
+    }
+    #[cfg(not(feature = "image"))]
+    {
+        let _ = activity;
+        eprintln!("--heatmap-out needs the image feature, not saving {}", path.display());
+    }
+}
+
+fn play<E: Engine + Send + 'static, R: Renderer>(renderer: R, simulation: Simulation<E>, update_interval: Duration, cli: &RunArgs) -> Simulation<E> {
+    let mut player = Player::new(renderer, simulation, update_interval);
+    if let Some(path) = cli.save_session.as_ref().or(cli.load_session.as_ref()) {
+        player.set_session_file(path);
+    }
 
-    if cli.no_render {
-        match cli.mode {
-            Mode::Serial => run_non_stop(SerialEngine, grid, max_iter),
-            Mode::Parallel => run_non_stop(ParallelEngine, grid, max_iter),
+    let simulation = player.run().unwrap_or_else(|error| {
+        eprintln!("could not render the simulation: {error}");
+        exit(1)
+    });
+    report_stop(&simulation);
+    simulation
+}
+
+/// Writes the generations into a GIF until the simulation stops, without rendering.
+fn record_gif<E: Engine>(mut simulation: Simulation<E>, path: &Path, cli: &RunArgs) -> Simulation<E> {
+    let mut gif = GifWriter::new(path)
+        .with_scale(cli.gif_scale.into())
+        .with_delay(Duration::from_millis(cli.gif_delay))
+        .with_every(cli.gif_every as usize);
+
+    let frames = loop {
+        if let Err(error) = gif.capture(simulation.grid(), &simulation.engine().agents()) {
+            break Err(error);
+        }
+        if simulation.is_finished() {
+            break gif.finish();
+        }
+        simulation.step();
+    };
+
+    match frames {
+        Ok(frames) => println!("{frames} frames written to {}", path.display()),
+        Err(error) => {
+            eprintln!("could not write GIF to {}: {error}", path.display());
+            exit(1)
+        },
+    }
+    report_stop(&simulation);
+    simulation
+}
+
+/// Runs the engines of two modes side by side from the same board, in a window, or checks that
+/// they agree without rendering.
+fn run_compare(mode: Mode, other: Mode, rule: Rule, cli: &RunArgs) {
+    let (Some(first), Some(second)) = (grid_engine(mode, cli, rule), grid_engine(other, cli, rule)) else {
+        eprintln!("--compare only supports the serial, parallel, numa, bit, sparse, hashlife, ltl and margolus modes");
+        exit(1)
+    };
+    if cli.record.is_some() || cli.gif.is_some() || cli.load_session.is_some() || cli.resume.is_some() {
+        eprintln!("--compare always starts from a new board in a window, ignoring --record, --gif, --load-session and --resume");
+    }
+    let (grid, _) = initial_grid(&first, &cli.board, rule);
+
+    if cli.renderer() == RendererKind::None {
+        let generations = cli.iterations.unwrap_or(COMPARE_GENERATIONS);
+        match compare_engines(&first, &second, &grid, generations) {
+            Ok(_) => println!("{} and {} engines agree for {generations} generations", first.name(), second.name()),
+            Err(mismatch) => {
+                eprintln!("{mismatch}");
+                exit(1)
+            },
         }
+        return;
+    }
+
+    let update_interval = cli.update_interval().unwrap_or(UPDATE_INTERVAL);
+    let comparison = CompareRenderer::new(cli.cell_size, Comparison::new(first, second, grid), update_interval).unwrap().run();
+    if let Some(generation) = comparison.first_mismatch() {
+        let (first, second) = comparison.names();
+        println!("{first} and {second} engines first differed at generation {generation}");
+    }
+}
+
+/// Without rendering, all `--iterations` are computed in a single update, skipping the
+/// intermediate generations. Without `--iterations`, it runs one step at a time like the other
+/// engines, until a stopping criterion is met.
+fn run_hashlife(cli: &RunArgs, session: Option<Session>, seed: Option<u64>) {
+    if cli.iterations.is_none() || cli.renderer() != RendererKind::None || cli.record.is_some() || cli.gif.is_some() || cli.expand || cli.checkpoint_every.is_some() || cli.noise > 0.0 || cli.stats_out.is_some() || cli.heatmap_out.is_some() {
+        return run(HashLifeEngine::new().with_step(cli.engine.step), cli, session, seed);
+    }
+
+    let generations = cli.iterations.unwrap_or_default().saturating_mul(cli.engine.step);
+    let engine = HashLifeEngine::new().with_step(generations);
+    let (grid, generation, seed) = match session {
+        Some(session) => (session.grid, session.generation, seed),
+        None => {
+            let (grid, seed) = initial_grid(&engine, &cli.board, cli.engine.rule);
+            (grid, 0, seed)
+        },
+    };
+
+    let start = Instant::now();
+    let next = engine.update(&grid);
+    println!("{:?}", start.elapsed());
+
+    let start = Checkpoint { engine: engine.name().to_owned(), rule: engine.life_rule(), generation, seed, grid };
+    let checkpoint = Checkpoint { generation: generation.saturating_add(generations), grid: next, ..start.clone() };
+    save_checkpoint(cli, &checkpoint);
+    save_replay(cli, &Replay { start, generations, expansion: None });
+    save_session(cli, checkpoint, None, None);
+}
+
+fn save_checkpoint(cli: &RunArgs, checkpoint: &Checkpoint) {
+    let Some(path) = &cli.checkpoint else {
+        return;
+    };
+
+    if let Err(error) = checkpoint.save(path) {
+        eprintln!("could not save checkpoint to {}: {error}", path.display());
+    }
+}
+
+fn save_replay(cli: &RunArgs, replay: &Replay) {
+    let Some(path) = &cli.save_replay else {
+        return;
+    };
+
+    // only engines of Life-like rules can be built again from the file, and without noise
+    if replay.start.rule.is_none() || cli.noise > 0.0 {
+        return;
+    }
+    if let Err(error) = replay.save(path) {
+        eprintln!("could not save replay to {}: {error}", path.display());
+    }
+}
+
+fn save_session(cli: &RunArgs, checkpoint: Checkpoint, noise: Option<Noise>, cycle_window: Option<usize>) {
+    let Some(path) = &cli.save_session else {
+        return;
+    };
+
+    let session = Session { noise, cycle_window, ..resumed_session(checkpoint, cli) };
+    if let Err(error) = session.save(path) {
+        eprintln!("could not save session to {}: {error}", path.display());
+    }
+}
+
+fn run_game(cli: &RunArgs) {
+    let mut rules = GameRules { cells_per_turn: cli.turn_cells, turns_per_player: cli.turns, ..GameRules::default() };
+    if let Some(iterations) = cli.iterations {
+        rules.max_generations = iterations;
+    }
+
+    let game = if cli.renderer() == RendererKind::None {
+        let mut game = ImmigrationGame::random_with(cli.board.height, cli.board.width, rules, &mut rng(&cli.board));
+        while game.phase() == Phase::Running {
+            game.step();
+        }
+        game
+    } else {
+        let game = ImmigrationGame::new(cli.board.height, cli.board.width, rules);
+        GameRenderer::new(cli.cell_size, game, cli.update_interval().unwrap_or(UPDATE_INTERVAL)).unwrap().run()
+    };
+
+    let [red, blue] = game.scores();
+    println!("{:?} after {} generations, Red {red} x {blue} Blue", game.phase(), game.generation());
+}
+
+#[cfg(feature = "continuous")]
+fn run_continuous<E: ContinuousEngine>(engine: E, cli: &RunArgs, session: Option<Session>) {
+    if session.is_some() || cli.save_session.is_some() {
+        eprintln!("sessions are not supported for continuous automata, ignoring");
+    }
+
+    let max_iter = cli.iterations.unwrap_or(usize::MAX);
+    let field = Field::random_with(cli.board.height, cli.board.width, &mut rng(&cli.board));
+
+    if cli.renderer() == RendererKind::None {
+        let start = Instant::now();
+
+        let _ = engine.advance(field, max_iter);
+
+        println!("{:?}", start.elapsed());
     } else {
-        match cli.mode {
-            Mode::Serial => run_interactive(SerialEngine, grid, cli.cell_size, max_iter),
-            Mode::Parallel => run_interactive(ParallelEngine, grid, cli.cell_size, max_iter),
+        FieldRenderer::new(cli.cell_size, engine, field, cli.update_interval().unwrap_or(UPDATE_INTERVAL)).unwrap().run(max_iter);
+    }
+}
+
+fn run_generations(rule: GenerationsRule, cli: &RunArgs, session: Option<Session>) {
+    if session.is_some() || cli.save_session.is_some() {
+        eprintln!("sessions are not supported for generations rules, ignoring");
+    }
+
+    let states = States::random_with(cli.board.height, cli.board.width, &mut rng(&cli.board));
+    run_states(GenerationsEngine::new(rule), states, "Generations", cli);
+}
+
+/// Runs Brian's Brain from a random board, with `--density` firing cells.
+fn run_brians_brain(cli: &RunArgs, session: Option<Session>) {
+    if session.is_some() || cli.save_session.is_some() {
+        eprintln!("sessions are not supported for Brian's Brain, ignoring");
+    }
+
+    let board = &cli.board;
+    let states = States::from(&Grid::random_with_density(board.height, board.width, board.density, &mut rng(board)));
+    run_states(BriansBrainEngine, states, "Brian's Brain", cli);
+}
+
+/// Runs Life with colored cells from a random board, with `--density` live cells.
+fn run_quadlife(engine: MultiColorEngine, cli: &RunArgs, session: Option<Session>) {
+    if session.is_some() || cli.save_session.is_some() {
+        eprintln!("sessions are not supported for colored cells, ignoring");
+    }
+
+    let board = &cli.board;
+    let states = engine.random_with(board.height, board.width, board.density, &mut rng(board));
+    let title = if engine.colors() == 2 { "Immigration" } else { "QuadLife" };
+    run_states(engine, states, title, cli);
+}
+
+/// Runs 3D Life from a random space of `--depth` layers, with `--density` live cells.
+///
+/// With `--record`, every slice of every generation is written as an image instead of rendering.
+fn run_3d(engine: Engine3d, cli: &RunArgs, session: Option<Session>) {
+    if session.is_some() || cli.save_session.is_some() {
+        eprintln!("sessions are not supported for 3D Life, ignoring");
+    }
+    if cli.board.pattern.is_some() || cli.board.preset.is_some() {
+        eprintln!("--pattern and --preset only have 2D patterns, ignoring");
+    }
+
+    let board = &cli.board;
+    let mut space = Grid3::random_with(board.depth, board.height, board.width, board.density, &mut rng(board));
+    let max_iter = cli.iterations.unwrap_or(usize::MAX);
+
+    if let Some(directory) = &cli.record {
+        let mut recorder = FrameRecorder::new(directory).with_scale(cli.record_scale.into());
+        for generation in 0 ..= max_iter {
+            if let Err(error) = recorder.record_layers(&space, generation) {
+                eprintln!("could not record to {}: {error}", directory.display());
+                exit(1)
+            }
+            if generation < max_iter {
+                space = engine.update(&space);
+            }
         }
+    } else if cli.renderer() == RendererKind::None {
+        let start = Instant::now();
+        let _ = engine.advance(space, max_iter);
+
+        println!("{:?}", start.elapsed());
+    } else {
+        let update_interval = cli.update_interval().unwrap_or(UPDATE_INTERVAL);
+        LayersRenderer::with_window(&WindowConfig::new("3D Life"), cli.cell_size, engine, space, update_interval).unwrap().run(max_iter);
     }
 }
 
-fn run_interactive<E: Engine>(engine: E, grid: Grid, cell_size: f64, max_iter: usize) {
-    let mut renderer = Renderer::new(cell_size, engine, grid, UPDATE_INTERVAL).unwrap();
+/// Runs the circuit in `--pattern`, or two clocks feeding a pair of diodes.
+fn run_wireworld(cli: &RunArgs, session: Option<Session>) {
+    if session.is_some() || cli.save_session.is_some() {
+        eprintln!("sessions are not supported for wireworld circuits, ignoring");
+    }
+    if cli.board.preset.is_some() {
+        eprintln!("--preset only has Life patterns, ignoring");
+    }
 
-    for _ in 0 ..= max_iter {
-        if renderer.next_update().is_none() {
-            return;
+    let circuit = match &cli.board.pattern {
+        Some(path) => std::fs::read_to_string(path).map_err(vida::Error::from).and_then(|text| wireworld::parse(&text)).unwrap_or_else(|error| {
+            eprintln!("could not load circuit from {}: {error}", path.display());
+            exit(1)
+        }),
+        None => patterns::wireworld_diodes(),
+    };
+    run_states(WireworldEngine, place_states(&circuit, &cli.board), "Wireworld", cli);
+}
+
+/// Places a multi-state pattern at the center of an empty board, moved by `--offset`.
+fn place_states(pattern: &States, board: &BoardArgs) -> States {
+    let (rows, columns) = pattern.shape();
+    let top = (board.height as isize - rows as isize) / 2 + board.offset.0;
+    let left = (board.width as isize - columns as isize) / 2 + board.offset.1;
+
+    let mut states = States::new(board.height, board.width);
+    let mut clipped = false;
+    for (row, cells) in pattern.iter().enumerate() {
+        for (col, &state) in cells.iter().enumerate().filter(|(_, &state)| state != 0) {
+            let row = usize::try_from(top + row as isize).ok().filter(|&row| row < board.height);
+            let col = usize::try_from(left + col as isize).ok().filter(|&col| col < board.width);
+
+            match row.zip(col) {
+                Some(position) => states[position] = state,
+                None => clipped = true,
+            }
         }
     }
+
+    if clipped {
+        eprintln!("pattern doesn't fit in the board, some cells were left out");
+    }
+    states
 }
 
-fn run_non_stop<E: Engine>(engine: E, mut grid: Grid, max_iter: usize) {
+/// Runs a multi-state automaton, in a window with the given title unless rendering is disabled.
+fn run_states<E: MultiStateEngine>(engine: E, states: States, title: &str, cli: &RunArgs) {
+    let max_iter = cli.iterations.unwrap_or(usize::MAX);
+
+    if cli.renderer() == RendererKind::None {
+        let start = Instant::now();
+
+        let _ = engine.advance(states, max_iter);
+
+        println!("{:?}", start.elapsed());
+    } else {
+        let update_interval = cli.update_interval().unwrap_or(UPDATE_INTERVAL);
+        StatesRenderer::with_window(&WindowConfig::new(title), cli.cell_size, engine, states, update_interval).unwrap().run(max_iter);
+    }
+}
+
+/// Runs until the simulation stops, saving a checkpoint every `--checkpoint-every` generations.
+fn run_non_stop<E: Engine>(mut simulation: Simulation<E>, cli: &RunArgs) -> Simulation<E> {
     let start = Instant::now();
+    match cli.checkpoint_every {
+        Some(every) => {
+            while !simulation.is_finished() {
+                simulation.step();
+                if simulation.generation() as u64 % every == 0 {
+                    save_checkpoint(cli, &simulation.checkpoint());
+                }
+            }
+        },
+        None => {
+            simulation.run();
+        },
+    }
+    println!("{:?}", start.elapsed());
 
-    for _ in 0 ..= max_iter {
-        grid = engine.update(&grid);
+    report_stop(&simulation);
+    if let (true, Some(stats)) = (cli.stats, simulation.stats()) {
+        print!("{}", stats.summary());
     }
+    simulation
+}
 
-    println!("{:?}", start.elapsed())
+/// Tells why the simulation stopped early, if it did.
+fn report_stop<E: Engine>(simulation: &Simulation<E>) {
+    match simulation.stop_reason() {
+        Some(StopReason::Extinction) => println!("extinct at generation {}", simulation.generation()),
+        Some(StopReason::Stable | StopReason::Cycle(1)) => println!("stable at generation {}", simulation.generation()),
+        Some(StopReason::Cycle(period)) => println!("cycle with period {period} at generation {}", simulation.generation()),
+        Some(StopReason::MaxGenerations) | None => (),
+    }
 }
@@ -0,0 +1,333 @@
+//! Searching for methuselahs: small patterns that take many generations to settle.
+//!
+//! The search keeps a pool of patterns inside a small box and, on each round, mutates every one of
+//! them by flipping a few of its cells. Mutants are evaluated concurrently with the `parallel`
+//! feature, by running them on a larger board until they settle as in a [soup
+//! search](crate::soup), and the best patterns by a [`Fitness`] make it to the next round.
+
+use std::collections::HashSet;
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+use rand::rngs::SmallRng;
+use rand::{Rng, SeedableRng};
+
+use crate::analysis::census;
+use crate::cell::{Grid, Overflow};
+use crate::engine::{Engine, SerialEngine};
+use crate::par::*;
+use crate::simulation::Simulation;
+use crate::soup::{self, Fate};
+
+/// How patterns are ranked in a [`MethuselahSearch`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub enum Fitness {
+    /// Generations until the pattern settles.
+    #[default]
+    Lifespan,
+    /// Live cells left once the pattern settles.
+    Population,
+    /// Generations until the pattern settles for each of its initial cells, which favors the
+    /// smallest patterns, like the R-pentomino.
+    Ratio,
+}
+
+impl Fitness {
+    /// Every fitness, in the same order as [`Fitness::NAMES`].
+    pub const ALL: [Self; 3] = [Self::Lifespan, Self::Population, Self::Ratio];
+
+    /// The name of each fitness, as parsed by [`FromStr`].
+    pub const NAMES: [&'static str; 3] = ["lifespan", "population", "ratio"];
+
+    #[inline]
+    #[must_use]
+    /// The name of the fitness.
+    pub const fn name(self) -> &'static str {
+        Self::NAMES[self as usize]
+    }
+
+    #[must_use]
+    /// Score of a pattern with `initial_population` cells that settled after `lifespan`
+    /// generations with `final_population` cells. Higher is better.
+    pub fn score(self, initial_population: usize, final_population: usize, lifespan: usize) -> f64 {
+        match self {
+            Self::Lifespan => lifespan as f64,
+            Self::Population => final_population as f64,
+            Self::Ratio => lifespan as f64 / initial_population.max(1) as f64,
+        }
+    }
+}
+
+impl Display for Fitness {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Error returned when there is no [`Fitness`] with a given name.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownFitnessError(String);
+
+impl Display for UnknownFitnessError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown fitness {:?}, expected one of {}", self.0, Fitness::NAMES.join(", "))
+    }
+}
+
+impl Error for UnknownFitnessError {}
+
+impl FromStr for Fitness {
+    type Err = UnknownFitnessError;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        Self::ALL.into_iter()
+            .find(|fitness| fitness.name().eq_ignore_ascii_case(name.trim()))
+            .ok_or_else(|| UnknownFitnessError(name.to_owned()))
+    }
+}
+
+/// A pattern evaluated by a [`MethuselahSearch`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Candidate {
+    /// The cells of the pattern, in a square box.
+    pub pattern: Grid,
+    /// Live cells in the pattern.
+    pub initial_population: usize,
+    /// Live cells once the pattern settled, or at the generation limit.
+    pub final_population: usize,
+    /// First generation of the final cycle, or the generation limit if the pattern never settled.
+    pub lifespan: usize,
+    pub fate: Fate,
+    /// Score by the fitness of the search.
+    pub score: f64,
+}
+
+/// Evolves small patterns into long-lived ones.
+///
+/// # Example
+///
+/// ```
+/// # use vida::engine::SparseEngine;
+/// # use vida::methuselah::MethuselahSearch;
+/// #
+/// let search = MethuselahSearch::new(SparseEngine::default(), 4)
+///     .with_board(48, 48)
+///     .with_max_generations(500)
+///     .with_population(6);
+/// let best = search.search(3, 587);
+///
+/// assert_eq!(best.len(), 6);
+/// assert!(best.windows(2).all(|pair| pair[0].score >= pair[1].score));
+/// assert_eq!(best, search.search(3, 587));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct MethuselahSearch<E = SerialEngine> {
+    engine: E,
+    size: usize,
+    rows: usize,
+    columns: usize,
+    fitness: Fitness,
+    max_generations: usize,
+    cycle_window: usize,
+    population: usize,
+    mutations: usize,
+}
+
+impl<E: Engine + Clone + Sync> MethuselahSearch<E> {
+    /// Side of the square board where patterns are evaluated, by default.
+    pub const BOARD_SIZE: usize = 256;
+    /// Generations after which a pattern is considered unsettled, by default.
+    pub const MAX_GENERATIONS: usize = 5000;
+
+    #[inline]
+    #[must_use]
+    /// Searches patterns inside a box of `size` by `size` cells, cloning the engine for each
+    /// evaluation.
+    pub fn new(engine: E, size: usize) -> Self {
+        Self {
+            engine,
+            size,
+            rows: Self::BOARD_SIZE,
+            columns: Self::BOARD_SIZE,
+            fitness: Fitness::default(),
+            max_generations: Self::MAX_GENERATIONS,
+            cycle_window: soup::SoupSearch::<E>::CYCLE_WINDOW,
+            population: 32,
+            mutations: 3,
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    /// Evaluates patterns at the center of a board of `(rows, columns)` cells. Cells that reach
+    /// its edges die, so larger boards let patterns live longer.
+    pub fn with_board(mut self, rows: usize, columns: usize) -> Self {
+        self.rows = rows.max(self.size);
+        self.columns = columns.max(self.size);
+        self
+    }
+
+    #[inline]
+    #[must_use]
+    /// How patterns are ranked.
+    pub const fn with_fitness(mut self, fitness: Fitness) -> Self {
+        self.fitness = fitness;
+        self
+    }
+
+    #[inline]
+    #[must_use]
+    /// Stops patterns that haven't settled after `max` generations.
+    pub const fn with_max_generations(mut self, max: usize) -> Self {
+        self.max_generations = max;
+        self
+    }
+
+    #[inline]
+    #[must_use]
+    /// Detects periods up to `window` generations when patterns settle.
+    pub fn with_cycle_window(mut self, window: usize) -> Self {
+        self.cycle_window = window.max(1);
+        self
+    }
+
+    #[inline]
+    #[must_use]
+    /// Number of patterns kept from one round to the next.
+    pub fn with_population(mut self, population: usize) -> Self {
+        self.population = population.max(1);
+        self
+    }
+
+    #[inline]
+    #[must_use]
+    /// Most cells flipped in each mutation, at least one.
+    pub fn with_mutations(mut self, mutations: usize) -> Self {
+        self.mutations = mutations.max(1);
+        self
+    }
+
+    #[must_use]
+    /// Runs a pattern at the center of the board until it settles.
+    pub fn evaluate(&self, pattern: &Grid) -> Candidate {
+        let (rows, columns) = pattern.shape();
+        let mut board = Grid::new(self.rows, self.columns);
+        let (top, left) = (self.rows.saturating_sub(rows) / 2, self.columns.saturating_sub(columns) / 2);
+        board.blit(pattern, top, left, Overflow::Clip).expect("clipping never fails");
+        let initial_population = board.population();
+
+        let mut simulation = Simulation::new(self.engine.clone(), board)
+            .with_max_generations(self.max_generations)
+            .stop_on_extinction(true)
+            .stop_on_cycle(Some(self.cycle_window));
+        let (lifespan, fate) = soup::settle(&mut simulation);
+        let final_population = simulation.grid().population();
+
+        Candidate {
+            pattern: pattern.clone(),
+            initial_population,
+            final_population,
+            lifespan,
+            fate,
+            score: self.fitness.score(initial_population, final_population, lifespan),
+        }
+    }
+
+    #[must_use]
+    /// Runs `rounds` rounds of mutations from random patterns, returning the pool of best
+    /// patterns, best first. The same seed always gives the same results.
+    pub fn search(&self, rounds: usize, seed: u64) -> Vec<Candidate> {
+        let mut rng = SmallRng::seed_from_u64(seed);
+        // canonical forms of every pattern evaluated, so none is evaluated twice
+        let mut seen = HashSet::new();
+
+        let initial: Vec<_> = (0..self.population).map(|_| Grid::random_with(self.size, self.size, &mut rng)).collect();
+        let mut pool = self.evaluate_new(initial, &mut seen);
+        self.rank(&mut pool);
+
+        for _ in 0..rounds {
+            let mutants: Vec<_> = pool.iter().map(|candidate| self.mutate(&candidate.pattern, &mut rng)).collect();
+            pool.extend(self.evaluate_new(mutants, &mut seen));
+            self.rank(&mut pool);
+        }
+        pool
+    }
+
+    /// Evaluates the patterns that were not seen before, concurrently.
+    fn evaluate_new(&self, patterns: Vec<Grid>, seen: &mut HashSet<census::Cells>) -> Vec<Candidate> {
+        let new: Vec<_> = patterns
+            .into_iter()
+            .filter(|pattern| seen.insert(census::canonical(&pattern.live_cells().collect::<Vec<_>>())))
+            .collect();
+        new.par_iter().map(|pattern| self.evaluate(pattern)).collect()
+    }
+
+    /// A copy of the pattern with up to `mutations` random cells flipped.
+    fn mutate<R: Rng + ?Sized>(&self, pattern: &Grid, rng: &mut R) -> Grid {
+        let mut mutant = pattern.clone();
+        let (rows, columns) = mutant.shape();
+        if rows > 0 && columns > 0 {
+            for _ in 0..rng.gen_range(1..=self.mutations) {
+                mutant[(rng.gen_range(0..rows), rng.gen_range(0..columns))].toggle();
+            }
+        }
+        mutant
+    }
+
+    /// Keeps the best patterns, best first, preferring smaller ones among equal scores.
+    fn rank(&self, pool: &mut Vec<Candidate>) {
+        pool.sort_by(|first, second| {
+            second.score.total_cmp(&first.score).then(first.initial_population.cmp(&second.initial_population))
+        });
+        pool.truncate(self.population);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::engine::SparseEngine;
+    use crate::patterns;
+
+    use super::*;
+
+    #[test]
+    pub fn evaluation() {
+        let search = MethuselahSearch::new(SerialEngine::default(), 5).with_board(40, 40);
+
+        let blinker = search.evaluate(&patterns::blinker());
+        assert_eq!((blinker.lifespan, blinker.fate, blinker.final_population), (0, Fate::Oscillating(2), 3));
+
+        let mut lonely = Grid::new(5, 5);
+        lonely[(2, 2)].toggle();
+        let lonely = search.evaluate(&lonely);
+        assert_eq!((lonely.lifespan, lonely.fate, lonely.score), (1, Fate::Extinct, 1.0));
+
+        // the R-pentomino is cut short by the walls of a small board, but still lives long
+        let ratio = search.with_fitness(Fitness::Ratio).evaluate(&patterns::r_pentomino());
+        assert!(ratio.lifespan > 100, "{ratio:?}");
+        assert_eq!(ratio.score, ratio.lifespan as f64 / 5.0);
+    }
+
+    #[test]
+    pub fn rounds_never_lose_the_best() {
+        let search = MethuselahSearch::new(SparseEngine::default(), 4).with_board(32, 32).with_max_generations(300).with_population(5);
+
+        let first = search.search(0, 7);
+        let later = search.search(4, 7);
+        assert!(later[0].score >= first[0].score);
+        assert!(later.iter().all(|candidate| candidate.pattern.shape() == (4, 4)));
+
+        let patterns: HashSet<_> = later.iter().map(|candidate| census::canonical(&candidate.pattern.live_cells().collect::<Vec<_>>())).collect();
+        assert_eq!(patterns.len(), later.len());
+    }
+
+    #[test]
+    pub fn fitness_names() {
+        for fitness in Fitness::ALL {
+            assert_eq!(fitness.to_string().parse(), Ok(fitness));
+        }
+        assert!("longevity".parse::<Fitness>().is_err());
+    }
+}
@@ -0,0 +1,199 @@
+//! Life with colored cells, like Immigration (two colors) and QuadLife (four colors).
+//!
+//! Cells are born and survive as in a Life-like [`Rule`], ignoring their colors, and a newborn cell
+//! takes the color of most of its parents. Cells are stored as [`States`], `0` for dead cells and
+//! `1 ..= colors` for live ones.
+
+use rand::Rng;
+
+use crate::cell::Cell;
+use crate::generations::{MultiStateEngine, States};
+use crate::par::*;
+use crate::rule::Rule;
+use crate::theme::{Color, Theme};
+
+/// Computes generations of a Life-like rule with colored cells, one row per task with the
+/// `parallel` feature.
+///
+/// The color of a newborn cell is the most common one among its live neighbors. When there is no
+/// single most common color, as when the three parents of a QuadLife cell are all different, it
+/// takes the first color missing from its neighbors, or the first of the tied colors if none is
+/// missing. Cells past the edges are dead.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct MultiColorEngine {
+    rule: Rule,
+    colors: u8,
+}
+
+impl MultiColorEngine {
+    /// Most colors supported by the engine.
+    pub const MAX_COLORS: u8 = 4;
+
+    /// Immigration, Conway's rule with two colors.
+    pub const IMMIGRATION: Self = Self::new(Rule::CONWAY, 2);
+    /// QuadLife, Conway's rule with four colors.
+    pub const QUADLIFE: Self = Self::new(Rule::CONWAY, 4);
+
+    #[inline]
+    #[must_use]
+    /// Creates an engine running the given rule with `colors` colors, clamped to
+    /// `1 ..= MAX_COLORS`.
+    pub const fn new(rule: Rule, colors: u8) -> Self {
+        let colors = if colors == 0 { 1 } else if colors > Self::MAX_COLORS { Self::MAX_COLORS } else { colors };
+        Self { rule, colors }
+    }
+
+    #[inline]
+    #[must_use]
+    /// The rule used to compute each generation.
+    pub const fn rule(&self) -> Rule {
+        self.rule
+    }
+
+    #[inline]
+    #[must_use]
+    /// The number of colors of live cells.
+    pub const fn colors(&self) -> u8 {
+        self.colors
+    }
+
+    #[must_use]
+    /// Creates a board of `(rows, columns)` cells, each live with probability `density` and with a
+    /// random color.
+    pub fn random_with<R: Rng + ?Sized>(&self, rows: usize, columns: usize, density: f64, rng: &mut R) -> States {
+        let mut states = States::new(rows, columns);
+        for state in states.flat_mut() {
+            if rng.gen_bool(density) {
+                *state = rng.gen_range(1..=self.colors);
+            }
+        }
+        states
+    }
+
+    #[must_use]
+    /// Color of a newborn cell with `counts[color - 1]` neighbors of each color.
+    fn majority(&self, counts: &[usize]) -> u8 {
+        let most = counts.iter().copied().max().unwrap_or(0);
+        let mut tied = (1..).zip(counts).filter(|&(_, &count)| count == most).map(|(color, _)| color);
+
+        let first = tied.next().unwrap_or(1);
+        if tied.next().is_none() {
+            return first;
+        }
+        (1..).zip(counts).find(|&(_, &count)| count == 0).map_or(first, |(color, _)| color)
+    }
+}
+
+impl Default for MultiColorEngine {
+    #[inline]
+    fn default() -> Self {
+        Self::QUADLIFE
+    }
+}
+
+impl MultiStateEngine for MultiColorEngine {
+    fn update(&self, states: &States) -> States {
+        let (rows, columns) = states.shape();
+        let mut next = States::new(rows, columns);
+        let topology = self.rule.topology();
+
+        // a copy with a border of dead cells, so neighbors are found without checking the edges
+        let width = columns + 2;
+        let mut padded = vec![0; (rows + 2) * width];
+        for (row, cells) in states.iter().enumerate() {
+            padded[(row + 1) * width + 1..][..columns].copy_from_slice(cells);
+        }
+
+        next.flat_mut().par_chunks_exact_mut(columns.max(1)).enumerate().for_each(|(row, cells)| {
+            let offsets: Vec<_> = topology.offsets(row).map(|(dy, dx)| (1 + dy) as usize * width + (1 + dx) as usize).collect();
+            for (col, cell) in cells.iter_mut().enumerate() {
+                let mut counts = [0; Self::MAX_COLORS as usize];
+                for &offset in &offsets {
+                    let neighbor = padded[row * width + col + offset];
+                    if neighbor > 0 {
+                        counts[usize::from(neighbor - 1)] += 1;
+                    }
+                }
+
+                let current = states[(row, col)];
+                let live = counts.iter().sum();
+                *cell = match (current, self.rule.next(Cell::from(current), live)) {
+                    (_, Cell::Dead) => 0,
+                    (0, Cell::Live) => self.majority(&counts[..usize::from(self.colors)]),
+                    (color, Cell::Live) => color,
+                };
+            }
+        });
+        next
+    }
+
+    #[inline]
+    fn name(&self) -> &'static str {
+        "quadlife"
+    }
+
+    #[inline]
+    fn color(&self, theme: &Theme, state: u8) -> Option<Color> {
+        theme.by_species(state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand::rngs::SmallRng;
+
+    use crate::cell::Grid;
+    use crate::engine::{Engine, SerialEngine};
+    use crate::game::{GameRules, ImmigrationGame};
+
+    use super::*;
+
+    fn live(states: &States) -> Grid {
+        Grid::try_from(states.iter().map(|row| row.iter().map(|&color| Cell::from(color)).collect::<Vec<_>>())).unwrap()
+    }
+
+    #[test]
+    pub fn colors_follow_life() {
+        let mut rng = SmallRng::seed_from_u64(581);
+        let engine = MultiColorEngine::new(Rule::HIGHLIFE, 4);
+        let states = engine.random_with(25, 31, 0.4, &mut rng);
+
+        let next = engine.update(&states);
+        assert_eq!(live(&next), SerialEngine::new(Rule::HIGHLIFE).update(&live(&states)));
+        assert!(next.flat().iter().all(|&color| color <= 4));
+        assert_eq!(engine.update(&States::new(0, 5)), States::new(0, 5));
+    }
+
+    #[test]
+    pub fn same_as_immigration_game() {
+        let mut rng = SmallRng::seed_from_u64(582);
+        let mut game = ImmigrationGame::random_with(20, 24, GameRules::default(), &mut rng);
+        let owners = |game: &ImmigrationGame| {
+            let mut states = States::new(20, 24);
+            for (row, col) in game.grid().live_cells() {
+                states[(row, col)] = game.owner(row, col).map_or(0, |player| player.index() as u8 + 1);
+            }
+            states
+        };
+
+        let mut states = owners(&game);
+        for _ in 0..5 {
+            game.step();
+            states = MultiColorEngine::IMMIGRATION.update(&states);
+            assert_eq!(states, owners(&game));
+        }
+    }
+
+    #[test]
+    pub fn quadlife_births() {
+        let mut states = States::new(3, 3);
+        states[(0, 0)] = 1;
+        states[(0, 2)] = 2;
+        states[(2, 0)] = 4;
+        assert_eq!(MultiColorEngine::QUADLIFE.update(&states)[(1, 1)], 3);
+
+        states[(2, 0)] = 2;
+        assert_eq!(MultiColorEngine::QUADLIFE.update(&states)[(1, 1)], 2);
+    }
+}
@@ -0,0 +1,145 @@
+//! Random flips of cells between generations, for experiments with noisy Life.
+
+use rand::rngs::SmallRng;
+use rand::{Rng, SeedableRng};
+
+use crate::cell::Grid;
+
+/// Flips each cell of a grid with a fixed probability, from a seeded random number generator.
+///
+/// The same seed flips the same cells on the same sequence of grids, so noisy runs can be
+/// reproduced. Each grid draws from its own generator, seeded from the seed and the number of
+/// grids [flipped before it](Noise::applied), so a run can also be resumed in the middle. Instead of drawing a number for every cell, the distance to the next flipped cell is
+/// drawn from a geometric distribution, so small probabilities cost little on large grids.
+///
+/// # Example
+///
+/// ```
+/// # use vida::cell::Grid;
+/// # use vida::noise::Noise;
+/// #
+/// let mut grid = Grid::new(100, 100);
+/// let flipped = Noise::new(0.01, 582).apply(&mut grid);
+///
+/// assert_eq!(grid.population(), flipped);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Noise {
+    probability: f64,
+    seed: u64,
+    applied: u64,
+}
+
+impl Noise {
+    #[inline]
+    #[must_use]
+    /// Flips cells with the given `probability`, clamped to `0.0 ..= 1.0`, drawing from a generator
+    /// seeded with `seed`.
+    pub fn new(probability: f64, seed: u64) -> Self {
+        Self { probability: probability.clamp(0.0, 1.0), seed, applied: 0 }
+    }
+
+    #[inline]
+    #[must_use]
+    /// Continues after `applied` grids were flipped, like when resuming a session.
+    pub const fn with_applied(mut self, applied: u64) -> Self {
+        self.applied = applied;
+        self
+    }
+
+    #[inline]
+    #[must_use]
+    /// The probability of flipping each cell.
+    pub const fn probability(&self) -> f64 {
+        self.probability
+    }
+
+    #[inline]
+    #[must_use]
+    /// The seed of the flips.
+    pub const fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    #[inline]
+    #[must_use]
+    /// Number of grids flipped so far.
+    pub const fn applied(&self) -> u64 {
+        self.applied
+    }
+
+    /// Flips random cells of the grid, returning how many were flipped.
+    pub fn apply(&mut self, grid: &mut Grid) -> usize {
+        let mut rng = SmallRng::seed_from_u64(self.seed.wrapping_add(self.applied));
+        self.applied += 1;
+        let cells = grid.flat_mut();
+        if self.probability <= 0.0 {
+            return 0;
+        }
+        if self.probability >= 1.0 {
+            cells.iter_mut().for_each(|cell| cell.toggle());
+            return cells.len();
+        }
+
+        let log = (1.0 - self.probability).ln();
+        let mut flipped = 0;
+        let mut position = skip(&mut rng, log);
+        while let Some(cell) = cells.get_mut(position) {
+            cell.toggle();
+            flipped += 1;
+            position = position.saturating_add(1).saturating_add(skip(&mut rng, log));
+        }
+        flipped
+    }
+}
+
+/// Cells left alone before the next flip, with `log` the logarithm of `1 - probability`.
+fn skip(rng: &mut SmallRng, log: f64) -> usize {
+    let uniform: f64 = rng.gen();
+    // `1 - uniform` is in `(0, 1]`, so the logarithm is finite
+    ((1.0 - uniform).ln() / log) as usize
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn reproducible_flips() {
+        let grid = Grid::random_seeded(200, 300, 582);
+
+        let (mut first, mut second) = (grid.clone(), grid.clone());
+        let flipped = Noise::new(0.001, 7).apply(&mut first);
+        assert_eq!(Noise::new(0.001, 7).apply(&mut second), flipped);
+        assert_eq!(first, second);
+
+        let changed = first.flat().iter().zip(grid.flat()).filter(|(after, before)| after != before).count();
+        assert_eq!(changed, flipped);
+        // 60 flips expected, with a standard deviation below 8
+        assert!((30..=90).contains(&flipped), "{flipped} flips");
+
+        // resuming after a grid flips the same cells as going on
+        let mut noise = Noise::new(0.001, 7);
+        noise.apply(&mut grid.clone());
+        let (mut going_on, mut resumed) = (grid.clone(), grid.clone());
+        noise.apply(&mut going_on);
+        Noise::new(0.001, 7).with_applied(1).apply(&mut resumed);
+        assert_eq!(going_on, resumed);
+        assert_ne!(going_on, first);
+    }
+
+    #[test]
+    pub fn edge_probabilities() {
+        let grid = Grid::random_seeded(20, 30, 583);
+
+        let mut same = grid.clone();
+        assert_eq!(Noise::new(0.0, 1).apply(&mut same), 0);
+        assert_eq!(same, grid);
+
+        let mut inverted = grid.clone();
+        assert_eq!(Noise::new(2.0, 1).apply(&mut inverted), 600);
+        assert_eq!(inverted, !&grid);
+
+        assert_eq!(Noise::new(0.5, 1).apply(&mut Grid::new(0, 5)), 0);
+    }
+}
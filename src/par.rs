@@ -0,0 +1,51 @@
+//! Data parallelism over slices, with rayon when the `parallel` feature is enabled.
+//!
+//! Without it, the same `par_*` methods return the sequential iterators of the standard library,
+//! so code written against rayon's prelude runs on a single thread unchanged.
+
+#[cfg(feature = "parallel")]
+pub(crate) use rayon::prelude::*;
+
+#[cfg(not(feature = "parallel"))]
+pub(crate) use serial::ParallelSlice;
+
+#[cfg(not(feature = "parallel"))]
+mod serial {
+    use std::slice::{Chunks, ChunksExact, ChunksExactMut, Iter, IterMut};
+
+    /// The slice methods of rayon's prelude, running sequentially.
+    pub(crate) trait ParallelSlice<T> {
+        fn par_iter(&self) -> Iter<'_, T>;
+        fn par_iter_mut(&mut self) -> IterMut<'_, T>;
+        fn par_chunks(&self, size: usize) -> Chunks<'_, T>;
+        fn par_chunks_exact(&self, size: usize) -> ChunksExact<'_, T>;
+        fn par_chunks_exact_mut(&mut self, size: usize) -> ChunksExactMut<'_, T>;
+    }
+
+    impl<T> ParallelSlice<T> for [T] {
+        #[inline]
+        fn par_iter(&self) -> Iter<'_, T> {
+            self.iter()
+        }
+
+        #[inline]
+        fn par_iter_mut(&mut self) -> IterMut<'_, T> {
+            self.iter_mut()
+        }
+
+        #[inline]
+        fn par_chunks(&self, size: usize) -> Chunks<'_, T> {
+            self.chunks(size)
+        }
+
+        #[inline]
+        fn par_chunks_exact(&self, size: usize) -> ChunksExact<'_, T> {
+            self.chunks_exact(size)
+        }
+
+        #[inline]
+        fn par_chunks_exact_mut(&mut self, size: usize) -> ChunksExactMut<'_, T> {
+            self.chunks_exact_mut(size)
+        }
+    }
+}
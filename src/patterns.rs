@@ -0,0 +1,303 @@
+//! Well-known Game of Life patterns, and a few Wireworld circuits.
+//!
+//! Each pattern comes in a grid just large enough to hold it, ready to be placed on a larger board.
+
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+use crate::cell::Grid;
+use crate::generations::States;
+use crate::io::plaintext;
+use crate::wireworld;
+
+const GLIDER: &str = "\
+.O
+..O
+OOO
+";
+
+const LWSS: &str = "\
+.O..O
+O
+O...O
+OOOO
+";
+
+const BLINKER: &str = "OOO\n";
+
+const PULSAR: &str = "\
+..OOO...OOO
+.
+O....O.O....O
+O....O.O....O
+O....O.O....O
+..OOO...OOO
+.
+..OOO...OOO
+O....O.O....O
+O....O.O....O
+O....O.O....O
+.
+..OOO...OOO
+";
+
+const GOSPER_GLIDER_GUN: &str = "\
+........................O
+......................O.O
+............OO......OO............OO
+...........O...O....OO............OO
+OO........O.....O...OO
+OO........O...O.OO....O.O
+..........O.....O.......O
+...........O...O
+............OO
+";
+
+const R_PENTOMINO: &str = "\
+.OO
+OO
+.O
+";
+
+const ACORN: &str = "\
+.O
+...O
+OO..OOO
+";
+
+const WIREWORLD_CLOCK: &str = "\
+.@##.....
+~...#####
+.###.....
+";
+
+const WIREWORLD_DIODES: &str = "\
+.@##...........##.......
+~...############.#######
+.###...........##.......
+.
+.@##............##......
+~...############.#######
+.###............##......
+";
+
+fn parse(cells: &str) -> Grid {
+    plaintext::parse(cells).expect("built-in patterns are valid").grid
+}
+
+#[must_use]
+/// The smallest spaceship, moving one cell diagonally every 4 generations.
+pub fn glider() -> Grid {
+    parse(GLIDER)
+}
+
+#[must_use]
+/// The lightweight spaceship, moving two cells to the side every 4 generations.
+pub fn lwss() -> Grid {
+    parse(LWSS)
+}
+
+#[must_use]
+/// The smallest oscillator, with period 2.
+pub fn blinker() -> Grid {
+    parse(BLINKER)
+}
+
+#[must_use]
+/// An oscillator with period 3.
+pub fn pulsar() -> Grid {
+    parse(PULSAR)
+}
+
+#[must_use]
+/// Bill Gosper's glider gun, the first known gun, shooting a glider every 30 generations.
+pub fn gosper_glider_gun() -> Grid {
+    parse(GOSPER_GLIDER_GUN)
+}
+
+#[must_use]
+/// A methuselah that takes 1103 generations to stabilize on an unbounded plane.
+pub fn r_pentomino() -> Grid {
+    parse(R_PENTOMINO)
+}
+
+#[must_use]
+/// A methuselah that takes 5206 generations to stabilize on an unbounded plane.
+pub fn acorn() -> Grid {
+    parse(ACORN)
+}
+
+#[must_use]
+/// A Wireworld clock, an electron going around a loop of 8 cells and sending a copy down the wire
+/// on its right every 8 generations.
+pub fn wireworld_clock() -> States {
+    wireworld::parse(WIREWORLD_CLOCK).expect("built-in circuits are valid")
+}
+
+#[must_use]
+/// Two Wireworld clocks feeding a pair of diodes: the top one lets their electrons through to the
+/// right, and the bottom one, facing the other way, stops them.
+pub fn wireworld_diodes() -> States {
+    wireworld::parse(WIREWORLD_DIODES).expect("built-in circuits are valid")
+}
+
+/// The built-in patterns, by name.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Preset {
+    Glider,
+    Lwss,
+    Blinker,
+    Pulsar,
+    GosperGun,
+    RPentomino,
+    Acorn,
+}
+
+impl Preset {
+    /// Every preset, in the same order as [`Preset::NAMES`].
+    pub const ALL: [Self; 7] = [Self::Glider, Self::Lwss, Self::Blinker, Self::Pulsar, Self::GosperGun, Self::RPentomino, Self::Acorn];
+
+    /// The name of each preset, as parsed by [`FromStr`].
+    pub const NAMES: [&'static str; 7] = ["glider", "lwss", "blinker", "pulsar", "gosper-gun", "r-pentomino", "acorn"];
+
+    #[inline]
+    #[must_use]
+    /// The name of the preset, in kebab case.
+    pub const fn name(self) -> &'static str {
+        Self::NAMES[self as usize]
+    }
+
+    #[must_use]
+    /// The cells of the pattern.
+    pub fn grid(self) -> Grid {
+        match self {
+            Self::Glider => glider(),
+            Self::Lwss => lwss(),
+            Self::Blinker => blinker(),
+            Self::Pulsar => pulsar(),
+            Self::GosperGun => gosper_glider_gun(),
+            Self::RPentomino => r_pentomino(),
+            Self::Acorn => acorn(),
+        }
+    }
+}
+
+impl Display for Preset {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Error returned when there is no [`Preset`] with a given name.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownPresetError(String);
+
+impl Display for UnknownPresetError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown preset {:?}, expected one of {}", self.0, Preset::NAMES.join(", "))
+    }
+}
+
+impl Error for UnknownPresetError {}
+
+impl FromStr for Preset {
+    type Err = UnknownPresetError;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        Self::ALL.into_iter()
+            .find(|preset| preset.name().eq_ignore_ascii_case(name.trim()))
+            .ok_or_else(|| UnknownPresetError(name.to_owned()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::engine::{BitEngine, Engine, HashLifeEngine, SerialEngine};
+    #[cfg(feature = "parallel")]
+    use crate::engine::ParallelEngine;
+    use crate::generations::MultiStateEngine;
+    use crate::wireworld::{Wire, WireworldEngine};
+
+    use super::*;
+
+    /// Places a pattern on an empty board, with its top-left corner at `(top, left)`.
+    fn board(pattern: &Grid, rows: usize, columns: usize, (top, left): (usize, usize)) -> Grid {
+        let mut grid = Grid::new(rows, columns);
+        for (row, cells) in pattern.iter().enumerate() {
+            grid[top + row][left..left + cells.len()].copy_from_slice(cells);
+        }
+        grid
+    }
+
+    fn generations(engine: &impl Engine, grid: &Grid, count: usize) -> Grid {
+        (0..count).fold(grid.clone(), |grid, _| engine.update(&grid))
+    }
+
+    fn for_each_engine(check: impl Fn(&dyn Fn(&Grid, usize) -> Grid)) {
+        check(&|grid, count| generations(&SerialEngine::default(), grid, count));
+        #[cfg(feature = "parallel")]
+        check(&|grid, count| generations(&ParallelEngine::default(), grid, count));
+        check(&|grid, count| generations(&BitEngine, grid, count));
+        check(&|grid, count| HashLifeEngine::new().with_step(count).update(grid));
+    }
+
+    #[test]
+    pub fn names() {
+        for preset in Preset::ALL {
+            assert_eq!(preset.name().parse(), Ok(preset));
+            assert!(preset.grid().population() > 0);
+        }
+        assert!("glider-gun".parse::<Preset>().is_err());
+    }
+
+    #[test]
+    pub fn spaceships_and_oscillators() {
+        for_each_engine(|run| {
+            let grid = board(&glider(), 20, 20, (2, 2));
+            assert_eq!(run(&grid, 4), board(&glider(), 20, 20, (3, 3)));
+
+            let grid = board(&lwss(), 20, 20, (8, 10));
+            assert_eq!(run(&grid, 4), board(&lwss(), 20, 20, (8, 8)));
+
+            let grid = board(&pulsar(), 17, 17, (2, 2));
+            assert_ne!(run(&grid, 1), grid);
+            assert_eq!(run(&grid, 3), grid);
+        });
+    }
+
+    #[test]
+    pub fn gun_shoots_gliders() {
+        let grid = board(&gosper_glider_gun(), 40, 60, (1, 1));
+
+        for_each_engine(|run| {
+            assert_eq!(run(&grid, 30).population(), grid.population() + 5);
+            assert_eq!(run(&grid, 60).population(), grid.population() + 10);
+        });
+    }
+
+    #[test]
+    pub fn wireworld_circuits() {
+        let clock = wireworld_clock();
+        let (mut states, mut pulses) = (clock.clone(), Vec::new());
+        for generation in 1..=32 {
+            states = WireworldEngine.update(&states);
+            if Wire::from_state(states[(1, 8)]) == Wire::Head {
+                pulses.push(generation);
+            }
+        }
+        assert_eq!(pulses, [7, 15, 23, 31]);
+        assert!(states.iter().zip(clock.iter()).all(|(row, start)| row[..4] == start[..4]));
+
+        let mut states = wireworld_diodes();
+        let (mut forward, mut backward) = (0, 0);
+        for _ in 0..64 {
+            states = WireworldEngine.update(&states);
+            forward += usize::from(Wire::from_state(states[(1, 23)]) == Wire::Head);
+            backward += usize::from(Wire::from_state(states[(5, 23)]) == Wire::Head);
+        }
+        assert!(forward > 0);
+        assert_eq!(backward, 0);
+    }
+}
@@ -0,0 +1,68 @@
+//! Text in the system clipboard, through the command line tools of each platform.
+
+use std::io::{self, Write};
+use std::process::{Command, Stdio};
+
+/// Commands that write their input to the clipboard, tried in order.
+#[cfg(target_os = "macos")]
+const COPY: &[&[&str]] = &[&["pbcopy"]];
+#[cfg(windows)]
+const COPY: &[&[&str]] = &[&["clip"]];
+#[cfg(not(any(target_os = "macos", windows)))]
+const COPY: &[&[&str]] = &[&["wl-copy"], &["xclip", "-selection", "clipboard"], &["xsel", "--clipboard", "--input"]];
+
+/// Commands that print the clipboard, tried in order.
+#[cfg(target_os = "macos")]
+const PASTE: &[&[&str]] = &[&["pbpaste"]];
+#[cfg(windows)]
+const PASTE: &[&[&str]] = &[&["powershell", "-NoProfile", "-Command", "Get-Clipboard"]];
+#[cfg(not(any(target_os = "macos", windows)))]
+const PASTE: &[&[&str]] = &[&["wl-paste", "--no-newline"], &["xclip", "-selection", "clipboard", "-out"], &["xsel", "--clipboard", "--output"]];
+
+/// Replaces the contents of the clipboard with `text`.
+///
+/// # Errors
+///
+/// If none of the clipboard tools of the platform could be run, or all of them failed.
+pub fn copy(text: &str) -> io::Result<()> {
+    first_success(COPY, |command| {
+        let mut child = command.stdin(Stdio::piped()).stdout(Stdio::null()).stderr(Stdio::null()).spawn()?;
+        let written = child.stdin.take().map_or(Ok(()), |mut stdin| stdin.write_all(text.as_bytes()));
+        let status = child.wait()?;
+        written?;
+        succeeded(status)
+    })
+}
+
+/// The text in the clipboard.
+///
+/// # Errors
+///
+/// If none of the clipboard tools of the platform could be run, or all of them failed.
+pub fn paste() -> io::Result<String> {
+    first_success(PASTE, |command| {
+        let output = command.stdin(Stdio::null()).stderr(Stdio::null()).output()?;
+        succeeded(output.status)?;
+        String::from_utf8(output.stdout).map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))
+    })
+}
+
+/// Runs each command until one succeeds, returning the last error otherwise.
+fn first_success<T>(commands: &[&[&str]], mut run: impl FnMut(&mut Command) -> io::Result<T>) -> io::Result<T> {
+    let mut error = io::Error::new(io::ErrorKind::NotFound, "no clipboard tool for this platform");
+    for (program, args) in commands.iter().filter_map(|command| command.split_first()) {
+        match run(Command::new(program).args(args)) {
+            Ok(result) => return Ok(result),
+            Err(failure) => error = io::Error::new(failure.kind(), format!("{program}: {failure}")),
+        }
+    }
+    Err(error)
+}
+
+fn succeeded(status: std::process::ExitStatus) -> io::Result<()> {
+    if status.success() {
+        Ok(())
+    } else {
+        Err(io::Error::new(io::ErrorKind::Other, format!("exited with {status}")))
+    }
+}
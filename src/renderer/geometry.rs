@@ -0,0 +1,300 @@
+use std::ops::Range;
+
+use piston_window::math::{Matrix2d, transform_pos};
+use piston_window::{BACK_END_MAX_VERTEX_COUNT, DrawState, Graphics};
+
+use crate::age::Ages;
+use crate::cell::{Cell, GridDelta, Topology};
+use crate::heatmap::Heatmap;
+use crate::theme::{Color, Theme};
+use crate::trail::Trails;
+
+/// Colored triangles ready to be sent to the GPU.
+///
+/// Building the geometry needs no access to the window, so it can be done in parallel, with one
+/// [`Geometry`] per task joined by [`Geometry::append`]. Drawing sends the vertices in a few large
+/// batches, instead of one draw call for each rectangle.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub(super) struct Geometry {
+    vertices: Vec<[f32; 2]>,
+    colors: Vec<Color>,
+}
+
+impl Geometry {
+    /// Adds a rectangle `[x, y, width, height]`, as two triangles in the coordinates of `transform`.
+    pub fn rectangle(&mut self, color: Color, [x, y, width, height]: [f64; 4], transform: Matrix2d) {
+        let corner = |x, y| transform_pos(transform, [x, y]).map(|coordinate| coordinate as f32);
+        let (top_left, top_right) = (corner(x, y), corner(x + width, y));
+        let (bottom_left, bottom_right) = (corner(x, y + height), corner(x + width, y + height));
+
+        self.vertices.extend([top_left, top_right, bottom_left, top_right, bottom_right, bottom_left]);
+        self.colors.extend([color; 6]);
+    }
+
+    /// Adds a pointy-top hexagon filling the rectangle `[x, y, width, height]`, as four triangles in
+    /// the coordinates of `transform`.
+    pub fn hexagon(&mut self, color: Color, [x, y, width, height]: [f64; 4], transform: Matrix2d) {
+        let corner = |x, y| transform_pos(transform, [x, y]).map(|coordinate| coordinate as f32);
+        let (middle, quarter) = (x + width / 2.0, height / 4.0);
+        let corners = [
+            corner(middle, y),
+            corner(x + width, y + quarter),
+            corner(x + width, y + height - quarter),
+            corner(middle, y + height),
+            corner(x, y + height - quarter),
+            corner(x, y + quarter),
+        ];
+
+        for i in 1..corners.len() - 1 {
+            self.vertices.extend([corners[0], corners[i], corners[i + 1]]);
+        }
+        self.colors.extend([color; 12]);
+    }
+
+    /// Adds the live cells of `row` in a hexagonal grid, like [`Geometry::live_row`], with each cell
+    /// drawn as a hexagon and odd rows shifted half a cell to the right, see
+    /// [`Topology::Hex`](crate::cell::Topology::Hex).
+    ///
+    /// # Panics
+    ///
+    /// If `columns` goes past the end of `cells`.
+    pub fn live_hexagons(&mut self, cells: &[Cell], (row, columns): (usize, Range<usize>), ages: Option<&Ages>, theme: &Theme, cell_size: f64, transform: Matrix2d) {
+        let y = row as f64 * cell_size;
+        let shift = if row % 2 == 0 { 0.0 } else { cell_size / 2.0 };
+
+        for col in columns.filter(|&col| cells[col].is_live()) {
+            let color = ages.map_or(theme.live, |ages| theme.by_age(ages[(row, col)]));
+            self.hexagon(color, [col as f64 * cell_size + shift, y, cell_size, cell_size], transform);
+        }
+    }
+
+    /// Adds the live cells of `cells[columns]`, the visible part of `row`, with horizontal runs of
+    /// the same color merged into a single rectangle.
+    ///
+    /// Without `ages`, every live cell has the `live` color of the theme. Otherwise, see
+    /// [`Theme::by_age`].
+    ///
+    /// # Panics
+    ///
+    /// If `columns` goes past the end of `cells`.
+    pub fn live_row(&mut self, cells: &[Cell], (row, columns): (usize, Range<usize>), ages: Option<&Ages>, theme: &Theme, cell_size: f64, transform: Matrix2d) {
+        let color_of = |col: usize| ages.map_or(theme.live, |ages| theme.by_age(ages[(row, col)]));
+        let y = row as f64 * cell_size;
+
+        let mut col = columns.start;
+        while col < columns.end {
+            if cells[col].is_dead() {
+                col += 1;
+                continue;
+            }
+
+            let (start, color) = (col, color_of(col));
+            col += 1;
+            while col < columns.end && cells[col].is_live() && color_of(col) == color {
+                col += 1;
+            }
+
+            let width = (col - start) as f64 * cell_size;
+            self.rectangle(color, [start as f64 * cell_size, y, width, cell_size], transform);
+        }
+    }
+
+    /// Adds the dead cells of `cells[columns]`, the visible part of `row`, that died recently,
+    /// colored by [`Theme::by_trail`].
+    ///
+    /// # Panics
+    ///
+    /// If `columns` goes past the end of `cells`.
+    pub fn trail_row(&mut self, cells: &[Cell], (row, columns): (usize, Range<usize>), trails: &Trails, theme: &Theme, cell_size: f64, transform: Matrix2d) {
+        let y = row as f64 * cell_size;
+        for (col, color) in trail_colors(cells, (row, columns), trails, theme) {
+            self.rectangle(color, [col as f64 * cell_size, y, cell_size, cell_size], transform);
+        }
+    }
+
+    /// Adds the trails of `row` in a hexagonal grid, like [`Geometry::trail_row`], with hexagons
+    /// placed like in [`Geometry::live_hexagons`].
+    ///
+    /// # Panics
+    ///
+    /// If `columns` goes past the end of `cells`.
+    pub fn trail_hexagons(&mut self, cells: &[Cell], (row, columns): (usize, Range<usize>), trails: &Trails, theme: &Theme, cell_size: f64, transform: Matrix2d) {
+        let y = row as f64 * cell_size;
+        let shift = if row % 2 == 0 { 0.0 } else { cell_size / 2.0 };
+        for (col, color) in trail_colors(cells, (row, columns), trails, theme) {
+            self.hexagon(color, [col as f64 * cell_size + shift, y, cell_size, cell_size], transform);
+        }
+    }
+
+    /// Adds the cells of `columns`, the visible part of `row`, colored by their activity as in
+    /// [`Theme::by_activity`], dead or alive. Cells that were never alive are left out.
+    pub fn heat_row(&mut self, activity: &Heatmap, (row, columns): (usize, Range<usize>), theme: &Theme, cell_size: f64, transform: Matrix2d) {
+        let y = row as f64 * cell_size;
+        for col in columns {
+            if let Some(color) = theme.by_activity(activity.fraction(row, col)) {
+                self.rectangle(color, [col as f64 * cell_size, y, cell_size, cell_size], transform);
+            }
+        }
+    }
+
+    /// Adds the cells born and the cells that died in `delta` inside `rows` and `columns`, in the
+    /// [`changes`](Theme::changes) colors of the theme, as hexagons in a [`Topology::Hex`] grid.
+    pub fn changes(&mut self, delta: &GridDelta, (rows, columns): (Range<usize>, Range<usize>), topology: Topology, theme: &Theme, cell_size: f64, transform: Matrix2d) {
+        let [born, died] = theme.changes;
+        let births = delta.births().iter().map(|&position| (position, born));
+        let deaths = delta.deaths().iter().map(|&position| (position, died));
+
+        for ((row, col), color) in births.chain(deaths).filter(|((row, col), _)| rows.contains(row) && columns.contains(col)) {
+            let (x, y) = (col as f64 * cell_size, row as f64 * cell_size);
+            if topology == Topology::Hex {
+                let shift = if row % 2 == 0 { 0.0 } else { cell_size / 2.0 };
+                self.hexagon(color, [x + shift, y, cell_size, cell_size], transform);
+            } else {
+                self.rectangle(color, [x, y, cell_size, cell_size], transform);
+            }
+        }
+    }
+
+    /// Adds the borders around the cells of `rows` and `columns`, as lines `width` wide.
+    ///
+    /// Each line is a single thin rectangle across all the cells, so the cost only grows with the
+    /// number of rows and columns, not with the number of cells.
+    pub fn grid_lines(&mut self, color: Color, (rows, columns): (Range<usize>, Range<usize>), cell_size: f64, width: f64, transform: Matrix2d) {
+        if rows.is_empty() || columns.is_empty() {
+            return;
+        }
+        let [left, top] = [columns.start, rows.start].map(|start| start as f64 * cell_size);
+        let [length, height] = [columns.len(), rows.len()].map(|cells| cells as f64 * cell_size);
+
+        for row in rows.start..=rows.end {
+            self.rectangle(color, [left, row as f64 * cell_size - width / 2.0, length, width], transform);
+        }
+        for col in columns.start..=columns.end {
+            self.rectangle(color, [col as f64 * cell_size - width / 2.0, top, width, height], transform);
+        }
+    }
+
+    #[must_use]
+    #[cfg(feature = "parallel")]
+    /// Joins two geometries, drawing the other after this one.
+    pub fn append(mut self, mut other: Self) -> Self {
+        self.vertices.append(&mut other.vertices);
+        self.colors.append(&mut other.colors);
+        self
+    }
+
+    /// Draws all triangles.
+    pub fn draw<G: Graphics>(&self, draw_state: &DrawState, graphics: &mut G) {
+        graphics.tri_list_c(draw_state, |send| {
+            let vertices = self.vertices.chunks(BACK_END_MAX_VERTEX_COUNT);
+            let colors = self.colors.chunks(BACK_END_MAX_VERTEX_COUNT);
+            for (vertices, colors) in vertices.zip(colors) {
+                send(vertices, colors);
+            }
+        });
+    }
+}
+
+/// The columns of the dead cells in `cells[columns]` with a trail, and their colors.
+fn trail_colors<'a>(cells: &'a [Cell], (row, columns): (usize, Range<usize>), trails: &'a Trails, theme: &'a Theme) -> impl Iterator<Item = (usize, Color)> + 'a {
+    columns
+        .filter(|&col| cells[col].is_dead())
+        .filter_map(move |col| theme.by_trail(trails.fade(row, col)).map(|color| (col, color)))
+}
+
+#[cfg(test)]
+mod tests {
+    use piston_window::math::identity;
+
+    use crate::cell::Cell::{Dead as D, Live as L};
+    use crate::cell::Grid;
+
+    use super::*;
+
+    #[test]
+    pub fn merged_runs() {
+        let theme = Theme::default();
+        let cells = [L, L, D, L, L, L, D, D, L];
+
+        let mut geometry = Geometry::default();
+        geometry.live_row(&cells, (0, 0..cells.len()), None, &theme, 1.0, identity());
+        assert_eq!(geometry.vertices.len(), 3 * 6);
+        assert_eq!(geometry.vertices[6..12], [[3.0, 0.0], [6.0, 0.0], [3.0, 1.0], [6.0, 0.0], [6.0, 1.0], [3.0, 1.0]]);
+
+        // only the visible columns, cutting the runs at the edges
+        let mut geometry = Geometry::default();
+        geometry.live_row(&cells, (0, 1..5), None, &theme, 2.0, identity());
+        assert_eq!(geometry.vertices.len(), 2 * 6);
+        assert_eq!(geometry.vertices[0], [2.0, 0.0]);
+        assert_eq!(geometry.vertices[10], [10.0, 2.0]);
+
+        // cells with different ages have different colors
+        let mut grid = Grid::try_from([cells]).unwrap();
+        let mut ages = Ages::new(&grid);
+        grid[(0, 4)] = D;
+        ages.update(&grid);
+        grid[(0, 4)] = L;
+        ages.update(&grid);
+
+        let mut geometry = Geometry::default();
+        geometry.live_row(&grid[0], (0, 0..cells.len()), Some(&ages), &theme, 1.0, identity());
+        assert_eq!(geometry.vertices.len(), 5 * 6);
+        assert_eq!(geometry.colors.len(), geometry.vertices.len());
+    }
+
+    #[test]
+    pub fn shifted_hexagons() {
+        let theme = Theme::default();
+        let cells = [L, D, L];
+
+        let mut geometry = Geometry::default();
+        geometry.live_hexagons(&cells, (0, 0..3), None, &theme, 4.0, identity());
+        assert_eq!(geometry.vertices.len(), 2 * 12);
+        assert_eq!(geometry.vertices[..3], [[2.0, 0.0], [4.0, 1.0], [4.0, 3.0]]);
+
+        // odd rows start half a cell to the right
+        let mut geometry = Geometry::default();
+        geometry.live_hexagons(&cells, (1, 1..3), None, &theme, 4.0, identity());
+        assert_eq!(geometry.vertices.len(), 12);
+        assert_eq!(geometry.vertices[0], [12.0, 4.0]);
+        assert_eq!(geometry.colors.len(), geometry.vertices.len());
+    }
+
+    #[test]
+    pub fn visible_births_and_deaths() {
+        let theme = Theme::default();
+        let mut horizontal = Grid::new(5, 5);
+        horizontal[2][1..4].fill(L);
+        let mut vertical = Grid::new(5, 5);
+        (1..4).for_each(|row| vertical[(row, 2)] = L);
+        let delta = horizontal.diff(&vertical);
+
+        // the birth in row 3 is out of view
+        let mut geometry = Geometry::default();
+        geometry.changes(&delta, (0..3, 0..5), Topology::Moore, &theme, 2.0, identity());
+        assert_eq!(geometry.vertices.len(), 3 * 6);
+        assert_eq!(geometry.vertices[0], [4.0, 2.0]);
+        assert_eq!((geometry.colors[0], geometry.colors[6]), (theme.changes[0], theme.changes[1]));
+
+        let mut geometry = Geometry::default();
+        geometry.changes(&delta, (0..3, 0..5), Topology::Hex, &theme, 2.0, identity());
+        assert_eq!(geometry.vertices.len(), 3 * 12);
+        assert_eq!(geometry.vertices[0], [6.0, 2.0]);
+    }
+
+    #[test]
+    pub fn one_rectangle_per_line() {
+        let color = Theme::default().live;
+
+        let mut geometry = Geometry::default();
+        geometry.grid_lines(color, (2..5, 10..100), 4.0, 0.5, identity());
+        // 4 horizontal and 91 vertical lines, however many cells are between them
+        assert_eq!(geometry.vertices.len(), (4 + 91) * 6);
+        assert_eq!(geometry.vertices[..3], [[40.0, 7.75], [400.0, 7.75], [40.0, 8.25]]);
+        assert_eq!(geometry.vertices[4 * 6..4 * 6 + 3], [[39.75, 8.0], [40.25, 8.0], [39.75, 20.0]]);
+
+        let mut geometry = Geometry::default();
+        geometry.grid_lines(color, (0..0, 0..10), 4.0, 0.5, identity());
+        assert!(geometry.vertices.is_empty());
+    }
+}
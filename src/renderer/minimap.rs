@@ -0,0 +1,118 @@
+use piston_window::{G2dTexture, GfxDevice, PistonWindow};
+
+use crate::theme::Theme;
+
+use super::raster::Density;
+use super::Frame;
+
+/// Largest side of the minimap, in pixels.
+pub(super) const MINIMAP_SIZE: f64 = 160.0;
+
+/// Space between the minimap and the edges of the window, in pixels.
+const MINIMAP_MARGIN: f64 = 8.0;
+
+/// The whole board shrunk into a corner of the window, as a [`Density`] texture.
+///
+/// The texture is only computed again when the [revision](Frame::revision) of the grid changes.
+pub(super) struct Minimap {
+    density: Density,
+    /// Where the minimap was last drawn, as `[x, y, width, height]` in the window, if it was.
+    area: Option<[f64; 4]>,
+}
+
+impl Minimap {
+    #[must_use]
+    pub fn new(window: &mut PistonWindow) -> Self {
+        Self { density: Density::new(window), area: None }
+    }
+
+    /// Downsamples the grid of `frame` into the texture, with up to `dpi` pixels of the texture
+    /// for each pixel of `area`, unless it was already done for this revision.
+    ///
+    /// Fails if the texture can't be created.
+    pub fn update(&mut self, frame: &Frame<'_>, theme: &Theme, [.., width, height]: [f64; 4], dpi: f64) -> Result<(), String> {
+        let (rows, columns) = frame.grid.shape();
+        let shape = (rows.min((height * dpi).ceil() as usize).max(1), columns.min((width * dpi).ceil() as usize).max(1));
+        self.density.update(frame, (0..rows, 0..columns), shape, theme)
+    }
+
+    #[inline]
+    #[must_use]
+    /// The texture from the last successful [`update`](Minimap::update).
+    pub const fn texture(&self) -> Option<&G2dTexture> {
+        self.density.texture()
+    }
+
+    /// Sends the pending texture uploads to the GPU, before drawing.
+    pub fn flush(&mut self, device: &mut GfxDevice) {
+        self.density.flush(device);
+    }
+
+    #[inline]
+    #[must_use]
+    /// Where the minimap was last drawn, if it was shown.
+    pub const fn area(&self) -> Option<[f64; 4]> {
+        self.area
+    }
+
+    /// Records where the minimap is drawn, or that it is hidden.
+    pub fn show_at(&mut self, area: Option<[f64; 4]>) {
+        self.area = area;
+    }
+}
+
+#[must_use]
+/// The `[x, y, width, height]` of the minimap of a `board` rectangle, with the same proportions, in
+/// the top-right corner of a window of `[width, height]`, or [`None`] if the window is too small
+/// for it.
+pub(super) fn layout([.., width, height]: [f64; 4], window: [f64; 2]) -> Option<[f64; 4]> {
+    if width <= 0.0 || height <= 0.0 || window.iter().any(|&side| side < 2.0 * (MINIMAP_SIZE + MINIMAP_MARGIN)) {
+        return None;
+    }
+    let scale = MINIMAP_SIZE / width.max(height);
+    let size = [width * scale, height * scale];
+    Some([window[0] - size[0] - MINIMAP_MARGIN, MINIMAP_MARGIN, size[0], size[1]])
+}
+
+#[must_use]
+/// The point of a `board` rectangle shown at a window `point` of a minimap drawn in `area`, or
+/// [`None`] if the point is outside of the minimap.
+pub(super) fn to_board(area: [f64; 4], board: [f64; 4], point: [f64; 2]) -> Option<[f64; 2]> {
+    let fraction = [0, 1].map(|i| (point[i] - area[i]) / area[i + 2]);
+    fraction.iter().all(|fraction| (0.0..=1.0).contains(fraction))
+        .then(|| [0, 1].map(|i| board[i] + fraction[i] * board[i + 2]))
+}
+
+#[must_use]
+/// The rectangle `[x, y, width, height]` of a minimap drawn in `area` that shows the `visible`
+/// rectangle of a `board`, clipped to the minimap.
+pub(super) fn from_board(area: [f64; 4], board: [f64; 4], visible: [f64; 4]) -> [f64; 4] {
+    let point = |i: usize, coordinate: f64| (area[i] + (coordinate - board[i]) / board[i + 2] * area[i + 2]).clamp(area[i], area[i] + area[i + 2]);
+    let [left, top] = [0, 1].map(|i| point(i, visible[i]));
+    let [right, bottom] = [0, 1].map(|i| point(i, visible[i] + visible[i + 2]));
+    [left, top, right - left, bottom - top]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn corner_of_the_window() {
+        let board = [-40.0, 0.0, 400.0, 200.0];
+        let area = layout(board, [800.0, 600.0]).unwrap();
+        assert_eq!(area, [800.0 - MINIMAP_SIZE - MINIMAP_MARGIN, MINIMAP_MARGIN, MINIMAP_SIZE, MINIMAP_SIZE / 2.0]);
+        assert_eq!(layout(board, [300.0, 600.0]), None);
+        assert_eq!(layout([0.0; 4], [800.0, 600.0]), None);
+
+        // the center of the minimap is the center of the board, and back
+        let center = [area[0] + area[2] / 2.0, area[1] + area[3] / 2.0];
+        assert_eq!(to_board(area, board, center), Some([160.0, 100.0]));
+        assert_eq!(to_board(area, board, [area[0] - 1.0, center[1]]), None);
+
+        assert_eq!(from_board(area, board, [160.0, 100.0, 200.0, 100.0]), [center[0], center[1], 80.0, 40.0]);
+        // clipped to the minimap
+        let visible = from_board(area, board, [-100.0, -100.0, 1000.0, 150.0]);
+        assert_eq!(visible, [area[0], area[1], area[2], 20.0]);
+    }
+}
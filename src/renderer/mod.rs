@@ -0,0 +1,681 @@
+//! Frontends that show a running simulation.
+//!
+//! A [`Renderer`] only draws frames and reports user input, while a [`Player`] owns the
+//! [`Simulation`] and decides when to advance it, so every frontend shares the same controls.
+
+use std::path::PathBuf;
+use std::time::Duration;
+
+use crate::age::Ages;
+use crate::cell::{Grid, GridDelta, Topology};
+use crate::edit::{Edit, EditHistory};
+use crate::engine::Engine;
+use crate::error::Result;
+use crate::heatmap::Heatmap;
+use crate::io::rle;
+use crate::rule::Rule;
+use crate::session::{Session, View};
+use crate::simulation::{Simulation, GROWTH};
+use crate::trail::Trails;
+
+/// Slowest update interval reachable with [`Input::Slower`], and with `--fps`.
+pub const MAX_UPDATE_INTERVAL: Duration = Duration::from_secs(10);
+
+mod clipboard;
+#[cfg(feature = "render")]
+mod geometry;
+#[cfg(feature = "render")]
+mod minimap;
+#[cfg(feature = "render")]
+mod raster;
+#[cfg(feature = "render")]
+mod recorder;
+mod terminal;
+#[cfg(feature = "render")]
+mod window;
+mod worker;
+
+#[cfg(feature = "render")]
+pub use recorder::FrameRecorder;
+pub use terminal::TerminalRenderer;
+#[cfg(feature = "render")]
+pub use window::{CompareRenderer, GameRenderer, LayersRenderer, StatesRenderer, WindowConfig, WindowRenderer};
+#[cfg(all(feature = "render", feature = "continuous"))]
+pub use window::FieldRenderer;
+pub use worker::Snapshot;
+
+use worker::{Command, Worker};
+
+/// Something that happened in a frontend, as reported by [`Renderer::handle_input`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Input {
+    /// Time to draw the next frame.
+    Frame,
+    /// The user closed the frontend.
+    Quit,
+    /// Pause or resume the simulation.
+    Pause,
+    /// Advance one generation, while paused.
+    Step,
+    /// Go back one generation, while paused, if the simulation
+    /// [keeps them](Simulation::with_rewind).
+    StepBack,
+    /// Replace the board with a random one, starting again from generation 0.
+    Randomize,
+    /// Halve the interval between generations.
+    Faster,
+    /// Double the interval between generations.
+    Slower,
+    /// Save the session file.
+    Save,
+    /// Load the session file.
+    Load,
+    /// Add dead cells past every edge of the board, for patterns that approach them.
+    Grow,
+    /// Show or hide the activity heatmap, if the simulation tracks it.
+    Heatmap,
+    /// Show or hide the cells born and dead in the last generation, if the simulation tracks them.
+    Changes,
+    /// Copy the cells from `(top, left)` to `(bottom, right)`, inclusive, to the clipboard as RLE.
+    Copy((usize, usize), (usize, usize)),
+    /// Paste the RLE pattern in the clipboard, with its top-left corner at `(row, col)`.
+    Paste((usize, usize)),
+    /// Make a live cell dead, or a dead cell live, at `(row, col)`.
+    Toggle((usize, usize)),
+    /// Kill the cells from `(top, left)` to `(bottom, right)`, inclusive.
+    Clear((usize, usize), (usize, usize)),
+    /// Revert the last edit, while paused.
+    Undo,
+    /// Make the last undone edit again, while paused.
+    Redo,
+}
+
+/// What is shown on each frame.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Frame<'a> {
+    pub grid: &'a Grid,
+    pub generation: usize,
+    /// Name of the engine computing the generations, see [`Engine::name`].
+    pub engine: &'a str,
+    /// Time the engine took to compute the last generation, see [`Snapshot::step_time`].
+    pub step_time: Duration,
+    /// Positions `(row, col)` of the agents of the engine, see [`Engine::agents`].
+    pub agents: &'a [(usize, usize)],
+    /// Age of each cell, if tracked by the simulation, see [`Simulation::track_ages`].
+    pub ages: Option<&'a Ages>,
+    /// How often each cell has been alive, while the heatmap is shown with [`Input::Heatmap`].
+    /// Cells are then colored by [`Theme::by_activity`](crate::theme::Theme::by_activity) instead
+    /// of their state.
+    pub activity: Option<&'a Heatmap>,
+    /// Where cells died recently, if tracked by the simulation, see [`Simulation::track_trails`].
+    /// Dead cells with a trail are colored by [`Theme::by_trail`](crate::theme::Theme::by_trail).
+    pub trails: Option<&'a Trails>,
+    /// Cells born and dead in the last generation, while shown with [`Input::Changes`]. They are
+    /// then colored by [`Theme::changes`](crate::theme::Theme::changes), over their state.
+    pub changes: Option<&'a GridDelta>,
+    pub paused: bool,
+    /// Current interval between generations.
+    pub update_interval: Duration,
+    /// Changes whenever the grid changes, so frontends can keep what they computed from a grid
+    /// until the next revision.
+    pub revision: u64,
+    /// Position of the initial grid in an expanding board, see [`Simulation::origin`].
+    pub origin: (usize, usize),
+    /// How the cells are arranged, so hexagonal grids are drawn with hexagons.
+    pub topology: Topology,
+}
+
+/// A frontend that can show a [`Simulation`].
+pub trait Renderer {
+    /// Prepares the frontend to show grids like `grid`, before the first frame.
+    fn init(&mut self, grid: &Grid) -> Result<()>;
+
+    /// Draws a frame.
+    fn draw(&mut self, frame: &Frame<'_>) -> Result<()>;
+
+    /// Waits for the next input, which is [`Input::Frame`] when there is nothing else to do.
+    ///
+    /// This is what paces the [`Player`], so frontends should block here until the next frame is
+    /// due.
+    fn handle_input(&mut self) -> Input;
+
+    #[must_use]
+    /// Size of each cell on screen, saved in sessions.
+    fn cell_size(&self) -> f64 {
+        1.0
+    }
+
+    /// Called when a grid with a different shape or cell size is loaded from a session.
+    fn resize(&mut self, _shape: (usize, usize), _cell_size: f64) {}
+
+    #[must_use]
+    /// The part of the board in view, saved in sessions, if the frontend can be zoomed and moved.
+    fn view(&self) -> Option<View> {
+        None
+    }
+
+    /// Shows the part of the board saved in a session, after [`resize`](Renderer::resize).
+    fn set_view(&mut self, _view: View) {}
+
+    #[must_use]
+    /// Whether the [`Player`] starts paused, for frontends that only advance with [`Input::Step`].
+    fn starts_paused(&self) -> bool {
+        false
+    }
+}
+
+impl<R: Renderer + ?Sized> Renderer for &mut R {
+    #[inline]
+    fn init(&mut self, grid: &Grid) -> Result<()> {
+        (**self).init(grid)
+    }
+
+    #[inline]
+    fn draw(&mut self, frame: &Frame<'_>) -> Result<()> {
+        (**self).draw(frame)
+    }
+
+    #[inline]
+    fn handle_input(&mut self) -> Input {
+        (**self).handle_input()
+    }
+
+    #[inline]
+    fn cell_size(&self) -> f64 {
+        (**self).cell_size()
+    }
+
+    #[inline]
+    fn resize(&mut self, shape: (usize, usize), cell_size: f64) {
+        (**self).resize(shape, cell_size);
+    }
+
+    #[inline]
+    fn view(&self) -> Option<View> {
+        (**self).view()
+    }
+
+    #[inline]
+    fn set_view(&mut self, view: View) {
+        (**self).set_view(view);
+    }
+
+    #[inline]
+    fn starts_paused(&self) -> bool {
+        (**self).starts_paused()
+    }
+}
+
+/// Runs a [`Simulation`] on a [`Renderer`].
+///
+/// The generations are computed by a worker thread, so the frontend keeps responding while a slow
+/// engine works. Each frame shows the latest [`Snapshot`] published by the worker, while the other
+/// inputs are sent to it and wait until they are applied.
+///
+/// # Example
+///
+/// ```no_run
+/// # use std::time::Duration;
+/// # use vida::cell::Grid;
+/// # use vida::engine::SerialEngine;
+/// # use vida::simulation::Simulation;
+/// # #[cfg(feature = "render")] {
+/// # use vida::renderer::{Player, WindowConfig, WindowRenderer};
+/// #
+/// let config = WindowConfig::new("Gliders").resizable(false).samples(4).position(100, 50);
+/// let simulation = Simulation::new(SerialEngine::default(), Grid::new(100, 100));
+/// let player = Player::new(WindowRenderer::with_window(config, 2.0), simulation, Duration::from_millis(50));
+/// let simulation = player.run().unwrap();
+/// # }
+/// ```
+pub struct Player<E, R> {
+    renderer: R,
+    /// The simulation, until the worker is started.
+    simulation: Option<Simulation<E>>,
+    worker: Option<Worker<E>>,
+    /// What is shown on the next frame.
+    snapshot: Snapshot,
+    engine: &'static str,
+    /// The rule of the engine, written in copied patterns.
+    rule: Option<Rule>,
+    update_interval: Duration,
+    paused: bool,
+    /// Whether the activity heatmap is shown.
+    heatmap: bool,
+    /// Whether births and deaths are shown.
+    changes: bool,
+    /// The toggles, pastes and clears made on the board, and the revision of the snapshot they
+    /// left, after which any other change makes them stale.
+    history: EditHistory,
+    edited: u64,
+    session_file: Option<PathBuf>,
+}
+
+impl<E: Engine + Send + 'static, R: Renderer> Player<E, R> {
+    #[must_use]
+    pub fn new(renderer: R, simulation: Simulation<E>, update_interval: Duration) -> Self {
+        Self {
+            paused: renderer.starts_paused(),
+            renderer,
+            snapshot: Snapshot::of(&simulation, 0),
+            engine: simulation.engine().name(),
+            rule: simulation.engine().life_rule(),
+            simulation: Some(simulation),
+            worker: None,
+            update_interval,
+            heatmap: false,
+            changes: false,
+            history: EditHistory::default(),
+            edited: 0,
+            session_file: None,
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    /// The state shown on the last frame, or the initial state before starting.
+    pub const fn snapshot(&self) -> &Snapshot {
+        &self.snapshot
+    }
+
+    #[inline]
+    #[must_use]
+    /// The interval between generations, which can be changed with [`Input::Faster`] and
+    /// [`Input::Slower`].
+    pub const fn update_interval(&self) -> Duration {
+        self.update_interval
+    }
+
+    #[inline]
+    #[must_use]
+    /// The frontend drawing the simulation.
+    pub const fn renderer(&self) -> &R {
+        &self.renderer
+    }
+
+    /// Sets the file used by [`Input::Save`] and [`Input::Load`].
+    pub fn set_session_file(&mut self, path: impl Into<PathBuf>) {
+        self.session_file = Some(path.into());
+    }
+
+    #[must_use]
+    /// Captures the state of the exploration shown on the last frame.
+    pub fn session(&self) -> Session {
+        Session {
+            grid: Grid::clone(&self.snapshot.grid),
+            engine: self.engine.to_owned(),
+            generation: self.snapshot.generation,
+            cell_size: self.renderer.cell_size(),
+            update_interval: self.update_interval,
+            rule: self.rule,
+            seed: self.snapshot.seed,
+            noise: self.snapshot.noise.clone(),
+            cycle_window: self.snapshot.cycle_window,
+            view: self.renderer.view(),
+        }
+    }
+
+    /// Continues from a saved session.
+    ///
+    /// The engine and its rule are kept, even if the session was saved with different ones.
+    pub fn restore(&mut self, session: Session) {
+        if session.rule.is_some() && session.rule != self.rule {
+            eprintln!("the session was saved with another rule, keeping the current one");
+        }
+        self.renderer.resize(session.grid.shape(), session.cell_size);
+        if let Some(view) = session.view {
+            self.renderer.set_view(view);
+        }
+        self.update_interval = session.update_interval;
+        self.send(Command::UpdateInterval(session.update_interval));
+        self.send(Command::Restore(Box::new(session)));
+    }
+
+    /// Applies a command to the simulation, waiting for the worker if it is running.
+    fn send(&mut self, command: Command) {
+        if let Some(worker) = &mut self.worker {
+            if let Some(snapshot) = worker.send(command) {
+                self.snapshot = snapshot;
+            }
+            return;
+        }
+
+        let Some(simulation) = &mut self.simulation else {
+            return;
+        };
+        let revision = self.snapshot.revision + 1;
+        match command {
+            Command::Restore(session) => simulation.restore(*session),
+            Command::Randomize => simulation.randomize(),
+            Command::Grow(margin) => simulation.grow(margin),
+            Command::Paste(pattern, position) => simulation.paste(&pattern, position),
+            Command::Step if self.paused && !simulation.is_finished() => simulation.step(),
+            Command::StepBack if self.paused => {
+                if !simulation.step_back() {
+                    return;
+                }
+            },
+            Command::Pause(_) | Command::Step | Command::StepBack | Command::UpdateInterval(_) => return,
+        }
+        self.snapshot = Snapshot::of(simulation, revision);
+    }
+
+    fn save_session(&self) {
+        let Some(path) = &self.session_file else {
+            return eprintln!("no session file to save to");
+        };
+
+        match self.session().save(path) {
+            Ok(()) => println!("session saved to {}", path.display()),
+            Err(error) => eprintln!("could not save session to {}: {error}", path.display()),
+        }
+    }
+
+    fn load_session(&mut self) {
+        let Some(path) = &self.session_file else {
+            return eprintln!("no session file to load from");
+        };
+
+        match Session::load(path) {
+            Ok(session) => {
+                if session.engine != self.engine {
+                    eprintln!("session was saved with the {} engine, continuing with {}", session.engine, self.engine);
+                }
+                println!("session loaded from {}", path.display());
+                self.restore(session);
+            },
+            Err(error) => eprintln!("could not load session from {}: {error}", path.display()),
+        }
+    }
+
+    /// Copies the cells from `top_left` to `bottom_right` of the last frame to the clipboard.
+    fn copy(&self, (top, left): (usize, usize), (bottom, right): (usize, usize)) {
+        let (rows, columns) = self.snapshot.grid.shape();
+        if top > bottom || left > right || bottom >= rows || right >= columns {
+            return eprintln!("the selection is not inside the board, nothing to copy");
+        }
+
+        let region = self.snapshot.grid.crop(top..=bottom, left..=right);
+        let rule = self.rule.as_ref().map(Rule::to_string);
+        match clipboard::copy(&rle::encode(&region, rule.as_deref())) {
+            Ok(()) => println!("copied {} by {} cells to the clipboard", bottom - top + 1, right - left + 1),
+            Err(error) => eprintln!("could not copy to the clipboard: {error}"),
+        }
+    }
+
+    /// Pastes the pattern in the clipboard at `position`.
+    fn paste(&mut self, position: (usize, usize)) {
+        let text = match clipboard::paste() {
+            Ok(text) => text,
+            Err(error) => return eprintln!("could not read the clipboard: {error}"),
+        };
+        match rle::parse(&text) {
+            Ok(pattern) => self.edit(pattern.grid, position),
+            Err(error) => eprintln!("the clipboard doesn't have an RLE pattern: {error}"),
+        }
+    }
+
+    /// Replaces the cells under `pattern` at `position`, so it can be undone.
+    fn edit(&mut self, pattern: Grid, position: (usize, usize)) {
+        if self.snapshot.revision != self.edited {
+            self.history.clear();
+        }
+        let Some(edit) = Edit::new(&self.snapshot.grid, pattern, position) else {
+            return;
+        };
+        self.send(Command::Paste(edit.after().clone(), edit.position()));
+        self.history.push(edit);
+        self.edited = self.snapshot.revision;
+    }
+
+    /// Reverts the last edit, or makes the last undone one again.
+    fn undo(&mut self, input: Input) {
+        if !self.paused {
+            return eprintln!("pause the simulation to undo or redo edits");
+        }
+        if self.snapshot.revision != self.edited {
+            // the board changed since, so the edits no longer apply to it
+            self.history.clear();
+        }
+
+        let change = if input == Input::Undo {
+            self.history.undo().map(|edit| (edit.before().clone(), edit.position()))
+        } else {
+            self.history.redo().map(|edit| (edit.after().clone(), edit.position()))
+        };
+        let Some((cells, position)) = change else {
+            return eprintln!("nothing to {}", if input == Input::Undo { "undo" } else { "redo" });
+        };
+        self.send(Command::Paste(cells, position));
+        self.edited = self.snapshot.revision;
+    }
+
+    fn change_speed(&mut self, input: Input) {
+        self.update_interval = match input {
+            Input::Faster => self.update_interval / 2,
+            _ => (self.update_interval * 2).clamp(Duration::from_millis(1), MAX_UPDATE_INTERVAL.max(self.update_interval)),
+        };
+        self.send(Command::UpdateInterval(self.update_interval));
+    }
+
+    fn draw(&mut self) -> Result<()> {
+        self.renderer.draw(&Frame {
+            grid: &self.snapshot.grid,
+            generation: self.snapshot.generation,
+            engine: self.engine,
+            step_time: self.snapshot.step_time,
+            agents: &self.snapshot.agents,
+            ages: self.snapshot.ages.as_deref(),
+            activity: self.snapshot.activity.as_deref().filter(|_| self.heatmap),
+            trails: self.snapshot.trails.as_deref(),
+            changes: self.snapshot.changes.as_deref().filter(|_| self.changes),
+            paused: self.paused,
+            update_interval: self.update_interval,
+            revision: self.snapshot.revision,
+            origin: self.snapshot.origin,
+            topology: self.snapshot.topology,
+        })
+    }
+
+    /// Handles the next input, returning `false` when the frontend is closed.
+    fn next_input(&mut self) -> Result<bool> {
+        let input = self.renderer.handle_input();
+        match input {
+            Input::Frame => {
+                if let Some(snapshot) = self.worker.as_ref().and_then(Worker::latest) {
+                    self.snapshot = snapshot;
+                }
+                self.draw()?;
+            },
+            Input::Quit => return Ok(false),
+            Input::Pause => {
+                self.paused = !self.paused;
+                self.send(Command::Pause(self.paused));
+            },
+            Input::Step => self.send(Command::Step),
+            Input::StepBack => self.send(Command::StepBack),
+            Input::Randomize => self.send(Command::Randomize),
+            Input::Faster | Input::Slower => self.change_speed(input),
+            Input::Save => self.save_session(),
+            Input::Load => self.load_session(),
+            Input::Grow => self.send(Command::Grow(GROWTH)),
+            Input::Copy(top_left, bottom_right) => self.copy(top_left, bottom_right),
+            Input::Paste(position) => self.paste(position),
+            Input::Toggle((row, col)) => {
+                if let Some(mut cell) = self.snapshot.grid.get_cell(row, col).copied() {
+                    cell.toggle();
+                    self.edit(Grid::new_with(1, 1, cell), (row, col));
+                }
+            },
+            Input::Clear((top, left), (bottom, right)) => {
+                let shape = (bottom.saturating_sub(top) + 1, right.saturating_sub(left) + 1);
+                self.edit(Grid::new(shape.0, shape.1), (top, left));
+            },
+            Input::Undo | Input::Redo => self.undo(input),
+            Input::Heatmap => {
+                if self.snapshot.activity.is_some() {
+                    self.heatmap = !self.heatmap;
+                } else {
+                    eprintln!("the simulation doesn't track the activity of the cells, no heatmap to show");
+                }
+            },
+            Input::Changes => {
+                if self.snapshot.changes.is_some() {
+                    self.changes = !self.changes;
+                } else {
+                    eprintln!("the simulation doesn't track the changes of the cells, no births and deaths to show");
+                }
+            },
+        }
+        Ok(true)
+    }
+
+    /// Opens the frontend and starts the worker.
+    fn init(&mut self) -> Result<()> {
+        self.renderer.init(&self.snapshot.grid)?;
+        if let Some(simulation) = self.simulation.take() {
+            self.worker = Some(Worker::spawn(simulation, self.update_interval, self.paused, self.snapshot.revision));
+        }
+        Ok(())
+    }
+
+    /// Stops the worker, returning the simulation.
+    fn stop(mut self) -> Simulation<E> {
+        match (self.worker.take(), self.simulation.take()) {
+            (Some(worker), _) => worker.stop(),
+            (None, Some(simulation)) => simulation,
+            (None, None) => unreachable!("the simulation is either in the player or in the worker"),
+        }
+    }
+
+    /// Runs until the frontend is closed, even after the simulation stops.
+    pub fn start(mut self) -> Result<()> {
+        self.init()?;
+        while self.next_input()? { }
+        self.stop();
+        Ok(())
+    }
+
+    /// Runs until the frontend is closed or the simulation stops, returning the simulation.
+    pub fn run(mut self) -> Result<Simulation<E>> {
+        self.init()?;
+        while self.snapshot.stop_reason.is_none() && self.next_input()? { }
+        if self.snapshot.stop_reason.is_some() {
+            // the last generation is shown too
+            self.draw()?;
+        }
+        Ok(self.stop())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::VecDeque;
+
+    use crate::cell::Cell;
+    use crate::engine::SerialEngine;
+    use crate::rewind::Rewind;
+
+    use super::*;
+
+    /// Replays a list of inputs, then repeats `then`.
+    struct Script {
+        inputs: VecDeque<Input>,
+        then: Input,
+        frames: Vec<(usize, bool)>,
+        revisions: Vec<u64>,
+    }
+
+    impl Script {
+        fn new(inputs: impl Into<VecDeque<Input>>, then: Input) -> Self {
+            Self { inputs: inputs.into(), then, frames: Vec::new(), revisions: Vec::new() }
+        }
+    }
+
+    impl Renderer for Script {
+        fn init(&mut self, _grid: &Grid) -> Result<()> {
+            Ok(())
+        }
+
+        fn draw(&mut self, frame: &Frame<'_>) -> Result<()> {
+            self.frames.push((frame.generation, frame.paused));
+            self.revisions.push(frame.revision);
+            Ok(())
+        }
+
+        fn handle_input(&mut self) -> Input {
+            self.inputs.pop_front().unwrap_or(self.then)
+        }
+
+        fn starts_paused(&self) -> bool {
+            // so no generation runs before the first input
+            self.then == Input::Quit
+        }
+    }
+
+    #[test]
+    pub fn pause_and_step() {
+        use Input::*;
+
+        // too slow for any update while the test runs
+        let interval = Duration::from_secs(8);
+        let inputs = [Frame, Step, Frame, Pause, Frame, Randomize, Frame, Faster, Faster, Slower, Frame];
+        let mut script = Script::new(inputs, Quit);
+        let simulation = Simulation::new(SerialEngine::default(), Grid::random_seeded(10, 10, 528));
+
+        let player = Player::new(&mut script, simulation, interval);
+        let simulation = player.run().unwrap();
+        assert_eq!(simulation.generation(), 0);
+
+        assert_eq!(script.frames, [(0, true), (1, true), (1, false), (0, false), (0, false)]);
+        assert_eq!(script.revisions, [0, 1, 1, 2, 2]);
+    }
+
+    #[test]
+    pub fn undo_and_redo_edits() {
+        use Input::*;
+
+        let inputs = [
+            Toggle((2, 1)), Toggle((2, 2)), Toggle((2, 3)), Clear((0, 0), (4, 4)),
+            Undo, Redo, Undo, Undo, Redo, Toggle((9, 9)), Frame,
+            // the step makes the edits stale
+            Step, Undo, Frame,
+        ];
+        let mut script = Script::new(inputs, Quit);
+        let simulation = Simulation::new(SerialEngine::default(), Grid::new(5, 5));
+
+        let player = Player::new(&mut script, simulation, Duration::from_secs(8));
+        let simulation = player.run().unwrap();
+
+        let mut blinker = Grid::new(5, 5);
+        for row in 1..4 {
+            blinker[(row, 2)] = Cell::Live;
+        }
+        assert_eq!(*simulation.grid(), blinker);
+        assert_eq!(script.frames, [(0, true), (1, true)]);
+    }
+
+    #[test]
+    pub fn steps_back() {
+        use Input::*;
+
+        let mut script = Script::new([Step, Step, Step, StepBack, StepBack, Frame], Quit);
+        let simulation = Simulation::new(SerialEngine::default(), Grid::random_seeded(10, 10, 597))
+            .with_rewind(Some(Rewind::new(8, usize::MAX)));
+
+        let player = Player::new(&mut script, simulation, Duration::from_secs(8));
+        let simulation = player.run().unwrap();
+        assert_eq!(simulation.generation(), 1);
+        assert_eq!(script.frames, [(1, true)]);
+    }
+
+    #[test]
+    pub fn updates_in_background() {
+        let mut script = Script::new([], Input::Frame);
+        let simulation = Simulation::new(SerialEngine::default(), Grid::new(5, 5)).with_max_generations(100);
+
+        let player = Player::new(&mut script, simulation, Duration::ZERO);
+        assert_eq!(player.run().unwrap().generation(), 100);
+        assert_eq!(script.frames.last(), Some(&(100, false)));
+    }
+}
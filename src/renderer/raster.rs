@@ -0,0 +1,407 @@
+use std::ops::Range;
+
+use piston_window::texture::{CreateTexture, Format, UpdateTexture};
+use piston_window::{Filter, G2dTexture, G2dTextureContext, GfxDevice, PistonWindow, TextureSettings};
+
+use crate::cell::{Grid, GridView};
+use crate::par::*;
+use crate::theme::{self, Theme};
+
+use super::Frame;
+
+/// The board as a texture with one pixel per cell, scaled by the GPU.
+///
+/// Drawing a rectangle for each live cell is wasteful when cells are smaller than a pixel, and
+/// too slow for grids with millions of cells. The texture is only uploaded again when the
+/// [revision](Frame::revision) of the grid changes, instead of on every frame, and then only the
+/// rows with cells that changed since the last upload, so large boards that are mostly still are
+/// cheap to show.
+pub(super) struct Raster {
+    context: G2dTextureContext,
+    texture: Option<G2dTexture>,
+    pixels: Vec<u8>,
+    shape: (usize, usize),
+    revision: Option<u64>,
+    /// The grid and agents currently in the texture.
+    drawn: Grid,
+    agents: Vec<(usize, usize)>,
+    /// Whether the texture shows a heatmap instead of the cells.
+    heatmap: bool,
+    /// Whether the texture shows the births and deaths of the last generation.
+    changes: bool,
+}
+
+impl Raster {
+    #[must_use]
+    pub fn new(window: &mut PistonWindow) -> Self {
+        Self {
+            context: window.create_texture_context(),
+            texture: None,
+            pixels: Vec::new(),
+            shape: (0, 0),
+            revision: None,
+            drawn: Grid::empty(),
+            agents: Vec::new(),
+            heatmap: false,
+            changes: false,
+        }
+    }
+
+    /// Rasterizes the grid of `frame` into the texture, unless it was already done for this revision.
+    ///
+    /// Fails if the grid is too large for a texture.
+    pub fn update(&mut self, frame: &Frame<'_>, theme: &Theme) -> Result<(), String> {
+        let shape = frame.grid.shape();
+        let (heatmap, changes) = (frame.activity.is_some(), frame.changes.is_some());
+        if self.texture.is_some() && self.revision == Some(frame.revision) && self.shape == shape && (self.heatmap, self.changes) == (heatmap, changes) {
+            return Ok(());
+        }
+
+        let (rows, columns) = shape;
+        let (Ok(width), Ok(height)) = (u16::try_from(columns), u16::try_from(rows)) else {
+            return Err(format!("grid with {rows} rows and {columns} columns is too large for a texture"));
+        };
+        let size = [u32::from(width), u32::from(height)];
+
+        // ages, activity, trails and changes change with every generation, so all of their cells
+        // would be repainted anyway
+        let plain = frame.ages.is_none() && frame.trails.is_none() && !heatmap && !self.heatmap && !changes && !self.changes;
+        let dirty = if self.texture.is_some() && self.shape == shape && plain {
+            repaint(&mut self.pixels, (&self.drawn, &self.agents), frame, theme)
+        } else {
+            rasterize(&mut self.pixels, frame, theme, 1);
+            Some(0..rows)
+        };
+        self.drawn.clone_from(frame.grid);
+        self.agents.clear();
+        self.agents.extend_from_slice(frame.agents);
+
+        let line = columns * 4;
+        let texture = match self.texture.take() {
+            Some(texture) if self.shape == shape && dirty.is_none() => Ok(texture),
+            Some(mut texture) if self.shape == shape => {
+                let rows = dirty.unwrap_or(0..0);
+                let pixels = &self.pixels[rows.start * line..rows.end * line];
+                let (offset, size) = ([0, rows.start as u32], [size[0], rows.len() as u32]);
+                UpdateTexture::update(&mut texture, &mut self.context, Format::Rgba8, pixels, offset, size).map(|()| texture)
+            },
+            _ => {
+                let settings = TextureSettings::new().filter(Filter::Nearest);
+                CreateTexture::create(&mut self.context, Format::Rgba8, &self.pixels, size, &settings)
+            },
+        };
+
+        self.texture = Some(texture.map_err(|error| error.to_string())?);
+        self.shape = shape;
+        self.revision = Some(frame.revision);
+        self.heatmap = heatmap;
+        self.changes = changes;
+        Ok(())
+    }
+
+    #[inline]
+    #[must_use]
+    /// The texture from the last successful [`update`](Raster::update).
+    pub const fn texture(&self) -> Option<&G2dTexture> {
+        self.texture.as_ref()
+    }
+
+    /// Sends the pending texture uploads to the GPU, before drawing.
+    pub fn flush(&mut self, device: &mut GfxDevice) {
+        self.context.encoder.flush(device);
+    }
+}
+
+/// The cells in view as a texture with fewer pixels than cells, each colored by the density of
+/// live cells it covers, see [`downsample`].
+///
+/// When cells are smaller than a pixel, a texture with one pixel per cell would have its cells
+/// dropped when the GPU scales it down, so patterns flicker and sparse ones vanish. The texture is
+/// only computed again when the revision of the grid, the cells in view or the size on screen
+/// change.
+pub(super) struct Density {
+    context: G2dTextureContext,
+    texture: Option<G2dTexture>,
+    pixels: Vec<u8>,
+    revision: Option<u64>,
+    /// The rows and columns in the texture, and its `(height, width)`.
+    cells: (Range<usize>, Range<usize>),
+    shape: (usize, usize),
+}
+
+impl Density {
+    #[must_use]
+    pub fn new(window: &mut PistonWindow) -> Self {
+        Self { context: window.create_texture_context(), texture: None, pixels: Vec::new(), revision: None, cells: (0..0, 0..0), shape: (0, 0) }
+    }
+
+    /// Downsamples the `rows` and `columns` of the grid of `frame` into a texture of
+    /// `(height, width)`, unless it was already done.
+    ///
+    /// Fails if the texture can't be created.
+    pub fn update(&mut self, frame: &Frame<'_>, cells: (Range<usize>, Range<usize>), shape: (usize, usize), theme: &Theme) -> Result<(), String> {
+        if self.texture.is_some() && self.revision == Some(frame.revision) && self.cells == cells && self.shape == shape {
+            return Ok(());
+        }
+
+        downsample(&mut self.pixels, &frame.grid.view(cells.0.clone(), cells.1.clone()), shape, theme);
+        let size = [shape.1 as u32, shape.0 as u32];
+        let texture = match self.texture.take() {
+            Some(mut texture) if self.shape == shape => {
+                UpdateTexture::update(&mut texture, &mut self.context, Format::Rgba8, &self.pixels, [0, 0], size).map(|()| texture)
+            },
+            _ => {
+                let settings = TextureSettings::new().filter(Filter::Nearest);
+                CreateTexture::create(&mut self.context, Format::Rgba8, &self.pixels, size, &settings)
+            },
+        };
+
+        self.texture = Some(texture.map_err(|error| error.to_string())?);
+        self.revision = Some(frame.revision);
+        self.cells = cells;
+        self.shape = shape;
+        Ok(())
+    }
+
+    #[inline]
+    #[must_use]
+    /// The texture from the last successful [`update`](Density::update).
+    pub const fn texture(&self) -> Option<&G2dTexture> {
+        self.texture.as_ref()
+    }
+
+    /// Sends the pending texture uploads to the GPU, before drawing.
+    pub fn flush(&mut self, device: &mut GfxDevice) {
+        self.context.encoder.flush(device);
+    }
+}
+
+/// Writes the board of a frame as RGBA pixels, in row-major order, with `scale` by `scale` pixels
+/// for each cell.
+///
+/// Dead cells have the background color of the theme, or fade from the trail color if they died
+/// recently, while live cells are colored like in
+/// [`Geometry::live_row`](super::geometry::Geometry::live_row). With the activity of the frame,
+/// every cell is colored by it instead, like in [`Geometry::heat_row`](super::geometry::Geometry::heat_row).
+/// The births and deaths of the frame, if any, and then the agents are painted over the cells.
+pub(super) fn rasterize(pixels: &mut Vec<u8>, frame: &Frame<'_>, theme: &Theme, scale: usize) {
+    let [dead, live, agent] = [theme.dead, theme.live, theme.agent].map(theme::to_rgba8);
+    let (rows, columns) = frame.grid.shape();
+    let line = columns * scale * 4;
+    pixels.resize(rows * scale * line, 0);
+
+    if line == 0 {
+        return;
+    }
+    pixels.par_chunks_exact_mut(line * scale).enumerate().for_each(|(row, block)| {
+        let (first, others) = block.split_at_mut(line);
+        for ((col, cell), pixels) in frame.grid[row].iter().enumerate().zip(first.chunks_exact_mut(scale * 4)) {
+            let color = match (frame.activity, frame.ages) {
+                (Some(activity), _) => theme.by_activity(activity.fraction(row, col)).map_or(dead, theme::to_rgba8),
+                _ if cell.is_dead() => frame.trails.and_then(|trails| theme.by_trail(trails.fade(row, col))).map_or(dead, theme::to_rgba8),
+                (None, Some(ages)) => theme::to_rgba8(theme.by_age(ages[(row, col)])),
+                (None, None) => live,
+            };
+            for pixel in pixels.chunks_exact_mut(4) {
+                pixel.copy_from_slice(&color);
+            }
+        }
+        for other in others.chunks_exact_mut(line) {
+            other.copy_from_slice(first);
+        }
+    });
+
+    let mut paint = |(row, col): (usize, usize), color: &[u8; 4]| {
+        if row >= rows || col >= columns {
+            return;
+        }
+        for y in row * scale..(row + 1) * scale {
+            let start = y * line + col * scale * 4;
+            for pixel in pixels[start..start + scale * 4].chunks_exact_mut(4) {
+                pixel.copy_from_slice(color);
+            }
+        }
+    };
+    if let Some(changes) = frame.changes {
+        let [born, died] = theme.changes.map(theme::to_rgba8);
+        changes.births().iter().for_each(|&position| paint(position, &born));
+        changes.deaths().iter().for_each(|&position| paint(position, &died));
+    }
+    frame.agents.iter().for_each(|&position| paint(position, &agent));
+}
+
+/// Writes the `cells` as `(height, width)` RGBA pixels, in row-major order, where each pixel is
+/// colored by the fraction of live cells in the block of cells it covers, from the `dead` to the
+/// `live` color of the theme.
+///
+/// Unlike [`rasterize`], which needs a pixel for each cell, this shrinks a board of any size
+/// without losing sparse patterns. Pixels past the size of the board cover no cell and are left
+/// dead.
+pub(super) fn downsample(pixels: &mut Vec<u8>, cells: &GridView<'_>, (height, width): (usize, usize), theme: &Theme) {
+    let (rows, columns) = cells.shape();
+    let line = width * 4;
+    pixels.resize(height * line, 0);
+
+    if line == 0 {
+        return;
+    }
+    pixels.par_chunks_exact_mut(line).enumerate().for_each(|(y, pixels)| {
+        let band = cells.view(y * rows / height..(y + 1) * rows / height, ..);
+        for (x, pixel) in pixels.chunks_exact_mut(4).enumerate() {
+            let block = band.view(.., x * columns / width..(x + 1) * columns / width);
+            let cells = block.rows() * block.columns();
+            let density = if cells == 0 { 0.0 } else { block.population() as f32 / cells as f32 };
+            pixel.copy_from_slice(&theme::to_rgba8(theme::lerp(theme.dead, theme.live, density)));
+        }
+    });
+}
+
+/// Repaints the pixels written by [`rasterize`] for the `drawn` grid and agents, without scaling,
+/// with the cells that changed in the grid of `frame` and where its agents moved.
+///
+/// Returns the range of rows that changed, if any. The grid of `frame` must have the same shape
+/// as the drawn one, and no ages, activity or trails.
+pub(super) fn repaint(pixels: &mut [u8], (drawn, agents): (&Grid, &[(usize, usize)]), frame: &Frame<'_>, theme: &Theme) -> Option<Range<usize>> {
+    let [dead, live, agent] = [theme.dead, theme.live, theme.agent].map(theme::to_rgba8);
+    let (rows, columns) = frame.grid.shape();
+    let inside = |&(row, col): &(usize, usize)| row < rows && col < columns;
+
+    let mut dirty: Option<Range<usize>> = None;
+    let mut paint = |(row, col): (usize, usize), color: [u8; 4]| {
+        let start = (row * columns + col) * 4;
+        pixels[start..start + 4].copy_from_slice(&color);
+        dirty = Some(dirty.as_ref().map_or(row..row + 1, |rows| rows.start.min(row)..rows.end.max(row + 1)));
+    };
+
+    // cells under the old agents are painted back before drawing the new ones
+    let delta = drawn.diff(frame.grid);
+    for position in delta.changed().chain(agents.iter().copied().filter(inside)) {
+        paint(position, if frame.grid[position].is_live() { live } else { dead });
+    }
+    for position in frame.agents.iter().copied().filter(inside) {
+        paint(position, agent);
+    }
+    dirty
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::age::Ages;
+    use crate::cell::{Cell, Grid, Topology};
+    use crate::trail::Trails;
+
+    use super::*;
+
+    fn frame<'a>(grid: &'a Grid, ages: Option<&'a Ages>, agents: &'a [(usize, usize)]) -> Frame<'a> {
+        Frame { grid, generation: 0, engine: "serial", step_time: Duration::ZERO, agents, ages, activity: None, trails: None, changes: None, paused: false, update_interval: Duration::ZERO, revision: 0, origin: (0, 0), topology: Topology::Moore }
+    }
+
+    #[test]
+    pub fn scaled_pixels() {
+        let theme = Theme::LIGHT;
+        let mut grid = Grid::new(2, 3);
+        grid[(0, 1)] = Cell::Live;
+        grid[(1, 2)] = Cell::Live;
+
+        let mut pixels = Vec::new();
+        rasterize(&mut pixels, &frame(&grid, None, &[]), &theme, 1);
+
+        let [white, black] = [[255; 4], [0, 0, 0, 255]];
+        assert_eq!(pixels.chunks(4).collect::<Vec<_>>(), [white, black, white, white, white, black]);
+
+        rasterize(&mut pixels, &frame(&grid, None, &[(1, 0)]), &theme, 2);
+        let red = theme::to_rgba8(theme.agent);
+        let lines: Vec<Vec<_>> = pixels.chunks(6 * 4).map(|line| line.chunks(4).collect()).collect();
+        assert_eq!(lines, [
+            [white, white, black, black, white, white],
+            [white, white, black, black, white, white],
+            [red, red, white, white, black, black],
+            [red, red, white, white, black, black],
+        ]);
+
+        let mut ages = Ages::new(&grid);
+        ages.update(&grid);
+        grid[(0, 0)] = Cell::Live;
+        ages.update(&grid);
+
+        rasterize(&mut pixels, &frame(&grid, Some(&ages), &[]), &theme, 1);
+        assert_eq!(pixels[0..4], theme::to_rgba8(theme.young));
+        assert_eq!(pixels[4..8], theme::to_rgba8(theme.by_age(3)));
+        assert_eq!(pixels[8..12], white);
+
+        // the cell that died keeps its trail, and nothing is left of the trail after a generation
+        let mut trails = Trails::new(&grid, 2);
+        let next = Grid::new(2, 3);
+        trails.update(&grid.diff(&next));
+        rasterize(&mut pixels, &Frame { trails: Some(&trails), ..frame(&next, None, &[]) }, &theme, 1);
+        assert_eq!(pixels[0..4], theme::to_rgba8(theme.trail));
+        assert_eq!(pixels[8..12], white);
+        assert_eq!(pixels[20..24], theme::to_rgba8(theme.trail));
+        trails.update(&next.diff(&next));
+        rasterize(&mut pixels, &Frame { trails: Some(&trails), ..frame(&next, None, &[]) }, &theme, 1);
+        assert_eq!(pixels[0..4], theme::to_rgba8(theme.by_trail(0.5).unwrap()));
+
+        // births and deaths are painted over the cells
+        let mut moved = next.clone();
+        moved[(1, 0)] = Cell::Live;
+        let changes = grid.diff(&moved);
+        rasterize(&mut pixels, &Frame { changes: Some(&changes), ..frame(&moved, None, &[]) }, &theme, 1);
+        let [born, died] = theme.changes.map(theme::to_rgba8);
+        assert_eq!(pixels.chunks(4).collect::<Vec<_>>(), [died, died, white, born, white, died]);
+    }
+
+    #[test]
+    pub fn density_of_each_block() {
+        let theme = Theme::LIGHT;
+        let mut grid = Grid::new(4, 6);
+        grid[0][..3].fill(Cell::Live);
+        grid[(3, 5)] = Cell::Live;
+
+        let mut pixels = Vec::new();
+        downsample(&mut pixels, &grid.view(.., ..), (2, 2), &theme);
+        let shade = |density| theme::to_rgba8(theme::lerp(theme.dead, theme.live, density));
+        assert_eq!(pixels.chunks(4).collect::<Vec<_>>(), [shade(0.5), shade(0.0), shade(0.0), shade(1.0 / 6.0)]);
+
+        // a pixel for each cell is the same as rasterizing it
+        let mut expected = Vec::new();
+        downsample(&mut pixels, &grid.view(.., ..), (4, 6), &theme);
+        rasterize(&mut expected, &frame(&grid, None, &[]), &theme, 1);
+        assert_eq!(pixels, expected);
+
+        // only the cells in view
+        downsample(&mut pixels, &grid.view(..2, 3..), (1, 1), &theme);
+        assert_eq!(pixels, shade(0.0));
+        downsample(&mut pixels, &grid.view(2.., 2..), (1, 2), &theme);
+        assert_eq!(pixels.chunks(4).collect::<Vec<_>>(), [shade(0.0), shade(0.25)]);
+
+        downsample(&mut pixels, &grid.view(.., ..), (8, 0), &theme);
+        assert!(pixels.is_empty());
+    }
+
+    #[test]
+    pub fn repaints_changed_rows() {
+        let theme = Theme::DARK;
+        let mut grid = Grid::random_seeded(8, 5, 547);
+        let mut pixels = Vec::new();
+        rasterize(&mut pixels, &frame(&grid, None, &[(0, 0)]), &theme, 1);
+        assert_eq!(repaint(&mut pixels, (&grid, &[(0, 0)]), &frame(&grid, None, &[(0, 0)]), &theme), Some(0..1));
+
+        let drawn = grid.clone();
+        for position in [(3, 1), (5, 4)] {
+            grid[position].toggle();
+        }
+        // the agent leaves too
+        assert_eq!(repaint(&mut pixels, (&drawn, &[(0, 0)]), &frame(&grid, None, &[]), &theme), Some(0..6));
+        let mut expected = Vec::new();
+        rasterize(&mut expected, &frame(&grid, None, &[]), &theme, 1);
+        assert_eq!(pixels, expected);
+
+        assert_eq!(repaint(&mut pixels, (&grid, &[]), &frame(&grid, None, &[]), &theme), None);
+        assert_eq!(repaint(&mut pixels, (&grid, &[]), &frame(&grid, None, &[(7, 2)]), &theme), Some(7..8));
+        assert_eq!(repaint(&mut pixels, (&grid, &[(7, 2)]), &frame(&grid, None, &[(6, 2)]), &theme), Some(6..8));
+        rasterize(&mut expected, &frame(&grid, None, &[(6, 2)]), &theme, 1);
+        assert_eq!(pixels, expected);
+    }
+}
@@ -0,0 +1,208 @@
+use std::fs::{self, File};
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::cell::{Grid, Topology};
+use crate::error::{Error, Result};
+use crate::life3d::Grid3;
+use crate::theme::Theme;
+
+use super::raster::rasterize;
+use super::{Frame, Input, Renderer};
+
+/// Writes each generation as a PNG image to a directory, without a display.
+///
+/// The files are named after their generation, like `000042.png`, so they sort in order and can
+/// be joined into a video with other tools. The simulation runs as fast as the images are written,
+/// one generation at a time, so no generation is skipped.
+///
+/// # Example
+///
+/// ```no_run
+/// # use std::time::Duration;
+/// # use vida::cell::Grid;
+/// # use vida::engine::SerialEngine;
+/// # use vida::renderer::{FrameRecorder, Player};
+/// # use vida::simulation::Simulation;
+/// #
+/// let simulation = Simulation::new(SerialEngine::default(), Grid::random(100, 100)).with_max_generations(500);
+/// let recorder = FrameRecorder::new("frames").with_scale(4);
+/// Player::new(recorder, simulation, Duration::ZERO).run().unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct FrameRecorder {
+    directory: PathBuf,
+    scale: usize,
+    theme: Theme,
+    /// What the recorder asks of the [`Player`](super::Player) next.
+    next_input: Input,
+    /// The revision of the last image written, to write each generation only once.
+    revision: Option<u64>,
+    pixels: Vec<u8>,
+}
+
+impl FrameRecorder {
+    #[must_use]
+    /// Records into `directory`, which is created if needed, with one pixel per cell.
+    pub fn new(directory: impl Into<PathBuf>) -> Self {
+        Self { directory: directory.into(), scale: 1, theme: Theme::default(), next_input: Input::Frame, revision: None, pixels: Vec::new() }
+    }
+
+    #[inline]
+    #[must_use]
+    /// Draws each cell as a square of `scale` by `scale` pixels.
+    pub fn with_scale(mut self, scale: usize) -> Self {
+        self.scale = scale.max(1);
+        self
+    }
+
+    #[inline]
+    #[must_use]
+    /// Colors used in the images.
+    pub const fn with_theme(mut self, theme: Theme) -> Self {
+        self.theme = theme;
+        self
+    }
+
+    #[inline]
+    #[must_use]
+    /// Where the images are written.
+    pub fn directory(&self) -> &Path {
+        &self.directory
+    }
+
+    /// Writes every `z`-slice of a [3D Life](crate::life3d) space as an image, named after the
+    /// generation and the slice, like `000042-007.png`, so the stack of each generation sorts
+    /// together.
+    ///
+    /// This is used directly instead of through a [`Player`](super::Player), which only runs 2D
+    /// simulations.
+    pub fn record_layers(&mut self, space: &Grid3, generation: usize) -> Result<()> {
+        fs::create_dir_all(&self.directory)?;
+
+        for (z, layer) in space.layers().iter().enumerate() {
+            let frame = Frame {
+                grid: layer,
+                generation,
+                engine: "life3d",
+                step_time: Duration::ZERO,
+                agents: &[],
+                ages: None,
+                activity: None,
+                trails: None,
+                changes: None,
+                paused: false,
+                update_interval: Duration::ZERO,
+                revision: 0,
+                origin: (0, 0),
+                topology: Topology::Moore,
+            };
+            rasterize(&mut self.pixels, &frame, &self.theme, self.scale);
+
+            let path = self.directory.join(format!("{generation:06}-{z:03}.png"));
+            self.write_png(&path, space.columns() * self.scale, space.rows() * self.scale)?;
+        }
+        Ok(())
+    }
+
+    fn write_png(&self, path: &Path, width: usize, height: usize) -> Result<()> {
+        let (width, height) = (u32::try_from(width).map_err(Error::render)?, u32::try_from(height).map_err(Error::render)?);
+        let mut encoder = png::Encoder::new(BufWriter::new(File::create(path)?), width, height);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+
+        let mut writer = encoder.write_header().map_err(Error::render)?;
+        writer.write_image_data(&self.pixels).map_err(Error::render)?;
+        writer.finish().map_err(Error::render)?;
+        Ok(())
+    }
+}
+
+impl Renderer for FrameRecorder {
+    fn init(&mut self, _grid: &Grid) -> Result<()> {
+        fs::create_dir_all(&self.directory)?;
+        Ok(())
+    }
+
+    fn draw(&mut self, frame: &Frame<'_>) -> Result<()> {
+        if self.revision == Some(frame.revision) {
+            return Ok(());
+        }
+        self.revision = Some(frame.revision);
+
+        rasterize(&mut self.pixels, frame, &self.theme, self.scale);
+        let (rows, columns) = frame.grid.shape();
+        let path = self.directory.join(format!("{:06}.png", frame.generation));
+        self.write_png(&path, columns * self.scale, rows * self.scale)
+    }
+
+    /// Alternates between drawing a frame and stepping a generation, so every generation is drawn
+    /// exactly once, regardless of the update interval.
+    fn handle_input(&mut self) -> Input {
+        let input = self.next_input;
+        self.next_input = if input == Input::Frame { Input::Step } else { Input::Frame };
+        input
+    }
+
+    #[inline]
+    fn cell_size(&self) -> f64 {
+        self.scale as f64
+    }
+
+    #[inline]
+    fn starts_paused(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::engine::SerialEngine;
+    use crate::simulation::Simulation;
+
+    use super::super::Player;
+    use super::*;
+
+    #[test]
+    pub fn every_generation() {
+        let directory = std::env::temp_dir().join(format!("vida-recorder-{}", std::process::id()));
+        let simulation = Simulation::new(SerialEngine::default(), Grid::random_seeded(6, 5, 535)).with_max_generations(3);
+
+        let simulation = Player::new(FrameRecorder::new(&directory).with_scale(3), simulation, Duration::from_secs(1)).run().unwrap();
+        assert_eq!(simulation.generation(), 3);
+
+        let mut files: Vec<_> = fs::read_dir(&directory).unwrap().map(|entry| entry.unwrap().file_name()).collect();
+        files.sort();
+        assert_eq!(files, ["000000.png", "000001.png", "000002.png", "000003.png"]);
+
+        let decoder = png::Decoder::new(File::open(directory.join("000003.png")).unwrap());
+        let info = decoder.read_info().unwrap().info().clone();
+        assert_eq!((info.width, info.height), (15, 18));
+
+        fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[test]
+    pub fn slice_stacks() {
+        let directory = std::env::temp_dir().join(format!("vida-layers-{}", std::process::id()));
+        let mut recorder = FrameRecorder::new(&directory).with_scale(2);
+
+        let space = Grid3::new(3, 4, 5);
+        recorder.record_layers(&space, 0).unwrap();
+        recorder.record_layers(&space, 1).unwrap();
+
+        let mut files: Vec<_> = fs::read_dir(&directory).unwrap().map(|entry| entry.unwrap().file_name()).collect();
+        files.sort();
+        let names = ["000000-000.png", "000000-001.png", "000000-002.png", "000001-000.png", "000001-001.png", "000001-002.png"];
+        assert_eq!(files, names);
+
+        let decoder = png::Decoder::new(File::open(directory.join("000001-002.png")).unwrap());
+        let info = decoder.read_info().unwrap().info().clone();
+        assert_eq!((info.width, info.height), (10, 8));
+
+        fs::remove_dir_all(directory).unwrap();
+    }
+}
@@ -0,0 +1,251 @@
+//! Drawing in a terminal, for machines without a display, like over SSH.
+//!
+//! The board is drawn with half block characters, two rows of cells per line of text, using only
+//! ANSI escape sequences. `Space` pauses or resumes, `N` or `.` advances one generation while
+//! paused, `R` replaces the board with a random one, `+` and `-` change the speed, `S` and `L` save
+//! and load the session, `G` grows the board on every side, and `Q` quits.
+//!
+//! On Linux the terminal is switched to raw mode, so keys take effect as soon as they are pressed.
+//! On other platforms, keys are only read after `Enter`.
+
+use std::io::{self, Read, Write};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::thread;
+use std::time::Duration;
+
+use crate::cell::Grid;
+use crate::error::Result;
+
+use super::{Frame, Input, Renderer};
+
+/// Longest wait for a key before the next frame.
+const FRAME_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Size used when the terminal size can't be determined.
+const DEFAULT_SIZE: (usize, usize) = (24, 80);
+
+/// Draws the simulation in the terminal.
+#[derive(Default)]
+pub struct TerminalRenderer {
+    keys: Option<Receiver<u8>>,
+    screen: Option<Screen>,
+    /// Generation, pause state, speed and terminal size of the last frame, to skip drawing it again.
+    last_frame: Option<(usize, bool, Duration, (usize, usize))>,
+}
+
+impl TerminalRenderer {
+    #[inline]
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl Renderer for TerminalRenderer {
+    fn init(&mut self, _grid: &Grid) -> Result<()> {
+        self.keys = Some(spawn_key_reader());
+        self.screen = Some(Screen::enter()?);
+        Ok(())
+    }
+
+    fn draw(&mut self, frame: &Frame<'_>) -> Result<()> {
+        let (lines, columns) = sys::terminal_size().unwrap_or(DEFAULT_SIZE);
+        let current = Some((frame.generation, frame.paused, frame.update_interval, (lines, columns)));
+        if current == self.last_frame {
+            return Ok(());
+        }
+        self.last_frame = current;
+
+        let status = format!(
+            "generation {}{} | {:?} per generation | space: pause, n: step, +/-: speed, q: quit",
+            frame.generation,
+            if frame.paused { " (paused)" } else { "" },
+            frame.update_interval,
+        );
+
+        // one line is kept for the status
+        let board = self::frame(frame.grid, lines.saturating_sub(1), columns);
+        let mut stdout = io::stdout().lock();
+        write!(stdout, "\x1b[H{board}\x1b[J{status}\x1b[K")?;
+        stdout.flush()?;
+        Ok(())
+    }
+
+    fn handle_input(&mut self) -> Input {
+        let Some(keys) = &self.keys else {
+            // without an input, the simulation just keeps running
+            thread::sleep(FRAME_INTERVAL);
+            return Input::Frame;
+        };
+
+        match keys.recv_timeout(FRAME_INTERVAL) {
+            Ok(b'q' | b'Q') => Input::Quit,
+            Ok(b' ') => Input::Pause,
+            Ok(b'n' | b'N' | b'.') => Input::Step,
+            Ok(b'b' | b'B' | b',') => Input::StepBack,
+            Ok(b'r' | b'R') => Input::Randomize,
+            Ok(b'+' | b'=') => Input::Faster,
+            Ok(b'-') => Input::Slower,
+            Ok(b's' | b'S') => Input::Save,
+            Ok(b'l' | b'L') => Input::Load,
+            Ok(b'g' | b'G') => Input::Grow,
+            Ok(_) | Err(RecvTimeoutError::Timeout) => Input::Frame,
+            Err(RecvTimeoutError::Disconnected) => {
+                self.keys = None;
+                Input::Frame
+            },
+        }
+    }
+
+    fn resize(&mut self, _shape: (usize, usize), _cell_size: f64) {
+        self.last_frame = None;
+    }
+}
+
+#[must_use]
+/// Draws the top-left corner of the grid that fits in `lines` lines of `columns` characters.
+///
+/// Each line holds two rows of cells, and ends by clearing the rest of the terminal line.
+fn frame(grid: &Grid, lines: usize, columns: usize) -> String {
+    let columns = columns.min(grid.columns());
+    let mut output = String::new();
+
+    for pair in grid.iter().take(2 * lines).collect::<Vec<_>>().chunks(2) {
+        let (top, bottom) = (pair[0], pair.get(1).copied());
+        for col in 0..columns {
+            let upper = top[col].is_live();
+            let lower = bottom.is_some_and(|row| row[col].is_live());
+            output.push(match (upper, lower) {
+                (true, true) => '█',
+                (true, false) => '▀',
+                (false, true) => '▄',
+                (false, false) => ' ',
+            });
+        }
+        output.push_str("\x1b[K\r\n");
+    }
+    output
+}
+
+/// Reads single bytes from the standard input in a background thread.
+///
+/// The thread stays blocked on the input after the renderer returns, which is harmless since
+/// nothing else reads from it.
+fn spawn_key_reader() -> Receiver<u8> {
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        let mut buffer = [0; 16];
+        let mut stdin = io::stdin();
+        while let Ok(read @ 1..) = stdin.read(&mut buffer) {
+            if buffer[..read].iter().any(|&byte| sender.send(byte).is_err()) {
+                return;
+            }
+        }
+    });
+    receiver
+}
+
+/// Switches to the alternate screen in raw mode, restoring the terminal when dropped.
+struct Screen {
+    mode: Option<sys::Mode>,
+}
+
+impl Screen {
+    fn enter() -> io::Result<Self> {
+        let mode = sys::enable_raw_mode();
+        let mut stdout = io::stdout();
+        write!(stdout, "\x1b[?1049h\x1b[?25l\x1b[2J")?;
+        stdout.flush()?;
+        Ok(Self { mode })
+    }
+}
+
+impl Drop for Screen {
+    fn drop(&mut self) {
+        let mut stdout = io::stdout();
+        let _ = write!(stdout, "\x1b[?25h\x1b[?1049l");
+        let _ = stdout.flush();
+
+        if let Some(mode) = self.mode.take() {
+            sys::restore_mode(mode);
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod sys {
+    use std::mem;
+
+    pub type Mode = libc::termios;
+
+    /// `(lines, columns)` of the terminal on the standard output.
+    pub fn terminal_size() -> Option<(usize, usize)> {
+        // SAFETY: `winsize` is plain data, valid when zeroed, and is what `TIOCGWINSZ` writes to.
+        unsafe {
+            let mut size: libc::winsize = mem::zeroed();
+            if libc::ioctl(libc::STDOUT_FILENO, libc::TIOCGWINSZ, &mut size) != 0 || size.ws_row == 0 {
+                return None;
+            }
+            Some((size.ws_row.into(), size.ws_col.into()))
+        }
+    }
+
+    /// Disables line buffering and echo on the standard input, returning the previous mode.
+    pub fn enable_raw_mode() -> Option<Mode> {
+        // SAFETY: `termios` is plain data, valid when zeroed, and is filled by `tcgetattr`.
+        unsafe {
+            let mut mode: Mode = mem::zeroed();
+            if libc::tcgetattr(libc::STDIN_FILENO, &mut mode) != 0 {
+                return None;
+            }
+
+            let mut raw = mode;
+            raw.c_lflag &= !(libc::ICANON | libc::ECHO);
+            raw.c_cc[libc::VMIN] = 1;
+            raw.c_cc[libc::VTIME] = 0;
+            (libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, &raw) == 0).then_some(mode)
+        }
+    }
+
+    pub fn restore_mode(mode: Mode) {
+        // SAFETY: `mode` was read by `tcgetattr`.
+        unsafe {
+            libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, &mode);
+        }
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+mod sys {
+    pub type Mode = ();
+
+    pub fn terminal_size() -> Option<(usize, usize)> {
+        None
+    }
+
+    pub fn enable_raw_mode() -> Option<Mode> {
+        None
+    }
+
+    pub fn restore_mode(_mode: Mode) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cell::Cell;
+
+    use super::*;
+
+    #[test]
+    pub fn half_blocks() {
+        let mut grid = Grid::new(5, 4);
+        grid[0][0] = Cell::Live;
+        grid[1][1] = Cell::Live;
+        grid[0][2] = Cell::Live;
+        grid[1][2] = Cell::Live;
+        grid[4][0] = Cell::Live;
+
+        assert_eq!(frame(&grid, 10, 10), "▀▄█ \x1b[K\r\n    \x1b[K\r\n▀   \x1b[K\r\n");
+        assert_eq!(frame(&grid, 1, 2), "▀▄\x1b[K\r\n");
+    }
+}
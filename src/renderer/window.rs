@@ -0,0 +1,1389 @@
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use piston_window::*;
+
+use crate::cell::{Grid, Topology};
+#[cfg(feature = "continuous")]
+use crate::continuous::{ContinuousEngine, Field};
+use crate::engine::Engine;
+use crate::error::{Error, Result};
+use crate::game::{ImmigrationGame, Outcome, Phase};
+use crate::generations::{MultiStateEngine, States};
+use crate::life3d::{Engine3d, Grid3};
+#[cfg(feature = "parallel")]
+use crate::par::*;
+use crate::session::View;
+use crate::theme::{self, Theme};
+use crate::verify::Comparison;
+
+use super::geometry::Geometry;
+use super::minimap::{self, Minimap};
+use super::raster::{Density, Raster};
+use super::{Frame, Input, Renderer};
+
+/// Settings for the window opened by the renderers.
+///
+/// See [`Player`](super::Player) for an example.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct WindowConfig {
+    title: String,
+    resizable: bool,
+    samples: u8,
+    vsync: bool,
+    position: Option<(i32, i32)>,
+    exit_on_esc: bool,
+}
+
+impl WindowConfig {
+    #[inline]
+    #[must_use]
+    /// Default settings, with the given title.
+    pub fn new(title: impl Into<String>) -> Self {
+        Self { title: title.into(), resizable: true, samples: 0, vsync: false, position: None, exit_on_esc: true }
+    }
+
+    #[inline]
+    #[must_use]
+    /// Sets the window title.
+    pub fn title(mut self, title: impl Into<String>) -> Self {
+        self.title = title.into();
+        self
+    }
+
+    #[inline]
+    #[must_use]
+    /// Whether the user can resize the window. Defaults to `true`.
+    pub fn resizable(mut self, resizable: bool) -> Self {
+        self.resizable = resizable;
+        self
+    }
+
+    #[inline]
+    #[must_use]
+    /// Number of samples for anti-aliasing, with `0` disabling it. Defaults to `0`.
+    pub fn samples(mut self, samples: u8) -> Self {
+        self.samples = samples;
+        self
+    }
+
+    #[inline]
+    #[must_use]
+    /// Whether to synchronize frames with the display refresh. Defaults to `false`.
+    pub fn vsync(mut self, vsync: bool) -> Self {
+        self.vsync = vsync;
+        self
+    }
+
+    #[inline]
+    #[must_use]
+    /// Initial position of the window on the screen. Defaults to letting the system choose.
+    pub fn position(mut self, x: i32, y: i32) -> Self {
+        self.position = Some((x, y));
+        self
+    }
+
+    #[inline]
+    #[must_use]
+    /// Whether `Esc` closes the window. Defaults to `true`.
+    pub fn exit_on_esc(mut self, exit_on_esc: bool) -> Self {
+        self.exit_on_esc = exit_on_esc;
+        self
+    }
+
+    /// Opens a window of the given size with these settings.
+    pub fn build(&self, size: [f64; 2]) -> Result<PistonWindow> {
+        let mut window: PistonWindow = WindowSettings::new(self.title.clone(), size)
+            .resizable(self.resizable)
+            .samples(self.samples)
+            .vsync(self.vsync)
+            .exit_on_esc(self.exit_on_esc)
+            .build()
+            .map_err(|error| Error::render(error.to_string()))?;
+
+        if let Some((x, y)) = self.position {
+            window.set_position([x, y]);
+        }
+        Ok(window)
+    }
+}
+
+impl Default for WindowConfig {
+    #[inline]
+    fn default() -> Self {
+        Self::new("Conway's Game of Life")
+    }
+}
+
+#[must_use]
+/// Size of a window showing `(rows, columns)` cells.
+fn window_size(cell_size: f64, (rows, columns): (usize, usize)) -> [f64; 2] {
+    [cell_size * columns as f64, cell_size * rows as f64]
+}
+
+/// Draws the simulation in a window, using [piston_window].
+///
+/// `Space` pauses or resumes, `N` or `.` advances one generation while paused, `B` or `,` goes back
+/// one, if the simulation [keeps them](crate::simulation::Simulation::with_rewind), `R` replaces the
+/// board with a random one, `+` and `-` change the speed, `S` and `L` save and load the session,
+/// `Shift+G` grows the board on every side, `H` shows or hides the heatmap of how often each cell has
+/// been alive, if the simulation [tracks it](crate::simulation::Simulation::track_activity), `D`
+/// shows or hides the cells born and dead in the last generation, in green and red, if the
+/// simulation [tracks them](crate::simulation::Simulation::track_changes), and `Esc` closes the
+/// window.
+///
+/// The mouse wheel zooms around the cursor, and dragging with the left button or the arrow keys
+/// move the view, except for `Left` and `Right` while paused, which step one generation back and
+/// forward unless `Shift` is held. `0` or `Home` go back to the whole board, which is also scaled
+/// to fit the window, centered, whenever it is resized. While part of the board is out of view, a
+/// minimap of the whole board is shown in the top-right corner, with the density of live cells
+/// and a rectangle around the part in view, and clicking it centers the view there.
+///
+/// Dragging with `Shift` held selects a rectangle of cells instead, which `Ctrl+C` copies to the
+/// system clipboard as RLE, and `Ctrl+V` pastes the RLE pattern in the clipboard with its top-left
+/// corner at the cell under the cursor. `Ctrl+click` toggles a cell and `Delete` kills the
+/// selected cells. While paused, `Ctrl+Z` undoes these edits and pastes, and `Ctrl+Y` or
+/// `Ctrl+Shift+Z` redoes them, until the board changes in any other way.
+///
+/// On HiDPI displays, the sizes that decide how cells are drawn are measured in physical pixels,
+/// and the text is rendered at the resolution of the display, so nothing is blurry.
+///
+/// `F1` shows or hides a HUD with the generation, the population, the time the engine took to
+/// compute the last generation, the frame rate and the name of the engine.
+///
+/// With [`WindowRenderer::with_graph`], the population of the last generations is plotted in a
+/// strip below the board.
+///
+/// Cells of at least six pixels on screen are drawn with borders between them, so
+/// each one can be told apart, unless `G` turns them off. The borders are batched with the cells
+/// in a single draw, with one thin rectangle for each line instead of one for each cell.
+///
+/// Boards with cells smaller than two pixels on screen, like grids much larger than the window,
+/// are drawn from a texture with one pixel per cell. It is only updated once per generation, and
+/// only in the rows where cells changed. Below one physical pixel per cell, the cells in view are
+/// shrunk into a [`Density`] texture instead, with one pixel of the texture for each pixel of the
+/// display shaded by the fraction of live cells it covers, unless the heatmap or the births and
+/// deaths are shown. Otherwise, grids of hexagonal rules are drawn with hexagons, with odd rows
+/// shifted half a cell to the right.
+///
+/// The window is only opened by [`Renderer::init`], once the size of the grid is known.
+pub struct WindowRenderer {
+    config: WindowConfig,
+    window: Option<PistonWindow>,
+    cell_size: f64,
+    render_event: Option<Event>,
+    paused: bool,
+    camera: Camera,
+    /// The camera of a restored session, kept over the resize of the window that follows it.
+    restored: Option<Camera>,
+    cursor: [f64; 2],
+    dragging: bool,
+    /// The rectangle `[x, y, width, height]` covered by the board, before the camera transform.
+    board: [f64; 4],
+    /// The `[width, height]` of the part of the window where the board is drawn.
+    viewport: [f64; 2],
+    /// Whether a `Shift` key is held.
+    shift: bool,
+    /// Whether a `Ctrl` key is held.
+    ctrl: bool,
+    /// The cells selected with `Shift` and the mouse, and whether they are still being dragged.
+    selection: Option<Selection>,
+    selecting: bool,
+    /// Whether cell borders are drawn, when cells are large enough.
+    grid_lines: bool,
+    raster: Option<Raster>,
+    density: Option<Density>,
+    minimap: Option<Minimap>,
+    graph: Option<PopulationGraph>,
+    hud: Hud,
+    /// The font of the HUD, loaded with the window.
+    glyphs: Option<Glyphs>,
+}
+
+/// Cells smaller than this on screen, in pixels, are drawn from a texture with one pixel per cell
+/// instead of one rectangle each, see [`Raster`].
+const RASTER_CELL_SIZE: f64 = 2.0;
+
+/// Cells smaller than this on screen, in physical pixels, are drawn by the density of live cells
+/// in each pixel, see [`Density`].
+const DENSITY_CELL_SIZE: f64 = 1.0;
+
+/// Cells at least this large on screen, in pixels, are drawn with borders.
+const GRID_CELL_SIZE: f64 = 6.0;
+
+/// Zoom change for each step of the mouse wheel.
+const ZOOM_STEP: f64 = 1.25;
+
+/// Pixels moved by each press of an arrow key.
+const PAN_STEP: f64 = 32.0;
+
+/// Font of the HUD, DejaVu Sans Mono.
+const FONT: &[u8] = include_bytes!("../../assets/fonts/DejaVuSansMono.ttf");
+
+/// Size of the text in the HUD, in points.
+const HUD_FONT_SIZE: u32 = 14;
+
+/// Space between the lines of the HUD and around them, in pixels.
+const HUD_SPACING: f64 = 4.0;
+
+/// Frames drawn over this span are counted in the frame rate of the HUD.
+const FPS_SPAN: Duration = Duration::from_secs(1);
+
+/// A rectangle of cells selected with the mouse, from the cell where the drag started to the one
+/// under the cursor.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+struct Selection {
+    anchor: (usize, usize),
+    corner: (usize, usize),
+}
+
+impl Selection {
+    /// The `(top, left)` and `(bottom, right)` cells, inclusive.
+    fn bounds(self) -> ((usize, usize), (usize, usize)) {
+        let (rows, columns) = ((self.anchor.0, self.corner.0), (self.anchor.1, self.corner.1));
+        ((rows.0.min(rows.1), columns.0.min(columns.1)), (rows.0.max(rows.1), columns.0.max(columns.1)))
+    }
+}
+
+/// The `(row, col)` of the cell under a window point, in a board covering `[x, y, width, height]`
+/// before the camera transform, or [`None`] if it is outside of the board. With `clamp`, points
+/// outside of the board give the nearest cell instead.
+fn cell_at(camera: Camera, [x, y, width, height]: [f64; 4], cell_size: f64, point: [f64; 2], clamp: bool) -> Option<(usize, usize)> {
+    let [columns, rows] = [width, height].map(|length| (length / cell_size).round());
+    let [px, py] = camera.to_board(point);
+    let [col, row] = [(px - x) / cell_size, (py - y) / cell_size].map(f64::floor);
+    if rows < 1.0 || columns < 1.0 {
+        None
+    } else if clamp {
+        Some((row.clamp(0.0, rows - 1.0) as usize, col.clamp(0.0, columns - 1.0) as usize))
+    } else if (0.0..rows).contains(&row) && (0.0..columns).contains(&col) {
+        Some((row as usize, col as usize))
+    } else {
+        None
+    }
+}
+
+/// What the HUD shows, kept between frames.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Hud {
+    visible: bool,
+    /// When each frame of the last [`FPS_SPAN`] was drawn.
+    frames: VecDeque<Instant>,
+    /// The population of the last revision, counted only once for each grid.
+    population: Option<(u64, usize)>,
+}
+
+impl Hud {
+    const HIDDEN: Self = Self { visible: false, frames: VecDeque::new(), population: None };
+
+    /// Counts a frame drawn at `now`.
+    fn tick(&mut self, now: Instant) {
+        self.frames.push_back(now);
+        while self.frames.front().is_some_and(|&first| now.duration_since(first) > FPS_SPAN) {
+            self.frames.pop_front();
+        }
+    }
+
+    /// Frames drawn per second, over the last [`FPS_SPAN`].
+    fn fps(&self) -> f64 {
+        match (self.frames.front(), self.frames.back()) {
+            (Some(first), Some(last)) if last > first => (self.frames.len() - 1) as f64 / last.duration_since(*first).as_secs_f64(),
+            _ => 0.0,
+        }
+    }
+
+    /// The lines of text shown for a frame.
+    fn lines(&mut self, frame: &Frame<'_>) -> [String; 5] {
+        let population = match self.population {
+            Some((revision, population)) if revision == frame.revision => population,
+            _ => frame.grid.population(),
+        };
+        self.population = Some((frame.revision, population));
+
+        [
+            format!("generation {}", frame.generation),
+            format!("population {population}"),
+            format!("update     {:.2?}", frame.step_time),
+            format!("fps        {:.1}", self.fps()),
+            format!("engine     {}", frame.engine),
+        ]
+    }
+}
+
+/// Height of the population graph, in pixels.
+const GRAPH_HEIGHT: f64 = 64.0;
+
+/// Generations shown in the population graph.
+const GRAPH_LENGTH: usize = 512;
+
+/// Population of the last [`GRAPH_LENGTH`] generations shown, one sample per grid revision.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct PopulationGraph {
+    revision: Option<u64>,
+    generation: usize,
+    populations: VecDeque<usize>,
+}
+
+impl PopulationGraph {
+    /// Samples the population of a new grid, starting over when the generation goes back, like
+    /// when a session is loaded.
+    fn record(&mut self, frame: &Frame<'_>) {
+        if self.revision == Some(frame.revision) {
+            return;
+        }
+        if frame.generation < self.generation {
+            self.populations.clear();
+        }
+        if self.populations.len() == GRAPH_LENGTH {
+            self.populations.pop_front();
+        }
+        self.populations.push_back(frame.grid.population());
+        self.revision = Some(frame.revision);
+        self.generation = frame.generation;
+    }
+
+    /// Segments `[x1, y1, x2, y2]` of the plot in a strip of `width` by `height` pixels, with the
+    /// largest population at the top.
+    fn segments(&self, width: f64, height: f64) -> Vec<[f64; 4]> {
+        let highest = self.populations.iter().copied().max().unwrap_or(0).max(1) as f64;
+        let step = width / (GRAPH_LENGTH - 1) as f64;
+        let point = |(index, &population): (usize, &usize)| [index as f64 * step, height * (1.0 - population as f64 / highest)];
+
+        let points: Vec<_> = self.populations.iter().enumerate().map(point).collect();
+        points.windows(2).map(|pair| [pair[0][0], pair[0][1], pair[1][0], pair[1][1]]).collect()
+    }
+}
+
+/// Maps board coordinates to window coordinates, as `window = board * zoom + offset`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+struct Camera {
+    zoom: f64,
+    offset: [f64; 2],
+}
+
+impl Camera {
+    /// Shows the board from its top-left corner, without zoom.
+    const IDENTITY: Self = Self { zoom: 1.0, offset: [0.0, 0.0] };
+
+    const MIN_ZOOM: f64 = 0.125;
+    const MAX_ZOOM: f64 = 64.0;
+
+    /// Zooms by `factor`, keeping the board point under `cursor` in the same place.
+    fn zoom_at(&mut self, cursor: [f64; 2], factor: f64) {
+        let zoom = (self.zoom * factor).clamp(Self::MIN_ZOOM, Self::MAX_ZOOM);
+        let ratio = zoom / self.zoom;
+
+        self.offset = [0, 1].map(|i| cursor[i] - (cursor[i] - self.offset[i]) * ratio);
+        self.zoom = zoom;
+    }
+
+    fn pan(&mut self, [dx, dy]: [f64; 2]) {
+        self.offset = [self.offset[0] + dx, self.offset[1] + dy];
+    }
+
+    /// Shows all of the rectangle `[x, y, width, height]` of the board, as large as it fits in a
+    /// `viewport` of `[width, height]` and centered in it.
+    fn fit([x, y, width, height]: [f64; 4], viewport: [f64; 2]) -> Self {
+        if width <= 0.0 || height <= 0.0 {
+            return Self::IDENTITY;
+        }
+        let zoom = (viewport[0] / width).min(viewport[1] / height).clamp(Self::MIN_ZOOM, Self::MAX_ZOOM);
+        let offset = [(viewport[0] - width * zoom) / 2.0 - x * zoom, (viewport[1] - height * zoom) / 2.0 - y * zoom];
+        Self { zoom, offset }
+    }
+
+    /// Moves the view so the board `point` is at the center of a `viewport` of `[width, height]`.
+    fn center_on(&mut self, point: [f64; 2], viewport: [f64; 2]) {
+        self.offset = [0, 1].map(|i| viewport[i] / 2.0 - point[i] * self.zoom);
+    }
+
+    /// The board point shown at a window point.
+    fn to_board(self, point: [f64; 2]) -> [f64; 2] {
+        [0, 1].map(|i| (point[i] - self.offset[i]) / self.zoom)
+    }
+}
+
+impl WindowRenderer {
+    #[inline]
+    #[must_use]
+    pub fn new(cell_size: f64) -> Self {
+        Self::with_window(WindowConfig::default(), cell_size)
+    }
+
+    #[inline]
+    #[must_use]
+    /// Like [`WindowRenderer::new`], but with custom window settings.
+    pub const fn with_window(config: WindowConfig, cell_size: f64) -> Self {
+        Self { config, window: None, cell_size, render_event: None, paused: false, camera: Camera::IDENTITY, restored: None, cursor: [0.0, 0.0], dragging: false, board: [0.0; 4], viewport: [0.0; 2], shift: false, ctrl: false, selection: None, selecting: false, grid_lines: true, raster: None, density: None, minimap: None, graph: None, hud: Hud::HIDDEN, glyphs: None }
+    }
+
+    #[inline]
+    #[must_use]
+    /// Whether to plot the population of the last generations below the board.
+    pub fn with_graph(mut self, graph: bool) -> Self {
+        self.graph = graph.then(PopulationGraph::default);
+        self
+    }
+
+    /// Size of the window for `(rows, columns)` cells, with room for the graph.
+    fn window_size(&self, cell_size: f64, shape: (usize, usize)) -> [f64; 2] {
+        let [width, height] = window_size(cell_size, shape);
+        [width, if self.graph.is_some() { height + GRAPH_HEIGHT } else { height }]
+    }
+}
+
+impl Renderer for WindowRenderer {
+    fn init(&mut self, grid: &Grid) -> Result<()> {
+        let mut window = self.config.build(self.window_size(self.cell_size, grid.shape()))?;
+        self.raster = Some(Raster::new(&mut window));
+        self.density = Some(Density::new(&mut window));
+        self.minimap = Some(Minimap::new(&mut window));
+        let (rows, columns) = grid.shape();
+        self.board = [0.0, 0.0, columns as f64 * self.cell_size, rows as f64 * self.cell_size];
+        self.viewport = window_size(self.cell_size, grid.shape());
+        self.glyphs = Glyphs::from_bytes(FONT, window.create_texture_context(), TextureSettings::new())
+            .map_err(|()| eprintln!("could not load the font, the HUD won't be shown"))
+            .ok();
+        self.window = Some(window);
+        Ok(())
+    }
+
+    fn draw(&mut self, frame: &Frame<'_>) -> Result<()> {
+        let (Some(window), Some(event)) = (&mut self.window, self.render_event.take()) else {
+            return Ok(());
+        };
+        let cell_size = self.cell_size;
+        if let Some(graph) = &mut self.graph {
+            graph.record(frame);
+        }
+        self.hud.tick(Instant::now());
+        let hud = if self.hud.visible { Some(self.hud.lines(frame)) } else { None };
+        let glyphs = self.glyphs.as_mut();
+
+        if frame.paused != self.paused {
+            self.paused = frame.paused;
+            let title = if frame.paused { format!("{} (paused)", self.config.title) } else { self.config.title.clone() };
+            window.set_title(title);
+        }
+
+        // only the cells inside the window are drawn, keeping the initial grid in place when the
+        // board expands
+        let (rows, columns) = frame.grid.shape();
+        let size = window.size();
+        let origin = [frame.origin.1 as f64, frame.origin.0 as f64];
+        self.board = [-origin[0] * cell_size, -origin[1] * cell_size, columns as f64 * cell_size, rows as f64 * cell_size];
+        // physical pixels for each pixel of the window, above 1 on HiDPI displays
+        let draw_size = window.draw_size();
+        let dpi = if size.width > 0.0 && draw_size.width > 0.0 { draw_size.width / size.width } else { 1.0 };
+        let to_cell = |[x, y]: [f64; 2]| [x / cell_size + origin[0], y / cell_size + origin[1]];
+        let [left, top] = to_cell(self.camera.to_board([0.0, 0.0])).map(|coordinate| coordinate.floor().max(0.0) as usize);
+        let [right, bottom] = to_cell(self.camera.to_board([size.width, size.height])).map(|coordinate| coordinate.ceil().max(0.0) as usize);
+        let (visible_rows, visible_columns) = (top.min(rows)..bottom.min(rows), left.min(columns)..right.min(columns));
+
+        let camera = self.camera;
+        let theme = Theme::default();
+        // size of a cell on the display, in physical pixels
+        let pixels = cell_size * camera.zoom * dpi;
+        let density = match &mut self.density {
+            Some(density) if pixels < DENSITY_CELL_SIZE && frame.activity.is_none() && frame.changes.is_none() => {
+                let shape = [visible_rows.len(), visible_columns.len()].map(|cells| ((cells as f64 * pixels).ceil() as usize).max(1));
+                match density.update(frame, (visible_rows.clone(), visible_columns.clone()), (shape[0], shape[1]), &theme) {
+                    Ok(()) => Some(density),
+                    Err(error) => {
+                        eprintln!("{error}, drawing a pixel for each cell instead");
+                        self.density = None;
+                        None
+                    },
+                }
+            },
+            _ => None,
+        };
+        let raster = match &mut self.raster {
+            Some(raster) if density.is_none() && pixels < RASTER_CELL_SIZE => match raster.update(frame, &theme) {
+                Ok(()) => Some(raster),
+                Err(error) => {
+                    eprintln!("{error}, drawing each cell instead");
+                    self.raster = None;
+                    None
+                },
+            },
+            _ => None,
+        };
+        // the minimap is only needed while part of the board is out of view
+        let [view_left, view_top] = camera.to_board([0.0, 0.0]);
+        let [view_right, view_bottom] = camera.to_board(self.viewport);
+        let visible = [view_left, view_top, view_right - view_left, view_bottom - view_top];
+        let [x, y, width, height] = self.board;
+        let zoomed_in = view_left > x || view_top > y || view_right < x + width || view_bottom < y + height;
+        let area = minimap::layout(self.board, [size.width, size.height]).filter(|_| zoomed_in);
+        let minimap = match (&mut self.minimap, area) {
+            (Some(minimap), Some(area)) => match minimap.update(frame, &theme, area, dpi) {
+                Ok(()) => {
+                    minimap.show_at(Some(area));
+                    Some((minimap, area, minimap::from_board(area, self.board, visible)))
+                },
+                Err(error) => {
+                    eprintln!("{error}, the minimap won't be shown");
+                    self.minimap = None;
+                    None
+                },
+            },
+            (minimap, _) => {
+                if let Some(minimap) = minimap {
+                    minimap.show_at(None);
+                }
+                None
+            },
+        };
+        let graph = self.graph.as_ref();
+        let selection = self.selection.map(Selection::bounds);
+        // hexagons have no straight lines between rows
+        let grid_lines = self.grid_lines && pixels >= GRID_CELL_SIZE && frame.topology != Topology::Hex;
+
+        window.draw_2d(&event, |context, graphics, device| {
+            let transform = context.transform.trans(camera.offset[0], camera.offset[1]).zoom(camera.zoom)
+                .trans(-origin[0] * cell_size, -origin[1] * cell_size);
+            clear(theme.dead, graphics);
+
+            if let Some(density) = density {
+                density.flush(device);
+                if let Some(texture) = density.texture() {
+                    let (width, height) = texture.get_size();
+                    let [x, y] = [visible_columns.start, visible_rows.start].map(|start| start as f64 * cell_size);
+                    let [columns, rows] = [visible_columns.len(), visible_rows.len()].map(|cells| cells as f64 * cell_size);
+                    image(texture, transform.trans(x, y).scale(columns / f64::from(width), rows / f64::from(height)), graphics);
+                }
+            } else if let Some(raster) = raster {
+                raster.flush(device);
+                if let Some(texture) = raster.texture() {
+                    image(texture, transform.scale(cell_size, cell_size), graphics);
+                }
+            } else {
+                let live_row = |mut geometry: Geometry, row: usize| {
+                    let (cells, columns) = (&frame.grid[row], (row, visible_columns.clone()));
+                    match (frame.activity, frame.trails, frame.topology) {
+                        (Some(activity), _, _) => geometry.heat_row(activity, columns, &theme, cell_size, transform),
+                        (None, trails, Topology::Hex) => {
+                            if let Some(trails) = trails {
+                                geometry.trail_hexagons(cells, columns.clone(), trails, &theme, cell_size, transform);
+                            }
+                            geometry.live_hexagons(cells, columns, frame.ages, &theme, cell_size, transform);
+                        },
+                        (None, trails, _) => {
+                            if let Some(trails) = trails {
+                                geometry.trail_row(cells, columns.clone(), trails, &theme, cell_size, transform);
+                            }
+                            geometry.live_row(cells, columns, frame.ages, &theme, cell_size, transform);
+                        },
+                    }
+                    geometry
+                };
+                #[cfg(feature = "parallel")]
+                let mut live = visible_rows.clone().into_par_iter()
+                    .fold(Geometry::default, live_row)
+                    .reduce(Geometry::default, Geometry::append);
+                #[cfg(not(feature = "parallel"))]
+                let mut live = visible_rows.clone().fold(Geometry::default(), live_row);
+                if let Some(changes) = frame.changes {
+                    let visible = (visible_rows.clone(), visible_columns.clone());
+                    live.changes(changes, visible, frame.topology, &theme, cell_size, transform);
+                }
+                if grid_lines {
+                    let color = theme::lerp(theme.dead, theme.live, 0.15);
+                    let visible = (visible_rows.clone(), visible_columns.clone());
+                    live.grid_lines(color, visible, cell_size, 1.0 / (camera.zoom * dpi), transform);
+                }
+                live.draw(&context.draw_state, graphics);
+            }
+
+            for &(row, col) in frame.agents {
+                let (x, y) = (col as f64, row as f64);
+                let rect = rectangle::square(x * cell_size, y * cell_size, cell_size);
+                rectangle(theme.agent, rect, transform, graphics);
+            }
+
+            if let Some(((top, left), (bottom, right))) = selection {
+                let (x, y) = (left as f64 * cell_size, top as f64 * cell_size);
+                let area = [x, y, (right + 1 - left) as f64 * cell_size, (bottom + 1 - top) as f64 * cell_size];
+                let [red, green, blue, _] = theme.selection;
+                Rectangle::new([red, green, blue, 0.25]).border(rectangle::Border { color: theme.selection, radius: 1.0 / camera.zoom })
+                    .draw(area, &context.draw_state, transform, graphics);
+            }
+
+            if let Some((minimap, [x, y, width, height], visible)) = minimap {
+                minimap.flush(device);
+                if let Some(texture) = minimap.texture() {
+                    let (columns, rows) = texture.get_size();
+                    let border = rectangle::Border { color: theme::lerp(theme.dead, theme.live, 0.5), radius: 1.0 };
+                    Rectangle::new(theme.dead).border(border).draw([x, y, width, height], &context.draw_state, context.transform, graphics);
+                    image(texture, context.transform.trans(x, y).scale(width / f64::from(columns), height / f64::from(rows)), graphics);
+                    Rectangle::new_border(theme.selection, 1.0).draw(visible, &context.draw_state, context.transform, graphics);
+                }
+            }
+
+            if let Some(graph) = graph {
+                let strip = context.transform.trans(0.0, size.height - GRAPH_HEIGHT);
+                rectangle(theme::lerp(theme.dead, theme.live, 0.1), [0.0, 0.0, size.width, GRAPH_HEIGHT], strip, graphics);
+                for segment in graph.segments(size.width, GRAPH_HEIGHT) {
+                    line(theme.live, 0.5, segment, strip, graphics);
+                }
+            }
+
+            if let (Some(lines), Some(glyphs)) = (hud, glyphs) {
+                let longest = lines.iter().map(|line| line.chars().count()).max().unwrap_or(0);
+                let line_height = f64::from(HUD_FONT_SIZE) + HUD_SPACING;
+                // monospaced glyphs are about 0.6 em wide
+                let width = longest as f64 * 0.6 * f64::from(HUD_FONT_SIZE) + 2.0 * HUD_SPACING;
+                let height = lines.len() as f64 * line_height + HUD_SPACING;
+                let [red, green, blue, _] = theme.dead;
+                rectangle([red, green, blue, 0.8], [0.0, 0.0, width, height], context.transform, graphics);
+
+                // glyphs are rasterized at the resolution of the display, then scaled back
+                let font_size = (f64::from(HUD_FONT_SIZE) * dpi).round() as u32;
+                for (index, line) in lines.iter().enumerate() {
+                    let baseline = (index + 1) as f64 * line_height;
+                    let transform = context.transform.trans(HUD_SPACING, baseline).zoom(1.0 / dpi);
+                    if text(theme.live, font_size, line, glyphs, transform, graphics).is_err() {
+                        break;
+                    }
+                }
+                glyphs.factory.encoder.flush(device);
+            }
+        });
+        Ok(())
+    }
+
+    fn handle_input(&mut self) -> Input {
+        let Some(window) = &mut self.window else {
+            return Input::Quit;
+        };
+
+        for event in window.by_ref() {
+            if let Some(position) = event.mouse_cursor_args() {
+                if self.dragging {
+                    self.camera.pan([position[0] - self.cursor[0], position[1] - self.cursor[1]]);
+                }
+                self.cursor = position;
+                if let (true, Some(selection)) = (self.selecting, &mut self.selection) {
+                    let corner = cell_at(self.camera, self.board, self.cell_size, position, true);
+                    selection.corner = corner.unwrap_or(selection.corner);
+                }
+            }
+            if let Some(args) = event.resize_args() {
+                // the graph keeps its height at the bottom
+                let [width, height] = args.window_size;
+                self.viewport = [width, if self.graph.is_some() { (height - GRAPH_HEIGHT).max(0.0) } else { height }];
+                self.camera = self.restored.take().unwrap_or_else(|| Camera::fit(self.board, self.viewport));
+            }
+            if let Some([_, scroll]) = event.mouse_scroll_args() {
+                self.camera.zoom_at(self.cursor, ZOOM_STEP.powf(scroll));
+            }
+            match event.release_args() {
+                Some(Button::Mouse(MouseButton::Left)) => (self.dragging, self.selecting) = (false, false),
+                Some(Button::Keyboard(Key::LShift | Key::RShift)) => self.shift = false,
+                Some(Button::Keyboard(Key::LCtrl | Key::RCtrl)) => self.ctrl = false,
+                _ => (),
+            }
+
+            let on_minimap = |minimap: &Minimap| minimap.area().and_then(|area| minimap::to_board(area, self.board, self.cursor));
+            if let (Some(Button::Mouse(MouseButton::Left)), Some(point)) = (event.press_args(), self.minimap.as_ref().and_then(on_minimap)) {
+                self.camera.center_on(point, self.viewport);
+                continue;
+            }
+            match event.press_args() {
+                Some(Button::Mouse(MouseButton::Left)) if self.shift => {
+                    let cell = cell_at(self.camera, self.board, self.cell_size, self.cursor, false);
+                    self.selection = cell.map(|cell| Selection { anchor: cell, corner: cell });
+                    self.selecting = self.selection.is_some();
+                },
+                Some(Button::Mouse(MouseButton::Left)) if self.ctrl => {
+                    if let Some(cell) = cell_at(self.camera, self.board, self.cell_size, self.cursor, false) {
+                        return Input::Toggle(cell);
+                    }
+                },
+                Some(Button::Mouse(MouseButton::Left)) => (self.dragging, self.selection) = (true, None),
+                Some(Button::Keyboard(Key::LCtrl | Key::RCtrl)) => self.ctrl = true,
+                Some(Button::Keyboard(Key::C)) if self.ctrl => {
+                    if let Some((top_left, bottom_right)) = self.selection.map(Selection::bounds) {
+                        return Input::Copy(top_left, bottom_right);
+                    }
+                },
+                Some(Button::Keyboard(Key::Delete | Key::Backspace)) => {
+                    if let Some((top_left, bottom_right)) = self.selection.map(Selection::bounds) {
+                        return Input::Clear(top_left, bottom_right);
+                    }
+                },
+                Some(Button::Keyboard(Key::Z)) if self.ctrl && self.shift => return Input::Redo,
+                Some(Button::Keyboard(Key::Z)) if self.ctrl => return Input::Undo,
+                Some(Button::Keyboard(Key::Y)) if self.ctrl => return Input::Redo,
+                Some(Button::Keyboard(Key::V)) if self.ctrl => {
+                    if let Some(cell) = cell_at(self.camera, self.board, self.cell_size, self.cursor, false) {
+                        return Input::Paste(cell);
+                    }
+                },
+                Some(Button::Keyboard(Key::Left)) if self.paused && !self.shift => return Input::StepBack,
+                Some(Button::Keyboard(Key::Right)) if self.paused && !self.shift => return Input::Step,
+                Some(Button::Keyboard(Key::Left)) => self.camera.pan([PAN_STEP, 0.0]),
+                Some(Button::Keyboard(Key::Right)) => self.camera.pan([-PAN_STEP, 0.0]),
+                Some(Button::Keyboard(Key::Up)) => self.camera.pan([0.0, PAN_STEP]),
+                Some(Button::Keyboard(Key::Down)) => self.camera.pan([0.0, -PAN_STEP]),
+                Some(Button::Keyboard(Key::D0 | Key::Home)) => self.camera = Camera::fit(self.board, self.viewport),
+                Some(Button::Keyboard(Key::Space)) => return Input::Pause,
+                Some(Button::Keyboard(Key::Period | Key::N)) => return Input::Step,
+                Some(Button::Keyboard(Key::Comma | Key::B)) => return Input::StepBack,
+                Some(Button::Keyboard(Key::R)) => return Input::Randomize,
+                Some(Button::Keyboard(Key::Plus | Key::Equals | Key::NumPadPlus)) => return Input::Faster,
+                Some(Button::Keyboard(Key::Minus | Key::NumPadMinus)) => return Input::Slower,
+                Some(Button::Keyboard(Key::S)) => return Input::Save,
+                Some(Button::Keyboard(Key::L)) => return Input::Load,
+                Some(Button::Keyboard(Key::LShift | Key::RShift)) => self.shift = true,
+                Some(Button::Keyboard(Key::G)) if self.shift => return Input::Grow,
+                Some(Button::Keyboard(Key::G)) => self.grid_lines = !self.grid_lines,
+                Some(Button::Keyboard(Key::H)) => return Input::Heatmap,
+                Some(Button::Keyboard(Key::D)) => return Input::Changes,
+                Some(Button::Keyboard(Key::F1)) => self.hud.visible = !self.hud.visible,
+                _ => (),
+            }
+
+            if event.render_args().is_some() {
+                self.render_event = Some(event);
+                return Input::Frame;
+            }
+        }
+        Input::Quit
+    }
+
+    #[inline]
+    fn cell_size(&self) -> f64 {
+        self.cell_size
+    }
+
+    fn resize(&mut self, shape: (usize, usize), cell_size: f64) {
+        self.cell_size = cell_size;
+        let size = self.window_size(cell_size, shape);
+        if let Some(window) = &mut self.window {
+            window.set_size(size);
+        }
+    }
+
+    #[inline]
+    fn view(&self) -> Option<View> {
+        Some(View { zoom: self.camera.zoom, offset: self.camera.offset })
+    }
+
+    fn set_view(&mut self, View { zoom, offset }: View) {
+        self.camera = Camera { zoom: zoom.clamp(Camera::MIN_ZOOM, Camera::MAX_ZOOM), offset };
+        self.restored = Some(self.camera);
+    }
+}
+
+/// Draws a continuous-state automaton, with each cell colored as in [`Theme::by_value`].
+#[cfg(feature = "continuous")]
+pub struct FieldRenderer<E> {
+    window: PistonWindow,
+    cell_size: f64,
+    engine: E,
+    field: Field,
+    update_interval: Duration,
+    last_update_time: Instant,
+}
+
+#[cfg(feature = "continuous")]
+impl<E: ContinuousEngine> FieldRenderer<E> {
+    pub fn new(cell_size: f64, engine: E, field: Field, update_interval: Duration) -> Result<Self> {
+        Self::with_window(&WindowConfig::new("Continuous Life"), cell_size, engine, field, update_interval)
+    }
+
+    /// Like [`FieldRenderer::new`], but with custom window settings.
+    pub fn with_window(config: &WindowConfig, cell_size: f64, engine: E, field: Field, update_interval: Duration) -> Result<Self> {
+        let window = config.build(window_size(cell_size, field.shape()))?;
+
+        let last_update_time = Instant::now() - update_interval;
+
+        Ok(Self { window, cell_size, engine, field, update_interval, last_update_time })
+    }
+
+    fn update(&mut self) -> Option<()> {
+        if self.last_update_time.elapsed() >= self.update_interval {
+            self.field = self.engine.update(&self.field);
+            self.last_update_time = Instant::now();
+            Some(())
+        } else {
+            None
+        }
+    }
+
+    fn render(&mut self, event: &Event) -> Option<()> {
+        self.window.draw_2d(event, |context, graphics, _device| {
+            let theme = Theme::default();
+            let cell_size = self.cell_size;
+
+            clear(theme.dead, graphics);
+            for (row, values) in self.field.iter().enumerate() {
+                for (col, &value) in values.iter().enumerate() {
+                    if let Some(color) = theme.by_value(value) {
+                        let (x, y) = (col as f64, row as f64);
+                        let rect = rectangle::square(x * cell_size, y * cell_size, cell_size);
+                        rectangle(color, rect, context.transform, graphics);
+                    }
+                }
+            }
+        })
+    }
+
+    /// Runs until the window is closed or `max_iter` generations are computed.
+    pub fn run(mut self, max_iter: usize) {
+        let mut generations = 0;
+
+        while generations < max_iter {
+            let Some(event) = self.window.next() else {
+                return;
+            };
+            if event.update_args().is_some() && self.update().is_some() {
+                generations += 1;
+            }
+
+            if event.render_args().is_some() {
+                self.render(&event);
+            }
+        }
+    }
+}
+
+/// Draws a multi-state automaton, like a [`GenerationsRule`](crate::rule::GenerationsRule) or
+/// Wireworld, with each cell colored by [`MultiStateEngine::color`].
+pub struct StatesRenderer<E> {
+    window: PistonWindow,
+    cell_size: f64,
+    engine: E,
+    states: States,
+    update_interval: Duration,
+    last_update_time: Instant,
+}
+
+impl<E: MultiStateEngine> StatesRenderer<E> {
+    pub fn new(cell_size: f64, engine: E, states: States, update_interval: Duration) -> Result<Self> {
+        Self::with_window(&WindowConfig::new("Multi-state Life"), cell_size, engine, states, update_interval)
+    }
+
+    /// Like [`StatesRenderer::new`], but with custom window settings.
+    pub fn with_window(config: &WindowConfig, cell_size: f64, engine: E, states: States, update_interval: Duration) -> Result<Self> {
+        let window = config.build(window_size(cell_size, states.shape()))?;
+
+        let last_update_time = Instant::now() - update_interval;
+
+        Ok(Self { window, cell_size, engine, states, update_interval, last_update_time })
+    }
+
+    fn update(&mut self) -> Option<()> {
+        if self.last_update_time.elapsed() >= self.update_interval {
+            self.states = self.engine.update(&self.states);
+            self.last_update_time = Instant::now();
+            Some(())
+        } else {
+            None
+        }
+    }
+
+    fn render(&mut self, event: &Event) -> Option<()> {
+        self.window.draw_2d(event, |context, graphics, _device| {
+            let theme = Theme::default();
+            let cell_size = self.cell_size;
+
+            clear(theme.dead, graphics);
+            for (row, cells) in self.states.iter().enumerate() {
+                for (col, &state) in cells.iter().enumerate() {
+                    if let Some(color) = self.engine.color(&theme, state) {
+                        let (x, y) = (col as f64, row as f64);
+                        let rect = rectangle::square(x * cell_size, y * cell_size, cell_size);
+                        rectangle(color, rect, context.transform, graphics);
+                    }
+                }
+            }
+        })
+    }
+
+    /// Runs until the window is closed or `max_iter` generations are computed.
+    pub fn run(mut self, max_iter: usize) {
+        let mut generations = 0;
+
+        while generations < max_iter {
+            let Some(event) = self.window.next() else {
+                return;
+            };
+            if event.update_args().is_some() && self.update().is_some() {
+                generations += 1;
+            }
+
+            if event.render_args().is_some() {
+                self.render(&event);
+            }
+        }
+    }
+}
+
+/// Runs [3D Life](crate::life3d) in a window, showing one `z`-slice of the space at a time.
+///
+/// `[` and `]` (or `PageDown` and `PageUp`) move to the slice below or above. The slice and the
+/// generation are shown in the window title.
+pub struct LayersRenderer {
+    window: PistonWindow,
+    cell_size: f64,
+    engine: Engine3d,
+    space: Grid3,
+    z: usize,
+    generation: usize,
+    update_interval: Duration,
+    last_update_time: Instant,
+}
+
+impl LayersRenderer {
+    pub fn new(cell_size: f64, engine: Engine3d, space: Grid3, update_interval: Duration) -> Result<Self> {
+        Self::with_window(&WindowConfig::new("3D Life"), cell_size, engine, space, update_interval)
+    }
+
+    /// Like [`LayersRenderer::new`], but with custom window settings.
+    pub fn with_window(config: &WindowConfig, cell_size: f64, engine: Engine3d, space: Grid3, update_interval: Duration) -> Result<Self> {
+        let window = config.build(window_size(cell_size, (space.rows(), space.columns())))?;
+
+        let last_update_time = Instant::now() - update_interval;
+        // start from the middle slice, which is the most active one for random spaces
+        let z = space.depth() / 2;
+
+        Ok(Self { window, cell_size, engine, space, z, generation: 0, update_interval, last_update_time })
+    }
+
+    #[inline]
+    #[must_use]
+    /// The slice being shown.
+    pub const fn z(&self) -> usize {
+        self.z
+    }
+
+    fn update(&mut self) -> Option<()> {
+        if self.last_update_time.elapsed() >= self.update_interval {
+            self.space = self.engine.update(&self.space);
+            self.generation += 1;
+            self.last_update_time = Instant::now();
+            Some(())
+        } else {
+            None
+        }
+    }
+
+    fn render(&mut self, event: &Event) -> Option<()> {
+        let theme = Theme::default();
+        let cell_size = self.cell_size;
+        let layer = self.space.layer(self.z);
+
+        self.window.draw_2d(event, |context, graphics, _device| {
+            clear(theme.dead, graphics);
+            for (row, col) in layer.into_iter().flat_map(Grid::live_cells) {
+                let (x, y) = (col as f64, row as f64);
+                let rect = rectangle::square(x * cell_size, y * cell_size, cell_size);
+                rectangle(theme.live, rect, context.transform, graphics);
+            }
+        })
+    }
+
+    fn title(&self) -> String {
+        let depth = self.space.depth();
+        format!("3D Life {}: z = {} of {depth} | generation {}", self.engine.rule(), self.z, self.generation)
+    }
+
+    /// Runs until the window is closed or `max_iter` generations are computed.
+    pub fn run(mut self, max_iter: usize) {
+        let mut title = String::new();
+
+        while self.generation < max_iter {
+            let Some(event) = self.window.next() else {
+                return;
+            };
+            match event.press_args() {
+                Some(Button::Keyboard(Key::LeftBracket | Key::PageDown)) => self.z = self.z.saturating_sub(1),
+                Some(Button::Keyboard(Key::RightBracket | Key::PageUp)) => {
+                    self.z = (self.z + 1).min(self.space.depth().saturating_sub(1));
+                },
+                _ => (),
+            }
+
+            if event.update_args().is_some() {
+                self.update();
+            }
+
+            if event.render_args().is_some() {
+                self.render(&event);
+            }
+
+            let current = self.title();
+            if current != title {
+                self.window.set_title(current.clone());
+                title = current;
+            }
+        }
+    }
+}
+
+/// Plays the [`ImmigrationGame`] in a window.
+///
+/// While placing, a left click puts a cell for the current player and `Enter` passes the turn.
+/// The phase and the scores are shown in the window title.
+pub struct GameRenderer {
+    window: PistonWindow,
+    cell_size: f64,
+    game: ImmigrationGame,
+    cursor: [f64; 2],
+    update_interval: Duration,
+    last_update_time: Instant,
+}
+
+impl GameRenderer {
+    pub fn new(cell_size: f64, game: ImmigrationGame, update_interval: Duration) -> Result<Self> {
+        Self::with_window(&WindowConfig::new("Immigration"), cell_size, game, update_interval)
+    }
+
+    /// Like [`GameRenderer::new`], but with custom window settings.
+    pub fn with_window(config: &WindowConfig, cell_size: f64, game: ImmigrationGame, update_interval: Duration) -> Result<Self> {
+        let window = config.build(window_size(cell_size, game.grid().shape()))?;
+
+        let last_update_time = Instant::now() - update_interval;
+
+        Ok(Self { window, cell_size, game, cursor: [0.0, 0.0], update_interval, last_update_time })
+    }
+
+    #[inline]
+    #[must_use]
+    /// The match being played.
+    pub const fn game(&self) -> &ImmigrationGame {
+        &self.game
+    }
+
+    fn update(&mut self) {
+        if self.last_update_time.elapsed() >= self.update_interval {
+            self.game.step();
+            self.last_update_time = Instant::now();
+        }
+    }
+
+    fn render(&mut self, event: &Event) -> Option<()> {
+        let theme = Theme::default();
+        let cell_size = self.cell_size;
+        let game = &self.game;
+
+        self.window.draw_2d(event, |context, graphics, _device| {
+            clear(theme.dead, graphics);
+
+            let (rows, columns) = game.grid().shape();
+            for row in 0..rows {
+                for col in 0..columns {
+                    if let Some(player) = game.owner(row, col) {
+                        let (x, y) = (col as f64, row as f64);
+                        let rect = rectangle::square(x * cell_size, y * cell_size, cell_size);
+                        rectangle(theme.players[player.index()], rect, context.transform, graphics);
+                    }
+                }
+            }
+        })
+    }
+
+    fn title(&self) -> String {
+        let [red, blue] = self.game.scores();
+        let status = match self.game.phase() {
+            Phase::Placing { player, remaining, .. } => format!("{player:?} to place, {remaining} left (Enter to pass)"),
+            Phase::Running => format!("generation {}", self.game.generation()),
+            Phase::Finished(Outcome::Winner(player)) => format!("{player:?} wins"),
+            Phase::Finished(Outcome::Draw) => "draw".to_owned(),
+        };
+        format!("Immigration: {status} | Red {red} x {blue} Blue")
+    }
+
+    /// Runs until the window is closed, returning the final state of the match.
+    pub fn run(mut self) -> ImmigrationGame {
+        let mut title = String::new();
+
+        while let Some(event) = self.window.next() {
+            if let Some(position) = event.mouse_cursor_args() {
+                self.cursor = position;
+            }
+
+            match event.press_args() {
+                Some(Button::Mouse(MouseButton::Left)) => {
+                    let [x, y] = self.cursor.map(|coordinate| (coordinate / self.cell_size).floor());
+                    if x >= 0.0 && y >= 0.0 {
+                        self.game.place(y as usize, x as usize);
+                    }
+                },
+                Some(Button::Keyboard(Key::Return)) => self.game.end_turn(),
+                _ => (),
+            }
+
+            if event.update_args().is_some() {
+                self.update();
+            }
+
+            if event.render_args().is_some() {
+                self.render(&event);
+            }
+
+            let current = self.title();
+            if current != title {
+                self.window.set_title(current.clone());
+                title = current;
+            }
+        }
+
+        self.game
+    }
+}
+
+/// Space between the two boards of a [`CompareRenderer`], in pixels.
+const COMPARE_GAP: f64 = 8.0;
+
+/// Runs two engines side by side in a window, from the same grid, to check a new engine by eye.
+///
+/// The first engine is drawn on the left and the second on the right, with the cells where they
+/// disagree in the [`mismatch`](Theme::mismatch) color on both sides. `Space` pauses or resumes,
+/// `N` or `.` advances one generation while paused and `+` and `-` change the speed. The
+/// generation and where the engines first disagreed are shown in the window title.
+pub struct CompareRenderer<A, B> {
+    window: PistonWindow,
+    cell_size: f64,
+    comparison: Comparison<A, B>,
+    paused: bool,
+    update_interval: Duration,
+    last_update_time: Instant,
+}
+
+impl<A: Engine, B: Engine> CompareRenderer<A, B> {
+    pub fn new(cell_size: f64, comparison: Comparison<A, B>, update_interval: Duration) -> Result<Self> {
+        Self::with_window(&WindowConfig::new("Comparison"), cell_size, comparison, update_interval)
+    }
+
+    /// Like [`CompareRenderer::new`], but with custom window settings.
+    pub fn with_window(config: &WindowConfig, cell_size: f64, comparison: Comparison<A, B>, update_interval: Duration) -> Result<Self> {
+        let [width, height] = window_size(cell_size, comparison.grids().0.shape());
+        let window = config.build([2.0 * width + COMPARE_GAP, height])?;
+
+        let last_update_time = Instant::now() - update_interval;
+
+        Ok(Self { window, cell_size, comparison, paused: false, update_interval, last_update_time })
+    }
+
+    #[inline]
+    #[must_use]
+    /// The engines being compared.
+    pub const fn comparison(&self) -> &Comparison<A, B> {
+        &self.comparison
+    }
+
+    fn update(&mut self) {
+        if !self.paused && self.last_update_time.elapsed() >= self.update_interval {
+            self.comparison.step();
+            self.last_update_time = Instant::now();
+        }
+    }
+
+    fn render(&mut self, event: &Event) -> Option<()> {
+        let theme = Theme::default();
+        let cell_size = self.cell_size;
+        let comparison = &self.comparison;
+        let (first, second) = comparison.grids();
+        let offset = window_size(cell_size, first.shape())[0] + COMPARE_GAP;
+
+        self.window.draw_2d(event, |context, graphics, _device| {
+            clear(theme::lerp(theme.dead, theme.live, 0.2), graphics);
+
+            for (grid, transform) in [(first, context.transform), (second, context.transform.trans(offset, 0.0))] {
+                let [width, height] = window_size(cell_size, grid.shape());
+                rectangle(theme.dead, [0.0, 0.0, width, height], transform, graphics);
+
+                let mut geometry = Geometry::default();
+                for (row, cells) in grid.iter().enumerate() {
+                    geometry.live_row(cells, (row, 0..cells.len()), None, &theme, cell_size, transform);
+                }
+                for (row, col) in comparison.mismatches().changed() {
+                    let (x, y) = (col as f64, row as f64);
+                    geometry.rectangle(theme.mismatch, rectangle::square(x * cell_size, y * cell_size, cell_size), transform);
+                }
+                geometry.draw(&context.draw_state, graphics);
+            }
+        })
+    }
+
+    fn title(&self) -> String {
+        let (first, second) = self.comparison.names();
+        let differ = self.comparison.mismatches().len();
+        let status = match self.comparison.first_mismatch() {
+            None => "same cells".to_owned(),
+            Some(generation) if differ == 0 => format!("same cells, but they differed at generation {generation}"),
+            Some(generation) => format!("{differ} cells differ, first at generation {generation}"),
+        };
+        let paused = if self.paused { " (paused)" } else { "" };
+        format!("{first} x {second}: generation {}, {status}{paused}", self.comparison.generation())
+    }
+
+    /// Runs until the window is closed, returning the engines as they were last shown.
+    pub fn run(mut self) -> Comparison<A, B> {
+        let mut title = String::new();
+
+        while let Some(event) = self.window.next() {
+            match event.press_args() {
+                Some(Button::Keyboard(Key::Space)) => self.paused = !self.paused,
+                Some(Button::Keyboard(Key::Period | Key::N)) if self.paused => self.comparison.step(),
+                Some(Button::Keyboard(Key::Plus | Key::Equals | Key::NumPadPlus)) => self.update_interval /= 2,
+                Some(Button::Keyboard(Key::Minus | Key::NumPadMinus)) => {
+                    self.update_interval = (self.update_interval * 2).clamp(Duration::from_millis(1), super::MAX_UPDATE_INTERVAL.max(self.update_interval));
+                },
+                _ => (),
+            }
+
+            if event.update_args().is_some() {
+                self.update();
+            }
+
+            if event.render_args().is_some() {
+                self.render(&event);
+            }
+
+            let current = self.title();
+            if current != title {
+                self.window.set_title(current.clone());
+                title = current;
+            }
+        }
+
+        self.comparison
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn zoom_keeps_cursor_point() {
+        let mut camera = Camera::IDENTITY;
+        camera.pan([10.0, -4.0]);
+        let cursor = [120.0, 45.0];
+        let before = camera.to_board(cursor);
+
+        camera.zoom_at(cursor, 3.0);
+        assert_eq!(camera.zoom, 3.0);
+        assert_eq!(camera.to_board(cursor), before);
+
+        camera.zoom_at(cursor, 1000.0);
+        assert_eq!(camera.zoom, Camera::MAX_ZOOM);
+        assert_eq!(camera.to_board([0.0, 0.0]).map(f64::floor), [108.0, 48.0]);
+    }
+
+    #[test]
+    pub fn cells_under_the_cursor() {
+        let mut camera = Camera::IDENTITY;
+        let board = [-20.0, 0.0, 100.0, 50.0];
+        assert_eq!(cell_at(camera, board, 10.0, [5.0, 15.0], false), Some((1, 2)));
+        assert_eq!(cell_at(camera, board, 10.0, [85.0, 15.0], false), None);
+        assert_eq!(cell_at(camera, board, 10.0, [85.0, -15.0], true), Some((0, 9)));
+        assert_eq!(cell_at(camera, [0.0; 4], 10.0, [5.0, 5.0], true), None);
+
+        camera.zoom_at([0.0, 0.0], 2.0);
+        assert_eq!(cell_at(camera, board, 10.0, [5.0, 15.0], false), Some((0, 2)));
+
+        let selection = Selection { anchor: (4, 1), corner: (2, 3) };
+        assert_eq!(selection.bounds(), ((2, 1), (4, 3)));
+    }
+
+    #[test]
+    pub fn fit_centers_the_board() {
+        let camera = Camera::fit([0.0, 0.0, 100.0, 50.0], [400.0, 400.0]);
+        assert_eq!((camera.zoom, camera.offset), (4.0, [0.0, 100.0]));
+        assert_eq!(camera.to_board([200.0, 200.0]), [50.0, 25.0]);
+
+        let mut camera = Camera::fit([10.0, 10.0, 20.0, 20.0], [30.0, 60.0]);
+        assert_eq!((camera.zoom, camera.offset), (1.5, [-15.0, 0.0]));
+        // like clicking the minimap
+        camera.center_on([12.0, 40.0], [30.0, 60.0]);
+        assert_eq!(camera.to_board([15.0, 30.0]), [12.0, 40.0]);
+
+        assert_eq!(Camera::fit([0.0, 0.0, 0.0, 10.0], [400.0, 400.0]), Camera::IDENTITY);
+    }
+
+    #[test]
+    pub fn hud_lines() {
+        let mut hud = Hud::HIDDEN;
+        let start = Instant::now();
+        for frame in 0..=30 {
+            hud.tick(start + Duration::from_millis(frame * 50));
+        }
+        // only the frames of the last second are counted
+        assert_eq!(hud.frames.len(), 21);
+        assert!((hud.fps() - 20.0).abs() < 1e-9);
+
+        let glider = crate::patterns::glider();
+        let frame = Frame {
+            grid: &glider,
+            generation: 42,
+            engine: "sparse",
+            step_time: Duration::from_micros(1500),
+            agents: &[],
+            ages: None,
+            activity: None,
+            trails: None,
+            changes: None,
+            paused: false,
+            update_interval: Duration::ZERO,
+            revision: 7,
+            origin: (0, 0),
+            topology: Topology::default(),
+        };
+        assert_eq!(hud.lines(&frame), [
+            "generation 42",
+            "population 5",
+            "update     1.50ms",
+            "fps        20.0",
+            "engine     sparse",
+        ]);
+        // the population is counted again only for new grids
+        assert_eq!(hud.lines(&Frame { grid: &Grid::new(3, 3), ..frame })[1], "population 5");
+        assert_eq!(hud.lines(&Frame { grid: &Grid::new(3, 3), revision: 8, ..frame })[1], "population 0");
+        assert_eq!(Hud::HIDDEN.fps(), 0.0);
+    }
+
+    #[test]
+    pub fn graph_of_last_generations() {
+        let grids = [Grid::new(4, 4), crate::patterns::blinker(), crate::patterns::glider()];
+        let frame = |grid, generation, revision| Frame {
+            grid,
+            generation,
+            engine: "serial",
+            step_time: Duration::ZERO,
+            agents: &[],
+            ages: None,
+            activity: None,
+            trails: None,
+            changes: None,
+            paused: false,
+            update_interval: Duration::ZERO,
+            revision,
+            origin: (0, 0),
+            topology: Topology::default(),
+        };
+
+        let mut graph = PopulationGraph::default();
+        for generation in 0..GRAPH_LENGTH + 9 {
+            graph.record(&frame(&grids[generation % 3], generation, generation as u64));
+            // redrawing the same revision doesn't add a sample
+            graph.record(&frame(&grids[generation % 3], generation, generation as u64));
+        }
+        assert_eq!(graph.populations.len(), GRAPH_LENGTH);
+        assert_eq!(graph.populations.back(), Some(&3));
+
+        let segments = graph.segments(511.0, 50.0);
+        assert_eq!(segments.len(), GRAPH_LENGTH - 1);
+        assert_eq!(segments[0], [0.0, 50.0, 1.0, 20.0]);
+        assert_eq!(segments[1], [1.0, 20.0, 2.0, 0.0]);
+
+        graph.record(&frame(&grids[2], 0, 0));
+        assert_eq!(graph.populations, [5]);
+        assert!(graph.segments(511.0, 50.0).is_empty());
+    }
+}
@@ -0,0 +1,315 @@
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender, TryRecvError};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use crate::age::Ages;
+use crate::cell::{Grid, GridDelta, Topology};
+use crate::engine::Engine;
+use crate::heatmap::Heatmap;
+use crate::noise::Noise;
+use crate::session::Session;
+use crate::simulation::{Simulation, StopReason};
+use crate::trail::Trails;
+
+/// The state of a simulation at some generation, as published by its worker thread.
+///
+/// The grid and the ages are shared with the simulation instead of copied, see
+/// [`Simulation::shared_grid`], so taking a snapshot is cheap even for large boards.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Snapshot {
+    pub grid: Arc<Grid>,
+    pub generation: usize,
+    /// Positions `(row, col)` of the agents of the engine, see [`Engine::agents`].
+    pub agents: Vec<(usize, usize)>,
+    /// Age of each cell, if tracked by the simulation, see [`Simulation::track_ages`].
+    pub ages: Option<Arc<Ages>>,
+    /// How often each cell has been alive, if tracked by the simulation, see
+    /// [`Simulation::track_activity`].
+    pub activity: Option<Arc<Heatmap>>,
+    /// Where cells died recently, if tracked by the simulation, see [`Simulation::track_trails`].
+    pub trails: Option<Arc<Trails>>,
+    /// Cells born and dead in the last generation, if tracked by the simulation, see
+    /// [`Simulation::track_changes`].
+    pub changes: Option<Arc<GridDelta>>,
+    /// Why the simulation stopped, if it did.
+    pub stop_reason: Option<StopReason>,
+    /// Time the engine took to compute the last generation, or zero before a [`Worker`] computed
+    /// one.
+    pub step_time: Duration,
+    /// Changes whenever the grid changes, see [`Frame::revision`](super::Frame::revision).
+    pub revision: u64,
+    /// Position of the initial grid in an expanding board, see [`Simulation::origin`].
+    pub origin: (usize, usize),
+    /// How the cells are arranged, from the rule of the engine.
+    pub topology: Topology,
+    /// Seed of the initial random board, see [`Simulation::seed`].
+    pub seed: Option<u64>,
+    /// Random flips between generations, see [`Simulation::noise`].
+    pub noise: Option<Noise>,
+    /// How far back repeated boards are looked for, see [`Simulation::cycle_window`].
+    pub cycle_window: Option<usize>,
+}
+
+impl Snapshot {
+    #[must_use]
+    /// The current state of a simulation.
+    pub fn of<E: Engine>(simulation: &Simulation<E>, revision: u64) -> Self {
+        Self {
+            grid: simulation.shared_grid(),
+            generation: simulation.generation(),
+            agents: simulation.engine().agents(),
+            ages: simulation.shared_ages(),
+            activity: simulation.shared_activity(),
+            trails: simulation.shared_trails(),
+            changes: simulation.shared_changes(),
+            stop_reason: simulation.stop_reason(),
+            step_time: Duration::ZERO,
+            revision,
+            origin: simulation.origin(),
+            topology: simulation.engine().life_rule().map(|rule| rule.topology()).unwrap_or_default(),
+            seed: simulation.seed(),
+            noise: simulation.noise().cloned(),
+            cycle_window: simulation.cycle_window(),
+        }
+    }
+}
+
+/// Changes to a running simulation, applied by the worker between generations.
+#[derive(Debug, Clone, PartialEq)]
+pub(super) enum Command {
+    Pause(bool),
+    /// Advance one generation, if paused.
+    Step,
+    /// Go back one generation, if paused and it was kept.
+    StepBack,
+    /// Start again from a random board with the same shape, at generation 0.
+    Randomize,
+    /// Continue from a saved session.
+    Restore(Box<Session>),
+    /// Add dead cells past every edge of the board.
+    Grow(usize),
+    /// Copy a pattern into the board, with its top-left corner at `(row, col)`.
+    Paste(Grid, (usize, usize)),
+    UpdateInterval(Duration),
+}
+
+/// What the worker shares with the [`Player`](super::Player).
+#[derive(Debug, Default)]
+struct Shared {
+    state: Mutex<Published>,
+    changed: Condvar,
+}
+
+#[derive(Debug, Default)]
+struct Published {
+    /// The latest snapshot not yet taken by the player.
+    snapshot: Option<Snapshot>,
+    /// Commands applied so far.
+    handled: u64,
+    /// Set when the worker thread exits, even by a panic.
+    stopped: bool,
+}
+
+impl Shared {
+    fn lock(&self) -> MutexGuard<'_, Published> {
+        // a panic in the worker is reported when joining it
+        self.state.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// Handle to a thread computing the generations of a [`Simulation`], so a slow engine never
+/// blocks the frontend.
+///
+/// The worker paces itself with the update interval and publishes a [`Snapshot`] after each
+/// generation, replacing the previous one if it wasn't taken yet. Publishing only holds the lock
+/// for as long as it takes to swap a few pointers, so the frontend never blocks the engine.
+#[derive(Debug)]
+pub(super) struct Worker<E> {
+    commands: Sender<Command>,
+    shared: Arc<Shared>,
+    thread: JoinHandle<Simulation<E>>,
+    /// Commands sent so far.
+    sent: u64,
+}
+
+impl<E: Engine + Send + 'static> Worker<E> {
+    #[must_use]
+    /// Starts computing generations in a new thread. Unless `paused`, the first one is due
+    /// immediately.
+    pub fn spawn(simulation: Simulation<E>, update_interval: Duration, paused: bool, revision: u64) -> Self {
+        let (commands, receiver) = mpsc::channel();
+        let shared = Arc::<Shared>::default();
+
+        let runner = Runner {
+            simulation,
+            commands: receiver,
+            shared: Arc::clone(&shared),
+            update_interval,
+            paused,
+            revision,
+            published: Some(revision),
+            step_time: Duration::ZERO,
+        };
+        let thread = thread::spawn(move || runner.run());
+
+        Self { commands, shared, thread, sent: 0 }
+    }
+
+    /// Sends a command and waits until it is applied, which may take until the current generation
+    /// is computed. Returns the snapshot after the command, unless the worker stopped.
+    pub fn send(&mut self, command: Command) -> Option<Snapshot> {
+        if self.commands.send(command).is_err() {
+            return None;
+        }
+        self.sent += 1;
+
+        let mut state = self.shared.lock();
+        while state.handled < self.sent && !state.stopped {
+            state = self.shared.changed.wait(state).unwrap_or_else(|poisoned| poisoned.into_inner());
+        }
+        state.snapshot.take()
+    }
+
+    #[must_use]
+    /// The latest snapshot, if a new one was published since the last call. Never blocks for
+    /// longer than it takes the worker to publish a snapshot.
+    pub fn latest(&self) -> Option<Snapshot> {
+        self.shared.lock().snapshot.take()
+    }
+
+    /// Stops the worker, returning the simulation.
+    ///
+    /// # Panics
+    ///
+    /// If the engine panicked in the worker thread.
+    pub fn stop(self) -> Simulation<E> {
+        drop(self.commands);
+        self.thread.join().unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+    }
+}
+
+/// The worker side of a [`Worker`].
+struct Runner<E> {
+    simulation: Simulation<E>,
+    commands: Receiver<Command>,
+    shared: Arc<Shared>,
+    update_interval: Duration,
+    paused: bool,
+    revision: u64,
+    /// Revision of the last snapshot published.
+    published: Option<u64>,
+    /// Time spent in the last step.
+    step_time: Duration,
+}
+
+impl<E: Engine> Runner<E> {
+    fn run(mut self) -> Simulation<E> {
+        let _stopped = StopGuard(Arc::clone(&self.shared));
+        let mut next_update = Instant::now();
+
+        loop {
+            let wait = if self.paused || self.simulation.is_finished() {
+                None
+            } else {
+                Some(next_update.saturating_duration_since(Instant::now()))
+            };
+            let command = match wait {
+                None => self.commands.recv().ok(),
+                Some(Duration::ZERO) => match self.commands.try_recv() {
+                    Ok(command) => Some(command),
+                    Err(TryRecvError::Empty) => {
+                        self.step();
+                        // generations are never owed, so a slow engine just runs continuously
+                        next_update = (next_update + self.update_interval).max(Instant::now());
+                        continue;
+                    },
+                    Err(TryRecvError::Disconnected) => None,
+                },
+                Some(timeout) => match self.commands.recv_timeout(timeout) {
+                    Ok(command) => Some(command),
+                    Err(RecvTimeoutError::Timeout) => continue,
+                    Err(RecvTimeoutError::Disconnected) => None,
+                },
+            };
+
+            let Some(command) = command else {
+                return self.simulation;
+            };
+            self.apply(command, &mut next_update);
+        }
+    }
+
+    fn step(&mut self) {
+        let start = Instant::now();
+        self.simulation.step();
+        self.step_time = start.elapsed();
+        self.revision += 1;
+        self.publish();
+    }
+
+    fn apply(&mut self, command: Command, next_update: &mut Instant) {
+        match command {
+            Command::Pause(paused) => {
+                self.paused = paused;
+                // generations are not owed for the time spent paused
+                *next_update = Instant::now() + self.update_interval;
+            },
+            Command::Step if self.paused && !self.simulation.is_finished() => self.step(),
+            Command::Step => (),
+            Command::StepBack => {
+                if self.paused && self.simulation.step_back() {
+                    self.revision += 1;
+                }
+            },
+            Command::Randomize => {
+                self.simulation.randomize();
+                self.revision += 1;
+            },
+            Command::Restore(session) => {
+                self.simulation.restore(*session);
+                self.revision += 1;
+            },
+            Command::Grow(margin) => {
+                self.simulation.grow(margin);
+                self.revision += 1;
+            },
+            Command::Paste(pattern, position) => {
+                self.simulation.paste(&pattern, position);
+                self.revision += 1;
+            },
+            Command::UpdateInterval(interval) => {
+                self.update_interval = interval;
+                *next_update = Instant::now() + interval;
+            },
+        }
+
+        self.publish();
+        self.shared.lock().handled += 1;
+        self.shared.changed.notify_all();
+    }
+
+    /// Publishes a snapshot of the current generation, unless it was already published.
+    fn publish(&mut self) {
+        if self.published == Some(self.revision) {
+            return;
+        }
+
+        let snapshot = Snapshot { step_time: self.step_time, ..Snapshot::of(&self.simulation, self.revision) };
+        let previous = self.shared.lock().snapshot.replace(snapshot);
+        self.published = Some(self.revision);
+        // the last reference to an old grid is dropped outside the lock
+        drop(previous);
+    }
+}
+
+/// Tells the player that the worker stopped when dropped, so it doesn't wait forever for a
+/// worker that panicked.
+struct StopGuard(Arc<Shared>);
+
+impl Drop for StopGuard {
+    fn drop(&mut self) {
+        self.0.lock().stopped = true;
+        self.0.changed.notify_all();
+    }
+}
@@ -0,0 +1,120 @@
+//! Recording runs to watch them again later.
+//!
+//! The engines are deterministic, so a replay only keeps where the run started and how far it
+//! went, and the generations are computed again while playing it back. The file starts with the
+//! magic bytes `VIDAREPL` and a version number, then the number of generations and the expansion
+//! limit, followed by a [`Checkpoint`] of the first generation.
+
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::Path;
+
+use crate::checkpoint::{invalid, read_array, read_usize, Checkpoint};
+use crate::engine::Engine;
+use crate::simulation::Simulation;
+
+const MAGIC: &[u8; 8] = b"VIDAREPL";
+const VERSION: u32 = 1;
+
+/// A run of a [`Simulation`], from [`Simulation::replay`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Replay {
+    /// The first generation of the run.
+    pub start: Checkpoint,
+    /// How many generations were computed after the first one.
+    pub generations: usize,
+    /// Largest shape of an expanding board, see [`Simulation::expand_up_to`].
+    pub expansion: Option<(usize, usize)>,
+}
+
+impl Replay {
+    #[must_use]
+    /// Starts the run again with the given engine, stopping where the recorded run stopped.
+    pub fn simulation<E: Engine>(&self, engine: E) -> Simulation<E> {
+        Simulation::resume(engine, self.start.clone())
+            .with_max_generations(self.start.generation.saturating_add(self.generations))
+            .expand_up_to(self.expansion)
+    }
+
+    /// Writes the replay to a file, replacing it if it already exists.
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let mut file = BufWriter::new(File::create(path)?);
+        self.write_to(&mut file)?;
+        file.flush()
+    }
+
+    /// Reads a replay from a file.
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        Self::read_from(BufReader::new(File::open(path)?))
+    }
+
+    /// Writes the replay in the binary format.
+    pub fn write_to<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        let (rows, columns) = self.expansion.unwrap_or((0, 0));
+
+        writer.write_all(MAGIC)?;
+        writer.write_all(&VERSION.to_le_bytes())?;
+        writer.write_all(&(self.generations as u64).to_le_bytes())?;
+        writer.write_all(&[u8::from(self.expansion.is_some())])?;
+        writer.write_all(&(rows as u64).to_le_bytes())?;
+        writer.write_all(&(columns as u64).to_le_bytes())?;
+        self.start.write_to(writer)
+    }
+
+    /// Reads a replay in the binary format.
+    pub fn read_from<R: Read>(mut reader: R) -> io::Result<Self> {
+        let mut magic = [0; MAGIC.len()];
+        reader.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            return Err(invalid("not a vida replay file"));
+        }
+        let version = u32::from_le_bytes(read_array(&mut reader)?);
+        if version != VERSION {
+            return Err(invalid(format!("unsupported replay version {version}")));
+        }
+
+        let generations = read_usize(&mut reader)?;
+        let [expands] = read_array(&mut reader)?;
+        let expansion = (read_usize(&mut reader)?, read_usize(&mut reader)?);
+        let start = Checkpoint::read_from(reader)?;
+
+        Ok(Self { start, generations, expansion: (expands != 0).then_some(expansion) })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cell::Grid;
+    use crate::engine::SparseEngine;
+    use crate::rule::Rule;
+
+    use super::*;
+
+    #[test]
+    pub fn replays_the_same_run() {
+        let engine = SparseEngine::new(Rule::HIGHLIFE);
+        let mut simulation = Simulation::new(engine, Grid::random_seeded(16, 24, 545)).with_seed(Some(545));
+        for _ in 0..5 {
+            simulation.step();
+        }
+        // only the generations after the last reset are replayed
+        simulation.reset(simulation.grid().clone(), 100);
+        for _ in 0..20 {
+            simulation.step();
+        }
+
+        let mut buffer = Vec::new();
+        simulation.replay().write_to(&mut buffer).unwrap();
+        let replay = Replay::read_from(buffer.as_slice()).unwrap();
+        assert_eq!(replay, simulation.replay());
+        assert_eq!((replay.start.generation, replay.generations, replay.start.seed), (100, 20, None));
+
+        let mut replayed = replay.simulation(engine);
+        replayed.run();
+        assert_eq!(replayed.generation(), simulation.generation());
+        assert_eq!(replayed.grid(), simulation.grid());
+
+        assert!(Replay::read_from(&buffer[..buffer.len() - 1]).is_err());
+        assert!(Replay::read_from(&buffer[MAGIC.len()..]).is_err());
+    }
+}
@@ -0,0 +1,195 @@
+//! Recent generations, kept to step a simulation backwards.
+
+use std::collections::VecDeque;
+use std::mem::size_of;
+
+use crate::cell::{Grid, GridDelta};
+
+/// The [`GridDelta`] of each recent generation, from the one before it, up to a number of
+/// generations and a memory budget.
+///
+/// Deltas only keep the cells that changed, so a long history of a sparse board costs much less
+/// than copies of the grid. Once either limit is passed, the oldest generations are forgotten.
+///
+/// # Example
+///
+/// ```
+/// # use vida::cell::{Cell, Grid};
+/// # use vida::engine::{Engine, SerialEngine};
+/// # use vida::rewind::Rewind;
+/// #
+/// let mut blinker = Grid::new(5, 5);
+/// blinker[2][1..4].fill(Cell::Live);
+/// let mut rewind = Rewind::new(2, usize::MAX);
+///
+/// for _ in 0..3 {
+///     let next = SerialEngine::default().update(&blinker);
+///     rewind.push(blinker.diff(&next));
+///     blinker = next;
+/// }
+/// assert_eq!(rewind.len(), 2);
+///
+/// let vertical = blinker.clone();
+/// assert!(rewind.step_back(&mut blinker));
+/// assert!(rewind.step_back(&mut blinker));
+/// assert!(!rewind.step_back(&mut blinker));
+/// assert_eq!(blinker, vertical);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Rewind {
+    /// The deltas from each generation to the next, oldest first.
+    deltas: VecDeque<GridDelta>,
+    limit: usize,
+    budget: usize,
+    /// Memory used by the deltas.
+    bytes: usize,
+}
+
+impl Rewind {
+    #[must_use]
+    /// Keeps up to `limit` generations, in up to `budget` bytes.
+    pub fn new(limit: usize, budget: usize) -> Self {
+        Self { deltas: VecDeque::new(), limit, budget, bytes: 0 }
+    }
+
+    /// Records the changes of a new generation, forgetting the oldest ones past the limits.
+    pub fn push(&mut self, mut delta: GridDelta) {
+        if self.limit == 0 {
+            return;
+        }
+        delta.shrink_to_fit();
+        self.bytes += memory(&delta);
+        self.deltas.push_back(delta);
+
+        while self.deltas.len() > self.limit || self.bytes > self.budget {
+            let Some(oldest) = self.deltas.pop_front() else {
+                break;
+            };
+            self.bytes -= memory(&oldest);
+        }
+    }
+
+    /// Takes the changes of the last generation, so the grid can go back from it.
+    pub fn pop(&mut self) -> Option<GridDelta> {
+        let delta = self.deltas.pop_back()?;
+        self.bytes -= memory(&delta);
+        Some(delta)
+    }
+
+    #[inline]
+    #[must_use]
+    /// The changes of the last generation kept, without taking them.
+    pub fn last(&self) -> Option<&GridDelta> {
+        self.deltas.back()
+    }
+
+    /// Turns `grid` back into the generation before it, or returns `false` if no earlier
+    /// generation is kept.
+    ///
+    /// # Panics
+    ///
+    /// If the generations kept have another shape.
+    pub fn step_back(&mut self, grid: &mut Grid) -> bool {
+        let Some(delta) = self.pop() else {
+            return false;
+        };
+        grid.apply(&delta.reversed());
+        true
+    }
+
+    /// Forgets every generation, like when the board is replaced.
+    pub fn clear(&mut self) {
+        self.deltas.clear();
+        self.bytes = 0;
+    }
+
+    #[inline]
+    #[must_use]
+    /// Number of generations that can be stepped back.
+    pub fn len(&self) -> usize {
+        self.deltas.len()
+    }
+
+    #[inline]
+    #[must_use]
+    /// Checks if there is no generation to step back to.
+    pub fn is_empty(&self) -> bool {
+        self.deltas.is_empty()
+    }
+
+    #[inline]
+    #[must_use]
+    /// Most generations kept.
+    pub const fn limit(&self) -> usize {
+        self.limit
+    }
+
+    #[inline]
+    #[must_use]
+    /// Most memory used by the generations kept, in bytes.
+    pub const fn budget(&self) -> usize {
+        self.budget
+    }
+
+    #[inline]
+    #[must_use]
+    /// Memory used by the generations kept, in bytes.
+    pub const fn bytes(&self) -> usize {
+        self.bytes
+    }
+}
+
+/// Bytes used by a delta, counting each position it holds.
+fn memory(delta: &GridDelta) -> usize {
+    size_of::<GridDelta>() + delta.len() * size_of::<(usize, usize)>()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::engine::SerialEngine;
+    use crate::patterns;
+    use crate::simulation::Simulation;
+
+    use super::*;
+
+    #[test]
+    pub fn steps_back_to_earlier_generations() {
+        let glider = patterns::glider().expanded((0, 0), (12, 12));
+        let mut simulation = Simulation::new(SerialEngine::default(), glider.clone()).with_rewind(Some(Rewind::new(16, usize::MAX)));
+        for _ in 0..20 {
+            simulation.step();
+        }
+        assert_eq!(simulation.rewind().map(Rewind::len), Some(16));
+
+        let mut expected = Simulation::new(SerialEngine::default(), glider);
+        for _ in 0..4 {
+            expected.step();
+        }
+        while simulation.step_back() { }
+        assert_eq!((simulation.generation(), simulation.grid()), (4, expected.grid()));
+
+        // the generations stepped back are computed again
+        simulation.step();
+        expected.step();
+        assert_eq!(simulation.grid(), expected.grid());
+        assert_eq!(simulation.rewind().map(Rewind::len), Some(1));
+    }
+
+    #[test]
+    pub fn memory_budget() {
+        let grid = Grid::random_seeded(32, 32, 597);
+        let budget = 4 * memory(&grid.diff(&Grid::new(32, 32)));
+        let mut simulation = Simulation::new(SerialEngine::default(), grid).with_rewind(Some(Rewind::new(1000, budget)));
+        for _ in 0..50 {
+            simulation.step();
+        }
+
+        let rewind = simulation.rewind().unwrap();
+        assert!(rewind.bytes() <= rewind.budget());
+        assert!((1..50).contains(&rewind.len()), "{}", rewind.len());
+
+        let mut none = Rewind::new(0, usize::MAX);
+        none.push(GridDelta::default());
+        assert!(none.is_empty());
+    }
+}
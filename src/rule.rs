@@ -0,0 +1,841 @@
+//! Life-like rules, written as rulestrings like `B3/S23`, and their multi-state and Larger than
+//! Life extensions.
+
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+
+use crate::cell::{Cell, Neighborhood, Topology};
+
+/// A Life-like rule: which neighbor counts make a dead cell live, and which keep a live cell alive.
+///
+/// Rules are written in the B/S notation, listing the birth counts after `B` and the survival
+/// counts after `S`, so Conway's Game of Life is `B3/S23`. Neighbors are counted in the Moore
+/// neighborhood, unless the rule ends with `V` for the von Neumann neighborhood, like `B2/S013V`,
+/// or with `H` for hexagonal grids, like `B2/S34H`. Any other subset of the eight neighbors is
+/// given by their `(dy, dx)` offsets in an extra `N` part, like `B2/S1/N(-1,0)(0,-1)(0,1)`. See
+/// [`Topology`].
+///
+/// # Example
+///
+/// ```
+/// # use vida::cell::Cell;
+/// # use vida::rule::Rule;
+/// #
+/// let highlife: Rule = "B36/S23".parse().unwrap();
+///
+/// assert_eq!(highlife, Rule::HIGHLIFE);
+/// assert_eq!(highlife.next(Cell::Dead, 6), Cell::Live);
+/// assert_eq!(highlife.to_string(), "B36/S23");
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Rule {
+    /// Bit `n` is set when a dead cell with `n` live neighbors is born.
+    birth: u16,
+    /// Bit `n` is set when a live cell with `n` live neighbors survives.
+    survival: u16,
+    topology: Topology,
+}
+
+impl Rule {
+    /// Conway's Game of Life, `B3/S23`.
+    pub const CONWAY: Self = Self::new(&[3], &[2, 3]);
+    /// HighLife, `B36/S23`, where replicators appear.
+    pub const HIGHLIFE: Self = Self::new(&[3, 6], &[2, 3]);
+    /// Day & Night, `B3678/S34678`, symmetric between live and dead cells.
+    pub const DAY_AND_NIGHT: Self = Self::new(&[3, 6, 7, 8], &[3, 4, 6, 7, 8]);
+
+    #[must_use]
+    /// Creates a rule from its birth and survival counts.
+    ///
+    /// # Panics
+    ///
+    /// If any count is larger than 8.
+    pub const fn new(birth: &[u8], survival: &[u8]) -> Self {
+        Self { birth: Self::mask(birth), survival: Self::mask(survival), topology: Topology::Moore }
+    }
+
+    #[inline]
+    #[must_use]
+    /// The same rule, on a grid with another arrangement of cells.
+    pub const fn with_topology(mut self, topology: Topology) -> Self {
+        self.topology = topology;
+        self
+    }
+
+    #[inline]
+    #[must_use]
+    /// The arrangement of cells the rule runs on.
+    pub const fn topology(&self) -> Topology {
+        self.topology
+    }
+
+    #[inline]
+    #[must_use]
+    /// Counts the live neighbors of a cell in `row`, as given by the topology of the rule.
+    pub fn count(&self, neighborhood: &Neighborhood, row: usize) -> usize {
+        self.topology.count(neighborhood, row)
+    }
+
+    const fn mask(counts: &[u8]) -> u16 {
+        let mut mask = 0;
+        let mut i = 0;
+        while i < counts.len() {
+            assert!(counts[i] <= 8, "neighbor counts go from 0 to 8");
+            mask |= 1 << counts[i];
+            i += 1;
+        }
+        mask
+    }
+
+    #[inline]
+    #[must_use]
+    /// Checks if a dead cell with `live_neighbors` is born.
+    pub const fn is_birth(&self, live_neighbors: usize) -> bool {
+        live_neighbors <= 8 && self.birth & (1 << live_neighbors) != 0
+    }
+
+    #[inline]
+    #[must_use]
+    /// Checks if a live cell with `live_neighbors` survives.
+    pub const fn is_survival(&self, live_neighbors: usize) -> bool {
+        live_neighbors <= 8 && self.survival & (1 << live_neighbors) != 0
+    }
+
+    #[inline]
+    #[must_use]
+    /// The next state of a cell with `live_neighbors` out of its eight neighbors.
+    pub const fn next(&self, cell: Cell, live_neighbors: usize) -> Cell {
+        let live = match cell {
+            Cell::Dead => self.is_birth(live_neighbors),
+            Cell::Live => self.is_survival(live_neighbors),
+        };
+
+        if live { Cell::Live } else { Cell::Dead }
+    }
+}
+
+impl Default for Rule {
+    #[inline]
+    fn default() -> Self {
+        Self::CONWAY
+    }
+}
+
+impl Display for Rule {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let counts = |mask: u16| (0..=8).filter(|n| mask & (1 << n) != 0).map(|n| n.to_string()).collect::<String>();
+
+        write!(f, "B{}/S{}", counts(self.birth), counts(self.survival))?;
+        match self.topology {
+            Topology::Moore => Ok(()),
+            Topology::VonNeumann => f.write_str("V"),
+            Topology::Hex => f.write_str("H"),
+            Topology::Custom(_) => {
+                f.write_str("/N")?;
+                self.topology.offsets(0).try_for_each(|(dy, dx)| write!(f, "({dy},{dx})"))
+            },
+        }
+    }
+}
+
+/// Error returned when parsing a [`Rule`] fails.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseRuleError(String);
+
+impl Display for ParseRuleError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid rule: {}", self.0)
+    }
+}
+
+impl Error for ParseRuleError {}
+
+impl FromStr for Rule {
+    type Err = ParseRuleError;
+
+    /// Parses `B3/S23`, in any case and in any order of the parts, with a `V` or `H` at the end or
+    /// an `N` part for other neighborhoods.
+    fn from_str(rule: &str) -> Result<Self, Self::Err> {
+        let error = |message: &str| ParseRuleError(message.to_owned());
+
+        let rule = rule.trim();
+        let (rule, mut topology) = match rule.chars().last().map(|char| char.to_ascii_uppercase()) {
+            Some('V') => (&rule[..rule.len() - 1], Topology::VonNeumann),
+            Some('H') => (&rule[..rule.len() - 1], Topology::Hex),
+            _ => (rule, Topology::Moore),
+        };
+
+        let mut birth = None;
+        let mut survival = None;
+        for part in rule.trim().split('/') {
+            let mut chars = part.trim().chars();
+            let target = match chars.next().map(|char| char.to_ascii_uppercase()) {
+                Some('B') => &mut birth,
+                Some('S') => &mut survival,
+                Some('N') if topology == Topology::Moore => {
+                    topology = parse_offsets(chars.as_str()).ok_or_else(|| error("expected neighbor offsets like N(-1,0)(1,0)"))?;
+                    continue;
+                },
+                Some('N') => return Err(error("repeated neighborhood")),
+                _ => return Err(error("expected a rule like B3/S23")),
+            };
+            if target.is_some() {
+                return Err(error("repeated B or S part"));
+            }
+
+            let mut mask = 0;
+            for char in chars {
+                match char.to_digit(10) {
+                    Some(count @ 0..=8) => mask |= 1 << count,
+                    _ => return Err(error("neighbor counts must be digits from 0 to 8")),
+                }
+            }
+            *target = Some(mask);
+        }
+
+        match (birth, survival) {
+            (Some(birth), Some(survival)) => Ok(Self { birth, survival, topology }),
+            _ => Err(error("expected both B and S parts")),
+        }
+    }
+}
+
+/// Parses a list of `(dy, dx)` offsets like `(-1,0)(1,0)` into a custom neighborhood.
+fn parse_offsets(offsets: &str) -> Option<Topology> {
+    let offsets = offsets.trim().strip_suffix(')')?;
+    let offsets: Option<Vec<_>> = offsets.split(')')
+        .map(|offset| {
+            let (dy, dx) = offset.trim().strip_prefix('(')?.split_once(',')?;
+            Some((dy.trim().parse().ok()?, dx.trim().parse().ok()?))
+        })
+        .collect();
+    Topology::from_offsets(&offsets?)
+}
+
+/// A Generations rule: a Life-like rule where cells that don't survive fade through dying states
+/// before they are dead.
+///
+/// Cells are numbered from `0`, dead, to `states - 1`. Dead cells with the birth counts of live
+/// neighbors become live, in state `1`. Live cells with the survival counts stay live, the others
+/// start dying, and dying cells move to the next state every generation until they wrap back to
+/// dead. Only live cells count as neighbors, and dying cells can't be born again.
+///
+/// Rules are written in Golly's `S/B/C` notation, like `345/2/4` for Star Wars, or as
+/// `B2/S345/C4`.
+///
+/// # Example
+///
+/// ```
+/// # use vida::rule::GenerationsRule;
+/// #
+/// let star_wars: GenerationsRule = "345/2/4".parse().unwrap();
+/// assert_eq!(star_wars, GenerationsRule::STAR_WARS);
+/// assert_eq!(star_wars.to_string(), "B2/S345/C4");
+///
+/// assert_eq!(star_wars.next(0, 2), 1);
+/// assert_eq!(star_wars.next(1, 2), 2);
+/// assert_eq!(star_wars.next(3, 2), 0);
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct GenerationsRule {
+    rule: Rule,
+    states: u8,
+}
+
+impl GenerationsRule {
+    /// Star Wars, `345/2/4`.
+    pub const STAR_WARS: Self = Self::new(Rule::new(&[2], &[3, 4, 5]), 4);
+    /// Brian's Brain, `/2/3`, where no cell survives and every live cell leaves a dying trail.
+    pub const BRIANS_BRAIN: Self = Self::new(Rule::new(&[2], &[]), 3);
+
+    #[must_use]
+    /// Creates a Generations rule from a Life-like rule and the number of states, counting the
+    /// dead and live ones.
+    ///
+    /// # Panics
+    ///
+    /// If there are less than 2 states.
+    pub const fn new(rule: Rule, states: u8) -> Self {
+        assert!(states >= 2, "cells need at least a dead and a live state");
+        Self { rule, states }
+    }
+
+    #[inline]
+    #[must_use]
+    /// The birth and survival counts of live cells.
+    pub const fn rule(&self) -> Rule {
+        self.rule
+    }
+
+    #[inline]
+    #[must_use]
+    /// Number of states, counting the dead and live ones.
+    pub const fn states(&self) -> u8 {
+        self.states
+    }
+
+    #[inline]
+    #[must_use]
+    /// The next state of a cell in `state` with `live_neighbors` out of its eight neighbors.
+    pub const fn next(&self, state: u8, live_neighbors: usize) -> u8 {
+        match state {
+            0 if self.rule.is_birth(live_neighbors) => 1,
+            0 => 0,
+            1 if self.rule.is_survival(live_neighbors) => 1,
+            _ => (state + 1) % self.states,
+        }
+    }
+}
+
+impl Display for GenerationsRule {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/C{}", self.rule, self.states)
+    }
+}
+
+impl FromStr for GenerationsRule {
+    type Err = ParseRuleError;
+
+    /// Parses `345/2/4` or `B2/S345/C4`.
+    fn from_str(rule: &str) -> Result<Self, Self::Err> {
+        let error = |message: &str| ParseRuleError(message.to_owned());
+        let digits = |part: &str| part.chars().all(|char| char.is_ascii_digit());
+
+        let parts: Vec<_> = rule.trim().split('/').map(str::trim).collect();
+        let (rule, states) = match parts[..] {
+            [survival, birth, states] if digits(survival) && digits(birth) => (format!("B{birth}/S{survival}").parse()?, states),
+            [ref rule @ .., states] if rule.len() >= 2 => (rule.join("/").parse()?, states.trim_start_matches(['C', 'c'])),
+            _ => return Err(error("expected a rule like 345/2/4 or B2/S345/C4")),
+        };
+
+        match states.parse() {
+            Ok(states @ 2..) => Ok(Self::new(rule, states)),
+            _ => Err(error("the number of states must be from 2 to 255")),
+        }
+    }
+}
+
+/// A Larger than Life rule: a Life-like rule over a square neighborhood of any radius, with ranges
+/// of neighbor counts for birth and survival.
+///
+/// Rules are written in Golly's notation, like `R5,C0,M1,S34..58,B34..45,NM` for Bosco's Rule.
+/// `R` is the radius of the neighborhood, `M1` counts the cell itself among its neighbors, and `S`
+/// and `B` are the inclusive ranges of counts for survival and birth. `N` is the shape of the
+/// neighborhood, either the square Moore neighborhood (`NM`, the default) or the diamond von
+/// Neumann neighborhood (`NN`), with the cells at most `R` steps away along rows and columns.
+/// Only two states (`C0` or `C2`) are supported.
+///
+/// # Example
+///
+/// ```
+/// # use vida::cell::Cell;
+/// # use vida::rule::LtlRule;
+/// #
+/// let bosco: LtlRule = "R5,C0,M1,S34..58,B34..45".parse().unwrap();
+/// assert_eq!(bosco, LtlRule::BOSCO);
+/// assert_eq!(bosco.to_string(), "R5,C0,M1,S34..58,B34..45,NM");
+///
+/// assert_eq!(bosco.neighbors(), 121);
+/// assert_eq!(bosco.next(Cell::Dead, 40), Cell::Live);
+/// assert_eq!(bosco.next(Cell::Live, 60), Cell::Dead);
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct LtlRule {
+    radius: usize,
+    middle: bool,
+    survival: (usize, usize),
+    birth: (usize, usize),
+    von_neumann: bool,
+}
+
+impl LtlRule {
+    /// Bosco's Rule, `R5,C0,M1,S34..58,B34..45`, with its own gliders and oscillators.
+    pub const BOSCO: Self = Self::new(5, true, 34..=58, 34..=45);
+
+    #[must_use]
+    /// Creates a rule with the given radius and ranges of neighbor counts, including the cell
+    /// itself if `middle` is set.
+    ///
+    /// # Panics
+    ///
+    /// If the radius is zero.
+    pub const fn new(radius: usize, middle: bool, survival: RangeInclusive<usize>, birth: RangeInclusive<usize>) -> Self {
+        assert!(radius > 0, "the neighborhood needs a radius of at least 1");
+        Self { radius, middle, survival: (*survival.start(), *survival.end()), birth: (*birth.start(), *birth.end()), von_neumann: false }
+    }
+
+    #[inline]
+    #[must_use]
+    /// Counts the neighbors in the von Neumann neighborhood (`NN`) instead of the Moore one.
+    pub const fn with_von_neumann(mut self, von_neumann: bool) -> Self {
+        self.von_neumann = von_neumann;
+        self
+    }
+
+    #[inline]
+    #[must_use]
+    /// Whether the neighborhood is the von Neumann diamond (`NN`) instead of the Moore square.
+    pub const fn von_neumann(&self) -> bool {
+        self.von_neumann
+    }
+
+    #[inline]
+    #[must_use]
+    /// How far the neighborhood reaches on each side of the cell.
+    pub const fn radius(&self) -> usize {
+        self.radius
+    }
+
+    #[inline]
+    #[must_use]
+    /// Whether the cell counts as its own neighbor.
+    pub const fn middle(&self) -> bool {
+        self.middle
+    }
+
+    #[inline]
+    #[must_use]
+    /// Counts of live neighbors that keep a live cell alive.
+    pub const fn survival(&self) -> RangeInclusive<usize> {
+        self.survival.0..=self.survival.1
+    }
+
+    #[inline]
+    #[must_use]
+    /// Counts of live neighbors that make a dead cell live.
+    pub const fn birth(&self) -> RangeInclusive<usize> {
+        self.birth.0..=self.birth.1
+    }
+
+    #[inline]
+    #[must_use]
+    /// Number of cells counted as neighbors.
+    pub const fn neighbors(&self) -> usize {
+        let cells = if self.von_neumann {
+            2 * self.radius * (self.radius + 1) + 1
+        } else {
+            (2 * self.radius + 1) * (2 * self.radius + 1)
+        };
+        if self.middle { cells } else { cells - 1 }
+    }
+
+    #[inline]
+    #[must_use]
+    /// The next state of a cell with `live_neighbors` in its neighborhood.
+    pub const fn next(&self, cell: Cell, live_neighbors: usize) -> Cell {
+        let (min, max) = match cell {
+            Cell::Dead => self.birth,
+            Cell::Live => self.survival,
+        };
+
+        if min <= live_neighbors && live_neighbors <= max { Cell::Live } else { Cell::Dead }
+    }
+}
+
+impl Default for LtlRule {
+    #[inline]
+    fn default() -> Self {
+        Self::BOSCO
+    }
+}
+
+impl Display for LtlRule {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let (survival, birth) = (self.survival, self.birth);
+        let neighborhood = if self.von_neumann { 'N' } else { 'M' };
+        write!(f, "R{},C0,M{},S{}..{},B{}..{},N{neighborhood}", self.radius, u8::from(self.middle), survival.0, survival.1, birth.0, birth.1)
+    }
+}
+
+impl FromStr for LtlRule {
+    type Err = ParseRuleError;
+
+    /// Parses `R5,C0,M1,S34..58,B34..45,NM` or `...,NN`, in any case. `C`, `M` and `N` are
+    /// optional.
+    fn from_str(rule: &str) -> Result<Self, Self::Err> {
+        let error = |message: &str| ParseRuleError(message.to_owned());
+        let number = |value: &str| value.trim().parse::<usize>().map_err(|_| error("expected a number"));
+        let range = |value: &str| match value.split_once("..") {
+            Some((start, end)) => Ok(number(start)?..=number(end)?),
+            None => Err(error("expected a range like 34..58")),
+        };
+
+        let (mut radius, mut middle, mut survival, mut birth, mut von_neumann) = (None, false, None, None, false);
+        for part in rule.trim().split(',') {
+            let part = part.trim();
+            let Some(key) = part.chars().next().map(|char| char.to_ascii_uppercase()) else {
+                return Err(error("empty part"));
+            };
+            let value = &part[1..];
+
+            match key {
+                'R' => radius = Some(number(value)?),
+                'C' if number(value)? <= 2 => (),
+                'C' => return Err(error("only rules with 2 states are supported")),
+                'M' => middle = number(value)? != 0,
+                'S' => survival = Some(range(value)?),
+                'B' => birth = Some(range(value)?),
+                'N' if value.eq_ignore_ascii_case("M") => von_neumann = false,
+                'N' if value.eq_ignore_ascii_case("N") => von_neumann = true,
+                'N' => return Err(error("only the Moore (NM) and von Neumann (NN) neighborhoods are supported")),
+                _ => return Err(error("expected a rule like R5,C0,M1,S34..58,B34..45")),
+            }
+        }
+
+        match (radius, survival, birth) {
+            (Some(radius @ 1..), Some(survival), Some(birth)) => Ok(Self::new(radius, middle, survival, birth).with_von_neumann(von_neumann)),
+            (Some(0), _, _) => Err(error("the radius must be at least 1")),
+            _ => Err(error("expected the R, S and B parts")),
+        }
+    }
+}
+
+/// A totalistic rule of 3D Life, in Carter Bays' notation `EeFf`: live cells with `E ..= e` of
+/// their 26 neighbors survive, and dead cells with `F ..= f` are born.
+///
+/// # Example
+///
+/// ```
+/// # use vida::cell::Cell;
+/// # use vida::rule::Rule3d;
+/// #
+/// let rule: Rule3d = "5766".parse().unwrap();
+/// assert_eq!(rule, Rule3d::LIFE_5766);
+/// assert_eq!(rule.next(Cell::Dead, 6), Cell::Live);
+/// assert_eq!(rule.next(Cell::Live, 8), Cell::Dead);
+///
+/// // bounds past 9 are separated by commas
+/// assert_eq!("10,21,10,21".parse::<Rule3d>().unwrap().to_string(), "10,21,10,21");
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Rule3d {
+    survival: (u8, u8),
+    birth: (u8, u8),
+}
+
+impl Rule3d {
+    /// Bays' Life 5766, with small oscillators and a glider.
+    pub const LIFE_5766: Self = Self::new(5..=7, 6..=6);
+    /// Bays' Life 4555, which is more active than 5766.
+    pub const LIFE_4555: Self = Self::new(4..=5, 5..=5);
+
+    #[inline]
+    #[must_use]
+    /// Creates a rule from the counts of live neighbors that keep a live cell alive and those that
+    /// make a dead cell live.
+    pub const fn new(survival: RangeInclusive<u8>, birth: RangeInclusive<u8>) -> Self {
+        Self { survival: (*survival.start(), *survival.end()), birth: (*birth.start(), *birth.end()) }
+    }
+
+    #[inline]
+    #[must_use]
+    /// Counts of live neighbors that keep a live cell alive.
+    pub const fn survival(&self) -> RangeInclusive<u8> {
+        self.survival.0..=self.survival.1
+    }
+
+    #[inline]
+    #[must_use]
+    /// Counts of live neighbors that make a dead cell live.
+    pub const fn birth(&self) -> RangeInclusive<u8> {
+        self.birth.0..=self.birth.1
+    }
+
+    #[inline]
+    #[must_use]
+    /// The next state of a cell with `live_neighbors` among its 26 neighbors.
+    pub const fn next(&self, cell: Cell, live_neighbors: u8) -> Cell {
+        let (min, max) = match cell {
+            Cell::Dead => self.birth,
+            Cell::Live => self.survival,
+        };
+
+        if min <= live_neighbors && live_neighbors <= max { Cell::Live } else { Cell::Dead }
+    }
+}
+
+impl Default for Rule3d {
+    #[inline]
+    fn default() -> Self {
+        Self::LIFE_5766
+    }
+}
+
+impl Display for Rule3d {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let bounds = [self.survival.0, self.survival.1, self.birth.0, self.birth.1];
+        if bounds.iter().all(|&bound| bound < 10) {
+            bounds.iter().try_for_each(|bound| write!(f, "{bound}"))
+        } else {
+            write!(f, "{},{},{},{}", bounds[0], bounds[1], bounds[2], bounds[3])
+        }
+    }
+}
+
+impl FromStr for Rule3d {
+    type Err = ParseRuleError;
+
+    /// Parses `5766`, or `5,7,6,6` with commas for bounds past 9.
+    fn from_str(rule: &str) -> Result<Self, Self::Err> {
+        let error = |message: &str| ParseRuleError(message.to_owned());
+
+        let rule = rule.trim();
+        let bounds: Option<Vec<u8>> = if rule.contains(',') {
+            rule.split(',').map(|bound| bound.trim().parse().ok()).collect()
+        } else {
+            rule.chars().map(|digit| digit.to_digit(10).map(|digit| digit as u8)).collect()
+        };
+
+        match bounds.as_deref() {
+            Some(&[survival, max_survival, birth, max_birth]) if [survival, max_survival, birth, max_birth].iter().all(|&bound| bound <= 26) => {
+                Ok(Self::new(survival..=max_survival, birth..=max_birth))
+            },
+            Some(&[_, _, _, _]) => Err(error("there are only 26 neighbors")),
+            _ => Err(error("expected a rule like 5766 or 5,7,6,6")),
+        }
+    }
+}
+
+/// A rule of a block cellular automaton, giving the next state of each 2x2 block of cells.
+///
+/// Blocks are numbered by their live cells, with `1` for the top left cell, `2` for the top
+/// right, `4` for the bottom left and `8` for the bottom right, and the rule is the table of the
+/// next block for each of the 16 blocks. Rules whose table is a permutation are reversible, see
+/// [`BlockRule::inverse`].
+///
+/// # Example
+///
+/// ```
+/// # use vida::rule::BlockRule;
+/// #
+/// let critters: BlockRule = "critters".parse().unwrap();
+/// assert_eq!(critters, BlockRule::CRITTERS);
+/// assert_eq!(critters.to_string(), "M15,14,13,3,11,5,6,1,7,9,10,2,12,4,8,0");
+///
+/// // blocks with two live cells stay the same, and the others are complemented
+/// assert_eq!(critters.next(0b0110), 0b0110);
+/// assert_eq!(critters.next(0b0001), 0b1110);
+/// assert_eq!(critters.inverse().unwrap().next(0b1110), 0b0001);
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(transparent)]
+pub struct BlockRule {
+    table: [u8; 16],
+}
+
+impl BlockRule {
+    /// Critters, where blocks without exactly two live cells are complemented, and those that
+    /// had three are also rotated by 180 degrees. It is reversible, and gliders emerge from soups.
+    pub const CRITTERS: Self = Self::new([15, 14, 13, 3, 11, 5, 6, 1, 7, 9, 10, 2, 12, 4, 8, 0]);
+    /// The billiard ball machine, where lone cells move diagonally and bounce off each other.
+    pub const BILLIARD_BALL: Self = Self::new([0, 8, 4, 3, 2, 5, 9, 7, 1, 6, 10, 11, 12, 13, 14, 15]);
+    /// Tron, where blocks of a single color are complemented and the others stay the same.
+    pub const TRON: Self = Self::new([15, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 0]);
+
+    /// The names of the built-in rules, as parsed by [`FromStr`].
+    pub const NAMES: [(&'static str, Self); 3] = [("critters", Self::CRITTERS), ("billiard-ball", Self::BILLIARD_BALL), ("tron", Self::TRON)];
+
+    #[inline]
+    #[must_use]
+    /// Creates a rule from the next block for each block, keeping only the 4 bits of each.
+    pub const fn new(table: [u8; 16]) -> Self {
+        let mut table = table;
+        let mut i = 0;
+        while i < table.len() {
+            table[i] &= 0b1111;
+            i += 1;
+        }
+        Self { table }
+    }
+
+    #[inline]
+    #[must_use]
+    /// The next block for each block.
+    pub const fn table(&self) -> [u8; 16] {
+        self.table
+    }
+
+    #[inline]
+    #[must_use]
+    /// The next state of a block, ignoring bits past the first 4.
+    pub const fn next(&self, block: u8) -> u8 {
+        self.table[(block & 0b1111) as usize]
+    }
+
+    #[must_use]
+    /// The rule that undoes this one, if it is reversible, i.e. no two blocks have the same next
+    /// block.
+    pub fn inverse(&self) -> Option<Self> {
+        let mut inverse = [None; 16];
+        for (block, &next) in (0..).zip(&self.table) {
+            if inverse[usize::from(next)].replace(block).is_some() {
+                return None;
+            }
+        }
+        Some(Self::new(inverse.map(|block| block.unwrap_or(0))))
+    }
+
+    #[inline]
+    #[must_use]
+    /// Checks if the rule can be run backwards, see [`BlockRule::inverse`].
+    pub fn is_reversible(&self) -> bool {
+        self.inverse().is_some()
+    }
+}
+
+impl Default for BlockRule {
+    #[inline]
+    fn default() -> Self {
+        Self::CRITTERS
+    }
+}
+
+impl Display for BlockRule {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let table: Vec<_> = self.table.iter().map(u8::to_string).collect();
+        write!(f, "M{}", table.join(","))
+    }
+}
+
+impl FromStr for BlockRule {
+    type Err = ParseRuleError;
+
+    /// Parses the name of a built-in rule, like `critters`, or the table of next blocks after an
+    /// `M`, like `M15,14,13,3,11,5,6,1,7,9,10,2,12,4,8,0`.
+    fn from_str(rule: &str) -> Result<Self, Self::Err> {
+        let error = |message: &str| ParseRuleError(message.to_owned());
+
+        let rule = rule.trim();
+        if let Some(&(_, named)) = Self::NAMES.iter().find(|(name, _)| name.eq_ignore_ascii_case(rule)) {
+            return Ok(named);
+        }
+
+        let Some(table) = rule.strip_prefix(['M', 'm']) else {
+            return Err(error("expected critters, billiard-ball, tron or a table like M15,14,13,...,0"));
+        };
+        let table: Vec<u8> = table.split(',')
+            .map(|block| block.trim().parse().ok().filter(|&block| block < 16))
+            .collect::<Option<_>>()
+            .ok_or_else(|| error("expected blocks from 0 to 15"))?;
+        let table = table.try_into().map_err(|_| error("expected the next block for each of the 16 blocks"))?;
+        Ok(Self::new(table))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn parse_rulestrings() {
+        assert_eq!("B3/S23".parse(), Ok(Rule::CONWAY));
+        assert_eq!("s23/b3".parse(), Ok(Rule::CONWAY));
+        assert_eq!("B3678/S34678".parse(), Ok(Rule::DAY_AND_NIGHT));
+        assert_eq!("B/S".parse::<Rule>().unwrap().to_string(), "B/S");
+
+        assert!("B3".parse::<Rule>().is_err());
+        assert!("B39/S23".parse::<Rule>().is_err());
+        assert!("B3/S23/B3".parse::<Rule>().is_err());
+        assert!("23/3".parse::<Rule>().is_err());
+
+        let hex: Rule = "B2/S34H".parse().unwrap();
+        assert_eq!(hex, Rule::new(&[2], &[3, 4]).with_topology(Topology::Hex));
+        assert_eq!(hex.to_string(), "B2/S34H");
+        assert_eq!("s23/b3".parse::<Rule>().map(|rule| rule.topology()), Ok(Topology::Moore));
+        assert!("B2/S34HH".parse::<Rule>().is_err());
+
+        let von_neumann: Rule = "b2/s013v".parse().unwrap();
+        assert_eq!(von_neumann.topology(), Topology::VonNeumann);
+        assert_eq!(von_neumann.to_string(), "B2/S013V");
+
+        let custom: Rule = "B2/S1/N(-1,0)(0,-1)(0, 1)".parse().unwrap();
+        assert_eq!(custom.topology(), Topology::from_offsets(&[(0, 1), (-1, 0), (0, -1)]).unwrap());
+        assert_eq!(custom.to_string(), "B2/S1/N(-1,0)(0,-1)(0,1)");
+        assert_eq!(custom.to_string().parse(), Ok(custom));
+        assert!("B2/S1/N(-1,0)(0,0)".parse::<Rule>().is_err());
+        assert!("B2/S1/N(-2,0)".parse::<Rule>().is_err());
+        assert!("B2/S1/N(-1,0)V".parse::<Rule>().is_err());
+        assert!("B2/S1/N-1,0".parse::<Rule>().is_err());
+    }
+
+    #[test]
+    pub fn generations_rules() {
+        assert_eq!("345/2/4".parse(), Ok(GenerationsRule::STAR_WARS));
+        assert_eq!("s345/b2/c4".parse(), Ok(GenerationsRule::STAR_WARS));
+        assert_eq!("/2/3".parse(), Ok(GenerationsRule::BRIANS_BRAIN));
+        assert_eq!(GenerationsRule::BRIANS_BRAIN.to_string().parse(), Ok(GenerationsRule::BRIANS_BRAIN));
+        assert_eq!("23/3/2".parse::<GenerationsRule>().map(|rule| rule.rule()), Ok(Rule::CONWAY));
+
+        assert!("345/2".parse::<GenerationsRule>().is_err());
+        assert!("345/2/1".parse::<GenerationsRule>().is_err());
+        assert!("345/2/256".parse::<GenerationsRule>().is_err());
+        assert!("B2/S9/C3".parse::<GenerationsRule>().is_err());
+
+        let custom: GenerationsRule = "B2/S/N(-1,0)(1,0)/C3".parse().unwrap();
+        assert_eq!(custom.to_string().parse(), Ok(custom));
+        assert_eq!(custom.rule().topology().offsets(0).count(), 2);
+
+        let rule = GenerationsRule::BRIANS_BRAIN;
+        assert_eq!([rule.next(0, 2), rule.next(1, 2), rule.next(2, 2), rule.next(0, 3)], [1, 2, 0, 0]);
+        let life = GenerationsRule::new(Rule::CONWAY, 2);
+        assert_eq!([life.next(1, 1), life.next(1, 2), life.next(0, 3)], [0, 1, 1]);
+    }
+
+    #[test]
+    pub fn larger_than_life_rules() {
+        assert_eq!("r5,c0,m1,s34..58,b34..45,nm".parse(), Ok(LtlRule::BOSCO));
+        assert_eq!("R5,M1,B34..45,S34..58".parse(), Ok(LtlRule::BOSCO));
+        assert_eq!(LtlRule::BOSCO.to_string().parse(), Ok(LtlRule::BOSCO));
+
+        let life: LtlRule = "R1,C2,M0,S2..3,B3..3".parse().unwrap();
+        assert_eq!(life.neighbors(), 8);
+        for count in 0..=8 {
+            for cell in [Cell::Dead, Cell::Live] {
+                assert_eq!(life.next(cell, count), Rule::CONWAY.next(cell, count));
+            }
+        }
+
+        assert!("R0,C0,M1,S1..2,B1..2".parse::<LtlRule>().is_err());
+        assert!("R5,C3,M1,S34..58,B34..45".parse::<LtlRule>().is_err());
+        assert!("R5,C0,M1,S34..58,B34..45,NC".parse::<LtlRule>().is_err());
+
+        let diamond: LtlRule = "R2,C0,M1,S2..5,B3..4,NN".parse().unwrap();
+        assert_eq!(diamond, LtlRule::new(2, true, 2..=5, 3..=4).with_von_neumann(true));
+        assert_eq!(diamond.neighbors(), 13);
+        assert!("R5,C0,M1,S34,B34..45".parse::<LtlRule>().is_err());
+        assert!("R5,C0,M1,S34..58".parse::<LtlRule>().is_err());
+    }
+
+    #[test]
+    pub fn block_rules() {
+        for (name, rule) in BlockRule::NAMES {
+            assert_eq!(name.parse(), Ok(rule));
+            assert_eq!(rule.to_string().parse(), Ok(rule));
+            assert!(rule.is_reversible(), "{name}");
+
+            let inverse = rule.inverse().unwrap();
+            assert!((0..16).all(|block| inverse.next(rule.next(block)) == block));
+        }
+        assert_eq!("Billiard-Ball".parse(), Ok(BlockRule::BILLIARD_BALL));
+
+        let irreversible: BlockRule = "M0,0,0,0,0,0,0,0,15,15,15,15,15,15,15,15".parse().unwrap();
+        assert_eq!(irreversible.inverse(), None);
+        assert!("M0,1,2".parse::<BlockRule>().is_err());
+        assert!("M0,1,2,3,4,5,6,7,8,9,10,11,12,13,14,16".parse::<BlockRule>().is_err());
+        assert!("critter".parse::<BlockRule>().is_err());
+    }
+
+    #[test]
+    pub fn rules_in_3d() {
+        assert_eq!("4555".parse(), Ok(Rule3d::LIFE_4555));
+        assert_eq!(" 5, 7, 6, 6 ".parse(), Ok(Rule3d::LIFE_5766));
+        assert_eq!(Rule3d::LIFE_5766.to_string().parse(), Ok(Rule3d::LIFE_5766));
+        assert_eq!(Rule3d::LIFE_4555.survival(), 4..=5);
+
+        assert!("576".parse::<Rule3d>().is_err());
+        assert!("5,7,6,27".parse::<Rule3d>().is_err());
+        assert!("57a6".parse::<Rule3d>().is_err());
+    }
+}
@@ -0,0 +1,246 @@
+//! Saving and restoring an interactive exploration.
+//!
+//! A session file is plain text: a version line, one `key value` line per setting, and then the
+//! grid itself, one row per line using the same characters as the [`Display`](std::fmt::Display)
+//! implementation of [`Cell`]. Unknown keys are ignored, so newer files can still be read as long
+//! as the version matches.
+
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, ErrorKind, Write};
+use std::path::Path;
+use std::time::Duration;
+
+use crate::cell::{Cell, Grid};
+use crate::io::MAX_CELLS;
+use crate::noise::Noise;
+use crate::rule::Rule;
+
+const HEADER: &str = "vida session 1";
+
+/// Everything needed to resume an exploration where it was left.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Session {
+    /// The current board.
+    pub grid: Grid,
+    /// Name of the engine in use, from [`Engine::name`](crate::engine::Engine::name).
+    pub engine: String,
+    /// Generation number of the board.
+    pub generation: usize,
+    /// Size of each cell on screen.
+    pub cell_size: f64,
+    /// Delay between generations.
+    pub update_interval: Duration,
+    /// Rule of the engine, if it runs a Life-like rule, see
+    /// [`Engine::life_rule`](crate::engine::Engine::life_rule).
+    pub rule: Option<Rule>,
+    /// Seed of the initial random board, see [`Simulation::seed`](crate::simulation::Simulation::seed).
+    pub seed: Option<u64>,
+    /// Random flips between generations, and how many grids were flipped, see
+    /// [`Simulation::noise`](crate::simulation::Simulation::noise).
+    pub noise: Option<Noise>,
+    /// How many generations back repeated boards are looked for, see
+    /// [`Simulation::stop_on_cycle`](crate::simulation::Simulation::stop_on_cycle).
+    pub cycle_window: Option<usize>,
+    /// The part of the board in view, if it was shown in a window.
+    pub view: Option<View>,
+}
+
+/// How the board is shown in a window, as `window = board * zoom + offset`, in pixels.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct View {
+    pub zoom: f64,
+    pub offset: [f64; 2],
+}
+
+impl Session {
+    /// Writes the session to a file, replacing it if it already exists.
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let mut file = BufWriter::new(File::create(path)?);
+        self.write_to(&mut file)?;
+        file.flush()
+    }
+
+    /// Reads a session from a file.
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        Self::read_from(BufReader::new(File::open(path)?))
+    }
+
+    /// Writes the session in the text format.
+    pub fn write_to<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        writeln!(writer, "{HEADER}")?;
+        writeln!(writer, "engine {}", self.engine)?;
+        writeln!(writer, "generation {}", self.generation)?;
+        writeln!(writer, "cell_size {}", self.cell_size)?;
+        writeln!(writer, "update_interval_ms {}", self.update_interval.as_millis())?;
+        if let Some(rule) = self.rule {
+            writeln!(writer, "rule {rule}")?;
+        }
+        if let Some(seed) = self.seed {
+            writeln!(writer, "seed {seed}")?;
+        }
+        if let Some(noise) = &self.noise {
+            writeln!(writer, "noise {} {} {}", noise.probability(), noise.seed(), noise.applied())?;
+        }
+        if let Some(window) = self.cycle_window {
+            writeln!(writer, "cycle_window {window}")?;
+        }
+        if let Some(View { zoom, offset: [x, y] }) = self.view {
+            writeln!(writer, "view {zoom} {x} {y}")?;
+        }
+        writeln!(writer, "grid {} {}", self.grid.rows(), self.grid.columns())?;
+        write!(writer, "{}", self.grid)
+    }
+
+    /// Reads a session in the text format.
+    pub fn read_from<R: BufRead>(reader: R) -> io::Result<Self> {
+        let mut lines = reader.lines();
+
+        if lines.next().transpose()?.as_deref() != Some(HEADER) {
+            return Err(invalid("not a vida session file"));
+        }
+
+        let mut engine = None;
+        let mut generation = 0;
+        let mut cell_size = None;
+        let mut update_interval = None;
+        let mut shape: Option<(usize, usize)> = None;
+        let (mut rule, mut seed, mut noise, mut cycle_window, mut view) = (None, None, None, None, None);
+
+        for line in &mut lines {
+            let line = line?;
+            let (key, value) = line.split_once(' ').unwrap_or((&line, ""));
+
+            match key {
+                "engine" => engine = Some(value.to_owned()),
+                "generation" => generation = parse(key, value)?,
+                "cell_size" => cell_size = Some(parse(key, value)?),
+                "update_interval_ms" => update_interval = Some(Duration::from_millis(parse(key, value)?)),
+                "rule" => rule = Some(parse(key, value)?),
+                "seed" => seed = Some(parse(key, value)?),
+                "noise" => {
+                    let [probability, noise_seed, applied] = values(key, value)?;
+                    noise = Some(Noise::new(parse(key, probability)?, parse(key, noise_seed)?).with_applied(parse(key, applied)?));
+                },
+                "cycle_window" => cycle_window = Some(parse(key, value)?),
+                "view" => {
+                    let [zoom, x, y] = values(key, value)?;
+                    let (zoom, offset): (f64, [f64; 2]) = (parse(key, zoom)?, [parse(key, x)?, parse(key, y)?]);
+                    if zoom <= 0.0 || !zoom.is_finite() || !offset.iter().all(|value| value.is_finite()) {
+                        return Err(invalid(format!("invalid value for {key}: {value:?}")));
+                    }
+                    view = Some(View { zoom, offset });
+                },
+                "grid" => {
+                    let (rows, columns) = value.split_once(' ').ok_or_else(|| invalid("missing grid shape"))?;
+                    shape = Some((parse(key, rows)?, parse(key, columns)?));
+                    break;
+                },
+                _ => (),
+            }
+        }
+
+        let (rows, columns) = shape.ok_or_else(|| invalid("missing grid"))?;
+        if rows == 0 || columns == 0 {
+            return Err(invalid(format!("empty grid of {rows}x{columns} cells")));
+        }
+        let mut grid = rows.checked_mul(columns)
+            .filter(|&cells| cells <= MAX_CELLS)
+            .and_then(|_| Grid::try_new(rows, columns))
+            .ok_or_else(|| invalid(format!("grid of {rows}x{columns} cells is too large")))?;
+        for row in 0..rows {
+            let line = lines.next().transpose()?.ok_or_else(|| invalid("grid has too few rows"))?;
+            if line.chars().count() != columns {
+                return Err(invalid(format!("grid row {row} has the wrong length")));
+            }
+
+            for (cell, char) in grid[row].iter_mut().zip(line.chars()) {
+                *cell = match char {
+                    'D' => Cell::Dead,
+                    'L' => Cell::Live,
+                    _ => return Err(invalid(format!("invalid cell {char:?} in grid row {row}"))),
+                };
+            }
+        }
+
+        Ok(Self {
+            grid,
+            engine: engine.ok_or_else(|| invalid("missing engine"))?,
+            generation,
+            cell_size: cell_size.ok_or_else(|| invalid("missing cell_size"))?,
+            update_interval: update_interval.ok_or_else(|| invalid("missing update_interval_ms"))?,
+            rule,
+            seed,
+            noise,
+            cycle_window,
+            view,
+        })
+    }
+}
+
+#[inline]
+#[must_use]
+fn invalid(message: impl Into<String>) -> io::Error {
+    io::Error::new(ErrorKind::InvalidData, message.into())
+}
+
+fn parse<T: std::str::FromStr>(key: &str, value: &str) -> io::Result<T> {
+    value.trim().parse().map_err(|_| invalid(format!("invalid value for {key}: {value:?}")))
+}
+
+/// The `N` values of a key, separated by spaces.
+fn values<'a, const N: usize>(key: &str, value: &'a str) -> io::Result<[&'a str; N]> {
+    let values: Vec<_> = value.split_whitespace().collect();
+    values.try_into().map_err(|_| invalid(format!("expected {N} values for {key}: {value:?}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand::rngs::SmallRng;
+
+    use super::*;
+
+    #[test]
+    pub fn round_trip() {
+        let mut rng = SmallRng::seed_from_u64(493);
+        let session = Session {
+            grid: Grid::random_with(7, 13, &mut rng),
+            engine: "parallel".to_owned(),
+            generation: 42,
+            cell_size: 2.5,
+            update_interval: Duration::from_millis(250),
+            rule: Some(Rule::HIGHLIFE),
+            seed: Some(493),
+            noise: Some(Noise::new(0.01, 7).with_applied(42)),
+            cycle_window: Some(12),
+            view: Some(View { zoom: 2.5, offset: [-120.0, 33.5] }),
+        };
+
+        let mut buffer = Vec::new();
+        session.write_to(&mut buffer).unwrap();
+
+        assert_eq!(Session::read_from(buffer.as_slice()).unwrap(), session);
+
+        // files without the newer keys still load
+        let session = Session { rule: None, seed: None, noise: None, cycle_window: None, view: None, ..session };
+        let text = "vida session 1\nengine parallel\ngeneration 42\ncell_size 2.5\nupdate_interval_ms 250\n";
+        let text = format!("{text}grid 7 13\n{}", session.grid);
+        assert_eq!(Session::read_from(text.as_bytes()).unwrap(), session);
+    }
+
+    #[test]
+    pub fn rejects_truncated_grid() {
+        let text = "vida session 1\nengine serial\ncell_size 2\nupdate_interval_ms 10\ngrid 2 2\nDL\n";
+
+        assert!(Session::read_from(text.as_bytes()).is_err());
+    }
+
+    #[test]
+    pub fn rejects_empty_and_huge_grids() {
+        for shape in ["3 0", "0 3", "99999999999 99999999999", "4294967296 4294967296"] {
+            let text = format!("vida session 1\nengine serial\ncell_size 2\nupdate_interval_ms 10\ngrid {shape}\nDL\n");
+            let error = Session::read_from(text.as_bytes()).unwrap_err();
+            assert_eq!(error.kind(), ErrorKind::InvalidData, "{shape}");
+        }
+    }
+}
@@ -0,0 +1,1051 @@
+//! Driving an [`Engine`] through many generations.
+
+use std::io::{self, ErrorKind};
+use std::path::Path;
+use std::sync::Arc;
+
+use rand::SeedableRng;
+use rand::rngs::SmallRng;
+
+use crate::age::Ages;
+use crate::cell::{Cell, Grid, GridDelta, GridPool, Overflow};
+use crate::checkpoint::Checkpoint;
+use crate::cycle::CycleDetector;
+use crate::engine::{Engine, SerialEngine};
+use crate::heatmap::Heatmap;
+use crate::noise::Noise;
+#[cfg(feature = "parallel")]
+use crate::par::*;
+use crate::replay::Replay;
+use crate::rewind::Rewind;
+use crate::session::Session;
+use crate::stats::Stats;
+use crate::trail::Trails;
+
+/// Dead cells added past an edge when live cells reach it, see [`Simulation::expand_up_to`].
+pub(crate) const GROWTH: usize = 32;
+
+/// An engine together with the grid it is evolving, and when to stop it.
+///
+/// Each generation is computed into a second buffer, which is swapped with the current grid, so
+/// running a simulation doesn't allocate. The grids are reference counted, so another thread can
+/// hold on to a generation with [`shared_grid`](Simulation::shared_grid) without copying it, in
+/// which case the next one is computed into a grid from a [`GridPool`], that is reused once the
+/// other thread lets go of it. The first grid is also kept, for
+/// [`replay`](Simulation::replay). By default, a simulation never stops on its own.
+///
+/// # Example
+///
+/// ```
+/// # use vida::cell::{Cell, Grid};
+/// # use vida::engine::SerialEngine;
+/// # use vida::simulation::{Simulation, StopReason};
+/// #
+/// let mut grid = Grid::new(4, 4);
+/// grid[(1, 1)] = Cell::Live;
+///
+/// let mut simulation = Simulation::new(SerialEngine::default(), grid)
+///     .with_max_generations(100)
+///     .stop_on_extinction(true);
+///
+/// assert_eq!(simulation.run(), StopReason::Extinction);
+/// assert_eq!(simulation.generation(), 1);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Simulation<E> {
+    engine: E,
+    grid: Arc<Grid>,
+    /// The previous generation, after the first step.
+    previous: Arc<Grid>,
+    /// Grids that were shared when they would be reused, waiting to be free again.
+    pool: GridPool,
+    generation: usize,
+    max_generations: Option<usize>,
+    stop_on_extinction: bool,
+    stop_on_stable: bool,
+    stable: bool,
+    cycles: Option<CycleDetector>,
+    /// Period of the cycle found in the last step.
+    period: Option<usize>,
+    stats: Option<Stats>,
+    ages: Option<Arc<Ages>>,
+    activity: Option<Arc<Heatmap>>,
+    trails: Option<Arc<Trails>>,
+    /// Cells born and dead in the last generation.
+    changes: Option<Arc<GridDelta>>,
+    /// Recent generations, for [`step_back`](Simulation::step_back).
+    rewind: Option<Rewind>,
+    /// Largest shape the grid can expand to, if it expands at all.
+    expansion: Option<(usize, usize)>,
+    /// Position of the initial grid inside the current one.
+    origin: (usize, usize),
+    /// Seed of the initial random grid, kept in checkpoints.
+    seed: Option<u64>,
+    /// Fraction of live cells in the grids drawn by [`Simulation::randomize`].
+    density: f64,
+    /// Random flips applied after each generation.
+    noise: Option<Noise>,
+    /// The grid and generation where the simulation started, or was last reset or edited, for replays.
+    start: (Arc<Grid>, usize),
+}
+
+/// Why a [`Simulation`] stopped.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum StopReason {
+    /// The generation limit was reached.
+    MaxGenerations,
+    /// There are no live cells and no agents left.
+    Extinction,
+    /// The last generation was the same as the one before it.
+    Stable,
+    /// The last generation repeated an earlier one, with the given period.
+    Cycle(usize),
+}
+
+impl<E: Engine> Simulation<E> {
+    #[inline]
+    #[must_use]
+    /// Starts a simulation at generation zero.
+    pub fn new(engine: E, grid: Grid) -> Self {
+        let grid = Arc::new(grid);
+        Self {
+            engine,
+            start: (Arc::clone(&grid), 0),
+            grid,
+            previous: Arc::new(Grid::empty()),
+            pool: GridPool::default(),
+            generation: 0,
+            max_generations: None,
+            stop_on_extinction: false,
+            stop_on_stable: false,
+            stable: false,
+            cycles: None,
+            period: None,
+            stats: None,
+            ages: None,
+            activity: None,
+            trails: None,
+            changes: None,
+            rewind: None,
+            expansion: None,
+            origin: (0, 0),
+            seed: None,
+            density: 0.5,
+            noise: None,
+        }
+    }
+
+    #[must_use]
+    /// Continues the simulation saved in a checkpoint, with the given engine.
+    ///
+    /// The engine isn't checked against the one in the checkpoint, see
+    /// [`load`](Simulation::load) for that.
+    pub fn resume(engine: E, checkpoint: Checkpoint) -> Self {
+        Self::new(engine, checkpoint.grid)
+            .with_generation(checkpoint.generation)
+            .with_seed(checkpoint.seed)
+    }
+
+    /// Continues the simulation saved to a file by [`save`](Simulation::save).
+    ///
+    /// Fails if the checkpoint was saved with another engine or rule.
+    pub fn load(engine: E, path: impl AsRef<Path>) -> io::Result<Self> {
+        let checkpoint = Checkpoint::load(path)?;
+        if checkpoint.engine != engine.name() {
+            let message = format!("checkpoint saved with the {} engine, not {}", checkpoint.engine, engine.name());
+            return Err(io::Error::new(ErrorKind::InvalidData, message));
+        }
+        if checkpoint.rule != engine.life_rule() {
+            return Err(io::Error::new(ErrorKind::InvalidData, "checkpoint saved with another rule"));
+        }
+        Ok(Self::resume(engine, checkpoint))
+    }
+
+    #[inline]
+    #[must_use]
+    /// Starts counting from another generation, like when resuming a session.
+    pub fn with_generation(mut self, generation: usize) -> Self {
+        self.generation = generation;
+        self.start.1 = generation;
+        self
+    }
+
+    #[inline]
+    #[must_use]
+    /// Uses another pool for the grids shared while running, like a larger one for a renderer
+    /// that holds on to a few generations.
+    pub fn with_pool(mut self, pool: GridPool) -> Self {
+        self.pool = pool;
+        self
+    }
+
+    #[inline]
+    #[must_use]
+    /// Records the seed of a random initial grid, so checkpoints can tell where the run came from.
+    pub fn with_seed(mut self, seed: Option<u64>) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    #[inline]
+    #[must_use]
+    /// The seed of the initial grid, if set with [`with_seed`](Simulation::with_seed).
+    pub const fn seed(&self) -> Option<u64> {
+        self.seed
+    }
+
+    #[inline]
+    #[must_use]
+    /// Sets the fraction of live cells in the grids drawn by [`randomize`](Simulation::randomize),
+    /// one half by default.
+    ///
+    /// # Panics
+    ///
+    /// If `density` is not between 0 and 1.
+    pub fn with_density(mut self, density: f64) -> Self {
+        assert!((0.0..=1.0).contains(&density), "density must be between 0 and 1");
+        self.density = density;
+        self
+    }
+
+    #[inline]
+    #[must_use]
+    /// Flips random cells after each generation is computed, by any engine, or never with
+    /// [`None`]. The flips are seen by the stopping criteria and statistics like any other change.
+    pub fn with_noise(mut self, noise: Option<Noise>) -> Self {
+        self.noise = noise;
+        self
+    }
+
+    #[inline]
+    #[must_use]
+    /// The random flips applied after each generation, if set with
+    /// [`with_noise`](Simulation::with_noise).
+    pub const fn noise(&self) -> Option<&Noise> {
+        self.noise.as_ref()
+    }
+
+    #[inline]
+    #[must_use]
+    /// Keeps the recent generations in `rewind`, so the simulation can
+    /// [step back](Simulation::step_back) to them, or none with [`None`].
+    ///
+    /// Engines with agents keep their own state, which can't go back with the grid, so no
+    /// generations are kept for them.
+    pub fn with_rewind(mut self, rewind: Option<Rewind>) -> Self {
+        self.rewind = rewind.filter(|_| self.engine.agents().is_empty());
+        self
+    }
+
+    #[inline]
+    #[must_use]
+    /// The recent generations kept, if set with [`with_rewind`](Simulation::with_rewind).
+    pub const fn rewind(&self) -> Option<&Rewind> {
+        self.rewind.as_ref()
+    }
+
+    #[inline]
+    #[must_use]
+    /// Stops once the generation number reaches `max`, counting from zero even for simulations
+    /// started at another generation.
+    pub fn with_max_generations(mut self, max: usize) -> Self {
+        self.max_generations = Some(max);
+        self
+    }
+
+    #[inline]
+    #[must_use]
+    /// Stops when there are no live cells and no agents left.
+    pub fn stop_on_extinction(mut self, stop: bool) -> Self {
+        self.stop_on_extinction = stop;
+        self
+    }
+
+    #[inline]
+    #[must_use]
+    /// Stops when a generation is the same as the one before it, i.e. only still lifes are left.
+    pub fn stop_on_stable(mut self, stop: bool) -> Self {
+        self.stop_on_stable = stop;
+        self
+    }
+
+    #[must_use]
+    /// Stops when a generation repeats any of the last `window` generations, detecting still lifes
+    /// and oscillators with period up to `window`. [`None`] disables the detection.
+    pub fn stop_on_cycle(mut self, window: Option<usize>) -> Self {
+        self.cycles = window.map(|window| {
+            let mut cycles = CycleDetector::new(window);
+            cycles.push(&self.grid);
+            cycles
+        });
+        self.period = None;
+        self
+    }
+
+    #[inline]
+    #[must_use]
+    /// The longest period detected, if set with [`stop_on_cycle`](Simulation::stop_on_cycle).
+    pub fn cycle_window(&self) -> Option<usize> {
+        self.cycles.as_ref().map(CycleDetector::window)
+    }
+
+    #[inline]
+    #[must_use]
+    /// Grows the board when live cells reach its edges, adding dead cells past those edges, so
+    /// patterns like gliders keep going instead of dying at the wall. The grid never grows past
+    /// `limit` rows and columns, and [`None`] keeps the board fixed.
+    ///
+    /// Engines with agents keep their own positions, so their boards never expand. See
+    /// [`origin`](Simulation::origin) for where the initial grid ends up.
+    pub fn expand_up_to(mut self, limit: Option<(usize, usize)>) -> Self {
+        self.expansion = limit;
+        self
+    }
+
+    #[inline]
+    #[must_use]
+    /// Position `(row, col)` of the initial grid inside the current one, which only moves when the
+    /// board expands up or to the left.
+    pub const fn origin(&self) -> (usize, usize) {
+        self.origin
+    }
+
+    #[must_use]
+    /// Records population, births and deaths on every generation from now on.
+    pub fn collect_stats(mut self, collect: bool) -> Self {
+        self.stats = collect.then(|| Stats::new(&self.grid, self.generation));
+        self
+    }
+
+    #[inline]
+    #[must_use]
+    /// The statistics collected so far, if enabled with [`collect_stats`](Simulation::collect_stats).
+    pub const fn stats(&self) -> Option<&Stats> {
+        self.stats.as_ref()
+    }
+
+    #[must_use]
+    /// Counts how many generations each cell has been alive, from now on.
+    pub fn track_ages(mut self, track: bool) -> Self {
+        self.ages = track.then(|| Arc::new(Ages::new(&self.grid)));
+        self
+    }
+
+    #[inline]
+    #[must_use]
+    /// The age of each cell, if enabled with [`track_ages`](Simulation::track_ages).
+    pub fn ages(&self) -> Option<&Ages> {
+        self.ages.as_deref()
+    }
+
+    #[inline]
+    #[must_use]
+    /// The ages of the cells, shared without copying them. See [`shared_grid`](Simulation::shared_grid).
+    pub fn shared_ages(&self) -> Option<Arc<Ages>> {
+        self.ages.clone()
+    }
+
+    #[must_use]
+    /// Counts how many generations each cell has been alive in total, from now on, for heatmaps.
+    pub fn track_activity(mut self, track: bool) -> Self {
+        self.activity = track.then(|| Arc::new(Heatmap::new(&self.grid)));
+        self
+    }
+
+    #[inline]
+    #[must_use]
+    /// The activity of each cell, if enabled with [`track_activity`](Simulation::track_activity).
+    pub fn activity(&self) -> Option<&Heatmap> {
+        self.activity.as_deref()
+    }
+
+    #[inline]
+    #[must_use]
+    /// The activity of the cells, shared without copying it. See [`shared_grid`](Simulation::shared_grid).
+    pub fn shared_activity(&self) -> Option<Arc<Heatmap>> {
+        self.activity.clone()
+    }
+
+    #[must_use]
+    /// Follows where cells died, from now on, keeping trails that fade out after `length`
+    /// generations, or none at all.
+    pub fn track_trails(mut self, length: Option<usize>) -> Self {
+        self.trails = length.map(|length| Arc::new(Trails::new(&self.grid, length)));
+        self
+    }
+
+    #[inline]
+    #[must_use]
+    /// Where cells died recently, if enabled with [`track_trails`](Simulation::track_trails).
+    pub fn trails(&self) -> Option<&Trails> {
+        self.trails.as_deref()
+    }
+
+    #[inline]
+    #[must_use]
+    /// The trails of the cells, shared without copying them. See [`shared_grid`](Simulation::shared_grid).
+    pub fn shared_trails(&self) -> Option<Arc<Trails>> {
+        self.trails.clone()
+    }
+
+    #[must_use]
+    /// Keeps the cells born and dead in each generation, from the one before it.
+    pub fn track_changes(mut self, track: bool) -> Self {
+        self.changes = track.then(|| Arc::new(GridDelta::default()));
+        self
+    }
+
+    #[inline]
+    #[must_use]
+    /// The cells born and dead in the last generation, if enabled with
+    /// [`track_changes`](Simulation::track_changes). Empty after the board is replaced, edited or
+    /// enlarged.
+    pub fn changes(&self) -> Option<&GridDelta> {
+        self.changes.as_deref()
+    }
+
+    #[inline]
+    #[must_use]
+    /// The changes of the last generation, shared without copying them. See [`shared_grid`](Simulation::shared_grid).
+    pub fn shared_changes(&self) -> Option<Arc<GridDelta>> {
+        self.changes.clone()
+    }
+
+    #[inline]
+    #[must_use]
+    /// The engine used to compute each generation.
+    pub const fn engine(&self) -> &E {
+        &self.engine
+    }
+
+    #[inline]
+    #[must_use]
+    /// The current state of the board.
+    pub fn grid(&self) -> &Grid {
+        &self.grid
+    }
+
+    #[inline]
+    #[must_use]
+    /// The current state of the board, shared without copying it.
+    ///
+    /// The simulation never changes a grid while it is shared, so the returned grid stays the
+    /// same after the next steps.
+    pub fn shared_grid(&self) -> Arc<Grid> {
+        Arc::clone(&self.grid)
+    }
+
+    #[inline]
+    #[must_use]
+    /// The current generation number.
+    pub const fn generation(&self) -> usize {
+        self.generation
+    }
+
+    #[must_use]
+    /// The current state of the simulation, to be saved and resumed later.
+    pub fn checkpoint(&self) -> Checkpoint {
+        Checkpoint {
+            engine: self.engine.name().to_owned(),
+            rule: self.engine.life_rule(),
+            generation: self.generation,
+            seed: self.seed,
+            grid: Grid::clone(&self.grid),
+        }
+    }
+
+    #[must_use]
+    /// The run since the simulation started, or was last [`reset`](Simulation::reset), to be
+    /// played again later.
+    pub fn replay(&self) -> Replay {
+        let (grid, generation) = &self.start;
+        let start = Checkpoint {
+            engine: self.engine.name().to_owned(),
+            rule: self.engine.life_rule(),
+            generation: *generation,
+            seed: self.seed,
+            grid: Grid::clone(grid),
+        };
+        Replay { start, generations: self.generation - generation, expansion: self.expansion }
+    }
+
+    /// Writes a [`checkpoint`](Simulation::checkpoint) to a file, to be resumed with
+    /// [`load`](Simulation::load).
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        self.checkpoint().save(path)
+    }
+
+    #[inline]
+    #[must_use]
+    /// Ends the simulation, returning the current grid.
+    pub fn into_grid(self) -> Grid {
+        let (grid, start) = (self.grid, self.start);
+        drop(start);
+        Arc::try_unwrap(grid).unwrap_or_else(|grid| Grid::clone(&grid))
+    }
+
+    /// Replaces the board and the generation number, keeping the engine and stopping criteria.
+    pub fn reset(&mut self, grid: Grid, generation: usize) {
+        self.replace_grid(grid);
+        self.generation = generation;
+        self.start = (Arc::clone(&self.grid), generation);
+        // the board no longer comes from the seed
+        self.seed = None;
+        self.origin = (0, 0);
+        self.stable = false;
+        self.period = None;
+
+        if let Some(cycles) = &mut self.cycles {
+            cycles.clear();
+            cycles.push(&self.grid);
+        }
+        if let Some(stats) = &mut self.stats {
+            *stats = Stats::new(&self.grid, generation);
+        }
+        if let Some(ages) = &mut self.ages {
+            *ages = Arc::new(Ages::new(&self.grid));
+        }
+        if let Some(activity) = &mut self.activity {
+            *activity = Arc::new(Heatmap::new(&self.grid));
+        }
+        if let Some(trails) = &mut self.trails {
+            *trails = Arc::new(Trails::new(&self.grid, trails.length()));
+        }
+        if let Some(changes) = &mut self.changes {
+            *changes = Arc::default();
+        }
+        if let Some(rewind) = &mut self.rewind {
+            rewind.clear();
+        }
+    }
+
+    /// Replaces the board with a random one of the same shape, like [`reset`](Simulation::reset),
+    /// drawn from a new seed with the density set by [`with_density`](Simulation::with_density).
+    ///
+    /// The new seed is recorded, so checkpoints can tell where the board came from.
+    pub fn randomize(&mut self) {
+        let seed = rand::random();
+        let (rows, columns) = self.grid.shape();
+        let grid = Grid::random_with_density(rows, columns, self.density, &mut SmallRng::seed_from_u64(seed));
+        self.reset(grid, 0);
+        self.seed = Some(seed);
+    }
+
+    /// Continues from a saved session, like [`reset`](Simulation::reset), also restoring its seed.
+    ///
+    /// The noise and the cycle detection are restored if the session has them, and kept
+    /// otherwise, as in sessions saved by older versions.
+    pub fn restore(&mut self, session: Session) {
+        self.reset(session.grid, session.generation);
+        self.seed = session.seed;
+        if let Some(noise) = session.noise {
+            self.noise = Some(noise);
+        }
+        if let Some(window) = session.cycle_window {
+            let mut cycles = CycleDetector::new(window);
+            cycles.push(&self.grid);
+            self.cycles = Some(cycles);
+        }
+    }
+
+    /// Copies every cell of `pattern` into the board, with its top-left corner at `(row, col)`,
+    /// leaving out the cells past the edges, as in [`Grid::blit`].
+    ///
+    /// The generation, the [`origin`](Simulation::origin) and the tracked ages, activity and
+    /// trails are kept, but replays start again from the edited board.
+    pub fn paste(&mut self, pattern: &Grid, (row, col): (usize, usize)) {
+        let mut grid = Grid::clone(&self.grid);
+        if grid.blit(pattern, row, col, Overflow::Clip).is_err() {
+            return;
+        }
+        self.replace_grid(grid);
+        self.start = (Arc::clone(&self.grid), self.generation);
+        self.seed = None;
+        self.stable = false;
+        self.period = None;
+
+        if let Some(cycles) = &mut self.cycles {
+            cycles.clear();
+            cycles.push(&self.grid);
+        }
+        if let Some(changes) = &mut self.changes {
+            *changes = Arc::default();
+        }
+        if let Some(rewind) = &mut self.rewind {
+            rewind.clear();
+        }
+    }
+
+    /// Computes the next generation, even if the simulation should have stopped.
+    pub fn step(&mut self) {
+        if let Some(limit) = self.expansion {
+            self.expand(limit);
+        }
+
+        if Arc::get_mut(&mut self.previous).is_none() {
+            // still shared, so it is left alone until it is free again
+            let (rows, columns) = self.grid.shape();
+            let shared = std::mem::replace(&mut self.previous, self.pool.acquire(rows, columns));
+            self.pool.release(shared);
+        }
+        let previous = Arc::get_mut(&mut self.previous).expect("grids from the pool are not shared");
+        self.engine.update_into(&self.grid, previous);
+        if let Some(noise) = &mut self.noise {
+            noise.apply(previous);
+        }
+        std::mem::swap(&mut self.grid, &mut self.previous);
+        self.generation += 1;
+
+        if self.stop_on_stable {
+            self.stable = self.grid == self.previous && self.engine.agents().is_empty();
+        }
+        if let Some(cycles) = &mut self.cycles {
+            self.period = cycles.push(&self.grid).filter(|_| self.engine.agents().is_empty());
+        }
+        if let Some(stats) = &mut self.stats {
+            stats.record(&self.previous, &self.grid);
+        }
+        if let Some(ages) = &mut self.ages {
+            Arc::make_mut(ages).update(&self.grid);
+        }
+        if let Some(activity) = &mut self.activity {
+            Arc::make_mut(activity).update(&self.grid);
+        }
+        let delta = (self.trails.is_some() || self.changes.is_some() || self.rewind.is_some()).then(|| self.previous.diff(&self.grid));
+        if let (Some(trails), Some(delta)) = (&mut self.trails, &delta) {
+            Arc::make_mut(trails).update(delta);
+        }
+        if let (Some(changes), Some(delta)) = (&mut self.changes, &delta) {
+            *changes = Arc::new(delta.clone());
+        }
+        if let (Some(rewind), Some(delta)) = (&mut self.rewind, delta) {
+            rewind.push(delta);
+        }
+    }
+
+    /// Goes back to the previous generation, if it was kept by
+    /// [`with_rewind`](Simulation::with_rewind), returning `false` otherwise.
+    ///
+    /// Statistics, the activity of the cells and their changes go back with it, while the ages of
+    /// cells that come back to life start again from `1`, and the trails start again.
+    pub fn step_back(&mut self) -> bool {
+        let Some(rewind) = &mut self.rewind else {
+            return false;
+        };
+        let mut grid = Grid::clone(&self.grid);
+        if !rewind.step_back(&mut grid) {
+            return false;
+        }
+
+        if let Some(activity) = &mut self.activity {
+            Arc::make_mut(activity).step_back(&self.grid);
+        }
+        self.replace_grid(grid);
+        self.generation -= 1;
+        self.stable = false;
+        self.period = None;
+
+        if let Some(cycles) = &mut self.cycles {
+            cycles.clear();
+            cycles.push(&self.grid);
+        }
+        if let Some(stats) = &mut self.stats {
+            stats.forget_last();
+        }
+        if let Some(ages) = &mut self.ages {
+            Arc::make_mut(ages).step_back(&self.grid);
+        }
+        if let Some(trails) = &mut self.trails {
+            *trails = Arc::new(Trails::new(&self.grid, trails.length()));
+        }
+        if let Some(changes) = &mut self.changes {
+            // the changes into the earlier generation are only known if it was kept too
+            *changes = Arc::new(self.rewind.as_ref().and_then(Rewind::last).cloned().unwrap_or_default());
+        }
+        true
+    }
+
+    /// Adds dead cells past each edge with a live cell, before computing the next generation.
+    fn expand(&mut self, (max_rows, max_columns): (usize, usize)) {
+        let (rows, columns) = self.grid.shape();
+        if rows == 0 || columns == 0 || !self.engine.agents().is_empty() {
+            return;
+        }
+
+        let grid = &self.grid;
+        let live_row = |row: usize| grid[row].iter().any(Cell::is_live);
+        let live_column = |col: usize| grid.iter().any(|cells| cells[col].is_live());
+        let growth = |live: bool, room: usize| if live { GROWTH.min(room) } else { 0 };
+
+        let top = growth(live_row(0), max_rows.saturating_sub(rows));
+        let bottom = growth(live_row(rows - 1), max_rows.saturating_sub(rows + top));
+        let left = growth(live_column(0), max_columns.saturating_sub(columns));
+        let right = growth(live_column(columns - 1), max_columns.saturating_sub(columns + left));
+        if top + bottom + left + right == 0 {
+            return;
+        }
+
+        self.enlarge((top, left), (rows + top + bottom, columns + left + right));
+    }
+
+    /// Adds `margin` dead cells past every edge of the board, keeping the generation.
+    ///
+    /// Like automatic expansion, this moves the [`origin`](Simulation::origin).
+    pub fn grow(&mut self, margin: usize) {
+        let (rows, columns) = self.grid.shape();
+        if margin > 0 && rows > 0 && columns > 0 {
+            self.enlarge((margin, margin), (rows + 2 * margin, columns + 2 * margin));
+        }
+    }
+
+    /// Copies the board at `(top, left)` of a larger one with `shape`.
+    fn enlarge(&mut self, (top, left): (usize, usize), shape: (usize, usize)) {
+        self.replace_grid(self.grid.expanded((top, left), shape));
+        self.origin = (self.origin.0 + top, self.origin.1 + left);
+
+        // earlier generations are in another position, so they never repeat
+        if let Some(cycles) = &mut self.cycles {
+            cycles.clear();
+            cycles.push(&self.grid);
+        }
+        if let Some(ages) = &mut self.ages {
+            *ages = Arc::new(ages.expanded((top, left), shape));
+        }
+        if let Some(activity) = &mut self.activity {
+            *activity = Arc::new(activity.expanded((top, left), shape));
+        }
+        if let Some(trails) = &mut self.trails {
+            *trails = Arc::new(trails.expanded((top, left), shape));
+        }
+        if let Some(changes) = &mut self.changes {
+            *changes = Arc::default();
+        }
+        // earlier generations have another shape
+        if let Some(rewind) = &mut self.rewind {
+            rewind.clear();
+        }
+    }
+
+    /// Replaces the current grid, giving the old one to the pool.
+    fn replace_grid(&mut self, grid: Grid) {
+        let old = std::mem::replace(&mut self.grid, Arc::new(grid));
+        self.pool.release(old);
+    }
+
+    #[must_use]
+    /// The first criterion met by the current generation, if the simulation should stop.
+    pub fn stop_reason(&self) -> Option<StopReason> {
+        if self.max_generations.is_some_and(|max| self.generation >= max) {
+            Some(StopReason::MaxGenerations)
+        } else if self.stop_on_extinction && self.grid.population() == 0 && self.engine.agents().is_empty() {
+            Some(StopReason::Extinction)
+        } else if self.stable {
+            Some(StopReason::Stable)
+        } else {
+            self.period.map(StopReason::Cycle)
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    /// Checks if any stopping criterion was met.
+    pub fn is_finished(&self) -> bool {
+        self.stop_reason().is_some()
+    }
+
+    /// Computes generations until one of the stopping criteria is met.
+    ///
+    /// Without any criteria, this never returns.
+    pub fn run(&mut self) -> StopReason {
+        loop {
+            if let Some(reason) = self.stop_reason() {
+                return reason;
+            }
+            self.step();
+        }
+    }
+
+    #[cfg(feature = "parallel")]
+    /// Runs one independent simulation for each seed, concurrently.
+    ///
+    /// Each run starts from a random grid generated from its seed, so the summaries are
+    /// reproducible. They are returned in the same order as the seeds.
+    pub fn run_batch(seeds: impl IntoParallelIterator<Item = u64>, config: &BatchConfig<E>) -> Vec<RunSummary>
+    where
+        E: Clone + Sync
+    {
+        seeds.into_par_iter()
+            .map(|seed| {
+                let mut rng = SmallRng::seed_from_u64(seed);
+                let grid = Grid::random_with(config.rows, config.columns, &mut rng);
+                let initial_population = grid.population();
+
+                let mut simulation = Self::new(config.engine.clone(), grid).with_max_generations(config.generations);
+                simulation.run();
+
+                RunSummary {
+                    seed,
+                    generations: simulation.generation(),
+                    initial_population,
+                    final_population: simulation.grid().population(),
+                }
+            })
+            .collect()
+    }
+}
+
+/// Parameters shared by all runs of [`Simulation::run_batch`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BatchConfig<E = SerialEngine> {
+    /// Engine cloned into each run.
+    pub engine: E,
+    /// Number of rows in each grid.
+    pub rows: usize,
+    /// Number of columns in each grid.
+    pub columns: usize,
+    /// Generations computed in each run.
+    pub generations: usize,
+}
+
+/// Outcome of a single run from [`Simulation::run_batch`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct RunSummary {
+    /// Seed used to generate the initial grid.
+    pub seed: u64,
+    /// Number of generations computed.
+    pub generations: usize,
+    /// Live cells in the initial grid.
+    pub initial_population: usize,
+    /// Live cells in the last generation.
+    pub final_population: usize,
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cell::Cell;
+    use crate::engine::{Ant, AntEngine, BitEngine, Turmite};
+
+    use super::*;
+
+    #[test]
+    #[cfg(feature = "parallel")]
+    pub fn batch_is_reproducible() {
+        let config = BatchConfig { engine: SerialEngine::default(), rows: 20, columns: 30, generations: 10 };
+
+        let batch = Simulation::run_batch(0..8_u64, &config);
+        assert_eq!(batch, Simulation::run_batch(0..8_u64, &config));
+
+        for (summary, seed) in batch.iter().zip(0..) {
+            let mut rng = SmallRng::seed_from_u64(seed);
+            let mut simulation = Simulation::new(SerialEngine::default(), Grid::random_with(20, 30, &mut rng));
+            for _ in 0..10 {
+                simulation.step()
+            }
+
+            assert_eq!(summary.seed, seed);
+            assert_eq!(summary.generations, 10);
+            assert_eq!(summary.final_population, simulation.grid().population());
+        }
+    }
+
+    #[test]
+    pub fn stopping_criteria() {
+        let mut block = Grid::new(6, 6);
+        for (row, col) in [(1, 1), (1, 2), (2, 1), (2, 2), (4, 4)] {
+            block[(row, col)] = Cell::Live;
+        }
+
+        // the lone cell dies and then the block stays still
+        let mut simulation = Simulation::new(SerialEngine::default(), block.clone()).stop_on_stable(true);
+        assert_eq!(simulation.run(), StopReason::Stable);
+        assert_eq!(simulation.generation(), 2);
+
+        let mut simulation = Simulation::new(SerialEngine::default(), block)
+            .with_generation(5)
+            .with_max_generations(7)
+            .stop_on_extinction(true);
+        assert_eq!(simulation.run(), StopReason::MaxGenerations);
+        assert_eq!(simulation.generation(), 7);
+    }
+
+    #[test]
+    pub fn noisy_runs() {
+        let grid = Grid::random_seeded(40, 50, 582);
+        let noise = Some(Noise::new(0.01, 9));
+
+        let mut serial = Simulation::new(SerialEngine::default(), grid.clone()).with_noise(noise.clone());
+        let mut bit = Simulation::new(BitEngine, grid.clone()).with_noise(noise.clone());
+        let mut quiet = Simulation::new(SerialEngine::default(), grid.clone());
+        for _ in 0..10 {
+            serial.step();
+            bit.step();
+            quiet.step();
+            assert_eq!(serial.grid(), bit.grid());
+        }
+        assert_ne!(serial.grid(), quiet.grid());
+
+        let mut empty = Simulation::new(SerialEngine::default(), Grid::new(40, 50)).with_noise(noise);
+        empty.step();
+        assert!(empty.grid().population() > 0);
+    }
+
+    #[test]
+    pub fn shared_grids_stay_the_same() {
+        let mut simulation = Simulation::new(SerialEngine::default(), Grid::random_seeded(8, 8, 539)).track_ages(true);
+        let (grid, ages) = (simulation.shared_grid(), simulation.shared_ages().unwrap());
+        let (copy, ages_copy) = (Grid::clone(&grid), Ages::clone(&ages));
+
+        for _ in 0..3 {
+            simulation.step();
+        }
+        assert_eq!(*grid, copy);
+        assert_eq!(*ages, ages_copy);
+        assert!(!Arc::ptr_eq(&grid, &simulation.shared_grid()));
+        assert_eq!(*simulation.shared_grid(), *simulation.grid());
+    }
+
+    #[test]
+    pub fn shared_grids_are_recycled() {
+        let mut simulation = Simulation::new(SerialEngine::default(), Grid::random_seeded(16, 16, 570));
+        simulation.step();
+
+        // a reader holding on to every generation, like a slow renderer
+        let mut shared: Vec<_> = (0..3).map(|_| {
+            let grid = simulation.shared_grid();
+            simulation.step();
+            grid
+        }).collect();
+        shared.push(simulation.shared_grid());
+        let addresses: Vec<_> = shared.iter().map(|grid| grid.flat().as_ptr()).collect();
+        shared.clear();
+
+        let mut reused = Vec::new();
+        for _ in 0..3 {
+            let grid = simulation.shared_grid();
+            reused.push(grid.flat().as_ptr());
+            simulation.step();
+        }
+        assert!(reused.iter().all(|address| addresses.contains(address)), "{reused:?} not in {addresses:?}");
+
+        let mut expected = Simulation::new(SerialEngine::default(), Grid::random_seeded(16, 16, 570));
+        for _ in 0..7 {
+            expected.step();
+        }
+        assert_eq!(simulation.grid(), expected.grid());
+    }
+
+    #[test]
+    pub fn growing_board() {
+        let grid = Grid::random_seeded(10, 12, 562);
+        let mut simulation = Simulation::new(SerialEngine::default(), grid.clone()).track_ages(true);
+        simulation.step();
+        let population = simulation.grid().population();
+
+        simulation.grow(3);
+        assert_eq!(simulation.grid().shape(), (16, 18));
+        assert_eq!(simulation.grid().population(), population);
+        assert_eq!(simulation.generation(), 1);
+        assert_eq!(simulation.origin(), (3, 3));
+        assert_eq!(simulation.ages().unwrap().get(15, 17), Some(0));
+
+        simulation.grow(0);
+        assert_eq!(simulation.grid().shape(), (16, 18));
+    }
+
+    #[test]
+    pub fn agents_are_not_rewound() {
+        let engine = AntEngine::new(Turmite::langton(), [Ant::new(2, 2)]);
+        let mut simulation = Simulation::new(engine, Grid::new(5, 5)).with_rewind(Some(Rewind::new(8, 1 << 20)));
+        assert!(simulation.rewind().is_none());
+
+        simulation.step();
+        assert!(!simulation.step_back());
+        assert_eq!(simulation.generation(), 1);
+    }
+
+    #[test]
+    pub fn pasting_a_pattern() {
+        let mut simulation = Simulation::new(SerialEngine::default(), Grid::new(8, 8)).track_ages(true).with_seed(Some(595));
+        simulation.step();
+
+        let block = Grid::new_with(2, 2, Cell::Live);
+        simulation.paste(&block, (7, 3));
+        assert_eq!(simulation.grid().population(), 2);
+        assert_eq!((simulation.generation(), simulation.seed()), (1, None));
+        assert_eq!(simulation.replay().start.grid, *simulation.grid());
+
+        simulation.paste(&block, (2, 2));
+        simulation.step();
+        assert_eq!(simulation.grid().view(2..4, 2..4).population(), 4);
+        assert_eq!(simulation.ages().unwrap().get(2, 2), Some(1));
+    }
+
+    #[test]
+    pub fn randomizing_records_the_seed() {
+        let mut simulation = Simulation::new(SerialEngine::default(), Grid::new(40, 50)).with_density(0.1);
+        simulation.step();
+        simulation.randomize();
+
+        let seed = simulation.seed().unwrap();
+        let expected = Grid::random_with_density(40, 50, 0.1, &mut SmallRng::seed_from_u64(seed));
+        assert_eq!((simulation.generation(), simulation.grid()), (0, &expected));
+    }
+
+    #[test]
+    pub fn changes_of_the_last_generation() {
+        let mut blinker = Grid::new(5, 5);
+        blinker[2][1..4].fill(Cell::Live);
+        let mut simulation = Simulation::new(SerialEngine::default(), blinker.clone()).track_changes(true)
+            .with_rewind(Some(crate::rewind::Rewind::new(8, usize::MAX)));
+        assert!(simulation.changes().is_some_and(GridDelta::is_empty));
+
+        simulation.step();
+        let changes = simulation.shared_changes().unwrap();
+        assert_eq!((changes.births(), changes.deaths()), (&[(1, 2), (3, 2)][..], &[(2, 1), (2, 3)][..]));
+        simulation.step();
+        assert_eq!(simulation.changes(), Some(&changes.reversed()));
+
+        // going back shows the changes into the earlier generation
+        simulation.step_back();
+        assert_eq!(simulation.changes(), Some(&*changes));
+        simulation.step_back();
+        assert!(simulation.changes().is_some_and(GridDelta::is_empty));
+
+        simulation.paste(&blinker, (0, 0));
+        assert!(simulation.changes().is_some_and(GridDelta::is_empty));
+    }
+
+    #[test]
+    pub fn expanding_board() {
+        let mut glider = Grid::new(6, 6);
+        for (row, col) in [(0, 1), (1, 2), (2, 0), (2, 1), (2, 2)] {
+            glider[(row, col)] = Cell::Live;
+        }
+
+        let mut simulation = Simulation::new(SerialEngine::default(), glider.clone()).expand_up_to(Some((1000, 1000)));
+        // the glider first touches the top and left edges
+        simulation.step();
+        assert_eq!(simulation.origin(), (GROWTH, GROWTH));
+
+        // then keeps going down and right, one cell every 4 generations
+        for _ in 1..4 * 100 {
+            simulation.step();
+        }
+        let (row, col) = simulation.origin();
+        assert_eq!(simulation.grid().population(), 5);
+        for (cells, expected) in simulation.grid().iter().skip(row + 100).zip(glider.iter().take(3)) {
+            assert_eq!(cells[col + 100..col + 103], expected[..3]);
+        }
+
+        let mut simulation = Simulation::new(SerialEngine::default(), glider.clone()).expand_up_to(Some((40, 50)));
+        for _ in 0..4 * 100 {
+            simulation.step();
+        }
+        assert_eq!(simulation.grid().shape(), (40, 50));
+
+        // a fixed board kills it much earlier
+        let mut simulation = Simulation::new(SerialEngine::default(), glider).stop_on_stable(true);
+        simulation.run();
+        assert!(simulation.generation() < 30);
+    }
+}
@@ -0,0 +1,370 @@
+//! Searching random soups, to see how long they live and what they settle into.
+//!
+//! Each soup is a random board generated from its own seed, run until it dies out, stops changing
+//! or repeats an earlier generation, so searches are reproducible and can be split across
+//! machines by their seeds. This is used by the `vida soup` command.
+
+use std::collections::BTreeMap;
+use std::fmt::{self, Display, Formatter};
+use std::io::{self, Write};
+
+use rand::SeedableRng;
+use rand::rngs::SmallRng;
+
+use crate::analysis::census::Census;
+use crate::cell::Grid;
+use crate::engine::{Engine, SerialEngine};
+use crate::par::*;
+use crate::simulation::{Simulation, StopReason};
+
+/// How a soup ended.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Fate {
+    /// No live cells were left.
+    Extinct,
+    /// Only still lifes were left.
+    Stable,
+    /// The board repeated with the given period, greater than one, which is the least common
+    /// multiple of the periods of its oscillators.
+    Oscillating(usize),
+    /// No repetition was found before the generation limit.
+    Unsettled,
+}
+
+impl Fate {
+    #[inline]
+    #[must_use]
+    /// Name of the fate, for reports.
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Extinct => "extinct",
+            Self::Stable => "stable",
+            Self::Oscillating(_) => "oscillating",
+            Self::Unsettled => "unsettled",
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    /// Period of the final board, if it settled: `0` for extinct soups and `1` for still lifes.
+    pub const fn period(self) -> Option<usize> {
+        match self {
+            Self::Extinct => Some(0),
+            Self::Stable => Some(1),
+            Self::Oscillating(period) => Some(period),
+            Self::Unsettled => None,
+        }
+    }
+}
+
+/// Outcome of a single soup.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Soup {
+    /// Seed of the initial board.
+    pub seed: u64,
+    /// Live cells in the initial board.
+    pub initial_population: usize,
+    /// Live cells when the search stopped.
+    pub final_population: usize,
+    /// First generation of the final cycle, or the generation limit for unsettled soups.
+    pub lifespan: usize,
+    pub fate: Fate,
+}
+
+impl Soup {
+    /// Header of the lines written by [`write_csv`].
+    pub const CSV_HEADER: &'static str = "seed,initial_population,final_population,lifespan,fate,period";
+}
+
+/// Runs random soups of the same size with the same engine.
+///
+/// # Example
+///
+/// ```
+/// # use vida::engine::BitEngine;
+/// # use vida::soup::SoupSearch;
+/// #
+/// let search = SoupSearch::new(BitEngine, 32, 32).with_max_generations(2000);
+/// let soups = search.run_all(&[1, 2, 3]);
+///
+/// assert_eq!(soups[1], search.run(2));
+/// assert!(soups.iter().all(|soup| soup.lifespan <= 2000));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct SoupSearch<E = SerialEngine> {
+    engine: E,
+    rows: usize,
+    columns: usize,
+    density: f64,
+    max_generations: usize,
+    cycle_window: usize,
+}
+
+impl<E: Engine + Clone> SoupSearch<E> {
+    /// Generations after which a soup is considered unsettled, by default.
+    pub const MAX_GENERATIONS: usize = 10_000;
+    /// Longest period detected by default, enough for the lifetimes of most common oscillators.
+    pub const CYCLE_WINDOW: usize = 60;
+
+    #[inline]
+    #[must_use]
+    /// Searches soups of `(rows, columns)` cells with half of them live, cloning the engine for
+    /// each soup.
+    pub fn new(engine: E, rows: usize, columns: usize) -> Self {
+        Self { engine, rows, columns, density: 0.5, max_generations: Self::MAX_GENERATIONS, cycle_window: Self::CYCLE_WINDOW }
+    }
+
+    #[inline]
+    #[must_use]
+    /// Fraction of live cells in the initial boards.
+    pub fn with_density(mut self, density: f64) -> Self {
+        self.density = density.clamp(0.0, 1.0);
+        self
+    }
+
+    #[inline]
+    #[must_use]
+    /// Stops soups that haven't settled after `max` generations.
+    pub fn with_max_generations(mut self, max: usize) -> Self {
+        self.max_generations = max;
+        self
+    }
+
+    #[inline]
+    #[must_use]
+    /// Detects periods up to `window` generations. Soups with longer periods are unsettled.
+    pub fn with_cycle_window(mut self, window: usize) -> Self {
+        self.cycle_window = window.max(1);
+        self
+    }
+
+    #[must_use]
+    /// The initial board of the soup with the given seed.
+    pub fn board(&self, seed: u64) -> Grid {
+        Grid::random_with_density(self.rows, self.columns, self.density, &mut SmallRng::seed_from_u64(seed))
+    }
+
+    #[must_use]
+    /// Runs the soup with the given seed until it settles.
+    pub fn run(&self, seed: u64) -> Soup {
+        self.run_soup(seed).0
+    }
+
+    /// Runs the soup with the given seed until it settles, returning its last board too.
+    fn run_soup(&self, seed: u64) -> (Soup, Grid) {
+        let grid = self.board(seed);
+        let initial_population = grid.population();
+
+        let mut simulation = Simulation::new(self.engine.clone(), grid)
+            .with_max_generations(self.max_generations)
+            .stop_on_extinction(true)
+            .stop_on_cycle(Some(self.cycle_window));
+
+        let (lifespan, fate) = settle(&mut simulation);
+        let soup = Soup { seed, initial_population, final_population: simulation.grid().population(), lifespan, fate };
+        (soup, simulation.into_grid())
+    }
+
+    #[must_use]
+    /// Runs the soups of every seed, concurrently with the `parallel` feature, returning them in
+    /// the same order as the seeds.
+    pub fn run_all(&self, seeds: &[u64]) -> Vec<Soup>
+    where
+        E: Sync
+    {
+        seeds.par_iter().map(|&seed| self.run(seed)).collect()
+    }
+
+    #[must_use]
+    /// Like [`SoupSearch::run_all`], also counting the objects left on the last board of every
+    /// soup.
+    pub fn run_all_with_census(&self, seeds: &[u64]) -> (Vec<Soup>, Census)
+    where
+        E: Sync
+    {
+        let settled: Vec<_> = seeds.par_iter().map(|&seed| {
+            let (soup, grid) = self.run_soup(seed);
+            (soup, Census::new(&grid))
+        }).collect();
+
+        let mut total = Census::default();
+        let soups = settled.into_iter().map(|(soup, census)| {
+            total.merge(&census);
+            soup
+        }).collect();
+        (soups, total)
+    }
+}
+
+/// Runs a simulation until it stops, returning the generation where it settled and how.
+///
+/// The simulation should stop on extinction and on cycles, like the ones of [`SoupSearch`]. The
+/// generation is the first one of the final cycle, or the one where the simulation stopped if it
+/// never settled.
+pub fn settle<E: Engine>(simulation: &mut Simulation<E>) -> (usize, Fate) {
+    let reason = simulation.run();
+    let generation = simulation.generation();
+    match reason {
+        StopReason::Extinction => (generation, Fate::Extinct),
+        // the generation `period` steps back is the first one to repeat, otherwise the repetition
+        // would have been found a step earlier
+        StopReason::Cycle(1) | StopReason::Stable => (generation - 1, Fate::Stable),
+        StopReason::Cycle(period) => (generation - period, Fate::Oscillating(period)),
+        StopReason::MaxGenerations => (generation, Fate::Unsettled),
+    }
+}
+
+/// Writes one line of comma separated values for each soup, after [`Soup::CSV_HEADER`] if
+/// `header` is set. The period is left empty for unsettled soups.
+pub fn write_csv<W: Write>(soups: &[Soup], header: bool, mut writer: W) -> io::Result<()> {
+    if header {
+        writeln!(writer, "{}", Soup::CSV_HEADER)?;
+    }
+    for soup in soups {
+        let period = soup.fate.period().map(|period| period.to_string()).unwrap_or_default();
+        writeln!(
+            writer,
+            "{},{},{},{},{},{period}",
+            soup.seed, soup.initial_population, soup.final_population, soup.lifespan, soup.fate.name(),
+        )?;
+    }
+    writer.flush()
+}
+
+/// Totals over many soups.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Tally {
+    pub soups: usize,
+    pub extinct: usize,
+    pub stable: usize,
+    pub unsettled: usize,
+    /// Number of soups that ended in each period longer than one.
+    pub periods: BTreeMap<usize, usize>,
+    /// Sum of the lifespans of the soups that settled.
+    pub total_lifespan: usize,
+    /// The soup that took the longest to settle.
+    pub longest: Option<Soup>,
+}
+
+impl Tally {
+    #[must_use]
+    /// Adds up the outcomes of the soups.
+    pub fn new(soups: &[Soup]) -> Self {
+        let mut tally = Self { soups: soups.len(), ..Self::default() };
+        for soup in soups {
+            match soup.fate {
+                Fate::Extinct => tally.extinct += 1,
+                Fate::Stable => tally.stable += 1,
+                Fate::Oscillating(period) => *tally.periods.entry(period).or_default() += 1,
+                Fate::Unsettled => {
+                    tally.unsettled += 1;
+                    continue;
+                },
+            }
+
+            tally.total_lifespan += soup.lifespan;
+            if tally.longest.map_or(true, |longest| soup.lifespan > longest.lifespan) {
+                tally.longest = Some(*soup);
+            }
+        }
+        tally
+    }
+
+    #[inline]
+    #[must_use]
+    /// Mean lifespan of the soups that settled.
+    pub fn mean_lifespan(&self) -> f64 {
+        let settled = self.soups - self.unsettled;
+        self.total_lifespan as f64 / settled.max(1) as f64
+    }
+}
+
+impl Display for Tally {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let oscillating: usize = self.periods.values().sum();
+        writeln!(f, "soups        {}", self.soups)?;
+        writeln!(f, "extinct      {}", self.extinct)?;
+        writeln!(f, "stable       {}", self.stable)?;
+        write!(f, "oscillating  {oscillating}")?;
+        for (period, count) in &self.periods {
+            write!(f, ", p{period}: {count}")?;
+        }
+        writeln!(f)?;
+        writeln!(f, "unsettled    {}", self.unsettled)?;
+        write!(f, "lifespan     mean {:.1}", self.mean_lifespan())?;
+        if let Some(longest) = self.longest {
+            write!(f, ", longest {} (seed {})", longest.lifespan, longest.seed)?;
+        }
+        writeln!(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::engine::BitEngine;
+
+    use super::*;
+
+    #[test]
+    pub fn fates_match_the_simulation() {
+        let search = SoupSearch::new(SerialEngine::default(), 24, 24).with_max_generations(1500);
+        let seeds: Vec<_> = (0..16).collect();
+        let soups = search.run_all(&seeds);
+        assert_eq!(soups, SoupSearch::new(BitEngine, 24, 24).with_max_generations(1500).run_all(&seeds));
+
+        for soup in &soups {
+            let mut simulation = Simulation::new(SerialEngine::default(), search.board(soup.seed));
+            assert_eq!(simulation.grid().population(), soup.initial_population);
+            for _ in 0..soup.lifespan {
+                simulation.step();
+            }
+            let settled = simulation.grid().clone();
+
+            match soup.fate {
+                Fate::Extinct => assert_eq!(settled.population(), 0),
+                Fate::Stable | Fate::Oscillating(_) => {
+                    let period = soup.fate.period().unwrap();
+                    for _ in 0..period {
+                        simulation.step();
+                    }
+                    assert_eq!(simulation.grid(), &settled, "seed {}", soup.seed);
+                    assert_eq!(settled.population(), soup.final_population);
+                },
+                Fate::Unsettled => assert_eq!(soup.lifespan, 1500),
+            }
+        }
+
+        let (with_census, census) = search.run_all_with_census(&seeds[..4]);
+        assert_eq!(with_census, soups[..4]);
+        assert!(census.objects() > 0);
+
+        let tally = Tally::new(&soups);
+        assert_eq!(tally.soups, 16);
+        assert_eq!(tally.extinct + tally.stable + tally.unsettled + tally.periods.values().sum::<usize>(), 16);
+    }
+
+    #[test]
+    pub fn csv_report() {
+        let soups = [
+            Soup { seed: 7, initial_population: 10, final_population: 0, lifespan: 4, fate: Fate::Extinct },
+            Soup { seed: 8, initial_population: 12, final_population: 6, lifespan: 30, fate: Fate::Oscillating(2) },
+            Soup { seed: 9, initial_population: 11, final_population: 5, lifespan: 100, fate: Fate::Unsettled },
+        ];
+
+        let mut csv = Vec::new();
+        write_csv(&soups, true, &mut csv).unwrap();
+        let expected = "seed,initial_population,final_population,lifespan,fate,period\n\
+            7,10,0,4,extinct,0\n8,12,6,30,oscillating,2\n9,11,5,100,unsettled,\n";
+        assert_eq!(String::from_utf8(csv).unwrap(), expected);
+
+        let tally = Tally::new(&soups);
+        assert_eq!(tally.mean_lifespan(), 17.0);
+        assert_eq!(tally.longest, Some(soups[1]));
+    }
+
+    #[test]
+    pub fn empty_soups() {
+        let soup = SoupSearch::new(SerialEngine::default(), 10, 10).with_density(0.0).run(1);
+        assert_eq!((soup.lifespan, soup.fate, soup.initial_population), (0, Fate::Extinct, 0));
+    }
+}
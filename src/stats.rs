@@ -0,0 +1,168 @@
+//! Statistics collected over the generations of a simulation.
+
+use std::fmt::{self, Display, Formatter};
+use std::io::{self, Write};
+
+use crate::cell::{Cell, Grid};
+use crate::par::*;
+
+/// What happened in a single generation.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct GenerationStats {
+    /// The generation number.
+    pub generation: usize,
+    /// Live cells in this generation.
+    pub population: usize,
+    /// Cells that were dead in the previous generation and are live now.
+    pub births: usize,
+    /// Cells that were live in the previous generation and are dead now.
+    pub deaths: usize,
+}
+
+/// Collects the [`GenerationStats`] of every generation, starting from an initial grid.
+///
+/// # Example
+///
+/// ```
+/// # use vida::cell::{Cell, Grid};
+/// # use vida::engine::{Engine, SerialEngine};
+/// # use vida::stats::Stats;
+/// #
+/// let mut blinker = Grid::new(5, 5);
+/// blinker[2][1..4].fill(Cell::Live);
+///
+/// let mut stats = Stats::new(&blinker, 0);
+/// let next = SerialEngine::default().update(&blinker);
+/// stats.record(&blinker, &next);
+///
+/// let last = stats.last();
+/// assert_eq!((last.generation, last.population, last.births, last.deaths), (1, 3, 2, 2));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Stats {
+    records: Vec<GenerationStats>,
+}
+
+impl Stats {
+    /// Header of the lines written by [`Stats::write_csv`].
+    pub const CSV_HEADER: &'static str = "generation,population,births,deaths";
+
+    #[must_use]
+    /// Starts collecting from the grid at the given generation, which has no births or deaths.
+    pub fn new(grid: &Grid, generation: usize) -> Self {
+        let initial = GenerationStats { generation, population: grid.population(), births: 0, deaths: 0 };
+        Self { records: vec![initial] }
+    }
+
+    /// Records the next generation, comparing it to the previous one.
+    pub fn record(&mut self, previous: &Grid, current: &Grid) {
+        let changed = |before: Cell, after: Cell| {
+            previous.flat().par_iter().zip(current.flat()).filter(|&(&old, &new)| old == before && new == after).count()
+        };
+        let (births, deaths) = (changed(Cell::Dead, Cell::Live), changed(Cell::Live, Cell::Dead));
+
+        self.records.push(GenerationStats {
+            generation: self.last().generation + 1,
+            population: current.population(),
+            births,
+            deaths,
+        });
+    }
+
+    /// Forgets the last generation recorded, when a simulation steps back from it. The initial
+    /// grid is always kept.
+    pub fn forget_last(&mut self) {
+        if self.records.len() > 1 {
+            self.records.pop();
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    /// Every generation recorded so far, starting with the initial grid.
+    pub fn records(&self) -> &[GenerationStats] {
+        &self.records
+    }
+
+    #[inline]
+    #[must_use]
+    /// The last generation recorded.
+    pub fn last(&self) -> &GenerationStats {
+        &self.records[self.records.len() - 1]
+    }
+
+    /// Writes one line of comma separated values for each generation, after [`Stats::CSV_HEADER`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use vida::cell::Grid;
+    /// # use vida::stats::Stats;
+    /// #
+    /// let mut csv = Vec::new();
+    /// Stats::new(&Grid::new(4, 4), 10).write_csv(&mut csv).unwrap();
+    ///
+    /// assert_eq!(String::from_utf8(csv).unwrap(), "generation,population,births,deaths\n10,0,0,0\n");
+    /// ```
+    pub fn write_csv<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        writeln!(writer, "{}", Self::CSV_HEADER)?;
+        for record in &self.records {
+            writeln!(writer, "{},{},{},{}", record.generation, record.population, record.births, record.deaths)?;
+        }
+        writer.flush()
+    }
+
+    #[must_use]
+    /// Aggregates the records into a summary table.
+    pub fn summary(&self) -> Summary {
+        let column = |value: fn(&GenerationStats) -> usize, records: &[GenerationStats]| {
+            let total: usize = records.iter().map(value).sum();
+            Column {
+                min: records.iter().map(value).min().unwrap_or(0),
+                max: records.iter().map(value).max().unwrap_or(0),
+                mean: total as f64 / records.len().max(1) as f64,
+                total,
+            }
+        };
+
+        // the initial grid has no births or deaths, so it is left out of them
+        let changes = &self.records[1..];
+        Summary {
+            generations: changes.len(),
+            population: column(|record| record.population, &self.records),
+            births: column(|record| record.births, changes),
+            deaths: column(|record| record.deaths, changes),
+        }
+    }
+}
+
+/// Aggregate values of one of the recorded statistics.
+#[derive(Debug, Copy, Clone, PartialEq, PartialOrd, Default)]
+pub struct Column {
+    pub min: usize,
+    pub max: usize,
+    pub mean: f64,
+    pub total: usize,
+}
+
+/// Summary of a [`Stats`] collection, displayed as a table.
+#[derive(Debug, Copy, Clone, PartialEq, PartialOrd, Default)]
+pub struct Summary {
+    /// Generations computed after the initial one.
+    pub generations: usize,
+    pub population: Column,
+    pub births: Column,
+    pub deaths: Column,
+}
+
+impl Display for Summary {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{} generations", self.generations)?;
+        writeln!(f, "{:<10} {:>10} {:>10} {:>12} {:>12}", "", "min", "max", "mean", "total")?;
+
+        for (name, column) in [("population", self.population), ("births", self.births), ("deaths", self.deaths)] {
+            writeln!(f, "{name:<10} {:>10} {:>10} {:>12.2} {:>12}", column.min, column.max, column.mean, column.total)?;
+        }
+        Ok(())
+    }
+}
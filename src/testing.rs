@@ -0,0 +1,187 @@
+//! Random grids for checking properties of engines, also for use in other crates.
+//!
+//! A [`GridGenerator`] makes grids of random shapes and densities from a seed, including the
+//! edge cases of empty grids and single rows or columns, and [`for_all`] checks a property on
+//! many of them, telling which grid broke it.
+//!
+//! # Example
+//!
+//! ```
+//! # use vida::engine::{Engine, SerialEngine};
+//! # use vida::testing::{for_all, GridGenerator};
+//! #
+//! for_all(GridGenerator::new(7), 50, |grid| {
+//!     assert_eq!(SerialEngine::default().update(grid).shape(), grid.shape());
+//! });
+//! ```
+
+use std::panic::{self, AssertUnwindSafe};
+
+use rand::rngs::SmallRng;
+use rand::{Rng, SeedableRng};
+
+use crate::cell::Grid;
+
+/// Largest side of the generated grids, unless set with [`GridGenerator::with_max_shape`].
+const MAX_SIDE: usize = 48;
+
+/// An endless source of random grids, always the same ones for the same seed.
+#[derive(Debug, Clone)]
+pub struct GridGenerator {
+    rng: SmallRng,
+    max_shape: (usize, usize),
+    density: Option<f64>,
+}
+
+impl GridGenerator {
+    #[must_use]
+    /// Makes grids up to 48 by 48 cells, each with a random density.
+    pub fn new(seed: u64) -> Self {
+        Self { rng: SmallRng::seed_from_u64(seed), max_shape: (MAX_SIDE, MAX_SIDE), density: None }
+    }
+
+    #[inline]
+    #[must_use]
+    /// Makes grids up to `(rows, columns)`.
+    pub fn with_max_shape(mut self, shape: (usize, usize)) -> Self {
+        self.max_shape = shape;
+        self
+    }
+
+    #[inline]
+    #[must_use]
+    /// Makes every grid with the same fraction of live cells, from 0 to 1.
+    pub fn with_density(mut self, density: f64) -> Self {
+        self.density = Some(density);
+        self
+    }
+
+    #[must_use]
+    /// A grid of random shape and density.
+    ///
+    /// One in eight grids has a single row or column, and one in sixteen no cells at all, since
+    /// that is where the bugs of most engines hide.
+    pub fn grid(&mut self) -> Grid {
+        let (max_rows, max_columns) = self.max_shape;
+        let mut rows = self.rng.gen_range(0..=max_rows);
+        let mut columns = self.rng.gen_range(0..=max_columns);
+        match self.rng.gen_range(0..16) {
+            0 => (rows, columns) = (0, 0),
+            1 => rows = rows.min(1),
+            2 => columns = columns.min(1),
+            _ => (),
+        }
+
+        let density = self.density.unwrap_or_else(|| self.rng.gen());
+        Grid::random_with_density(rows, columns, density, &mut self.rng)
+    }
+
+    #[must_use]
+    /// An empty grid of random shape.
+    pub fn empty_grid(&mut self) -> Grid {
+        let (max_rows, max_columns) = self.max_shape;
+        Grid::new(self.rng.gen_range(0..=max_rows), self.rng.gen_range(0..=max_columns))
+    }
+}
+
+impl Iterator for GridGenerator {
+    type Item = Grid;
+
+    #[inline]
+    fn next(&mut self) -> Option<Grid> {
+        Some(self.grid())
+    }
+}
+
+/// Checks a property on the first `cases` grids of a generator.
+///
+/// # Panics
+///
+/// When the property panics for some grid, with the failing case and its grid in the message.
+pub fn for_all(grids: impl IntoIterator<Item = Grid>, cases: usize, mut property: impl FnMut(&Grid)) {
+    for (case, grid) in grids.into_iter().take(cases).enumerate() {
+        if let Err(error) = panic::catch_unwind(AssertUnwindSafe(|| property(&grid))) {
+            let message = error.downcast_ref::<String>().map(String::as_str).or_else(|| error.downcast_ref::<&str>().copied());
+            let (rows, columns) = grid.shape();
+            panic!("property failed on case {case}, a {rows}x{columns} grid: {}\n{grid}", message.unwrap_or("panicked"));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cell::Cell;
+    use crate::engine::{BitEngine, Engine, HashLifeEngine, SerialEngine, SparseEngine};
+    #[cfg(feature = "parallel")]
+    use crate::engine::ParallelEngine;
+
+    use super::*;
+
+    fn engines() -> Vec<Box<dyn Engine>> {
+        vec![
+            Box::<SerialEngine>::default(),
+            #[cfg(feature = "parallel")]
+            Box::<ParallelEngine>::default(),
+            Box::new(BitEngine),
+            Box::<SparseEngine>::default(),
+            Box::new(HashLifeEngine::new()),
+        ]
+    }
+
+    #[test]
+    pub fn empty_grid_stays_empty() {
+        let mut generator = GridGenerator::new(550);
+        let grids = std::iter::from_fn(|| Some(generator.empty_grid()));
+        for_all(grids, 30, |grid| {
+            for engine in engines() {
+                assert_eq!(&engine.update(grid), grid, "{} engine", engine.name());
+            }
+        });
+    }
+
+    #[test]
+    pub fn single_cell_dies() {
+        let mut rng = SmallRng::seed_from_u64(550);
+        let grids = GridGenerator::new(551).with_density(0.0).filter(|grid| grid.cells() > 0).map(|mut grid| {
+            let (row, col) = (rng.gen_range(0..grid.rows()), rng.gen_range(0..grid.columns()));
+            grid[(row, col)] = Cell::Live;
+            grid
+        });
+        for_all(grids, 30, |grid| {
+            for engine in engines() {
+                assert_eq!(engine.update(grid).population(), 0, "{} engine", engine.name());
+            }
+        });
+    }
+
+    #[test]
+    #[cfg(feature = "parallel")]
+    pub fn independent_of_chunk_rows() {
+        for_all(GridGenerator::new(552), 40, |grid| {
+            let expected = ParallelEngine::default().update(grid);
+            for chunk_rows in [1, 2, 3, 7, 64] {
+                assert_eq!(ParallelEngine::default().with_chunk_rows(chunk_rows).update(grid), expected, "{chunk_rows} rows per chunk");
+            }
+        });
+    }
+
+    #[test]
+    #[cfg(feature = "parallel")]
+    pub fn serial_and_parallel_agree() {
+        for_all(GridGenerator::new(553).with_max_shape((100, 100)), 40, |grid| {
+            assert_eq!(ParallelEngine::default().update(grid), SerialEngine::default().update(grid));
+        });
+    }
+
+    #[test]
+    pub fn failures_show_the_grid() {
+        let result = panic::catch_unwind(|| for_all(GridGenerator::new(554), 100, |grid| assert!(grid.population() < 10)));
+        let error = result.unwrap_err();
+        let message = error.downcast_ref::<String>().unwrap();
+        assert!(message.starts_with("property failed on case "), "{message}");
+        assert!(message.contains("assertion failed"), "{message}");
+
+        // the same seed gives the same grids
+        assert!(GridGenerator::new(555).take(20).eq(GridGenerator::new(555).take(20)));
+    }
+}
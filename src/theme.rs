@@ -0,0 +1,192 @@
+//! Color schemes shared by the renderer and the exporters.
+
+use crate::wireworld::Wire;
+
+/// An RGBA color with components in `0.0 ..= 1.0`, the same format used by `piston_window`.
+pub type Color = [f32; 4];
+
+/// Colors used to draw a board.
+#[derive(Debug, Copy, Clone, PartialEq, PartialOrd)]
+pub struct Theme {
+    /// Color of [`Live`](crate::cell::Cell::Live) cells.
+    pub live: Color,
+    /// Color of [`Dead`](crate::cell::Cell::Dead) cells, also used as background.
+    pub dead: Color,
+    /// Color of newborn cells, when coloring by age. Older cells fade into the `live` color.
+    pub young: Color,
+    /// Color of agents walking over the board, like Langton's Ant.
+    pub agent: Color,
+    /// Colors of the two players in the Immigration game.
+    pub players: [Color; 2],
+    /// Colors of electron heads, electron tails and conductors in Wireworld.
+    pub wires: [Color; 3],
+    /// Colors of firing and refractory cells in Brian's Brain.
+    pub brain: [Color; 2],
+    /// Colors of live cells in multi-color variants of Life, like QuadLife.
+    pub species: [Color; 4],
+    /// Colors of the least and the most active cells in a heatmap, see [`Theme::by_activity`].
+    pub heat: [Color; 2],
+    /// Color of cells that just died, fading into the `dead` color, see [`Theme::by_trail`].
+    pub trail: Color,
+    /// Colors of the cells born and the cells that died in the last generation, when showing what
+    /// changed.
+    pub changes: [Color; 2],
+    /// Color of the region selected in the window, drawn translucent over the cells.
+    pub selection: Color,
+    /// Color of the cells where two engines disagree, when comparing them side by side.
+    pub mismatch: Color,
+}
+
+impl Theme {
+    /// Black cells on a white background.
+    pub const LIGHT: Self = Self {
+        live: [0.0, 0.0, 0.0, 1.0],
+        dead: [1.0, 1.0, 1.0, 1.0],
+        young: [1.0, 0.6, 0.0, 1.0],
+        agent: [1.0, 0.0, 0.0, 1.0],
+        players: [[0.8, 0.1, 0.1, 1.0], [0.1, 0.2, 0.8, 1.0]],
+        wires: [[0.1, 0.3, 1.0, 1.0], [1.0, 0.2, 0.1, 1.0], [0.8, 0.6, 0.0, 1.0]],
+        brain: [[0.1, 0.2, 0.8, 1.0], [0.6, 0.75, 1.0, 1.0]],
+        species: [[0.8, 0.1, 0.1, 1.0], [0.1, 0.2, 0.8, 1.0], [0.1, 0.6, 0.2, 1.0], [0.9, 0.6, 0.0, 1.0]],
+        heat: [[0.75, 0.85, 1.0, 1.0], [0.8, 0.0, 0.0, 1.0]],
+        trail: [0.6, 0.6, 0.6, 1.0],
+        changes: [[0.1, 0.65, 0.2, 1.0], [0.85, 0.1, 0.1, 1.0]],
+        selection: [0.2, 0.5, 1.0, 1.0],
+        mismatch: [0.9, 0.1, 0.1, 1.0],
+    };
+    /// White cells on a black background.
+    pub const DARK: Self = Self {
+        live: [1.0, 1.0, 1.0, 1.0],
+        dead: [0.0, 0.0, 0.0, 1.0],
+        young: [1.0, 0.9, 0.2, 1.0],
+        agent: [1.0, 0.0, 0.0, 1.0],
+        players: [[0.8, 0.1, 0.1, 1.0], [0.1, 0.2, 0.8, 1.0]],
+        wires: [[0.2, 0.5, 1.0, 1.0], [1.0, 0.2, 0.1, 1.0], [1.0, 0.8, 0.0, 1.0]],
+        brain: [[1.0, 1.0, 1.0, 1.0], [0.2, 0.4, 0.9, 1.0]],
+        species: [[1.0, 0.3, 0.3, 1.0], [0.3, 0.5, 1.0, 1.0], [0.3, 0.9, 0.4, 1.0], [1.0, 0.9, 0.2, 1.0]],
+        heat: [[0.1, 0.1, 0.5, 1.0], [1.0, 0.9, 0.2, 1.0]],
+        trail: [0.45, 0.45, 0.45, 1.0],
+        changes: [[0.3, 0.9, 0.4, 1.0], [1.0, 0.3, 0.3, 1.0]],
+        selection: [0.3, 0.6, 1.0, 1.0],
+        mismatch: [1.0, 0.2, 0.2, 1.0],
+    };
+}
+
+impl Theme {
+    /// Generations until a cell fades completely into the `live` color, see [`Theme::by_age`].
+    pub const AGE_SPAN: u16 = 64;
+
+    #[inline]
+    #[must_use]
+    /// Color of a live cell that has been alive for `age` generations, see
+    /// [`Ages`](crate::age::Ages).
+    pub fn by_age(&self, age: u16) -> Color {
+        lerp(self.young, self.live, f32::from(age.saturating_sub(1)) / f32::from(Self::AGE_SPAN))
+    }
+
+    /// Continuous states below this are drawn as background, see [`Theme::by_value`].
+    pub const MIN_VISIBLE_VALUE: f32 = 1.0 / 256.0;
+
+    #[inline]
+    #[must_use]
+    /// Color of a cell with a continuous state in `0.0 ..= 1.0`, like in Lenia, between the `dead`
+    /// and `live` colors, or [`None`] for states too small to be seen, which are drawn as background.
+    pub fn by_value(&self, value: f32) -> Option<Color> {
+        (value >= Self::MIN_VISIBLE_VALUE).then(|| lerp(self.dead, self.live, value))
+    }
+
+    #[inline]
+    #[must_use]
+    /// Color of a cell in a [`GenerationsRule`](crate::rule::GenerationsRule) with `states` states,
+    /// or [`None`] for dead cells, which are drawn as background.
+    ///
+    /// Live cells use the `live` color, and dying cells fade from it into the `dead` color.
+    pub fn by_state(&self, state: u8, states: u8) -> Option<Color> {
+        match state {
+            0 => None,
+            1 => Some(self.live),
+            _ => Some(lerp(self.live, self.dead, f32::from(state - 1) / f32::from(states.max(2) - 1))),
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    /// Color of a cell in Brian's Brain, or [`None`] for ready cells, which are drawn as background.
+    pub const fn by_brain(&self, state: u8) -> Option<Color> {
+        match state {
+            1 => Some(self.brain[0]),
+            2 => Some(self.brain[1]),
+            _ => None,
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    /// Color of a cell in a multi-color variant of Life, with colors counted from `1`, or [`None`]
+    /// for dead cells, which are drawn as background.
+    pub const fn by_species(&self, color: u8) -> Option<Color> {
+        match color {
+            1..=4 => Some(self.species[color as usize - 1]),
+            _ => None,
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    /// Color of a cell in a [`Heatmap`](crate::heatmap::Heatmap) with `fraction` of the activity of
+    /// the most active cell, or [`None`] for cells that were never alive, which are drawn as
+    /// background.
+    pub fn by_activity(&self, fraction: f32) -> Option<Color> {
+        (fraction > 0.0).then(|| lerp(self.heat[0], self.heat[1], fraction))
+    }
+
+    #[inline]
+    #[must_use]
+    /// Color of a dead cell with `fade` of its [trail](crate::trail::Trails::fade) left, or [`None`]
+    /// for cells without a trail, which are drawn as background.
+    pub fn by_trail(&self, fade: f32) -> Option<Color> {
+        (fade > 0.0).then(|| lerp(self.dead, self.trail, fade))
+    }
+
+    #[inline]
+    #[must_use]
+    /// Color of a cell in Wireworld, or [`None`] for empty cells, which are drawn as background.
+    pub const fn by_wire(&self, wire: Wire) -> Option<Color> {
+        match wire {
+            Wire::Empty => None,
+            Wire::Head => Some(self.wires[0]),
+            Wire::Tail => Some(self.wires[1]),
+            Wire::Conductor => Some(self.wires[2]),
+        }
+    }
+}
+
+impl Default for Theme {
+    #[inline]
+    fn default() -> Self {
+        Self::LIGHT
+    }
+}
+
+#[inline]
+#[must_use]
+/// Linear interpolation from `start` (at `0.0`) to `end` (at `1.0`).
+pub fn lerp(start: Color, end: Color, t: f32) -> Color {
+    let t = t.clamp(0.0, 1.0);
+    std::array::from_fn(|i| start[i] + (end[i] - start[i]) * t)
+}
+
+#[inline]
+#[must_use]
+/// Formats the RGB part of a color as `#rrggbb`.
+pub fn to_hex(color: Color) -> String {
+    let [r, g, b, _] = to_rgba8(color);
+    format!("#{r:02x}{g:02x}{b:02x}")
+}
+
+#[inline]
+#[must_use]
+/// Converts a color to 8-bit RGBA components, as used in images.
+pub fn to_rgba8(color: Color) -> [u8; 4] {
+    color.map(|component| (component.clamp(0.0, 1.0) * 255.0).round() as u8)
+}
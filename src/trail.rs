@@ -0,0 +1,169 @@
+//! Where cells died recently, to draw fading trails behind moving patterns.
+
+use crate::cell::{Grid, GridDelta};
+
+/// When each cell of a grid last died, fed with the [`GridDelta`] of each generation.
+///
+/// Dead cells that died in the last [`length`](Trails::length) generations [fade](Trails::fade)
+/// out over them, so the path of gliders and spaceships stays visible for a while. Only the
+/// deaths are looked at, so cells that were born again still have the time of their last death,
+/// and should be drawn as live cells instead.
+///
+/// # Example
+///
+/// ```
+/// # use vida::engine::{Engine, SerialEngine};
+/// # use vida::patterns;
+/// # use vida::trail::Trails;
+/// #
+/// let mut glider = patterns::glider().expanded((0, 0), (8, 8));
+/// let mut trails = Trails::new(&glider, 4);
+///
+/// for _ in 0..2 {
+///     let next = SerialEngine::default().update(&glider);
+///     trails.update(&glider.diff(&next));
+///     glider = next;
+/// }
+///
+/// // the top cell died on the first generation, so a quarter of its trail is gone
+/// assert_eq!(trails.fade(0, 1), 0.75);
+/// assert_eq!(trails.fade(7, 7), 0.0);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Trails {
+    /// The generation of the last death of each cell, counted from `1`, or `0` if it never died.
+    died: Box<[u32]>,
+    rows: usize,
+    columns: usize,
+    /// The generation of the last update.
+    clock: u32,
+    length: u32,
+}
+
+impl Trails {
+    #[must_use]
+    /// Starts following the deaths in a grid like `grid`, with trails that fade out after `length`
+    /// generations.
+    pub fn new(grid: &Grid, length: usize) -> Self {
+        let (rows, columns) = grid.shape();
+        let length = u32::try_from(length).unwrap_or(u32::MAX);
+        Self { died: vec![0; rows * columns].into_boxed_slice(), rows, columns, clock: 0, length }
+    }
+
+    /// Moves on to the next generation, marking the cells that died in it.
+    ///
+    /// If the delta has a different shape, the trails start again.
+    pub fn update(&mut self, delta: &GridDelta) {
+        if self.shape() != delta.shape() {
+            let (rows, columns) = delta.shape();
+            self.died = vec![0; rows * columns].into_boxed_slice();
+            (self.rows, self.columns, self.clock) = (rows, columns, 0);
+        }
+
+        self.clock = self.clock.saturating_add(1);
+        for &(row, col) in delta.deaths() {
+            self.died[row * self.columns + col] = self.clock;
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    /// The shape `(rows, columns)` of the grid.
+    pub const fn shape(&self) -> (usize, usize) {
+        (self.rows, self.columns)
+    }
+
+    #[inline]
+    #[must_use]
+    /// Generations until a trail disappears.
+    pub const fn length(&self) -> usize {
+        self.length as usize
+    }
+
+    #[must_use]
+    /// How much of the trail is left at a cell, from `1.0` if it died in the last generation to
+    /// `0.0` if it died [`length`](Trails::length) or more generations ago, never died, or is
+    /// out-of-bounds.
+    pub fn fade(&self, row: usize, col: usize) -> f32 {
+        let index = row.checked_mul(self.columns).and_then(|start| start.checked_add(col));
+        let Some(&died) = index.filter(|_| col < self.columns).and_then(|index| self.died.get(index)) else {
+            return 0.0;
+        };
+
+        let since = self.clock - died;
+        if died == 0 || since >= self.length {
+            0.0
+        } else {
+            1.0 - since as f32 / self.length as f32
+        }
+    }
+
+    #[must_use]
+    /// The trails of a larger grid of `(rows, columns)` cells, where this one was copied at
+    /// `(top, left)`, like [`Grid::expanded`].
+    ///
+    /// # Panics
+    ///
+    /// If this grid doesn't fit in the new one at that position.
+    pub fn expanded(&self, (top, left): (usize, usize), (rows, columns): (usize, usize)) -> Self {
+        assert!(top + self.rows <= rows && left + self.columns <= columns, "trails don't fit in the expanded grid");
+
+        let mut died = vec![0; rows * columns].into_boxed_slice();
+        if self.columns > 0 {
+            for (old, row) in self.died.chunks_exact(self.columns).zip(top..) {
+                let start = row * columns + left;
+                died[start..start + self.columns].copy_from_slice(old);
+            }
+        }
+        Self { died, rows, columns, ..*self }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cell::Cell;
+    use crate::engine::SerialEngine;
+    use crate::patterns;
+    use crate::simulation::Simulation;
+
+    use super::*;
+
+    #[test]
+    pub fn glider_leaves_a_trail() {
+        let glider = patterns::glider().expanded((0, 0), (20, 20));
+        let mut simulation = Simulation::new(SerialEngine::default(), glider).track_trails(Some(8));
+        for _ in 0..12 {
+            simulation.step();
+        }
+
+        let (grid, trails) = (simulation.grid(), simulation.trails().unwrap());
+        let fading = |row, col| grid[(row, col)].is_dead() && trails.fade(row, col) > 0.0;
+        let trail: Vec<_> = (0..20).flat_map(|row| (0..20).map(move |col| (row, col))).filter(|&(row, col)| fading(row, col)).collect();
+
+        // the glider moved 3 cells diagonally, with the trail behind it and nowhere else
+        assert!(trail.len() > 5, "{trail:?}");
+        assert!(trail.iter().all(|&(row, col)| (1..7).contains(&row) && (1..7).contains(&col)), "{trail:?}");
+        assert_eq!(trails.fade(0, 1), 0.0);
+        assert_eq!(trails.fade(0, 20), 0.0);
+
+        simulation.reset(Grid::new_with(3, 3, Cell::Live), 0);
+        simulation.step();
+        let trails = simulation.trails().unwrap();
+        assert_eq!((trails.shape(), trails.length()), ((3, 3), 8));
+        assert_eq!(trails.fade(1, 1), 1.0);
+        assert_eq!(trails.fade(0, 0), 0.0);
+    }
+
+    #[test]
+    pub fn expands_in_place() {
+        let mut grid = Grid::new(2, 3);
+        grid[(1, 2)] = Cell::Live;
+        let mut trails = Trails::new(&grid, 2);
+        trails.update(&grid.diff(&Grid::new(2, 3)));
+
+        let trails = trails.expanded((1, 2), (4, 6));
+        assert_eq!(trails.shape(), (4, 6));
+        assert_eq!(trails.fade(2, 4), 1.0);
+        assert_eq!(trails.fade(1, 2), 0.0);
+    }
+}
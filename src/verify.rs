@@ -0,0 +1,271 @@
+//! Checking engines against each other.
+//!
+//! Every Life engine must compute the same generations as [`SerialEngine`], which is the
+//! simplest one. The tests here run all of them on patterns with known behavior, and
+//! [`compare_engines`] can check new engines the same way.
+//!
+//! [`SerialEngine`]: crate::engine::SerialEngine
+
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+
+use crate::cell::{Grid, GridDelta};
+use crate::engine::Engine;
+
+/// The first generation where two engines disagree, from [`compare_engines`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mismatch {
+    /// Names of the engines compared, see [`Engine::name`].
+    pub engines: (&'static str, &'static str),
+    /// The first generation that differs, counting the initial grid as zero.
+    pub generation: usize,
+    /// The cells that the second engine got wrong, from the grid of the first one.
+    pub delta: GridDelta,
+}
+
+impl Display for Mismatch {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let (first, second) = self.engines;
+        write!(f, "{first} and {second} engines differ in {} cells at generation {}", self.delta.len(), self.generation)?;
+        if let Some((row, col)) = self.delta.changed().min() {
+            write!(f, ", starting at ({row}, {col})")?;
+        }
+        Ok(())
+    }
+}
+
+impl Error for Mismatch {}
+
+/// Runs both engines for `generations` updates from the same grid, checking that they agree on
+/// every generation.
+///
+/// Returns the last generation, or where the engines first disagree.
+pub fn compare_engines<A: Engine, B: Engine>(a: &A, b: &B, grid: &Grid, generations: usize) -> Result<Grid, Mismatch> {
+    let (mut first, mut second) = (grid.clone(), grid.clone());
+    let (mut next_first, mut next_second) = (Grid::empty(), Grid::empty());
+
+    for generation in 1..=generations {
+        a.update_into(&first, &mut next_first);
+        b.update_into(&second, &mut next_second);
+        std::mem::swap(&mut first, &mut next_first);
+        std::mem::swap(&mut second, &mut next_second);
+
+        if first != second {
+            let delta = if first.shape() == second.shape() { first.diff(&second) } else { GridDelta::default() };
+            return Err(Mismatch { engines: (a.name(), b.name()), generation, delta });
+        }
+    }
+    Ok(first)
+}
+
+/// Two engines running side by side from the same grid, keeping where they disagree.
+///
+/// Unlike [`compare_engines`], the engines keep going after they first disagree, each one from its
+/// own last generation, so the differences can be watched as they spread.
+///
+/// # Example
+///
+/// ```
+/// # use vida::engine::{HashLifeEngine, SerialEngine};
+/// # use vida::patterns;
+/// # use vida::verify::Comparison;
+/// #
+/// let blinker = patterns::blinker().expanded((1, 1), (5, 5));
+/// let mut comparison = Comparison::new(SerialEngine::default(), HashLifeEngine::new().with_step(2), blinker);
+///
+/// comparison.step();
+/// assert_eq!(comparison.mismatches().len(), 4);
+/// comparison.step();
+/// assert!(comparison.mismatches().is_empty());
+/// assert_eq!(comparison.first_mismatch(), Some(1));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Comparison<A, B> {
+    engines: (A, B),
+    grids: (Grid, Grid),
+    /// Buffers for the next generations.
+    next: (Grid, Grid),
+    generation: usize,
+    /// The cells that the second engine has wrong, from the grid of the first one.
+    mismatches: GridDelta,
+    first_mismatch: Option<usize>,
+}
+
+impl<A: Engine, B: Engine> Comparison<A, B> {
+    #[must_use]
+    /// Starts both engines from `grid`, at generation zero.
+    pub fn new(first: A, second: B, grid: Grid) -> Self {
+        Self {
+            engines: (first, second),
+            grids: (grid.clone(), grid),
+            next: (Grid::empty(), Grid::empty()),
+            generation: 0,
+            mismatches: GridDelta::default(),
+            first_mismatch: None,
+        }
+    }
+
+    /// Computes the next generation with both engines.
+    pub fn step(&mut self) {
+        self.engines.0.update_into(&self.grids.0, &mut self.next.0);
+        self.engines.1.update_into(&self.grids.1, &mut self.next.1);
+        std::mem::swap(&mut self.grids, &mut self.next);
+        self.generation += 1;
+
+        self.mismatches = self.grids.0.try_diff(&self.grids.1).unwrap_or_default();
+        if self.first_mismatch.is_none() && self.grids.0 != self.grids.1 {
+            self.first_mismatch = Some(self.generation);
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    /// Names of the engines compared, see [`Engine::name`].
+    pub fn names(&self) -> (&'static str, &'static str) {
+        (self.engines.0.name(), self.engines.1.name())
+    }
+
+    #[inline]
+    #[must_use]
+    /// The current grids of the first and the second engines.
+    pub const fn grids(&self) -> (&Grid, &Grid) {
+        (&self.grids.0, &self.grids.1)
+    }
+
+    #[inline]
+    #[must_use]
+    /// Generations computed by each engine.
+    pub const fn generation(&self) -> usize {
+        self.generation
+    }
+
+    #[inline]
+    #[must_use]
+    /// The cells where the engines disagree in the current generation, as the changes from the
+    /// grid of the first one to the grid of the second. Empty if the grids have different shapes.
+    pub const fn mismatches(&self) -> &GridDelta {
+        &self.mismatches
+    }
+
+    #[inline]
+    #[must_use]
+    /// The first generation where the engines disagreed, if they ever did.
+    pub const fn first_mismatch(&self) -> Option<usize> {
+        self.first_mismatch
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::analysis::census::Census;
+    use crate::cell::Cell;
+    use crate::engine::{BitEngine, HashLifeEngine, SerialEngine, SparseEngine};
+    #[cfg(feature = "parallel")]
+    use crate::engine::{NumaEngine, ParallelEngine};
+    use crate::patterns::{self, Preset};
+
+    use super::*;
+
+    /// Runs a check with every Life engine, each one new.
+    fn with_every_engine(check: impl Fn(&dyn Fn(&Grid, usize) -> Result<Grid, Mismatch>)) {
+        let serial = SerialEngine::default();
+        #[cfg(feature = "parallel")]
+        {
+            check(&|grid, generations| compare_engines(&serial, &ParallelEngine::default(), grid, generations));
+            check(&|grid, generations| compare_engines(&serial, &ParallelEngine::default().with_chunk_rows(1), grid, generations));
+            check(&|grid, generations| compare_engines(&serial, &NumaEngine::new().unwrap(), grid, generations));
+        }
+        check(&|grid, generations| compare_engines(&serial, &BitEngine, grid, generations));
+        check(&|grid, generations| compare_engines(&serial, &SparseEngine::default(), grid, generations));
+        check(&|grid, generations| compare_engines(&serial, &HashLifeEngine::new(), grid, generations));
+    }
+
+    /// The pattern at `(top, left)` of an empty grid.
+    fn placed(pattern: &Grid, (rows, columns): (usize, usize), (top, left): (usize, usize)) -> Grid {
+        pattern.expanded((top, left), (rows, columns))
+    }
+
+    #[test]
+    pub fn random_soups() {
+        with_every_engine(|compare| {
+            for (rows, columns, seed) in [(1, 1, 0), (7, 70, 1), (40, 60, 2), (65, 129, 3)] {
+                compare(&Grid::random_seeded(rows, columns, seed), 30).unwrap();
+            }
+        });
+    }
+
+    #[test]
+    pub fn blinker_has_period_two() {
+        let blinker = placed(&patterns::blinker(), (5, 5), (1, 1));
+        with_every_engine(|compare| {
+            assert_ne!(compare(&blinker, 1).unwrap(), blinker);
+            assert_eq!(compare(&blinker, 2).unwrap(), blinker);
+            assert_eq!(compare(&blinker, 100).unwrap(), blinker);
+        });
+    }
+
+    #[test]
+    pub fn glider_moves_diagonally() {
+        let glider = patterns::glider();
+        with_every_engine(|compare| {
+            for cycles in [1, 5] {
+                let start = placed(&glider, (20, 20), (2, 2));
+                let moved = compare(&start, 4 * cycles).unwrap();
+                assert_eq!(moved, start.translated(cycles as isize, cycles as isize, false));
+            }
+        });
+    }
+
+    #[test]
+    pub fn r_pentomino_census() {
+        // far enough from the walls that the escaping gliders never reach them
+        let (size, generations) = (640, 1103);
+        let start = placed(&Preset::RPentomino.grid(), (size, size), (size / 2, size / 2));
+
+        let sparse = SparseEngine::default();
+        let mut grid = start.clone();
+        let mut next = Grid::empty();
+        for _ in 0..generations {
+            sparse.update_into(&grid, &mut next);
+            std::mem::swap(&mut grid, &mut next);
+        }
+        // it stabilizes at generation 1103 with 116 cells, counting the six gliders
+        assert_eq!(grid.population(), 116);
+        assert_eq!(sparse.update(&grid).population(), 116);
+        assert_eq!(Census::new(&grid).to_string(), "block 8, beehive 4, loaf 1, boat 1, ship 1, blinker 4, glider 6");
+
+        let hashlife = HashLifeEngine::new().with_step(generations).update(&start);
+        assert_eq!(hashlife, grid);
+
+        // and a shorter run with every engine
+        let small = placed(&Preset::RPentomino.grid(), (96, 96), (48, 48));
+        with_every_engine(|compare| {
+            let grid = compare(&small, 60).unwrap();
+            assert!(grid.flat().contains(&Cell::Live));
+        });
+    }
+
+    #[test]
+    pub fn side_by_side() {
+        let soup = Grid::random_seeded(24, 24, 598);
+        let mut comparison = Comparison::new(SerialEngine::default(), BitEngine, soup.clone());
+        for _ in 0..20 {
+            comparison.step();
+            assert!(comparison.mismatches().is_empty());
+        }
+        assert_eq!((comparison.generation(), comparison.first_mismatch()), (20, None));
+        assert_eq!(comparison.grids().0, comparison.grids().1);
+        assert_eq!(*comparison.grids().0, compare_engines(&SerialEngine::default(), &BitEngine, &soup, 20).unwrap());
+        assert_eq!(comparison.names(), ("serial", "bit"));
+    }
+
+    #[test]
+    pub fn mismatch_is_reported() {
+        let grid = placed(&patterns::blinker(), (5, 5), (1, 1));
+        let error = compare_engines(&SerialEngine::default(), &HashLifeEngine::new().with_step(2), &grid, 3).unwrap_err();
+
+        assert_eq!(error.generation, 1);
+        assert_eq!(error.delta, SerialEngine::default().update(&grid).diff(&grid));
+        assert_eq!(error.to_string(), "serial and hashlife engines differ in 4 cells at generation 1, starting at (0, 2)");
+    }
+}
@@ -0,0 +1,203 @@
+//! Brian Silverman's Wireworld, where electrons flow along wires of conductor cells.
+//!
+//! Cells hold one of the four [`Wire`] states, stored as [`States`] with the same numbering used by
+//! Golly, so the electron heads are the live cells of [`States::to_grid`]. Circuits are written in
+//! a plaintext format, one character per cell, see [`parse`].
+
+use std::fmt::{self, Display, Formatter};
+
+use crate::cell::PaddedGrid;
+use crate::engine::neighbors;
+use crate::error::Error;
+use crate::generations::{MultiStateEngine, States};
+use crate::par::*;
+use crate::theme::{Color, Theme};
+
+/// The state of a cell in Wireworld.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+#[repr(u8)]
+pub enum Wire {
+    /// Background, which never changes.
+    #[default]
+    Empty = 0,
+    /// The front of an electron, which becomes a [`Tail`](Wire::Tail).
+    Head = 1,
+    /// The back of an electron, which becomes a [`Conductor`](Wire::Conductor) again.
+    Tail = 2,
+    /// A piece of wire, which becomes a [`Head`](Wire::Head) next to one or two heads.
+    Conductor = 3,
+}
+
+impl Wire {
+    #[inline]
+    #[must_use]
+    /// The state of this cell in the next generation, with `heads` electron heads around it.
+    pub const fn next(self, heads: usize) -> Self {
+        match self {
+            Self::Empty => Self::Empty,
+            Self::Head => Self::Tail,
+            Self::Tail => Self::Conductor,
+            Self::Conductor if heads == 1 || heads == 2 => Self::Head,
+            Self::Conductor => Self::Conductor,
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    /// The cell in a state number, with unknown states as [`Empty`](Wire::Empty).
+    pub const fn from_state(state: u8) -> Self {
+        match state {
+            1 => Self::Head,
+            2 => Self::Tail,
+            3 => Self::Conductor,
+            _ => Self::Empty,
+        }
+    }
+}
+
+impl From<Wire> for u8 {
+    #[inline]
+    fn from(wire: Wire) -> Self {
+        wire as u8
+    }
+}
+
+impl Display for Wire {
+    #[inline]
+    /// Writes the character used by [`parse`].
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let char = match self {
+            Self::Empty => '.',
+            Self::Head => '@',
+            Self::Tail => '~',
+            Self::Conductor => '#',
+        };
+        write!(f, "{char}")
+    }
+}
+
+impl TryFrom<char> for Wire {
+    type Error = ParseWireError;
+
+    /// Parses the character written by [`Display`], or a space for an empty cell.
+    #[inline]
+    fn try_from(char: char) -> Result<Self, Self::Error> {
+        match char {
+            '.' | ' ' => Ok(Self::Empty),
+            '@' => Ok(Self::Head),
+            '~' => Ok(Self::Tail),
+            '#' => Ok(Self::Conductor),
+            _ => Err(ParseWireError(char)),
+        }
+    }
+}
+
+/// Error returned when parsing a [`Wire`] fails, with the unexpected character.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParseWireError(pub char);
+
+impl Display for ParseWireError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid wire {:?}, expected '.', '#', '@' or '~'", self.0)
+    }
+}
+
+impl std::error::Error for ParseWireError {}
+
+/// Parses a circuit with one character per cell, as written by [`Wire`]: `.` for empty cells, `#`
+/// for conductors, `@` for electron heads and `~` for their tails.
+///
+/// Lines starting with `!` are comments, and short lines are padded with empty cells.
+///
+/// # Example
+///
+/// ```
+/// # use vida::wireworld::{self, Wire};
+/// #
+/// let circuit = wireworld::parse("~@##\n").unwrap();
+/// assert_eq!(circuit.shape(), (1, 4));
+/// assert_eq!(Wire::from_state(circuit[(0, 1)]), Wire::Head);
+/// ```
+pub fn parse(text: &str) -> Result<States, Error> {
+    let lines: Vec<_> = text.lines().filter(|line| !line.starts_with('!')).collect();
+    let columns = lines.iter().map(|line| line.trim_end().chars().count()).max().unwrap_or(0);
+
+    let mut states = States::new(lines.len(), columns);
+    for (row, line) in lines.iter().enumerate() {
+        for (col, char) in line.trim_end().chars().enumerate() {
+            states[(row, col)] = Wire::try_from(char)?.into();
+        }
+    }
+    Ok(states)
+}
+
+/// Computes generations of Wireworld, one row per task with the `parallel` feature.
+///
+/// Heads are counted in the Moore neighborhood, with empty cells past the edges.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct WireworldEngine;
+
+impl MultiStateEngine for WireworldEngine {
+    fn update(&self, states: &States) -> States {
+        let heads = PaddedGrid::from(&states.to_grid());
+        let mut next = States::new(states.rows(), states.columns());
+
+        next.flat_mut().par_chunks_exact_mut(states.columns().max(1)).enumerate().for_each(|(row, cells)| {
+            let current = &states.flat()[row * states.columns()..];
+            let counts = neighbors::count_row(heads.padded_row(row), heads.padded_row(row + 1), heads.padded_row(row + 2));
+            for ((cell, &state), count) in cells.iter_mut().zip(current).zip(counts) {
+                *cell = Wire::from_state(state).next(count).into();
+            }
+        });
+        next
+    }
+
+    #[inline]
+    fn name(&self) -> &'static str {
+        "wireworld"
+    }
+
+    #[inline]
+    fn color(&self, theme: &Theme, state: u8) -> Option<Color> {
+        theme.by_wire(Wire::from_state(state))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wires(states: &States) -> String {
+        states.iter().map(|row| row.iter().map(|&state| Wire::from_state(state).to_string()).collect::<String>() + "\n").collect()
+    }
+
+    #[test]
+    pub fn electrons_follow_wires() {
+        let mut states = parse("~@###.#\n").unwrap();
+
+        let expected = ["#~@##.#\n", "##~@#.#\n", "###~@.#\n", "####~.#\n", "#####.#\n"];
+        for expected in expected {
+            states = WireworldEngine.update(&states);
+            assert_eq!(wires(&states), expected);
+        }
+        assert_eq!(states.population(), 0);
+    }
+
+    #[test]
+    pub fn crowded_conductors_stay() {
+        let states = parse("@.@\n.#.\n@..\n").unwrap();
+        assert_eq!(wires(&WireworldEngine.update(&states)), "~.~\n.#.\n~..\n");
+
+        let states = parse("@@\n#.\n").unwrap();
+        assert_eq!(wires(&WireworldEngine.update(&states)), "~~\n@.\n");
+    }
+
+    #[test]
+    pub fn parsing() {
+        let states = parse("! a comment\n#@\n~\n").unwrap();
+        assert_eq!(wires(&states), "#@\n~.\n");
+        assert!(matches!(parse("#O#\n"), Err(Error::ParseError(_))));
+        assert_eq!(parse("").unwrap(), States::new(0, 0));
+        assert_eq!(WireworldEngine.update(&States::new(0, 5)), States::new(0, 5));
+    }
+}